@@ -34,11 +34,20 @@ export const commands: { [key: string]: Command } = {
   "backends": {
     hide: false,
     subcommands: {
+      "install": {
+        hide: false,
+      },
       "ls": {
         hide: false,
       },
+      "status": {
+        hide: false,
+      },
     },
   },
+  "bin": {
+    hide: false,
+  },
   "bin-paths": {
     hide: false,
   },
@@ -55,6 +64,14 @@ export const commands: { [key: string]: Command } = {
   },
   "completion": {
     hide: false,
+    subcommands: {
+      "install": {
+        hide: false,
+      },
+      "versions": {
+        hide: true,
+      },
+    },
   },
   "config": {
     hide: false,
@@ -71,6 +88,9 @@ export const commands: { [key: string]: Command } = {
       "set": {
         hide: false,
       },
+      "validate": {
+        hide: false,
+      },
     },
   },
   "current": {
@@ -95,6 +115,11 @@ export const commands: { [key: string]: Command } = {
   },
   "doctor": {
     hide: false,
+    subcommands: {
+      "network": {
+        hide: false,
+      },
+    },
   },
   "env": {
     hide: false,
@@ -105,12 +130,18 @@ export const commands: { [key: string]: Command } = {
   "generate": {
     hide: false,
     subcommands: {
+      "bootstrap": {
+        hide: false,
+      },
       "git-pre-commit": {
         hide: false,
       },
       "github-action": {
         hide: false,
       },
+      "gitlab-ci": {
+        hide: false,
+      },
       "task-docs": {
         hide: false,
       },
@@ -140,6 +171,9 @@ export const commands: { [key: string]: Command } = {
   "local": {
     hide: true,
   },
+  "lock": {
+    hide: false,
+  },
   "ls": {
     hide: false,
   },
@@ -164,19 +198,36 @@ export const commands: { [key: string]: Command } = {
       "ls-remote": {
         hide: false,
       },
+      "test": {
+        hide: false,
+      },
       "uninstall": {
         hide: false,
       },
+      "unlink": {
+        hide: false,
+      },
       "update": {
         hide: false,
       },
     },
   },
+  "prompt": {
+    hide: false,
+  },
   "prune": {
     hide: false,
   },
   "registry": {
     hide: false,
+    subcommands: {
+      "search": {
+        hide: false,
+      },
+      "update": {
+        hide: false,
+      },
+    },
   },
   "render-help": {
     hide: true,
@@ -213,6 +264,9 @@ export const commands: { [key: string]: Command } = {
       },
     },
   },
+  "setup": {
+    hide: false,
+  },
   "shell": {
     hide: false,
   },
@@ -225,6 +279,9 @@ export const commands: { [key: string]: Command } = {
       "python": {
         hide: false,
       },
+      "ruby": {
+        hide: false,
+      },
     },
   },
   "tasks": {
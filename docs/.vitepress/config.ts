@@ -51,6 +51,7 @@ export default defineConfig({
               {text: 'cargo', link: '/dev-tools/backends/cargo'},
               {text: 'go', link: '/dev-tools/backends/go'},
               {text: 'npm', link: '/dev-tools/backends/npm'},
+              {text: 'oci', link: '/dev-tools/backends/oci'},
               {text: 'pipx', link: '/dev-tools/backends/pipx'},
               {text: 'spm', link: '/dev-tools/backends/spm'},
               {text: 'ubi', link: '/dev-tools/backends/ubi'},
@@ -35,6 +35,17 @@ assert_fail() {
   fi
 }
 
+assert_exit_code() {
+  local status=0
+  debug "$ $1"
+  bash -c "$1" >/dev/null 2>&1 || status=$?
+  if [[ $status -eq $2 ]]; then
+    ok "[$1] exited with $2"
+  else
+    fail "[$1] exited with $status, expected $2"
+  fi
+}
+
 assert() {
   local actual
   actual="$(quiet_assert_succeed "$1")"
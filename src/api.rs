@@ -0,0 +1,152 @@
+//! Functions for reading what mise resolves for a directory, the same tools and env vars
+//! `mise ls --current` and `mise env` show, but as values instead of output.
+//!
+//! ```no_run
+//! let tools = mise::api::resolve_tools("/path/to/project".as_ref())?;
+//! for tool in &tools {
+//!     println!("{}@{} {}", tool.name, tool.version, tool.install_path.display());
+//! }
+//! let env = mise::api::env_for("/path/to/project".as_ref())?;
+//! println!("{}", env["PATH"]);
+//! # Ok::<(), eyre::Report>(())
+//! ```
+//!
+//! Nothing is installed and the network isn't used unless [`Options::with_network`] is set, so a
+//! request like `node@20` only resolves if a matching version is installed or cached.
+//!
+//! mise keeps its config in process-wide state, each call here reloads it for `dir`. Don't call
+//! these from several threads at once.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use confique::Partial;
+use eyre::Result;
+
+use crate::config::settings::SettingsPartial;
+use crate::config::{Config, Settings};
+use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::{backend, dirs, env};
+
+/// options for [`resolve_tools_with`] and [`env_for_with`]
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct Options {
+    /// fetch remote versions when no installed or cached version matches a request
+    pub network: bool,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_network(mut self, network: bool) -> Self {
+        self.network = network;
+        self
+    }
+}
+
+/// a tool version the config for a directory resolves to
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolvedTool {
+    /// e.g.: `node` or `npm:prettier`
+    pub name: String,
+    /// the version the request resolved to, e.g.: `20.17.0`
+    pub version: String,
+    /// the version as it was requested, e.g.: `20`
+    pub request: String,
+    /// the config file that requested it, `None` if it was set another way like
+    /// `MISE_NODE_VERSION`
+    pub source: Option<PathBuf>,
+    pub installed: bool,
+    pub install_path: PathBuf,
+    /// the dirs the tool adds to PATH, empty if it isn't installed
+    pub bin_paths: Vec<PathBuf>,
+}
+
+/// the tools active in `dir`
+pub fn resolve_tools(dir: &Path) -> Result<Vec<ResolvedTool>> {
+    resolve_tools_with(dir, &Options::default())
+}
+
+pub fn resolve_tools_with(dir: &Path, options: &Options) -> Result<Vec<ResolvedTool>> {
+    let (_, ts) = load(dir, options)?;
+    let mut tools = vec![];
+    for tvl in ts.versions.values() {
+        let backend = backend::get(&tvl.backend);
+        for tv in &tvl.versions {
+            let installed = backend.is_version_installed(tv, true);
+            let bin_paths = match installed {
+                true => backend.list_bin_paths(tv)?,
+                false => vec![],
+            };
+            tools.push(ResolvedTool {
+                name: backend.id().to_string(),
+                version: tv.version.clone(),
+                request: tv.request.version(),
+                source: tvl.source.path().map(|p| p.to_path_buf()),
+                installed,
+                install_path: tv.install_path(),
+                bin_paths,
+            });
+        }
+    }
+    Ok(tools)
+}
+
+/// the env vars mise sets in `dir`, including PATH with the tools' bin paths in front
+pub fn env_for(dir: &Path) -> Result<HashMap<String, String>> {
+    env_for_with(dir, &Options::default())
+}
+
+pub fn env_for_with(dir: &Path, options: &Options) -> Result<HashMap<String, String>> {
+    let (config, ts) = load(dir, options)?;
+    Ok(ts.env_with_path(&config)?.into_iter().collect())
+}
+
+fn load(dir: &Path, options: &Options) -> Result<(Arc<Config>, Toolset)> {
+    let dir = match dir.is_relative() {
+        true => env::current_dir()?.join(dir),
+        false => dir.to_path_buf(),
+    };
+    // also resets the settings
+    Config::reset();
+    let mut settings = SettingsPartial::empty();
+    settings.offline = Some(!options.network);
+    Settings::reset(Some(settings));
+    dirs::set_cwd(dir);
+    let config = Config::try_get()?;
+    let ts = ToolsetBuilder::new().build(&config)?;
+    Ok((config, ts))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test::reset;
+
+    #[test]
+    fn test_resolve_tools() {
+        reset();
+        let tools = resolve_tools(&env::HOME.join("cwd")).unwrap();
+        let tiny = tools.iter().find(|t| t.name == "tiny").unwrap();
+        assert_eq!(tiny.version, "3.1.0");
+        assert!(tiny.installed);
+        assert_eq!(tiny.bin_paths.len(), 1);
+        reset();
+    }
+
+    #[test]
+    fn test_env_for() {
+        reset();
+        let env = env_for(&env::HOME.join("cwd")).unwrap();
+        let tiny = dirs::INSTALLS.join("tiny");
+        assert!(env::split_paths(&env["PATH"]).any(|p| p.starts_with(&tiny)));
+        reset();
+    }
+}
@@ -57,7 +57,8 @@ impl AsdfBackend {
         Self {
             cache: ExternalPluginCache::default(),
             remote_version_cache: CacheManagerBuilder::new(
-                ba.cache_path.join("remote_versions.msgpack.z"),
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .with_fresh_file(plugin_path.clone())
@@ -117,10 +118,15 @@ impl AsdfBackend {
             return Ok(None);
         }
         let settings = Settings::get();
-        let raw_versions = match settings.paranoid {
-            true => HTTP_FETCH.get_text(format!("https://mise-versions.jdx.dev/{}", self.name)),
-            false => HTTP_FETCH.get_text(format!("http://mise-versions.jdx.dev/{}", self.name)),
+        let url = match settings.paranoid {
+            true => format!("https://mise-versions.jdx.dev/{}", self.name),
+            false => format!("http://mise-versions.jdx.dev/{}", self.name),
         };
+        let cache_path = self
+            .fa()
+            .remote_versions_cache_path
+            .join("remote_versions.list.json");
+        let raw_versions = HTTP_FETCH.get_text_incremental(url, &cache_path);
         let versions =
             // using http is not a security concern and enabling tls makes mise significantly slower
             match raw_versions {
@@ -190,7 +196,7 @@ impl AsdfBackend {
             //         sm.prepend_path(p);
             //     }
             // }
-            let output = sm.cmd(&Script::ListBinPaths).read()?;
+            let output = sm.read(&Script::ListBinPaths)?;
             output
                 .split_whitespace()
                 .map(|f| {
@@ -226,9 +232,10 @@ impl AsdfBackend {
     }
 
     fn script_man_for_tv(&self, tv: &ToolVersion) -> Result<ScriptManager> {
+        self.plugin.verify_pin()?;
         let config = Config::get();
         let mut sm = self.plugin.script_man.clone();
-        for (key, value) in &tv.request.options() {
+        for (key, value) in &tv.request.options().opts {
             let k = format!("RTX_TOOL_OPTS__{}", key.to_uppercase());
             sm = sm.with_env(k, value.clone());
             let k = format!("MISE_TOOL_OPTS__{}", key.to_uppercase());
@@ -308,6 +315,7 @@ impl Backend for AsdfBackend {
     }
 
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
+        self.toml.ensure_mise_version(&self.name)?;
         self.remote_version_cache
             .get_or_try_init(|| self.fetch_remote_versions())
             .wrap_err_with(|| {
@@ -401,6 +409,7 @@ impl Backend for AsdfBackend {
     }
 
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
+        self.toml.ensure_mise_version(&self.name)?;
         let mut sm = self.script_man_for_tv(&ctx.tv)?;
 
         for p in ctx.ts.list_paths() {
@@ -429,6 +438,7 @@ impl Backend for AsdfBackend {
     }
 
     fn list_bin_paths(&self, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        self.toml.ensure_mise_version(&self.name)?;
         Ok(self
             .cache
             .list_bin_paths(self, tv, || self.fetch_bin_paths(tv))?
@@ -451,6 +461,7 @@ impl Backend for AsdfBackend {
             // the second is to prevent infinite loops
             return Ok(BTreeMap::new());
         }
+        self.toml.ensure_mise_version(&self.name)?;
         self.cache
             .exec_env(config, self, tv, || self.fetch_exec_env(ts, tv))
     }
@@ -471,7 +482,7 @@ impl Debug for AsdfBackend {
 
 fn normalize_remote(remote: &str) -> eyre::Result<String> {
     let url = Url::parse(remote)?;
-    let host = url.host_str().unwrap();
+    let host = url.host_str().unwrap_or_default();
     let path = url.path().trim_end_matches(".git");
     Ok(format!("{host}{path}"))
 }
@@ -11,10 +11,10 @@ use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::env::{self, GITHUB_TOKEN};
-use crate::file;
 use crate::http::HTTP_FETCH;
 use crate::install_context::InstallContext;
 use crate::toolset::{ToolRequest, ToolVersion};
+use crate::{file, mise_lock};
 
 #[derive(Debug)]
 pub struct CargoBackend {
@@ -35,6 +35,13 @@ impl Backend for CargoBackend {
         Ok(vec!["cargo".into(), "rust".into()])
     }
 
+    fn is_build_heavy(&self) -> bool {
+        let settings = Settings::get();
+        self.git_url().is_some()
+            || !settings.cargo_binstall
+            || file::which_non_pristine("cargo-binstall").is_none()
+    }
+
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         if self.git_url().is_some() {
             // TODO: maybe fetch tags/branches from git?
@@ -57,9 +64,28 @@ impl Backend for CargoBackend {
     }
 
     fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
-        let config = Config::try_get()?;
         let install_arg = format!("{}@{}", self.name(), ctx.tv.version);
 
+        // a prebuilt binary is tried first, building from source is what takes so long
+        if self.git_url().is_none() && self.is_binstall_enabled(&ctx.tv) {
+            let mut cmd = CmdLineRunner::new("cargo-binstall")
+                .arg("-y")
+                .arg("--disable-strategies")
+                .arg("compile");
+            if let Some(token) = &*GITHUB_TOKEN {
+                cmd = cmd.env("GITHUB_TOKEN", token)
+            }
+            match self.run_install(ctx, cmd.arg(&install_arg)) {
+                Ok(()) => {
+                    mise_lock::record_strategy("prebuilt");
+                    return Ok(());
+                }
+                Err(err) => warn!(
+                    "no prebuilt binary of {install_arg} for this platform, building it from source: {err}"
+                ),
+            }
+        }
+
         let cmd = CmdLineRunner::new("cargo").arg("install");
         let mut cmd = if let Some(url) = self.git_url() {
             let mut cmd = cmd.arg(format!("--git={url}"));
@@ -77,12 +103,6 @@ impl Backend for CargoBackend {
                 ))?;
             }
             cmd
-        } else if self.is_binstall_enabled(&ctx.tv) {
-            let mut cmd = CmdLineRunner::new("cargo-binstall").arg("-y");
-            if let Some(token) = &*GITHUB_TOKEN {
-                cmd = cmd.env("GITHUB_TOKEN", token)
-            }
-            cmd.arg(install_arg)
         } else {
             cmd.arg(install_arg)
         };
@@ -96,15 +116,8 @@ impl Backend for CargoBackend {
                 cmd = cmd.arg("--no-default-features");
             }
         }
-
-        cmd.arg("--locked")
-            .arg("--root")
-            .arg(ctx.tv.install_path())
-            .with_pr(ctx.pr.as_ref())
-            .envs(ctx.ts.env_with_path(&config)?)
-            .prepend_path(ctx.ts.list_paths())?
-            .prepend_path(self.depedency_toolset()?.list_paths())?
-            .execute()?;
+        self.run_install(ctx, cmd)?;
+        mise_lock::record_strategy("source");
 
         Ok(())
     }
@@ -114,7 +127,8 @@ impl CargoBackend {
     pub fn from_arg(ba: BackendArg) -> Self {
         Self {
             remote_version_cache: CacheManagerBuilder::new(
-                ba.cache_path.join("remote_versions.msgpack.z"),
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .build(),
@@ -122,6 +136,25 @@ impl CargoBackend {
         }
     }
 
+    /// runs `cargo install` or `cargo-binstall` into the install dir of `ctx.tv`
+    fn run_install(&self, ctx: &InstallContext, cmd: CmdLineRunner) -> eyre::Result<()> {
+        let config = Config::try_get()?;
+        let mut cmd = cmd
+            .arg("--locked")
+            .arg("--root")
+            .arg(ctx.tv.install_path())
+            .with_pr(ctx.pr.as_ref())
+            .envs(ctx.ts.env_with_path(&config)?)
+            .prepend_path(ctx.ts.list_paths())?
+            .prepend_path(self.depedency_toolset()?.list_paths())?;
+        if let Some(compiler) = self.compiler_toolset(&ctx.tv)? {
+            cmd = cmd
+                .envs(compiler.env_with_path(&config)?)
+                .prepend_path(compiler.list_paths())?;
+        }
+        cmd.execute()
+    }
+
     fn is_binstall_enabled(&self, tv: &ToolVersion) -> bool {
         let settings = Settings::get();
         if !settings.cargo_binstall || file::which_non_pristine("cargo-binstall").is_none() {
@@ -132,6 +165,10 @@ impl CargoBackend {
             info!("not using cargo-binstall because features are specified");
             return false;
         }
+        if opts.contains_key("compiler") {
+            info!("not using cargo-binstall because a compiler is specified");
+            return false;
+        }
         true
     }
 
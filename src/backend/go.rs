@@ -28,6 +28,10 @@ impl Backend for GoBackend {
         Ok(vec!["go".into()])
     }
 
+    fn is_build_heavy(&self) -> bool {
+        true
+    }
+
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         self.remote_version_cache
             .get_or_try_init(|| {
@@ -70,12 +74,21 @@ impl Backend for GoBackend {
             ctx.tv.version.to_string()
         };
 
+        let env = match self.compiler_toolset(&ctx.tv)? {
+            Some(compiler) => {
+                let mut env = compiler.full_env()?;
+                // otherwise go switches to a newer toolchain if the module asks for one
+                env.insert("GOTOOLCHAIN".into(), "local".into());
+                env
+            }
+            None => self.dependency_env()?,
+        };
         let install = |v| {
             CmdLineRunner::new("go")
                 .arg("install")
                 .arg(format!("{}@{v}", self.name()))
                 .with_pr(ctx.pr.as_ref())
-                .envs(self.dependency_env()?)
+                .envs(&env)
                 .env("GOBIN", ctx.tv.install_path().join("bin"))
                 .execute()
         };
@@ -93,7 +106,8 @@ impl GoBackend {
     pub fn from_arg(ba: BackendArg) -> Self {
         Self {
             remote_version_cache: CacheManagerBuilder::new(
-                ba.cache_path.join("remote_versions.msgpack.z"),
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .build(),
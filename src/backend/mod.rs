@@ -1,21 +1,24 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::hash::Hash;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::{panic, thread};
 
 use console::style;
 use contracts::requires;
 use eyre::{bail, eyre, WrapErr};
 use itertools::Itertools;
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use regex::Regex;
 use strum::IntoEnumIterator;
-use versions::Versioning;
+use versions::{Requirement, Versioning};
 
 use self::backend_meta::BackendMeta;
-use crate::cli::args::{BackendArg, ToolVersionType};
+use crate::cli::args::{BackendArg, ToolArg, ToolVersionType};
 use crate::cmd::CmdLineRunner;
 use crate::config::settings::SETTINGS;
 use crate::config::{Config, Settings, CONFIG};
@@ -24,9 +27,11 @@ use crate::install_context::InstallContext;
 use crate::plugins::core::{CorePlugin, CORE_PLUGINS};
 use crate::plugins::{Plugin, PluginType, VERSION_REGEX};
 use crate::runtime_symlinks::is_runtime_symlink;
-use crate::toolset::{ToolRequest, ToolVersion, Toolset};
-use crate::ui::progress_report::SingleReport;
-use crate::{dirs, env, file, lock_file};
+use crate::toolset::{InstallOptions, ToolRequest, ToolVersion, Toolset, ToolsetBuilder};
+use crate::ui::ctrlc;
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::progress_report::{QuietReport, SingleReport};
+use crate::{dirs, env, file, install_manifest, lock_file, mise_lock};
 
 pub mod asdf;
 pub mod backend_meta;
@@ -34,6 +39,7 @@ pub mod cargo;
 mod external_plugin_cache;
 pub mod go;
 pub mod npm;
+pub mod oci;
 pub mod pipx;
 pub mod spm;
 pub mod ubi;
@@ -63,6 +69,7 @@ pub enum BackendType {
     Core,
     Go,
     Npm,
+    Oci,
     Pipx,
     Spm,
     Ubi,
@@ -75,6 +82,35 @@ impl Display for BackendType {
     }
 }
 
+impl BackendType {
+    /// the executable this backend runs to list and install tools, `None` if it doesn't run one
+    pub fn executable(&self) -> Option<&'static str> {
+        match self {
+            Self::Cargo => Some("cargo"),
+            Self::Go => Some("go"),
+            Self::Npm => Some("npm"),
+            Self::Pipx if Settings::get().pipx_uvx => Some("uv"),
+            Self::Pipx => Some("pipx"),
+            Self::Spm => Some("swift"),
+            Self::Ubi => Some("ubi"),
+            Self::Asdf | Self::Core | Self::Oci | Self::Vfox => None,
+        }
+    }
+
+    /// the tool mise installs to provide `executable()`, swift can't be installed by mise yet
+    pub fn dependency(&self) -> Option<&'static str> {
+        match self {
+            Self::Cargo => Some("rust"),
+            Self::Go => Some("go"),
+            Self::Npm => Some("node"),
+            Self::Pipx if Settings::get().pipx_uvx => Some("uv"),
+            Self::Pipx => Some("pipx"),
+            Self::Ubi => Some("cargo:ubi"),
+            Self::Asdf | Self::Core | Self::Oci | Self::Spm | Self::Vfox => None,
+        }
+    }
+}
+
 static TOOLS: Mutex<Option<BackendMap>> = Mutex::new(None);
 
 fn load_tools() -> BackendMap {
@@ -139,6 +175,7 @@ pub fn arg_to_backend(ba: BackendArg) -> ABackend {
         BackendType::Core => Arc::new(asdf::AsdfBackend::from_arg(ba)),
         BackendType::Npm => Arc::new(npm::NPMBackend::from_arg(ba)),
         BackendType::Go => Arc::new(go::GoBackend::from_arg(ba)),
+        BackendType::Oci => Arc::new(oci::OciBackend::from_arg(ba)),
         BackendType::Pipx => Arc::new(pipx::PIPXBackend::from_arg(ba)),
         BackendType::Spm => Arc::new(spm::SPMBackend::from_arg(ba)),
         BackendType::Ubi => Arc::new(ubi::UbiBackend::from_arg(ba)),
@@ -177,6 +214,11 @@ pub trait Backend: Debug + Send + Sync {
     fn get_dependencies(&self, _tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
         Ok(vec![])
     }
+    /// Whether installs compile the tool from source rather than only downloading it. At most
+    /// `build_jobs` of these run at once so they don't all compete for the cpus.
+    fn is_build_heavy(&self) -> bool {
+        false
+    }
     fn get_all_dependencies(&self, tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
         let mut deps = self.get_dependencies(tvr)?;
         let dep_backends = deps.iter().map(|fa| fa.into()).collect::<Vec<ABackend>>();
@@ -374,14 +416,53 @@ pub trait Backend: Debug + Send + Sync {
             }
         }
         let _lock = lock_file::get(&ctx.tv.install_path(), ctx.force)?;
+        // another request that resolved to this same version may have installed it while we
+        // were waiting for the lock
+        if !ctx.force && self.is_version_installed(&ctx.tv, true) {
+            ctx.pr.finish_with_message("installed".to_string());
+            return Ok(());
+        }
         self.create_install_dirs(&ctx.tv)?;
-
-        if let Err(e) = self.install_version_impl(&ctx) {
+        let cleanup = self.cleanup_install_dirs_on_interrupt(&SETTINGS, &ctx.tv);
+
+        let lock_source = ctx
+            .ts
+            .versions
+            .get(&ctx.tv.backend)
+            .map(|tvl| tvl.source.clone())
+            .filter(|_| SETTINGS.lockfile);
+        if lock_source.is_some() {
+            mise_lock::start_recording();
+        }
+        let res = self.install_version_impl(&ctx).and_then(|_| {
+            // runs before the install is marked complete so a failing hook fails the install
+            match ctx.tv.request.options().get("postinstall") {
+                Some(script) => {
+                    ctx.pr
+                        .set_message("running custom postinstall hook".to_string());
+                    self.run_postinstall_hook(&ctx, script)
+                        .wrap_err_with(|| format!("postinstall hook failed for {}", ctx.tv))
+                }
+                None => Ok(()),
+            }
+        });
+        let record_checksums = lock_source.is_some();
+        let res = match lock_source {
+            Some(source) => {
+                let recording = mise_lock::finish_recording();
+                res.and_then(|_| mise_lock::update(&source, &ctx.tv, recording))
+            }
+            None => res,
+        };
+        if let Err(e) = res {
             self.cleanup_install_dirs_on_error(&SETTINGS, &ctx.tv);
             return Err(e);
         }
 
         BackendMeta::write(&ctx.tv.backend)?;
+        if record_checksums {
+            install_manifest::write(&ctx.tv)?;
+        }
 
         self.cleanup_install_dirs(&SETTINGS, &ctx.tv);
         // attempt to touch all the .tool-version files to trigger updates in hook-env
@@ -393,14 +474,12 @@ pub trait Backend: Debug + Send + Sync {
                 debug!("error touching config file: {:?} {:?}", path, err);
             }
         }
+        // an interrupt from here on leaves the incomplete marker behind rather than deleting a
+        // finished install
+        drop(cleanup);
         if let Err(err) = file::remove_file(self.incomplete_file_path(&ctx.tv)) {
             debug!("error removing incomplete file: {:?}", err);
         }
-        if let Some(script) = ctx.tv.request.options().get("postinstall") {
-            ctx.pr
-                .finish_with_message("running custom postinstall hook".to_string());
-            self.run_postinstall_hook(&ctx, script)?;
-        }
         ctx.pr.finish_with_message("installed".to_string());
 
         Ok(())
@@ -412,6 +491,7 @@ pub trait Backend: Debug + Send + Sync {
             .with_pr(ctx.pr.as_ref())
             .arg("-c")
             .arg(script)
+            .envs(CONFIG.env()?)
             .envs(self.exec_env(&CONFIG, ctx.ts, &ctx.tv)?)
             .execute()?;
         Ok(())
@@ -441,6 +521,9 @@ pub trait Backend: Debug + Send + Sync {
         rmdir(&tv.install_path())?;
         rmdir(&tv.download_path())?;
         rmdir(&tv.cache_path())?;
+        if !dryrun {
+            install_manifest::remove(tv);
+        }
         Ok(())
     }
     fn uninstall_version_impl(
@@ -482,6 +565,7 @@ pub trait Backend: Debug + Send + Sync {
         let _ = remove_all_with_warning(tv.download_path());
         let _ = remove_all_with_warning(tv.cache_path());
         let _ = file::remove_file(tv.install_path()); // removes if it is a symlink
+        install_manifest::remove(tv);
         file::create_dir_all(tv.install_path())?;
         file::create_dir_all(tv.download_path())?;
         file::create_dir_all(tv.cache_path())?;
@@ -494,6 +578,20 @@ pub trait Backend: Debug + Send + Sync {
             self.cleanup_install_dirs(settings, tv);
         }
     }
+    fn cleanup_install_dirs_on_interrupt(
+        &self,
+        settings: &Settings,
+        tv: &ToolVersion,
+    ) -> ctrlc::CleanupGuard {
+        let mut paths = vec![];
+        if !settings.always_keep_install {
+            paths.push(tv.install_path());
+            if !settings.always_keep_download {
+                paths.push(tv.download_path());
+            }
+        }
+        ctrlc::cleanup_on_interrupt(paths)
+    }
     fn cleanup_install_dirs(&self, settings: &Settings, tv: &ToolVersion) {
         if !settings.always_keep_download && !settings.always_keep_install {
             let _ = remove_all_with_warning(tv.download_path());
@@ -521,8 +619,51 @@ pub trait Backend: Debug + Send + Sync {
         self.depedency_toolset()?.full_env()
     }
 
+    /// the toolchain the `compiler` option pins to build the tool with, e.g.:
+    /// `"cargo:ripgrep" = { version = "14", compiler = "1.78" }` builds with rust 1.78. It is
+    /// installed if it is missing but isn't added to the config.
+    fn compiler_toolset(&self, tv: &ToolVersion) -> eyre::Result<Option<Toolset>> {
+        let opts = tv.request.options();
+        let Some(compiler) = opts.get("compiler") else {
+            return Ok(None);
+        };
+        let Some(dependency) = self.get_type().dependency() else {
+            bail!("{} does not support the compiler option", self.id());
+        };
+        let arg: ToolArg = format!("{dependency}@{compiler}").parse()?;
+        let config = Config::get();
+        let mut ts = ToolsetBuilder::new()
+            .with_args(std::slice::from_ref(&arg))
+            .build(&config)?;
+        ts.versions.retain(|ba, _| *ba == arg.backend);
+        ts.install_arg_versions(&config, &InstallOptions::new())
+            .wrap_err_with(|| format!("failed to install the compiler for {tv}"))?;
+        if let Some((_, compiler)) = ts.list_current_versions().into_iter().next() {
+            debug!("building {tv} with {compiler}");
+            mise_lock::record_compiler(&compiler.version);
+        }
+        Ok(Some(ts))
+    }
+
     fn fuzzy_match_filter(&self, versions: Vec<String>, query: &str) -> eyre::Result<Vec<String>> {
-        let escaped_query = regex::escape(query);
+        if let Some(reqs) = version_constraint(query) {
+            if versions.is_empty() || versions.iter().any(|v| is_semver(v)) {
+                return Ok(versions
+                    .into_iter()
+                    .filter(|v| !is_unrequested_prerelease(v, query))
+                    .filter(|v| matches_constraint(&reqs, v))
+                    .collect());
+            }
+            static WARNED: Lazy<Mutex<HashSet<String>>> = Lazy::new(Default::default);
+            if WARNED.lock().unwrap().insert(self.id().to_string()) {
+                warn!(
+                    "{} does not use semver versions, {query} is matched as a prefix instead",
+                    self.id()
+                );
+            }
+        }
+        let orig_query = query;
+        let escaped_query = version_query_regex(query);
         let query = if query == "latest" {
             "v?[0-9].*"
         } else {
@@ -535,7 +676,7 @@ pub trait Backend: Debug + Send + Sync {
                 if query == v {
                     return true;
                 }
-                if VERSION_REGEX.is_match(v) {
+                if is_unrequested_prerelease(v, orig_query) {
                     return false;
                 }
                 query_regex.is_match(v)
@@ -545,6 +686,107 @@ pub trait Backend: Debug + Send + Sync {
     }
 }
 
+/// runs `f` for each item on its own thread, at most `http_concurrency` at a time since fetching
+/// version lists is mostly waiting on requests. Results are in the same order as `items` and an
+/// error for one item doesn't stop the others.
+pub fn fetch_each<T, R, F>(items: Vec<T>, f: F) -> Vec<(T, eyre::Result<R>)>
+where
+    T: Send,
+    R: Send,
+    F: Fn(&T) -> eyre::Result<R> + Sync,
+{
+    let total = items.len();
+    let workers = (SETTINGS.http_concurrency.max(1) as usize).min(total);
+    let pr: Box<dyn SingleReport> = match total > 1 && console::user_attended_stderr() {
+        true => MultiProgressReport::get().add("versions"),
+        false => Box::new(QuietReport::new()),
+    };
+    let report = |pending: usize| {
+        pr.set_message(format!("fetching, {pending} of {total} tools pending"));
+    };
+    report(total);
+    let pending = AtomicUsize::new(total);
+    let queue = Mutex::new(items.into_iter().enumerate().collect::<VecDeque<_>>());
+    let results = thread::scope(|s| {
+        (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let Some((i, item)) = queue.lock().unwrap().pop_front() else {
+                            break;
+                        };
+                        let res = f(&item);
+                        report(pending.fetch_sub(1, Ordering::Relaxed) - 1);
+                        results.push((i, item, res));
+                    }
+                    results
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|t| match t.join() {
+                Ok(x) => x,
+                Err(e) => panic::resume_unwind(e),
+            })
+            .collect::<Vec<_>>()
+    });
+    pr.finish_with_message(format!("fetched {total} tools"));
+    results
+        .into_iter()
+        .sorted_by_key(|(i, _, _)| *i)
+        .map(|(_, item, res)| (item, res))
+        .collect()
+}
+
+/// converts a version query into a regex fragment, `*` matches anything so `20.*` matches every
+/// 20.x release
+pub fn version_query_regex(query: &str) -> String {
+    regex::escape(query).replace(r"\*", ".*")
+}
+
+/// parses a semver range like `^20.1` or `>=3.10,<3.13`, `None` if `query` isn't one
+pub fn version_constraint(query: &str) -> Option<Vec<Requirement>> {
+    if !query.starts_with(['^', '~', '<', '>', '=']) {
+        return None;
+    }
+    query
+        .split(',')
+        .map(|r| {
+            let r = r.replace(' ', "");
+            let version = r.trim_start_matches(['^', '~', '<', '>', '=']);
+            let op = &r[..r.len() - version.len()];
+            // `^20.1` means `^20.1.0`, without the padding it would only match other 2 part versions
+            let parts = version.split('.').count();
+            match version.chars().all(|c| c.is_ascii_digit() || c == '.') && parts < 3 {
+                true => Requirement::new(&format!("{op}{version}{}", ".0".repeat(3 - parts))),
+                false => Requirement::new(&r),
+            }
+        })
+        .collect()
+}
+
+fn matches_constraint(reqs: &[Requirement], version: &str) -> bool {
+    match Versioning::new(version.trim_start_matches('v')) {
+        Some(v) => reqs.iter().all(|r| r.matches(&v)),
+        None => false,
+    }
+}
+
+fn is_semver(version: &str) -> bool {
+    let version = version.trim_start_matches('v');
+    version.starts_with(|c: char| c.is_ascii_digit())
+        && !matches!(
+            Versioning::new(version),
+            None | Some(Versioning::Complex(_))
+        )
+}
+
+/// prereleases are skipped unless the query asks for one, e.g. `3.13.*rc*`
+pub fn is_unrequested_prerelease(version: &str, query: &str) -> bool {
+    VERSION_REGEX.is_match(version) && !VERSION_REGEX.is_match(query)
+}
+
 fn find_match_in_list(list: &[String], query: &str) -> Option<String> {
     match list.contains(&query.to_string()) {
         true => Some(query.to_string()),
@@ -609,3 +851,78 @@ impl Ord for dyn Backend {
 pub fn reset() {
     *TOOLS.lock().unwrap() = None;
 }
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+
+    #[test]
+    fn test_version_query_regex() {
+        let re = Regex::new(&format!("^{}$", version_query_regex("20.*"))).unwrap();
+        assert!(re.is_match("20.1.0"));
+        assert!(re.is_match("20.10.3"));
+        assert!(!re.is_match("20"));
+        assert!(!re.is_match("200.1.0"));
+        assert!(is_unrequested_prerelease("3.13.0rc1", "3.13.*"));
+        assert!(!is_unrequested_prerelease("3.13.0rc1", "3.13.*rc1"));
+        assert!(!is_unrequested_prerelease("3.13.0", "3.13.*"));
+    }
+
+    #[test]
+    fn test_version_constraint() {
+        let matching = |query: &str, versions: &[&str]| {
+            let reqs = version_constraint(query).unwrap();
+            versions
+                .iter()
+                .filter(|v| matches_constraint(&reqs, v))
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+        };
+        let node = ["18.20.4", "20.0.0", "20.1.0", "20.11.1", "22.9.0"];
+        assert_eq!(matching("^20.1", &node), ["20.1.0", "20.11.1"]);
+        assert_eq!(matching("~20.1", &node), ["20.1.0"]);
+        assert_eq!(
+            matching(">=20", &node),
+            ["20.0.0", "20.1.0", "20.11.1", "22.9.0"]
+        );
+        let python = ["3.9.19", "3.10.0", "3.12.6", "3.13.0"];
+        assert_eq!(matching(">=3.10,<3.13", &python), ["3.10.0", "3.12.6"]);
+        assert_eq!(matching(">=3.10, <3.13", &python), ["3.10.0", "3.12.6"]);
+        assert_eq!(matching("<3.10", &["v3.9.1", "v3.10.0"]), ["v3.9.1"]);
+        assert!(version_constraint("20.1").is_none());
+        assert!(version_constraint("^").is_none());
+        assert!(is_semver("20.1.0"));
+        assert!(!is_semver("temurin-21.0.4+7.0.LTS"));
+    }
+
+    #[test]
+    fn test_fetch_each() {
+        let results = fetch_each((1..=10).collect(), |n: &u32| match n {
+            3 => bail!("failed to fetch {n}"),
+            n => Ok(n * 2),
+        });
+        assert_eq!(
+            results
+                .iter()
+                .map(|(n, res)| match res {
+                    Ok(v) => format!("{n}={v}"),
+                    Err(err) => format!("{n}: {err}"),
+                })
+                .collect::<Vec<_>>(),
+            [
+                "1=2",
+                "2=4",
+                "3: failed to fetch 3",
+                "4=8",
+                "5=10",
+                "6=12",
+                "7=14",
+                "8=16",
+                "9=18",
+                "10=20"
+            ]
+        );
+    }
+}
@@ -0,0 +1,509 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, eyre, Result, WrapErr};
+use itertools::Itertools;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use serde_derive::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+use url::Url;
+
+use crate::backend::{Backend, BackendType};
+use crate::cache::{CacheManager, CacheManagerBuilder};
+use crate::cli::args::BackendArg;
+use crate::config::Settings;
+use crate::env;
+use crate::file;
+use crate::http::HTTP;
+use crate::install_context::InstallContext;
+use crate::plugins::core::Platform;
+use crate::toolset::{ToolRequest, ToolVersion};
+
+/// manifest types we can read, indexes list a manifest per platform
+const MANIFEST_TYPES: &[&str] = &[
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+];
+
+/// the annotation oras and other artifact tools set to the filename of a layer
+const TITLE_ANNOTATION: &str = "org.opencontainers.image.title";
+
+/// installs tools published to a container registry, either as image layers or as artifacts
+/// pushed with e.g.: `oras push ghcr.io/org/tool:1.2.3 ./tool`
+#[derive(Debug)]
+pub struct OciBackend {
+    ba: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl Backend for OciBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Oci
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.ba
+    }
+
+    fn get_dependencies(&self, _tvr: &ToolRequest) -> Result<Vec<BackendArg>> {
+        Ok(vec![])
+    }
+
+    fn _list_remote_versions(&self) -> Result<Vec<String>> {
+        let reference = Reference::parse(self.name())?;
+        if let Some(tag) = reference.tag {
+            return Ok(vec![tag]);
+        }
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                let client = Client::new(&reference)?;
+                let tags: TagList = client.json(&reference.url("tags/list"))?;
+                Ok(tags.tags.into_iter().filter(|t| t != "latest").collect())
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("oci backend")?;
+        let reference = Reference::parse(self.name())?;
+        let tag = reference.tag.as_deref().unwrap_or(&ctx.tv.version);
+        let client = Client::new(&reference)?;
+        let platform = Platform::for_tv(&ctx.tv)?;
+
+        ctx.pr
+            .set_message(format!("fetch manifest {reference}:{tag}"));
+        let mut manifest: Manifest = client.json(&reference.url(&format!("manifests/{tag}")))?;
+        if !manifest.manifests.is_empty() {
+            let entry = pick_manifest(&manifest.manifests, &platform)
+                .wrap_err_with(|| format!("failed to pick a manifest for {}", ctx.tv))?;
+            manifest = client.manifest(&reference, &entry.digest)?;
+        }
+        if manifest.layers.is_empty() {
+            bail!("{reference}:{tag} has no layers to install");
+        }
+
+        let install_path = ctx.tv.install_path();
+        let download_path = ctx.tv.download_path();
+        for layer in &manifest.layers {
+            let blob = download_path.join(digest_hex(&layer.digest)?);
+            ctx.pr.set_message(format!("download {}", layer.digest));
+            client.download(
+                &reference.url(&format!("blobs/{}", layer.digest)),
+                &blob,
+                layer,
+                ctx,
+            )?;
+            if layer.is_tarball() {
+                ctx.pr.set_message(format!("extract {}", layer.digest));
+                file::untar(&blob, &install_path)?;
+            } else {
+                let Some(title) = layer.annotations.get(TITLE_ANNOTATION) else {
+                    bail!(
+                        "layer {} is a {} which mise can't install without a {TITLE_ANNOTATION} annotation",
+                        layer.digest,
+                        layer.media_type
+                    );
+                };
+                let Some(filename) = Path::new(title).file_name() else {
+                    bail!(
+                        "invalid {TITLE_ANNOTATION} on layer {}: {title}",
+                        layer.digest
+                    );
+                };
+                let bin = install_path.join("bin").join(filename);
+                file::create_dir_all(bin.parent().unwrap())?;
+                file::copy(&blob, &bin)?;
+                file::make_executable(&bin)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn list_bin_paths(&self, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        match tv.request {
+            ToolRequest::System(_) => Ok(vec![]),
+            _ => {
+                let opts = tv.request.options();
+                let bin_path = opts.get("bin_path").map(|s| s.as_str()).unwrap_or("bin");
+                Ok(vec![tv.install_short_path().join(bin_path)])
+            }
+        }
+    }
+}
+
+impl OciBackend {
+    pub fn from_arg(ba: BackendArg) -> Self {
+        Self {
+            remote_version_cache: CacheManagerBuilder::new(
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
+            )
+            .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
+            .build(),
+            ba,
+        }
+    }
+}
+
+/// a tool name like `ghcr.io/org/tool` split into the registry and repository. A tag can be
+/// pinned in the name as `ghcr.io/org/tool:1.2.3`, otherwise the tool version is the tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reference {
+    registry: String,
+    repository: String,
+    tag: Option<String>,
+}
+
+impl Reference {
+    fn parse(name: &str) -> Result<Self> {
+        let Some((registry, rest)) = name.split_once('/') else {
+            bail!("invalid oci reference: {name}, expected registry/repository e.g.: ghcr.io/org/tool");
+        };
+        if registry.is_empty() || rest.is_empty() {
+            bail!("invalid oci reference: {name}, expected registry/repository e.g.: ghcr.io/org/tool");
+        }
+        // only a colon in the last segment is a tag, one in the registry is a port
+        let (repository, tag) = match rest.rsplit_once(':') {
+            Some((repository, tag)) if !tag.contains('/') && !tag.is_empty() => {
+                (repository, Some(tag.to_string()))
+            }
+            _ => (rest, None),
+        };
+        let registry = match registry {
+            // docker hub is served from a different host than its name
+            "docker.io" => "registry-1.docker.io",
+            registry => registry,
+        };
+        Ok(Self {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            tag,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("https://{}/v2/{}/{path}", self.registry, self.repository)
+    }
+}
+
+impl std::fmt::Display for Reference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)
+    }
+}
+
+/// makes requests to a registry with the token it hands out for the repository, if it asks
+/// for one
+struct Client {
+    token: Option<String>,
+}
+
+impl Client {
+    fn new(reference: &Reference) -> Result<Self> {
+        // registries respond with 401 and where to get a token from, even for public images
+        let Some(challenge) = HTTP.auth_challenge(reference.url("tags/list"))? else {
+            return Ok(Self { token: None });
+        };
+        let Some(token_url) = token_url(&challenge, reference)? else {
+            return Ok(Self { token: None });
+        };
+        // credentials for the token endpoint come from netrc or MISE_HTTP_TOKEN_<HOST>
+        let body = HTTP
+            .get_with_headers(&token_url, &HeaderMap::new())
+            .wrap_err_with(|| format!("failed to get a token for {reference}"))?;
+        let token: TokenResponse = serde_json::from_str(&body)
+            .wrap_err_with(|| format!("failed to parse token response from {token_url}"))?;
+        let token = token
+            .token
+            .or(token.access_token)
+            .ok_or_else(|| eyre!("no token in response from {token_url}"))?;
+        Ok(Self { token: Some(token) })
+    }
+
+    fn headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_str(&MANIFEST_TYPES.join(", "))?);
+        if let Some(token) = &self.token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {token}"))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+
+    fn json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let body = HTTP.get_with_headers(url, &self.headers()?)?;
+        serde_json::from_str(&body).wrap_err_with(|| format!("failed to parse json from {url}"))
+    }
+
+    /// the manifest with `digest`, which it is checked against since the index only lists it
+    fn manifest(&self, reference: &Reference, digest: &str) -> Result<Manifest> {
+        digest_hex(digest)?;
+        let url = reference.url(&format!("manifests/{digest}"));
+        let body = HTTP.get_with_headers(&url, &self.headers()?)?;
+        verify_digest(body.as_bytes(), digest)
+            .wrap_err_with(|| format!("failed to verify manifest {url}"))?;
+        serde_json::from_str(&body).wrap_err_with(|| format!("failed to parse json from {url}"))
+    }
+
+    fn download(
+        &self,
+        url: &str,
+        path: &Path,
+        layer: &Descriptor,
+        ctx: &InstallContext,
+    ) -> Result<()> {
+        let mut headers = self.headers()?;
+        headers.remove(ACCEPT);
+        HTTP.download_file_with_checksum_and_headers(
+            url,
+            path,
+            &layer.digest,
+            &headers,
+            Some(ctx.pr.as_ref()),
+        )
+    }
+}
+
+/// the hex part of a `sha256:<hex>` or `sha512:<hex>` digest, which is used as a filename so
+/// anything else, e.g.: `sha256:../../bin`, is an error
+fn digest_hex(digest: &str) -> Result<&str> {
+    let (algo, hex) = digest.split_once(':').unwrap_or_default();
+    let len = match algo {
+        "sha256" => 64,
+        "sha512" => 128,
+        _ => bail!("unsupported digest: {digest}, expected sha256 or sha512"),
+    };
+    let is_hex = hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    if hex.len() != len || !is_hex {
+        bail!("invalid digest: {digest}");
+    }
+    Ok(hex)
+}
+
+/// fails unless `body` hashes to `digest`
+fn verify_digest(body: &[u8], digest: &str) -> Result<()> {
+    let actual = match digest.split_once(':') {
+        Some(("sha512", _)) => format!("sha512:{:x}", Sha512::digest(body)),
+        _ => format!("sha256:{:x}", Sha256::digest(body)),
+    };
+    if actual != digest {
+        bail!("digest mismatch\nExpected: {digest}\nActual:   {actual}");
+    }
+    Ok(())
+}
+
+/// the url to get a token from a `WWW-Authenticate: Bearer realm="...",service="..."`
+/// challenge, `None` if the registry wants another kind of auth
+fn token_url(challenge: &str, reference: &Reference) -> Result<Option<String>> {
+    let Some(params) = challenge.strip_prefix("Bearer ") else {
+        return Ok(None);
+    };
+    let params = parse_challenge(params);
+    let Some(realm) = params.get("realm") else {
+        bail!(
+            "no realm in auth challenge from {}: {challenge}",
+            reference.registry
+        );
+    };
+    let mut url = Url::parse(realm)?;
+    {
+        let mut query = url.query_pairs_mut();
+        if let Some(service) = params.get("service") {
+            query.append_pair("service", service);
+        }
+        let scope = match params.get("scope") {
+            Some(scope) => scope.clone(),
+            None => format!("repository:{}:pull", reference.repository),
+        };
+        query.append_pair("scope", &scope);
+    }
+    Ok(Some(url.to_string()))
+}
+
+/// `realm="https://ghcr.io/token",service="ghcr.io"` as a map, values may contain commas
+fn parse_challenge(params: &str) -> HashMap<String, String> {
+    let mut out = HashMap::new();
+    let mut rest = params.trim();
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, after)) => (value, after),
+                None => (quoted, ""),
+            },
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        out.insert(key, value.to_string());
+        rest = after.trim_start_matches(',').trim();
+    }
+    out
+}
+
+/// the manifest in an index for the os and arch of `platform`
+fn pick_manifest<'a>(manifests: &'a [Descriptor], platform: &Platform) -> Result<&'a Descriptor> {
+    let os = match platform.os.as_str() {
+        "macos" => "darwin",
+        os => os,
+    };
+    let (arch, variant) = match platform.arch.as_str() {
+        "x64" => ("amd64", None),
+        "x86" => ("386", None),
+        "arm" => ("arm", Some("v7")),
+        arch => (arch, None),
+    };
+    let matches = manifests
+        .iter()
+        .filter(|m| {
+            m.platform.as_ref().is_some_and(|p| {
+                p.os == os
+                    && p.architecture == arch
+                    && variant.map_or(true, |v| p.variant.as_deref().map_or(true, |pv| pv == v))
+            })
+        })
+        .collect_vec();
+    match matches.first() {
+        Some(m) => Ok(m),
+        None => bail!(
+            "no manifest for {os}/{arch}, the index has:\n{}",
+            manifests
+                .iter()
+                .filter_map(|m| m.platform.as_ref())
+                .map(|p| match &p.variant {
+                    Some(v) => format!("  {}/{}/{v}", p.os, p.architecture),
+                    None => format!("  {}/{}", p.os, p.architecture),
+                })
+                .join("\n")
+        ),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TagList {
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+/// an image manifest or an index of them, only the fields of the one it is are set
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    #[serde(default)]
+    media_type: String,
+    digest: String,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+    platform: Option<DescriptorPlatform>,
+}
+
+impl Descriptor {
+    fn is_tarball(&self) -> bool {
+        self.media_type.contains(".tar") || self.media_type.ends_with("rootfs.diff.tar.gzip")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DescriptorPlatform {
+    os: String,
+    architecture: String,
+    variant: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_reference_parse() {
+        let r = Reference::parse("ghcr.io/org/tool").unwrap();
+        assert_eq!(r.registry, "ghcr.io");
+        assert_eq!(r.repository, "org/tool");
+        assert_eq!(r.tag, None);
+        let r = Reference::parse("localhost:5000/tool:1.2.3").unwrap();
+        assert_eq!(r.registry, "localhost:5000");
+        assert_eq!(r.repository, "tool");
+        assert_eq!(r.tag.as_deref(), Some("1.2.3"));
+        let r = Reference::parse("docker.io/library/alpine").unwrap();
+        assert_eq!(r.registry, "registry-1.docker.io");
+        assert!(Reference::parse("tool").is_err());
+    }
+
+    #[test]
+    fn test_digest_hex() {
+        let hex = "a".repeat(64);
+        assert_eq!(digest_hex(&format!("sha256:{hex}")).unwrap(), hex);
+        assert!(digest_hex(&format!("sha512:{}", "0".repeat(128))).is_ok());
+        assert!(digest_hex("sha256:../../bin").is_err());
+        assert!(digest_hex(&format!("sha256:{}", "A".repeat(64))).is_err());
+        assert!(digest_hex(&format!("md5:{}", "a".repeat(32))).is_err());
+        assert!(digest_hex(&hex).is_err());
+    }
+
+    #[test]
+    fn test_verify_digest() {
+        let body = br#"{"layers": []}"#;
+        let digest = format!("sha256:{:x}", Sha256::digest(body));
+        assert!(verify_digest(body, &digest).is_ok());
+        let err = verify_digest(br#"{"layers": [{}]}"#, &digest).unwrap_err();
+        assert!(err.to_string().starts_with("digest mismatch"), "{err}");
+    }
+
+    #[test]
+    fn test_token_url() {
+        let reference = Reference::parse("ghcr.io/org/tool").unwrap();
+        let challenge = r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/tool:pull""#;
+        assert_eq!(
+            token_url(challenge, &reference).unwrap().unwrap(),
+            "https://ghcr.io/token?service=ghcr.io&scope=repository%3Aorg%2Ftool%3Apull"
+        );
+        let challenge = r#"Bearer realm="https://auth.example.com/token""#;
+        assert_eq!(
+            token_url(challenge, &reference).unwrap().unwrap(),
+            "https://auth.example.com/token?scope=repository%3Aorg%2Ftool%3Apull"
+        );
+        assert_eq!(token_url(r#"Basic realm="x""#, &reference).unwrap(), None);
+    }
+
+    #[test]
+    fn test_pick_manifest() {
+        let manifests: Vec<Descriptor> = serde_json::from_str(
+            r#"[
+                {"digest": "sha256:a", "platform": {"os": "linux", "architecture": "amd64"}},
+                {"digest": "sha256:b", "platform": {"os": "linux", "architecture": "arm64"}},
+                {"digest": "sha256:c", "platform": {"os": "darwin", "architecture": "arm64"}}
+            ]"#,
+        )
+        .unwrap();
+        let pick = |os: &str, arch: &str| {
+            let platform = Platform {
+                os: os.to_string(),
+                arch: arch.to_string(),
+            };
+            pick_manifest(&manifests, &platform).map(|m| m.digest.clone())
+        };
+        assert_eq!(pick("linux", "x64").unwrap(), "sha256:a");
+        assert_eq!(pick("linux", "arm64").unwrap(), "sha256:b");
+        assert_eq!(pick("macos", "arm64").unwrap(), "sha256:c");
+        assert!(pick("windows", "x64").is_err());
+    }
+}
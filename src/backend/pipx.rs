@@ -70,6 +70,13 @@ impl Backend for PIPXBackend {
                     let pkg: PypiPackage = HTTP_FETCH.json(url)?;
                     Ok(Some(pkg.info.version))
                 }
+                PipxRequest::Git(url) if url.starts_with("https://github.com/") => {
+                    let repo = url.strip_prefix("https://github.com/").unwrap();
+                    match github::latest_release(repo)? {
+                        Some(release) => Ok(Some(release.tag_name)),
+                        None => self.latest_version(Some("latest".into())),
+                    }
+                }
                 _ => self.latest_version(Some("latest".into())),
             })
             .cloned()
@@ -120,7 +127,8 @@ impl PIPXBackend {
     pub fn from_arg(ba: BackendArg) -> Self {
         Self {
             remote_version_cache: CacheManagerBuilder::new(
-                ba.cache_path.join("remote_versions.msgpack.z"),
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .build(),
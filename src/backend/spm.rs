@@ -21,6 +21,7 @@ use crate::{env, file, github};
 pub struct SPMBackend {
     ba: BackendArg,
     remote_version_cache: CacheManager<Vec<String>>,
+    latest_version_cache: CacheManager<Option<String>>,
 }
 
 // https://github.com/apple/swift-package-manager
@@ -41,6 +42,10 @@ impl Backend for SPMBackend {
         Ok(vec![])
     }
 
+    fn is_build_heavy(&self) -> bool {
+        true
+    }
+
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         let repo = SwiftPackageRepo::new(self.name())?;
         self.remote_version_cache
@@ -54,6 +59,16 @@ impl Backend for SPMBackend {
             .cloned()
     }
 
+    fn latest_stable_version(&self) -> eyre::Result<Option<String>> {
+        let repo = SwiftPackageRepo::new(self.name())?;
+        self.latest_version_cache
+            .get_or_try_init(|| match github::latest_release(repo.shorthand.as_str())? {
+                Some(release) => Ok(Some(release.tag_name)),
+                None => self.latest_version(Some("latest".into())),
+            })
+            .cloned()
+    }
+
     fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
         let settings = Settings::get();
         settings.ensure_experimental("spm backend")?;
@@ -88,7 +103,13 @@ impl SPMBackend {
     pub fn from_arg(ba: BackendArg) -> Self {
         Self {
             remote_version_cache: CacheManagerBuilder::new(
-                ba.cache_path.join("remote_versions.msgpack.z"),
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
+            )
+            .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
+            .build(),
+            latest_version_cache: CacheManagerBuilder::new(
+                ba.cache_path.join("latest_version.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .build(),
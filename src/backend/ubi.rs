@@ -1,19 +1,25 @@
 use std::fmt::Debug;
 
+use eyre::{bail, Result, WrapErr};
+use itertools::Itertools;
+use regex::Regex;
+
 use crate::backend::{Backend, BackendType};
 use crate::cache::{CacheManager, CacheManagerBuilder};
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::env::{self, GITHUB_TOKEN};
-use crate::github;
+use crate::github::{self, GithubAsset};
 use crate::install_context::InstallContext;
+use crate::plugins::core::Platform;
 use crate::toolset::ToolRequest;
 
 #[derive(Debug)]
 pub struct UbiBackend {
     ba: BackendArg,
     remote_version_cache: CacheManager<Vec<String>>,
+    latest_version_cache: CacheManager<Option<String>>,
 }
 
 // Uses ubi for installations https://github.com/houseabsolute/ubi
@@ -48,6 +54,18 @@ impl Backend for UbiBackend {
         }
     }
 
+    fn latest_stable_version(&self) -> eyre::Result<Option<String>> {
+        if name_is_url(self.name()) {
+            return self.latest_version(Some("latest".into()));
+        }
+        self.latest_version_cache
+            .get_or_try_init(|| match github::latest_release(self.name())? {
+                Some(release) => Ok(Some(release.tag_name)),
+                None => self.latest_version(Some("latest".into())),
+            })
+            .cloned()
+    }
+
     fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
         let config = Config::try_get()?;
         let settings = Settings::get();
@@ -59,8 +77,6 @@ impl Backend for UbiBackend {
         let mut cmd = CmdLineRunner::new("ubi")
             .arg("--in")
             .arg(path_with_bin)
-            .arg("--project")
-            .arg(self.name())
             .with_pr(ctx.pr.as_ref())
             .envs(ctx.ts.env_with_path(&config)?)
             .prepend_path(ctx.ts.list_paths())?
@@ -70,8 +86,21 @@ impl Backend for UbiBackend {
             cmd = cmd.env("GITHUB_TOKEN", token);
         }
 
-        if version != "latest" {
-            cmd = cmd.arg("--tag").arg(version);
+        let opts = ctx.tv.request.options();
+        match opts.get("asset_pattern") {
+            Some(pattern) if !name_is_url(self.name()) => {
+                let platform = Platform::for_tv(&ctx.tv)?;
+                let assets = github::list_release_assets(self.name(), version)?;
+                let asset = pick_asset(&assets, pattern, &platform)
+                    .wrap_err_with(|| format!("failed to pick a release asset for {}", ctx.tv))?;
+                cmd = cmd.arg("--url").arg(&asset.browser_download_url);
+            }
+            _ => {
+                cmd = cmd.arg("--project").arg(self.name());
+                if version != "latest" {
+                    cmd = cmd.arg("--tag").arg(version);
+                }
+            }
         }
 
         cmd.execute()
@@ -82,7 +111,13 @@ impl UbiBackend {
     pub fn from_arg(ba: BackendArg) -> Self {
         Self {
             remote_version_cache: CacheManagerBuilder::new(
-                ba.cache_path.join("remote_versions.msgpack.z"),
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
+            )
+            .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
+            .build(),
+            latest_version_cache: CacheManagerBuilder::new(
+                ba.cache_path.join("latest_version.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .build(),
@@ -94,3 +129,100 @@ impl UbiBackend {
 fn name_is_url(n: &str) -> bool {
     n.starts_with("http")
 }
+
+/// the asset whose name matches `pattern`. If several match, the one named for the os and arch
+/// of `platform` is used, the same way ubi picks between assets
+fn pick_asset<'a>(
+    assets: &'a [GithubAsset],
+    pattern: &str,
+    platform: &Platform,
+) -> Result<&'a GithubAsset> {
+    let re = Regex::new(pattern).wrap_err_with(|| format!("invalid asset_pattern: {pattern}"))?;
+    let matches = assets.iter().filter(|a| re.is_match(&a.name)).collect_vec();
+    let candidates = match matches.len() {
+        0 => bail!(
+            "no release asset matches asset_pattern {pattern}, the assets are:\n{}",
+            list_assets(assets.iter())
+        ),
+        1 => return Ok(matches[0]),
+        _ => matches
+            .iter()
+            .filter(|a| names_platform(&a.name, platform))
+            .collect_vec(),
+    };
+    match candidates.as_slice() {
+        [asset] => Ok(asset),
+        _ => bail!(
+            "{} release assets match asset_pattern {pattern}, refine it to match only one of:\n{}",
+            matches.len(),
+            list_assets(matches.into_iter())
+        ),
+    }
+}
+
+fn names_platform(name: &str, platform: &Platform) -> bool {
+    let name = name.to_lowercase();
+    let os: &[&str] = match platform.os.as_str() {
+        "macos" => &["darwin", "macos", "apple", "osx"],
+        "windows" => &["windows", "win64", "win32"],
+        os => &[os][..],
+    };
+    let arch: &[&str] = match platform.arch.as_str() {
+        "x64" => &["x86_64", "amd64", "x64"],
+        "arm64" => &["aarch64", "arm64"],
+        "x86" => &["i686", "i386", "386"],
+        "arm" => &["armv7", "armhf"],
+        _ => &[],
+    };
+    os.iter().any(|o| name.contains(o)) && arch.iter().any(|a| name.contains(a))
+}
+
+fn list_assets<'a>(assets: impl Iterator<Item = &'a GithubAsset>) -> String {
+    assets.map(|a| format!("  {}", a.name)).join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn assets(names: &[&str]) -> Vec<GithubAsset> {
+        names
+            .iter()
+            .map(|name| GithubAsset {
+                name: name.to_string(),
+                browser_download_url: format!("https://example.com/{name}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pick_asset() {
+        let linux = Platform {
+            os: "linux".to_string(),
+            arch: "x64".to_string(),
+        };
+        let assets = assets(&[
+            "tool-x86_64-unknown-linux-gnu.tar.gz",
+            "tool-x86_64-unknown-linux-musl.tar.gz",
+            "tool-aarch64-unknown-linux-musl.tar.gz",
+            "tool-x86_64-apple-darwin.tar.gz",
+        ]);
+        let pick = |pattern| pick_asset(&assets, pattern, &linux).map(|a| a.name.as_str());
+        assert_eq!(pick("gnu").unwrap(), "tool-x86_64-unknown-linux-gnu.tar.gz");
+        assert_eq!(
+            pick("musl").unwrap(),
+            "tool-x86_64-unknown-linux-musl.tar.gz"
+        );
+        assert_eq!(
+            pick("-full").unwrap_err().to_string(),
+            "no release asset matches asset_pattern -full, the assets are:\n  tool-x86_64-unknown-linux-gnu.tar.gz\n  tool-x86_64-unknown-linux-musl.tar.gz\n  tool-aarch64-unknown-linux-musl.tar.gz\n  tool-x86_64-apple-darwin.tar.gz"
+        );
+        assert_eq!(
+            pick("x86_64").unwrap_err().to_string(),
+            "3 release assets match asset_pattern x86_64, refine it to match only one of:\n  tool-x86_64-unknown-linux-gnu.tar.gz\n  tool-x86_64-unknown-linux-musl.tar.gz\n  tool-x86_64-apple-darwin.tar.gz"
+        );
+        assert!(pick("[").is_err());
+    }
+}
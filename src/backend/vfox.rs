@@ -111,6 +111,11 @@ impl VfoxBackend {
             .collect())
     }
 
+    /// where the plugin for `ba` is cloned to
+    pub fn plugin_path(ba: &BackendArg) -> PathBuf {
+        dirs::PLUGINS.join(ba.short.to_kebab_case())
+    }
+
     pub fn from_arg(ba: BackendArg) -> Self {
         let mut vfox = Vfox::new();
         vfox.plugin_dir = dirs::PLUGINS.to_path_buf();
@@ -119,10 +124,11 @@ impl VfoxBackend {
         vfox.install_dir = dirs::INSTALLS.to_path_buf();
         vfox.temp_dir = env::temp_dir().join("mise-vfox");
         let pathname = ba.short.to_kebab_case();
-        let plugin_path = dirs::PLUGINS.join(&pathname);
+        let plugin_path = Self::plugin_path(&ba);
         Self {
             remote_version_cache: CacheManagerBuilder::new(
-                ba.cache_path.join("remote_versions.msgpack.z"),
+                ba.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .with_fresh_file(dirs::DATA.to_path_buf())
@@ -1,20 +1,23 @@
 use std::cmp::min;
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use eyre::Result;
+use filetime::{set_file_atime, FileTime};
 use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
+use itertools::Itertools;
 use once_cell::sync::{Lazy, OnceCell};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use crate::build_time::built_info;
 use crate::config::Settings;
-use crate::file::{display_path, modified_duration};
+use crate::file::{display_path, format_bytes, modified_duration};
 use crate::hash::hash_to_str;
 use crate::rand::random_string;
 use crate::{dirs, file};
@@ -106,7 +109,7 @@ where
     {
         let val = self.cache.get_or_try_init(|| {
             let path = &self.cache_file_path;
-            if self.is_fresh() {
+            if Settings::get().use_cache() && self.is_fresh() {
                 match self.parse() {
                     Ok(val) => return Ok::<_, color_eyre::Report>(val),
                     Err(err) => {
@@ -126,6 +129,7 @@ where
     fn parse(&self) -> Result<T> {
         let path = &self.cache_file_path;
         trace!("reading {}", display_path(path));
+        CacheEntry::touch(path);
         let mut zlib = ZlibDecoder::new(File::open(path)?);
         let mut bytes = Vec::new();
         zlib.read_to_end(&mut bytes)?;
@@ -133,6 +137,9 @@ where
     }
 
     pub fn write(&self, val: &T) -> Result<()> {
+        if !Settings::get().write_cache() {
+            return Ok(());
+        }
         trace!("writing {}", display_path(&self.cache_file_path));
         if let Some(parent) = self.cache_file_path.parent() {
             file::create_dir_all(parent)?;
@@ -161,6 +168,10 @@ where
         if !self.cache_file_path.exists() {
             return false;
         }
+        if Settings::get().offline {
+            // stale data is better than none when the network can't be used
+            return true;
+        }
         if let Some(fresh_duration) = self.freshest_duration() {
             if let Ok(metadata) = self.cache_file_path.metadata() {
                 if let Ok(modified) = metadata.modified() {
@@ -184,6 +195,71 @@ where
     }
 }
 
+/// a file in one of the caches. The last access is the newer of its atime and mtime since atime
+/// isn't updated on filesystems mounted with `noatime`, see [`CacheEntry::touch`].
+#[derive(Debug, Clone)]
+pub(crate) struct CacheEntry {
+    pub(crate) path: PathBuf,
+    pub(crate) size: u64,
+    pub(crate) last_access: SystemTime,
+}
+
+impl CacheEntry {
+    fn new(path: PathBuf) -> Result<Self> {
+        let metadata = path.metadata()?;
+        let modified = metadata.modified()?;
+        let last_access = match metadata.accessed() {
+            Ok(accessed) => accessed.max(modified),
+            Err(_) => modified,
+        };
+        Ok(Self {
+            path,
+            size: metadata.len(),
+            last_access,
+        })
+    }
+
+    /// records that a cache file was just used. This sets the atime and leaves the mtime alone
+    /// since that is what decides if a cache is fresh.
+    pub(crate) fn touch(path: &Path) {
+        if let Err(err) = set_file_atime(path, FileTime::now()) {
+            trace!("failed to touch {}: {err}", display_path(path));
+        }
+    }
+
+    fn age(&self) -> Duration {
+        self.last_access.elapsed().unwrap_or_default()
+    }
+}
+
+/// the files in `dirs`, least recently used first. Dirs inside of another one are only listed once.
+pub(crate) fn entries(dirs: &[PathBuf]) -> Result<Vec<CacheEntry>> {
+    let files: BTreeSet<PathBuf> = dirs
+        .iter()
+        .map(|d| file::recursive_ls(d))
+        .flatten_ok()
+        .try_collect()?;
+    Ok(files
+        .into_iter()
+        .filter(|p| p.file_name().is_some_and(|n| n != ".auto_prune"))
+        .filter_map(|p| CacheEntry::new(p).ok())
+        .sorted_by_key(|e| e.last_access)
+        .collect())
+}
+
+/// every dir with cache files: the cache dir, and the download and remote versions caches if they
+/// were moved out of it
+pub(crate) fn dirs() -> Vec<PathBuf> {
+    let mut cache_dirs = vec![dirs::CACHE.to_path_buf()];
+    for dir in [*dirs::DOWNLOAD_CACHE, *dirs::REMOTE_VERSIONS_CACHE] {
+        if !dir.starts_with(*dirs::CACHE) {
+            cache_dirs.push(dir.to_path_buf());
+        }
+    }
+    cache_dirs
+}
+
+#[derive(Debug, Default)]
 pub(crate) struct PruneResults {
     pub(crate) size: u64,
     pub(crate) count: u64,
@@ -192,7 +268,10 @@ pub(crate) struct PruneResults {
 pub(crate) struct PruneOptions {
     pub(crate) dry_run: bool,
     pub(crate) verbose: bool,
-    pub(crate) age: Duration,
+    /// remove entries that have not been used in this long
+    pub(crate) age: Option<Duration>,
+    /// then remove the least recently used entries until the caches are at most this size
+    pub(crate) max_size: Option<u64>,
 }
 
 pub(crate) fn auto_prune() -> Result<()> {
@@ -219,54 +298,85 @@ pub(crate) fn auto_prune() -> Result<()> {
     }
     debug!("pruning old cache files, this behavior can be modified with the MISE_CACHE_PRUNE_AGE setting");
     prune(
-        *dirs::CACHE,
+        &dirs(),
         &PruneOptions {
             dry_run: false,
             verbose: false,
-            age,
+            age: Some(age),
+            max_size: None,
         },
     )?;
     Ok(())
 }
 
-pub(crate) fn prune(dir: &Path, opts: &PruneOptions) -> Result<PruneResults> {
-    let mut results = PruneResults { size: 0, count: 0 };
-    let remove = |file: &Path| {
-        if opts.dry_run || opts.verbose {
-            info!("pruning {}", display_path(file));
-        } else {
-            debug!("pruning {}", display_path(file));
+/// the entries `prune` removes with `opts`, least recently used first
+pub(crate) fn prunable(cache_dirs: &[PathBuf], opts: &PruneOptions) -> Result<Vec<CacheEntry>> {
+    let entries = entries(cache_dirs)?;
+    let mut size: u64 = entries.iter().map(|e| e.size).sum();
+    Ok(entries
+        .into_iter()
+        // entries are sorted by last access so the rest are newer
+        .take_while(|entry| {
+            let stale = opts.age.is_some_and(|age| entry.age() > age);
+            let over_size = opts.max_size.is_some_and(|max| size > max);
+            if stale || over_size {
+                size -= entry.size;
+            }
+            stale || over_size
+        })
+        .collect())
+}
+
+pub(crate) fn prune(cache_dirs: &[PathBuf], opts: &PruneOptions) -> Result<PruneResults> {
+    let mut results = PruneResults::default();
+    for entry in prunable(cache_dirs, opts)? {
+        let msg = format!(
+            "{} ({}, last used {} ago)",
+            display_path(&entry.path),
+            format_bytes(entry.size as f64),
+            humantime::format_duration(round_age(entry.age()))
+        );
+        match (opts.dry_run, opts.verbose) {
+            (true, _) => info!("would prune {msg}"),
+            (false, true) => info!("pruning {msg}"),
+            (false, false) => debug!("pruning {msg}"),
         }
         if !opts.dry_run {
-            file::remove_file_or_dir(file)?;
+            file::remove_file(&entry.path)?;
+        }
+        results.size += entry.size;
+        results.count += 1;
+    }
+    if let (false, Some(age)) = (opts.dry_run, opts.age) {
+        for dir in cache_dirs {
+            remove_empty_dirs(dir, age)?;
         }
-        Ok::<(), color_eyre::Report>(())
+    }
+    Ok(results)
+}
+
+/// rounds to days, hours or minutes so "last used 31days ago" doesn't go down to the second
+fn round_age(age: Duration) -> Duration {
+    let secs = age.as_secs();
+    let unit = match secs {
+        s if s >= 24 * 60 * 60 => 24 * 60 * 60,
+        s if s >= 60 * 60 => 60 * 60,
+        _ => 60,
     };
+    Duration::from_secs(secs - secs % unit)
+}
+
+/// only deletes empty directories if they're old since a new one may be about to be written to
+fn remove_empty_dirs(dir: &Path, age: Duration) -> Result<()> {
     for subdir in file::dir_subdirs(dir)? {
         let subdir = dir.join(&subdir);
-        let r = prune(&subdir, opts)?;
-        results.size += r.size;
-        results.count += r.count;
-        let metadata = subdir.metadata()?;
-        // only delete empty directories if they're old
-        if file::ls(&subdir)?.is_empty()
-            && metadata.modified()?.elapsed().unwrap_or_default() > opts.age
-        {
-            remove(&subdir)?;
-            results.count += 1;
-        }
-    }
-    for f in file::ls(dir)? {
-        let path = dir.join(&f);
-        let metadata = path.metadata()?;
-        let elapsed = metadata.accessed()?.elapsed().unwrap_or_default();
-        if elapsed > opts.age {
-            remove(&path)?;
-            results.size += metadata.len();
-            results.count += 1;
+        remove_empty_dirs(&subdir, age)?;
+        if file::ls(&subdir)?.is_empty() && modified_duration(&subdir).unwrap_or_default() > age {
+            debug!("pruning {}", display_path(&subdir));
+            file::remove_dir(&subdir)?;
         }
     }
-    Ok(results)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -285,4 +395,52 @@ mod tests {
         let val = cache.get_or_try_init(|| Ok(2)).unwrap();
         assert_eq!(val, &1);
     }
+
+    #[test]
+    fn test_prune() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dirs = vec![tmp.path().to_path_buf()];
+        let day = 24 * 60 * 60;
+        for (name, days_ago) in [("a/old", 40), ("a/older", 50), ("b/recent", 1), ("new", 0)] {
+            let path = tmp.path().join(name);
+            file::create_dir_all(path.parent().unwrap()).unwrap();
+            file::write(&path, "12345").unwrap();
+            let time = FileTime::from_unix_time(FileTime::now().unix_seconds() - days_ago * day, 0);
+            filetime::set_file_times(&path, time, time).unwrap();
+        }
+        let names = |entries: Vec<CacheEntry>| {
+            entries
+                .iter()
+                .map(|e| {
+                    e.path
+                        .strip_prefix(tmp.path())
+                        .unwrap()
+                        .display()
+                        .to_string()
+                })
+                .collect_vec()
+        };
+        assert_eq!(
+            names(entries(&dirs).unwrap()),
+            vec!["a/older", "a/old", "b/recent", "new"]
+        );
+
+        let mut opts = PruneOptions {
+            dry_run: true,
+            verbose: false,
+            age: Some(Duration::from_secs(30 * day as u64)),
+            max_size: None,
+        };
+        let r = prune(&dirs, &opts).unwrap();
+        assert_eq!((r.count, r.size), (2, 10));
+        assert_eq!(entries(&dirs).unwrap().len(), 4);
+
+        // the least recently used files are removed until at most 10 bytes are left
+        opts.dry_run = false;
+        opts.age = None;
+        opts.max_size = Some(10);
+        let r = prune(&dirs, &opts).unwrap();
+        assert_eq!((r.count, r.size), (2, 10));
+        assert_eq!(names(entries(&dirs).unwrap()), vec!["b/recent", "new"]);
+    }
 }
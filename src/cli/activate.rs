@@ -1,3 +1,4 @@
+use std::env::join_paths;
 use std::path::{Path, PathBuf};
 
 use eyre::Result;
@@ -5,7 +6,7 @@ use eyre::Result;
 use crate::env::PATH_KEY;
 use crate::file::touch_dir;
 use crate::shell::{get_shell, Shell, ShellType};
-use crate::{dirs, env};
+use crate::{dirs, env, hook_env};
 
 /// Initializes mise in the current shell session
 ///
@@ -67,6 +68,11 @@ impl Activate {
         } else {
             env::MISE_BIN.clone()
         };
+        // switching from PATH mode to shims, otherwise the hook keeps adding tools to PATH
+        if self.shims && env::is_activated() {
+            miseprint!("{}", hook_env::clear_old_env(shell.as_ref()))?;
+            miseprint!("{}", shell.deactivate())?;
+        }
         match self.shims {
             true => self.activate_shims(shell.as_ref(), &mise_bin)?,
             false => self.activate(shell.as_ref(), &mise_bin)?,
@@ -77,8 +83,7 @@ impl Activate {
 
     fn activate_shims(&self, shell: &dyn Shell, mise_bin: &Path) -> std::io::Result<()> {
         let exe_dir = mise_bin.parent().unwrap();
-        miseprint!("{}", self.prepend_path(shell, exe_dir))?;
-        miseprint!("{}", self.prepend_path(shell, &dirs::SHIMS))?;
+        miseprint!("{}", self.prepend_path(shell, &[exe_dir, &dirs::SHIMS]))?;
         Ok(())
     }
 
@@ -91,26 +96,52 @@ impl Activate {
         if self.status {
             flags.push(" --status");
         }
-        miseprint!("{}", self.prepend_path(shell, exe_dir))?;
+        miseprint!("{}", self.prepend_path(shell, &[exe_dir]))?;
         miseprint!("{}", shell.activate(mise_bin, flags.join("")))?;
         Ok(())
     }
 
-    fn prepend_path(&self, shell: &dyn Shell, p: &Path) -> String {
-        if is_dir_not_in_nix(p) && !is_dir_in_path(p) && !p.is_relative() {
-            shell.prepend_env(&PATH_KEY, p.to_string_lossy().as_ref())
+    /// prepends `dirs` to PATH, the last one ends up first. The dirs a previous `mise activate`
+    /// added are removed first so switching between shims and PATH mode doesn't leave the
+    /// other mode's dirs behind. If the user also added one of them to PATH, that entry is kept.
+    fn prepend_path(&self, shell: &dyn Shell, dirs: &[&Path]) -> String {
+        let previous = &*env::__MISE_ACTIVATE_PATHS;
+        let mut path = env::PATH.clone();
+        for p in previous {
+            if let Some(i) = path.iter().position(|x| x == p) {
+                path.remove(i);
+            }
+        }
+        let added = dirs
+            .iter()
+            .filter(|p| is_dir_not_in_nix(p) && !is_dir_in_path(&path, p) && !p.is_relative())
+            .map(|p| p.to_path_buf())
+            .collect::<Vec<_>>();
+        let mut out = String::new();
+        if previous.is_empty() {
+            for p in &added {
+                out.push_str(&shell.prepend_env(&PATH_KEY, p.to_string_lossy().as_ref()));
+            }
         } else {
-            String::new()
+            let path = added.iter().rev().cloned().chain(path);
+            let path = join_paths(path).unwrap_or_default();
+            out.push_str(&shell.set_env(&PATH_KEY, path.to_string_lossy().as_ref()));
+        }
+        match join_paths(&added) {
+            Ok(paths) if !added.is_empty() => {
+                out.push_str(&shell.set_env("__MISE_ACTIVATE_PATHS", &paths.to_string_lossy()))
+            }
+            _ if !previous.is_empty() => out.push_str(&shell.unset_env("__MISE_ACTIVATE_PATHS")),
+            _ => {}
         }
+        out
     }
 }
 
-fn is_dir_in_path(dir: &Path) -> bool {
+fn is_dir_in_path(path: &[PathBuf], dir: &Path) -> bool {
     let dir = dir.canonicalize().unwrap_or(dir.to_path_buf());
-    env::PATH
-        .clone()
-        .into_iter()
-        .any(|p| p.canonicalize().unwrap_or(p) == dir)
+    path.iter()
+        .any(|p| p.canonicalize().unwrap_or(p.clone()) == dir)
 }
 
 fn is_dir_not_in_nix(dir: &Path) -> bool {
@@ -22,6 +22,8 @@ pub struct BackendArg {
     pub backend_type: BackendType,
     /// ~/.local/share/mise/cache/<THIS>
     pub cache_path: PathBuf,
+    /// $MISE_REMOTE_VERSIONS_CACHE_DIR/<THIS>, the same as cache_path unless overridden
+    pub remote_versions_cache_path: PathBuf,
     /// ~/.local/share/mise/installs/<THIS>
     pub installs_path: PathBuf,
     /// ~/.local/share/mise/downloads/<THIS>
@@ -62,6 +64,7 @@ impl BackendArg {
             short,
             full,
             cache_path: dirs::CACHE.join(&pathname),
+            remote_versions_cache_path: dirs::REMOTE_VERSIONS_CACHE.join(&pathname),
             installs_path: dirs::INSTALLS.join(&pathname),
             downloads_path: dirs::DOWNLOADS.join(&pathname),
         }
@@ -101,7 +104,7 @@ impl Hash for BackendArg {
 static REGISTRY_BACKEND_MAP: Lazy<HashMap<&'static str, BackendArg>> = Lazy::new(|| {
     REGISTRY
         .iter()
-        .map(|(short, full)| (*short, BackendArg::new(short, full)))
+        .map(|(short, full)| (short.as_str(), BackendArg::new(short, full)))
         .collect()
 });
 
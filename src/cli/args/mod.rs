@@ -2,6 +2,8 @@ pub use backend_arg::BackendArg;
 pub use cd_arg::CdArg;
 pub use env_var_arg::EnvVarArg;
 pub use log_level_arg::{DebugArg, LogLevelArg, TraceArg};
+pub use no_cache_arg::{NoCacheArg, NoCacheWriteArg};
+pub use offline_arg::OfflineArg;
 pub use profile_arg::ProfileArg;
 pub use quiet_arg::QuietArg;
 pub use tool_arg::{ToolArg, ToolVersionType};
@@ -12,6 +14,8 @@ mod backend_arg;
 mod cd_arg;
 mod env_var_arg;
 mod log_level_arg;
+mod no_cache_arg;
+mod offline_arg;
 mod profile_arg;
 mod quiet_arg;
 mod tool_arg;
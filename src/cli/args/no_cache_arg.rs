@@ -0,0 +1,27 @@
+use clap::{Arg, ArgAction};
+
+pub struct NoCacheArg;
+
+impl NoCacheArg {
+    pub fn arg() -> Arg {
+        Arg::new("no-cache")
+            .long("no-cache")
+            .help("Ignore cached data and fetch it again")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("offline")
+            .global(true)
+    }
+}
+
+pub struct NoCacheWriteArg;
+
+impl NoCacheWriteArg {
+    pub fn arg() -> Arg {
+        Arg::new("no-cache-write")
+            .long("no-cache-write")
+            .help("Do not write fetched data to the caches")
+            .action(ArgAction::SetTrue)
+            .requires("no-cache")
+            .global(true)
+    }
+}
@@ -0,0 +1,13 @@
+use clap::{Arg, ArgAction};
+
+pub struct OfflineArg;
+
+impl OfflineArg {
+    pub fn arg() -> Arg {
+        Arg::new("offline")
+            .long("offline")
+            .help("Do not access the network, only use cached data")
+            .action(ArgAction::SetTrue)
+            .global(true)
+    }
+}
@@ -41,7 +41,11 @@ fn list_versions(config: &Config, args: &[String]) -> Result<()> {
     if args[2] == "all" {
         return LsRemote {
             prefix: None,
+            prefix_flag: None,
+            limit: None,
+            sort: None,
             all: false,
+            json: false,
             plugin: args.get(3).map(|s| s.parse()).transpose()?,
         }
         .run();
@@ -0,0 +1,120 @@
+use eyre::{bail, Result};
+
+use crate::backend::vfox::VfoxBackend;
+use crate::backend::{self, BackendType};
+use crate::cli::args::BackendArg;
+use crate::cli::backends::status::Status;
+use crate::config::{config_file, Config};
+use crate::env::MISE_GLOBAL_CONFIG_FILE;
+use crate::git::Git;
+use crate::toolset::{InstallOptions, ToolRequest, ToolsetBuilder};
+use crate::ui::multi_progress_report::MultiProgressReport;
+
+/// Install what a backend needs to install tools
+///
+/// e.g.: node for the npm backend or rust for the cargo backend. If the tool is in a config file
+/// that version is installed, otherwise the latest version is installed and added to the global
+/// config so the backend can find it. Nothing is installed if the backend's executable is already
+/// available.
+///
+/// For vfox this updates the vfox plugins that are installed.
+#[derive(Debug, clap::Args)]
+#[clap(after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
+pub struct BackendsInstall {
+    /// Backend(s) to install the dependencies of
+    #[clap(value_name = "BACKEND", required = true)]
+    backend: Vec<BackendType>,
+}
+
+impl BackendsInstall {
+    pub fn run(self) -> Result<()> {
+        for backend in &self.backend {
+            match backend {
+                BackendType::Vfox => update_vfox_plugins()?,
+                _ => install_dependency(*backend)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn install_dependency(backend: BackendType) -> Result<()> {
+    let config = Config::try_get()?;
+    let mut ts = ToolsetBuilder::new().build(&config)?;
+    let Some(dep) = backend.dependency() else {
+        if let Some(exe) = backend
+            .executable()
+            .filter(|_| !Status::new(&ts, backend).is_ready())
+        {
+            bail!("{exe} is needed by the {backend} backend but can't be installed by mise");
+        }
+        info!("{backend} backend does not need anything installed");
+        return Ok(());
+    };
+    let fa = BackendArg::from(dep);
+    let mpr = MultiProgressReport::get();
+    let opts = InstallOptions::new();
+    if let Some(tvl) = ts.versions.get(&fa) {
+        let requests = tvl
+            .requests
+            .iter()
+            .filter(|tr| !tr.is_installed())
+            .cloned()
+            .collect::<Vec<_>>();
+        if !requests.is_empty() {
+            ts.install_versions(&config, requests, &mpr, &opts)?;
+        }
+    } else if let Some(path) = Status::new(&ts, backend).executable {
+        info!("{backend} backend uses {}", path.display());
+        return Ok(());
+    } else {
+        let tr = ToolRequest::new(fa.clone(), "latest")?;
+        ts.install_versions(&config, vec![tr], &mpr, &opts)?;
+        let mut cf = config_file::parse_or_init(&MISE_GLOBAL_CONFIG_FILE)?;
+        cf.replace_versions(&fa, &["latest".to_string()])?;
+        cf.save()?;
+        info!(
+            "added {dep}@latest to {} for the {backend} backend",
+            crate::file::display_path(cf.get_path())
+        );
+    }
+    info!("{backend} backend is ready");
+    Ok(())
+}
+
+fn update_vfox_plugins() -> Result<()> {
+    for b in backend::list() {
+        if b.get_type() != BackendType::Vfox {
+            continue;
+        }
+        let git = Git::new(VfoxBackend::plugin_path(b.fa()));
+        if !git.is_repo() {
+            continue;
+        }
+        let (from, to) = git.update(None)?;
+        match from == to {
+            true => info!("{} is up to date", b.id()),
+            false => info!("updated {} from {from} to {to}", b.id()),
+        }
+    }
+    Ok(())
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+  $ <bold>mise backends install npm</bold>    # installs node if npm isn't available
+  $ <bold>mise backends install vfox</bold>   # updates the vfox plugins
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    #[test]
+    fn test_backends_install_core() {
+        reset();
+        assert_cli_snapshot!("backends", "install", "core", @"mise core backend does not need anything installed");
+    }
+}
@@ -1,19 +1,27 @@
 use eyre::Result;
 
-use crate::backend::{self, BackendType};
+use crate::cli::backends::status::{self, Status};
+use crate::config::Config;
+use crate::toolset::ToolsetBuilder;
 
 /// List built-in backends
+///
+/// A backend is ready if the program it runs to install tools was found, see
+/// `mise backends status` for details
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "list", after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
 pub struct BackendsLs {}
 
 impl BackendsLs {
     pub fn run(self) -> Result<()> {
-        let mut backends = backend::list_backend_types();
-        backends.retain(|f| *f != BackendType::Asdf);
-
-        for backend in backends {
-            miseprintln!("{}", backend);
+        let config = Config::try_get()?;
+        let ts = ToolsetBuilder::new().build(&config)?;
+        for backend in status::all() {
+            let ready = match Status::new(&ts, backend).is_ready() {
+                true => "ready",
+                false => "not ready",
+            };
+            miseprintln!("{:<6} {ready}", backend.to_string());
         }
         Ok(())
     }
@@ -23,20 +31,36 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
   $ <bold>mise backends ls</bold>
-  cargo
-  go
-  npm
-  pipx
-  spm
-  ubi
+  cargo  ready
+  core   ready
+  go     ready
+  npm    not ready
+  oci    ready
+  pipx   ready
+  spm    not ready
+  ubi    ready
+  vfox   ready
 "#
 );
 
 #[cfg(test)]
 mod tests {
 
+    use crate::test::reset;
+
     #[test]
     fn test_backends_list() {
-        assert_cli_snapshot!("backends", "list");
+        reset();
+        // whether a backend is ready depends on what is installed on the machine
+        let output = assert_cli!("backends", "list");
+        let backends = output
+            .lines()
+            .map(|l| l.split_whitespace().next().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            backends,
+            ["cargo", "core", "go", "npm", "oci", "pipx", "spm", "ubi", "vfox"]
+        );
+        assert!(output.lines().any(|l| l == "core   ready"));
     }
 }
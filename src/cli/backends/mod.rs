@@ -1,7 +1,9 @@
 use clap::Subcommand;
 use eyre::Result;
 
+mod install;
 mod ls;
+mod status;
 
 #[derive(Debug, clap::Args)]
 #[clap(about = "Manage backends", visible_alias = "b", aliases = ["backend", "backend-list"])]
@@ -12,13 +14,17 @@ pub struct Backends {
 
 #[derive(Debug, Subcommand)]
 enum Commands {
+    Install(install::BackendsInstall),
     Ls(ls::BackendsLs),
+    Status(status::BackendsStatus),
 }
 
 impl Commands {
     pub fn run(self) -> Result<()> {
         match self {
+            Self::Install(cmd) => cmd.run(),
             Self::Ls(cmd) => cmd.run(),
+            Self::Status(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+use eyre::Result;
+use itertools::Itertools;
+
+use crate::backend::vfox::VfoxBackend;
+use crate::backend::{self, BackendType};
+use crate::config::Config;
+use crate::file::display_path;
+use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::{env, file};
+
+/// Show whether backends have what they need to install tools
+///
+/// For backends that run another program, e.g. `npm` for the npm backend, this shows where it was
+/// found and its version. For vfox this shows the plugins and when they were last updated.
+#[derive(Debug, clap::Args)]
+#[clap(after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
+pub struct BackendsStatus {
+    /// Backend(s) to show, defaults to all of them
+    #[clap(value_name = "BACKEND")]
+    backend: Vec<BackendType>,
+}
+
+impl BackendsStatus {
+    pub fn run(self) -> Result<()> {
+        let config = Config::try_get()?;
+        let ts = ToolsetBuilder::new().build(&config)?;
+        let backends = match self.backend.is_empty() {
+            true => all(),
+            false => self.backend,
+        };
+        for (i, backend) in backends.into_iter().enumerate() {
+            if i > 0 {
+                miseprintln!();
+            }
+            print_status(&ts, backend)?;
+        }
+        Ok(())
+    }
+}
+
+/// the backends that can be listed, asdf is only used for plugins
+pub fn all() -> Vec<BackendType> {
+    let mut backends = backend::list_backend_types();
+    backends.retain(|f| *f != BackendType::Asdf);
+    backends
+}
+
+pub struct Status {
+    pub backend: BackendType,
+    /// where `backend.executable()` was found, either in a tool installed by mise or in PATH
+    pub executable: Option<PathBuf>,
+}
+
+impl Status {
+    pub fn new(ts: &Toolset, backend: BackendType) -> Self {
+        let executable = backend.executable().and_then(|exe| {
+            ts.list_paths()
+                .iter()
+                .chain(env::PATH.iter())
+                .map(|p| p.join(exe))
+                .find(|p| file::is_executable(p))
+        });
+        Self {
+            backend,
+            executable,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.backend.executable().is_none() || self.executable.is_some()
+    }
+
+    fn version(&self) -> Option<String> {
+        let exe = self.executable.as_ref()?;
+        // go is the only one without --version
+        let arg = match self.backend {
+            BackendType::Go => "version",
+            _ => "--version",
+        };
+        let output = cmd!(exe, arg).read().ok()?;
+        output.lines().next().map(|l| l.trim().to_string())
+    }
+}
+
+fn print_status(ts: &Toolset, backend: BackendType) -> Result<()> {
+    let status = Status::new(ts, backend);
+    match status.is_ready() {
+        true => miseprintln!("{backend}: ready"),
+        false => miseprintln!("{backend}: not ready"),
+    }
+    if let Some(exe) = backend.executable() {
+        match &status.executable {
+            Some(path) => {
+                miseprintln!("  executable: {}", display_path(path));
+                let version = status.version().unwrap_or_else(|| "unknown".into());
+                miseprintln!("  version: {version}");
+            }
+            None => miseprintln!("  executable: {exe} not found"),
+        }
+        match backend.dependency() {
+            Some(dep) if !status.is_ready() => {
+                miseprintln!("  run `mise backends install {backend}` to install {dep}")
+            }
+            None if !status.is_ready() => miseprintln!("  {exe} has to be installed manually"),
+            _ => {}
+        }
+    }
+    if backend == BackendType::Vfox {
+        let plugins = backend::list()
+            .into_iter()
+            .filter(|b| b.get_type() == BackendType::Vfox)
+            .map(|b| VfoxBackend::plugin_path(b.fa()))
+            .filter(|p| p.exists())
+            .sorted()
+            .collect_vec();
+        miseprintln!("  plugins: {}", plugins.len());
+        for path in plugins {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            miseprintln!("    {name} (updated {})", last_updated(&path));
+        }
+    }
+    Ok(())
+}
+
+/// when the plugin repo was last cloned or fetched
+fn last_updated(path: &std::path::Path) -> String {
+    let fetch_head = path.join(".git/FETCH_HEAD");
+    let path = match fetch_head.exists() {
+        true => fetch_head,
+        false => path.to_path_buf(),
+    };
+    match path.metadata().and_then(|m| m.modified()) {
+        Ok(time) => DateTime::<Local>::from(time)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        Err(_) => "unknown".into(),
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+  $ <bold>mise backends status npm</bold>
+  npm: ready
+    executable: ~/.local/share/mise/installs/node/20.17.0/bin/npm
+    version: 10.8.2
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    #[test]
+    fn test_backends_status_core() {
+        reset();
+        assert_cli_snapshot!("backends", "status", "core", @"core: ready");
+    }
+}
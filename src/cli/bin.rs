@@ -0,0 +1,132 @@
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::backend;
+use crate::cli::args::ToolArg;
+use crate::config::Config;
+use crate::errors::Error::VersionNotInstalled;
+use crate::file;
+use crate::toolset::{ToolVersion, ToolsetBuilder};
+
+/// Prints the path to an executable provided by a tool
+///
+/// Unlike `mise which` the executable doesn't need to have the same name as a shim
+/// and the tool is named so `mise bin node npx` finds the npx that comes with the node
+/// version that is active in this directory. Only the path is printed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Bin {
+    /// Tool that provides the executable
+    /// e.g.: node or node@20
+    #[clap(value_name = "TOOL@VERSION", verbatim_doc_comment)]
+    tool: ToolArg,
+
+    /// Name of the executable
+    #[clap(value_name = "BINARY")]
+    bin_name: String,
+
+    /// Use this installed version instead of the active one
+    #[clap(long, value_name = "VERSION")]
+    version: Option<String>,
+}
+
+impl Bin {
+    pub fn run(self) -> Result<()> {
+        let tv = self.tool_version()?;
+        let b = backend::get(&self.tool.backend);
+        if !b.is_version_installed(&tv, true) {
+            Err(VersionNotInstalled(
+                self.tool.backend.to_string(),
+                tv.version.clone(),
+            ))?;
+        }
+        match b.which(&tv, &self.bin_name)? {
+            Some(path) => {
+                miseprintln!("{}", path.display());
+                Ok(())
+            }
+            None => {
+                let bins = b
+                    .list_bin_paths(&tv)?
+                    .iter()
+                    .flat_map(|dir| file::ls(dir).unwrap_or_default())
+                    .filter(|p| file::is_executable(p))
+                    .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                    .sorted()
+                    .dedup()
+                    .join(", ");
+                bail!("{} not found in {tv}, it provides: {bins}", self.bin_name)
+            }
+        }
+    }
+
+    /// the version named with `--version` or `@VERSION`, otherwise the one active here
+    fn tool_version(&self) -> Result<ToolVersion> {
+        let config = Config::try_get()?;
+        let b = backend::get(&self.tool.backend);
+        if let Some(version) = &self.version {
+            let tr = self.tool.clone().with_version(version).tvr.unwrap();
+            return tr.resolve(b.as_ref(), false);
+        }
+        if let Some(tr) = &self.tool.tvr {
+            return tr.resolve(b.as_ref(), false);
+        }
+        let ts = ToolsetBuilder::new().build(&config)?;
+        match ts
+            .versions
+            .get(&self.tool.backend)
+            .and_then(|tvl| tvl.requests.first())
+        {
+            Some(tr) => tr.resolve(b.as_ref(), false),
+            None => bail!(
+                "{} is not active in this directory, use `mise use {}` or pass --version",
+                self.tool.backend,
+                self.tool.backend
+            ),
+        }
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise bin node npx</bold>
+    /home/jdx/.local/share/mise/installs/node/20.0.0/bin/npx
+
+    $ <bold>mise bin node npx --version 18</bold>
+    /home/jdx/.local/share/mise/installs/node/18.20.4/bin/npx
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_str_eq;
+
+    use crate::dirs;
+    use crate::test::reset;
+
+    #[test]
+    fn test_bin() {
+        reset();
+        assert_cli!("install", "tiny@2");
+        let stdout = assert_cli!("bin", "tiny", "rtx-tiny");
+        assert_str_eq!(
+            stdout.trim(),
+            dirs::DATA
+                .join("installs/tiny/3/bin/rtx-tiny")
+                .to_string_lossy()
+        );
+        let stdout = assert_cli!("bin", "tiny", "rtx-tiny", "--version", "2");
+        assert_str_eq!(
+            stdout.trim(),
+            dirs::DATA
+                .join("installs/tiny/2/bin/rtx-tiny")
+                .to_string_lossy()
+        );
+        let err = assert_cli_err!("bin", "tiny", "missing");
+        assert_str_eq!(
+            err.to_string(),
+            "missing not found in tiny@3.1.0, it provides: rtx-tiny"
+        );
+    }
+}
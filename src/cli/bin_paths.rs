@@ -1,25 +1,56 @@
 use eyre::Result;
 
 use crate::config::Config;
+use crate::env::PATH_KEY;
 use crate::toolset::ToolsetBuilder;
 
 /// List all the active runtime bin paths
+///
+/// These are the directories `mise activate` puts at the front of PATH for the current
+/// directory, in the same order, including `env._.path` entries and extra paths added by tools.
+/// Useful for configuring the PATH of an IDE without starting a shell.
 #[derive(Debug, clap::Args)]
-#[clap(verbatim_doc_comment)]
-pub struct BinPaths {}
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct BinPaths {
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+}
 
 impl BinPaths {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
         let ts = ToolsetBuilder::new().build(&config)?;
         ts.notify_if_versions_missing();
-        for p in ts.list_paths() {
+        let mut env = ts.env(&config)?;
+        let env_path = env.remove(&*PATH_KEY);
+        let paths = ts.list_final_paths(&config, env_path.as_deref())?;
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&paths)?);
+            return Ok(());
+        }
+        for p in paths {
             miseprintln!("{}", p.display());
         }
         Ok(())
     }
 }
 
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise bin-paths</bold>
+    /home/user/.local/share/mise/installs/node/20.0.0/bin
+    /home/user/.local/share/mise/installs/python/3.12.0/bin
+
+    $ <bold>mise bin-paths --json</bold>
+    [
+      "/home/user/.local/share/mise/installs/node/20.0.0/bin",
+      "/home/user/.local/share/mise/installs/python/3.12.0/bin"
+    ]
+"#
+);
+
 #[cfg(test)]
 mod tests {
     use crate::test::reset;
@@ -33,4 +64,16 @@ mod tests {
         ~/data/installs/dummy/ref-master/bin
         "###);
     }
+
+    #[test]
+    fn test_bin_paths_json() {
+        reset();
+        assert_cli!("i");
+        assert_cli_snapshot!("bin-paths", "--json", @r###"
+        [
+          "~/data/installs/tiny/3/bin",
+          "~/data/installs/dummy/ref-master/bin"
+        ]
+        "###);
+    }
 }
@@ -1,6 +1,9 @@
+use std::path::PathBuf;
+
 use eyre::Result;
 
-use crate::dirs::CACHE;
+use crate::dirs::{CACHE, DOWNLOAD_CACHE, REMOTE_VERSIONS_CACHE};
+use crate::file;
 use crate::file::{display_path, remove_all};
 
 /// Deletes all cache files in mise
@@ -10,14 +13,42 @@ pub struct CacheClear {
     /// Plugin(s) to clear cache for
     /// e.g.: node, python
     plugin: Option<Vec<String>>,
+
+    /// Only clear the cached lists of remote versions so the next listing fetches them in full
+    #[clap(long, conflicts_with = "downloads")]
+    remote_versions: bool,
+
+    /// Only clear the download cache
+    #[clap(long, conflicts_with = "plugin")]
+    downloads: bool,
 }
 
 impl CacheClear {
     pub fn run(self) -> Result<()> {
-        let cache_dirs = match &self.plugin {
+        if self.remote_versions {
+            return self.clear_remote_versions();
+        }
+        if self.downloads {
+            if DOWNLOAD_CACHE.exists() {
+                debug!("clearing download cache {}", display_path(*DOWNLOAD_CACHE));
+                remove_all(*DOWNLOAD_CACHE)?;
+            }
+            info!("download cache cleared");
+            return Ok(());
+        }
+        let mut cache_dirs: Vec<PathBuf> = match &self.plugin {
             Some(plugins) => plugins.iter().map(|p| CACHE.join(p)).collect(),
-            None => vec![CACHE.to_path_buf()],
+            None => vec![CACHE.to_path_buf(), DOWNLOAD_CACHE.to_path_buf()],
         };
+        // the remote versions cache may have been moved out of the cache dir
+        if !REMOTE_VERSIONS_CACHE.starts_with(*CACHE) {
+            match &self.plugin {
+                Some(plugins) => {
+                    cache_dirs.extend(plugins.iter().map(|p| REMOTE_VERSIONS_CACHE.join(p)))
+                }
+                None => cache_dirs.push(REMOTE_VERSIONS_CACHE.to_path_buf()),
+            }
+        }
         for p in cache_dirs {
             if p.exists() {
                 debug!("clearing cache from {}", display_path(&p));
@@ -30,10 +61,38 @@ impl CacheClear {
         }
         Ok(())
     }
+
+    fn clear_remote_versions(&self) -> Result<()> {
+        let plugins = match &self.plugin {
+            Some(plugins) => plugins.clone(),
+            None => file::dir_subdirs(&REMOTE_VERSIONS_CACHE)
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        };
+        for plugin in &plugins {
+            let dir = REMOTE_VERSIONS_CACHE.join(plugin);
+            for f in file::ls(&dir).unwrap_or_default() {
+                let name = f.file_name().unwrap_or_default().to_string_lossy();
+                if name.starts_with("remote_versions") {
+                    debug!("clearing remote versions cache {}", display_path(&f));
+                    file::remove_file(&f)?;
+                }
+            }
+        }
+        match &self.plugin {
+            Some(plugins) => info!("remote versions cache cleared for {}", plugins.join(", ")),
+            None => info!("remote versions cache cleared"),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::dirs;
+    use crate::file;
+
     #[test]
     fn test_cache_clear() {
         assert_cli_snapshot!("cache", "clear", @r###"
@@ -47,4 +106,24 @@ mod tests {
         mise cache cleared for tiny
         "###);
     }
+
+    #[test]
+    fn test_cache_clear_remote_versions() {
+        assert_cli_snapshot!("cache", "clear", "--remote-versions", "tiny", @r###"
+        mise remote versions cache cleared for tiny
+        "###);
+    }
+
+    #[test]
+    fn test_cache_clear_downloads() {
+        let download = dirs::DOWNLOAD_CACHE.join("test-artifact");
+        let other = dirs::CACHE.join("test-clear-downloads");
+        file::create_dir_all(*dirs::DOWNLOAD_CACHE).unwrap();
+        file::write(&download, "").unwrap();
+        file::write(&other, "").unwrap();
+        assert_cli_snapshot!("cache", "clear", "--downloads", @"mise download cache cleared");
+        assert!(!download.exists());
+        assert!(other.exists());
+        file::remove_file(&other).unwrap();
+    }
 }
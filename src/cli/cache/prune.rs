@@ -1,17 +1,24 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use eyre::{Result, WrapErr};
+
 use crate::cache;
-use crate::cache::{PruneOptions, PruneResults};
+use crate::cache::PruneOptions;
 use crate::config::Settings;
-use crate::dirs::CACHE;
-use eyre::Result;
-use number_prefix::NumberPrefix;
-use std::time::Duration;
+use crate::dirs::{CACHE, REMOTE_VERSIONS_CACHE};
+use crate::download_cache::parse_size;
+use crate::file::format_bytes;
 
 /// Removes stale mise cache files
 ///
 /// By default, this command will remove files that have not been accessed in 30 days.
-/// Change this with the MISE_CACHE_PRUNE_AGE environment variable.
+/// Change this with --older-than or the MISE_CACHE_PRUNE_AGE environment variable.
+///
+/// With --max-size the least recently used files are removed until the cache is at most that
+/// size. This covers every cache: remote versions, http responses and downloaded artifacts.
 #[derive(Debug, clap::Args)]
-#[clap(verbatim_doc_comment, visible_alias = "p")]
+#[clap(verbatim_doc_comment, visible_alias = "p", after_long_help = AFTER_LONG_HELP)]
 pub struct CachePrune {
     /// Plugin(s) to clear cache for
     /// e.g.: node, python
@@ -21,6 +28,14 @@ pub struct CachePrune {
     #[clap(long)]
     dry_run: bool,
 
+    /// Remove files that have not been used in this long, e.g.: 30d or 12h
+    #[clap(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    older_than: Option<Duration>,
+
+    /// Remove the least recently used files until the cache is at most this size, e.g.: 2G or 500MiB
+    #[clap(long, value_name = "SIZE")]
+    max_size: Option<String>,
+
     /// Show pruned files
     #[clap(long, short, action = clap::ArgAction::Count)]
     verbose: u8,
@@ -29,36 +44,62 @@ pub struct CachePrune {
 impl CachePrune {
     pub fn run(self) -> Result<()> {
         let settings = Settings::get();
-        let cache_dirs = vec![CACHE.to_path_buf()];
+        let cache_dirs: Vec<PathBuf> = match &self.plugin {
+            Some(plugins) => {
+                let mut cache_dirs: Vec<PathBuf> = plugins.iter().map(|p| CACHE.join(p)).collect();
+                // the remote versions cache may have been moved out of the cache dir
+                if !REMOTE_VERSIONS_CACHE.starts_with(*CACHE) {
+                    cache_dirs.extend(plugins.iter().map(|p| REMOTE_VERSIONS_CACHE.join(p)));
+                }
+                cache_dirs
+            }
+            None => cache::dirs(),
+        };
+        let max_size = match &self.max_size {
+            Some(size) => Some(parse_size(size).wrap_err_with(|| format!("invalid size: {size}"))?),
+            None => None,
+        };
+        let age = match (self.older_than, max_size) {
+            (Some(age), _) => Some(age),
+            // only prune by size unless an age is given too
+            (None, Some(_)) => None,
+            (None, None) => Some(
+                settings
+                    .cache_prune_age_duration()
+                    .unwrap_or(Duration::from_secs(30 * 24 * 60 * 60)),
+            ),
+        };
         let opts = PruneOptions {
             dry_run: self.dry_run,
             verbose: self.verbose > 0,
-            age: settings
-                .cache_prune_age_duration()
-                .unwrap_or(Duration::from_secs(30 * 24 * 60 * 60)),
+            age,
+            max_size,
         };
-        let mut results = PruneResults { size: 0, count: 0 };
-        for p in &cache_dirs {
-            let r = cache::prune(p, &opts)?;
-            results.size += r.size;
-            results.count += r.count;
-        }
+        let results = cache::prune(&cache_dirs, &opts)?;
         let count = results.count;
-        let size = bytes_str(results.size);
-        info!("cache pruned {count} files, {size} bytes");
+        let size = format_bytes(results.size as f64);
+        match self.dry_run {
+            true => info!("would prune {count} files, freeing {size}"),
+            false => info!("cache pruned {count} files, freed {size}"),
+        }
         Ok(())
     }
 }
 
-fn bytes_str(bytes: u64) -> String {
-    match NumberPrefix::binary(bytes as f64) {
-        NumberPrefix::Standalone(bytes) => format!("{} bytes", bytes),
-        NumberPrefix::Prefixed(prefix, n) => format!("{:.1} {}B", n, prefix),
-    }
-}
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+  $ <bold>mise cache prune --older-than 30d</bold>
+  $ <bold>mise cache prune --max-size 2G --dry-run</bold>
+  would prune ~/.cache/mise/downloads/url-1f4e... (45.2 MiB, last used 41days ago)
+  would prune 1 files, freeing 45.2 MiB
+"#
+);
 
 #[cfg(test)]
 mod tests {
+    use insta::assert_snapshot;
+
     use crate::test::reset;
 
     #[test]
@@ -72,4 +113,11 @@ mod tests {
         reset();
         assert_cli!("cache", "prune", "tiny");
     }
+
+    #[test]
+    fn test_cache_prune_invalid_size() {
+        reset();
+        let err = assert_cli_err!("cache", "prune", "--max-size", "2 parsecs");
+        assert_snapshot!(format!("{err:#}"), @"invalid size: 2 parsecs: unknown size unit: parsecs");
+    }
 }
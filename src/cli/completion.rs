@@ -1,12 +1,26 @@
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
 use clap::builder::PossibleValue;
-use clap::ValueEnum;
-use eyre::Result;
+use clap::{Subcommand, ValueEnum};
+use eyre::{eyre, Result};
+use itertools::Itertools;
 use strum::EnumString;
+use versions::Versioning;
+
+use crate::backend::{self, Backend};
+use crate::env;
+use crate::file::{self, display_path};
+use crate::shell::ShellType;
 
 /// Generate shell completions
 #[derive(Debug, clap::Args)]
 #[clap(aliases = ["complete", "completions"], verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Completion {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
     /// Shell type to generate completions for
     #[clap(required_unless_present = "shell_type")]
     shell: Option<Shell>,
@@ -25,43 +39,177 @@ pub struct Completion {
     usage: bool,
 }
 
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Install(CompletionInstall),
+    #[clap(hide = true)]
+    Versions(CompletionVersions),
+}
+
 impl Completion {
     pub fn run(self) -> Result<()> {
+        match self.command {
+            Some(Commands::Install(cmd)) => return cmd.run(),
+            Some(Commands::Versions(cmd)) => return cmd.run(),
+            None => {}
+        }
         let shell = self.shell.or(self.shell_type).unwrap();
+        miseprintln!("{}", script(shell));
 
-        let script = match self.call_usage(shell) {
-            Ok(script) => script,
-            Err(e) => {
-                debug!("usage command failed, falling back to prerendered completions");
-                debug!("error: {e:?}");
-                self.prerendered(shell)
-            }
+        Ok(())
+    }
+}
+
+/// Install completions into the shell's completion directory
+///
+/// These are loaded by the shell automatically so nothing needs to be added to its rc file,
+/// except for zsh where the directory needs to be in `fpath`.
+/// The completions call mise when they run so task and tool names are always current.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = INSTALL_AFTER_LONG_HELP)]
+struct CompletionInstall {
+    /// Shell type to install completions for, defaults to the current shell
+    shell: Option<Shell>,
+
+    /// Remove the completions instead
+    #[clap(long)]
+    uninstall: bool,
+}
+
+impl CompletionInstall {
+    fn run(self) -> Result<()> {
+        let shell = match self.shell {
+            Some(shell) => shell,
+            None => Shell::detect()?,
         };
-        miseprintln!("{}", script.trim());
+        let path = shell.completion_path();
+        if self.uninstall {
+            match path.exists() {
+                true => {
+                    file::remove_file(&path)?;
+                    miseprintln!("removed {shell} completions from {}", display_path(&path));
+                }
+                false => miseprintln!("{shell} completions are not installed"),
+            }
+            return Ok(());
+        }
+        let script = format!("{}\n", script(shell));
+        if file::read_to_string(&path).is_ok_and(|s| s == script) {
+            miseprintln!(
+                "{shell} completions are already installed at {}",
+                display_path(&path)
+            );
+        } else {
+            file::create_dir_all(path.parent().unwrap())?;
+            file::write(&path, script)?;
+            miseprintln!("installed {shell} completions to {}", display_path(&path));
+        }
+        if let Shell::Zsh = shell {
+            let dir = display_path(path.parent().unwrap());
+            miseprintln!("add `fpath=({dir} $fpath)` before `compinit` in ~/.zshrc if it isn't in fpath already");
+        }
+        Ok(())
+    }
+}
+
+/// Print the versions of a tool for shell completion, one `tool@version` per line
+///
+/// The installed versions come first, then the ones in the cached version list that aren't
+/// installed, each newest first. The network is never used so this stays fast and works
+/// offline, tools whose versions were never listed only complete their installed versions.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct CompletionVersions {
+    /// The tool and the start of the version, e.g.: node@20
+    #[clap(value_name = "TOOL@VERSION")]
+    tool: String,
+
+    /// Only complete installed versions
+    #[clap(long)]
+    installed: bool,
+}
 
+impl CompletionVersions {
+    /// called on every <TAB> so nothing is printed for a tool that can't be loaded rather than
+    /// writing errors into the prompt
+    fn run(self) -> Result<()> {
+        let (tool, prefix) = self.tool.split_once('@').unwrap_or((&self.tool, ""));
+        if tool.is_empty() {
+            return Ok(());
+        }
+        let backend = backend::get(&tool.into());
+        let installed = backend.list_installed_versions().unwrap_or_default();
+        let remote = match self.installed {
+            true => vec![],
+            false => cached_remote_versions(backend.as_ref()),
+        };
+        let newest_first = |versions: Vec<String>| {
+            versions
+                .into_iter()
+                .filter(|v| v.starts_with(prefix))
+                .sorted_by_cached_key(|v| Reverse(Versioning::new(v)))
+                .collect_vec()
+        };
+        let remote = newest_first(remote)
+            .into_iter()
+            .filter(|v| !installed.contains(v))
+            .collect_vec();
+        for version in newest_first(installed).into_iter().chain(remote) {
+            miseprintln!("{tool}@{version}");
+        }
         Ok(())
     }
+}
 
-    fn call_usage(&self, shell: Shell) -> std::io::Result<String> {
-        cmd!(
-            "usage",
-            "generate",
-            "completion",
-            shell.to_string(),
-            "mise",
-            "--usage-cmd",
-            "mise usage"
-        )
-        .read()
+/// the remote versions of `backend` if they were listed before. The backends fetch them when
+/// the cache file doesn't exist even when offline, e.g.: with `npm view`, so it is checked first.
+fn cached_remote_versions(backend: &dyn Backend) -> Vec<String> {
+    let cache = backend
+        .fa()
+        .remote_versions_cache_path
+        .join("remote_versions.msgpack.z");
+    if !cache.exists() {
+        return vec![];
     }
+    backend._list_remote_versions().unwrap_or_else(|err| {
+        debug!(
+            "failed to read the cached versions of {}: {err:#}",
+            backend.id()
+        );
+        vec![]
+    })
+}
 
-    fn prerendered(&self, shell: Shell) -> String {
-        match shell {
-            Shell::Bash => include_str!("../../completions/mise.bash"),
-            Shell::Fish => include_str!("../../completions/mise.fish"),
-            Shell::Zsh => include_str!("../../completions/_mise"),
+fn script(shell: Shell) -> String {
+    let script = match call_usage(shell) {
+        Ok(script) => script,
+        Err(e) => {
+            debug!("usage command failed, falling back to prerendered completions");
+            debug!("error: {e:?}");
+            prerendered(shell).to_string()
         }
-        .to_string()
+    };
+    script.trim().to_string()
+}
+
+fn call_usage(shell: Shell) -> std::io::Result<String> {
+    cmd!(
+        "usage",
+        "generate",
+        "completion",
+        shell.to_string(),
+        "mise",
+        "--usage-cmd",
+        "mise usage"
+    )
+    .read()
+}
+
+fn prerendered(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => include_str!("../../completions/mise.bash"),
+        Shell::Fish => include_str!("../../completions/mise.fish"),
+        Shell::Zsh => include_str!("../../completions/_mise"),
     }
 }
 
@@ -71,6 +219,19 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise completion bash > /etc/bash_completion.d/mise</bold>
     $ <bold>mise completion zsh  > /usr/local/share/zsh/site-functions/_mise</bold>
     $ <bold>mise completion fish > ~/.config/fish/completions/mise.fish</bold>
+    $ <bold>mise completion install</bold> - installs completions for the current shell
+"#
+);
+
+static INSTALL_AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise completion install</bold>
+    installed fish completions to ~/.config/fish/completions/mise.fish
+    $ <bold>mise completion install bash</bold>
+    installed bash completions to ~/.local/share/bash-completion/completions/mise
+    $ <bold>mise completion install bash --uninstall</bold>
+    removed bash completions from ~/.local/share/bash-completion/completions/mise
 "#
 );
 
@@ -82,6 +243,38 @@ enum Shell {
     Zsh,
 }
 
+impl Shell {
+    fn detect() -> Result<Self> {
+        match ShellType::load() {
+            Some(ShellType::Bash) => Ok(Self::Bash),
+            Some(ShellType::Fish) => Ok(Self::Fish),
+            Some(ShellType::Zsh) => Ok(Self::Zsh),
+            Some(shell) => Err(eyre!(
+                "completions are not available for {shell}, only for bash, fish and zsh"
+            )),
+            None => Err(eyre!("could not detect the shell, pass it as an argument")),
+        }
+    }
+
+    /// where completions for the current user go, bash and fish load them from here on their own
+    fn completion_path(&self) -> PathBuf {
+        match self {
+            Self::Bash => env::XDG_DATA_HOME
+                .join("bash-completion")
+                .join("completions")
+                .join("mise"),
+            Self::Fish => env::XDG_CONFIG_HOME
+                .join("fish")
+                .join("completions")
+                .join("mise.fish"),
+            Self::Zsh => env::XDG_DATA_HOME
+                .join("zsh")
+                .join("site-functions")
+                .join("_mise"),
+        }
+    }
+}
+
 impl ValueEnum for Shell {
     fn value_variants<'a>() -> &'a [Self] {
         &[Self::Bash, Self::Fish, Self::Zsh]
@@ -93,10 +286,23 @@ impl ValueEnum for Shell {
 
 #[cfg(test)]
 mod tests {
+    use crate::test::reset;
+
     #[test]
     fn test_completion() {
         assert_cli!("completion", "zsh");
         assert_cli!("completion", "bash");
         assert_cli!("completion", "fish");
     }
+
+    #[test]
+    fn test_completion_versions() {
+        reset();
+        // other tests install and uninstall tiny versions so only the shape is checked
+        let stdout = assert_cli!("completion", "versions", "--installed", "tiny@");
+        assert!(stdout.lines().all(|l| l.starts_with("tiny@")));
+        let stdout = assert_cli!("completion", "versions", "tiny@3");
+        assert!(stdout.lines().all(|l| l.starts_with("tiny@3")));
+        assert_cli!("completion", "versions", "@");
+    }
 }
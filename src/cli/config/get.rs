@@ -1,4 +1,4 @@
-use crate::cli::config::top_toml_config;
+use crate::cli::config::{parse_key, top_toml_config};
 use crate::file::display_path;
 use eyre::bail;
 use std::path::PathBuf;
@@ -7,7 +7,7 @@ use std::path::PathBuf;
 #[derive(Debug, clap::Args)]
 #[clap(after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
 pub struct ConfigGet {
-    /// The path of the config to display
+    /// The path of the config to display, e.g.: `tools.node` or `tools."npm:prettier"`
     pub key: Option<String>,
 
     /// The path to the mise.toml file to edit
@@ -27,8 +27,8 @@ impl ConfigGet {
             let config: toml::Value = std::fs::read_to_string(&file)?.parse()?;
             let mut value = &config;
             if let Some(key) = &self.key {
-                for k in key.split('.') {
-                    value = value.get(k).ok_or_else(|| {
+                for k in parse_key(key)? {
+                    value = value.get(&k).ok_or_else(|| {
                         eyre::eyre!("Key not found: {} in {}", key, display_path(&file))
                     })?;
                 }
@@ -58,6 +58,8 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     $ <bold>mise toml get tools.python</bold>
     3.12
+    $ <bold>mise toml get 'tools."npm:prettier"'</bold>
+    3
 "#
 );
 
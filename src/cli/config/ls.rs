@@ -6,7 +6,8 @@ use tabled::settings::{Modify, Width};
 use tabled::Tabled;
 
 use crate::config::config_file::ConfigFile;
-use crate::config::CONFIG;
+use crate::config::{Settings, CONFIG};
+use crate::env;
 use crate::file::display_path;
 use crate::ui::table;
 
@@ -21,6 +22,17 @@ pub struct ConfigLs {
 
 impl ConfigLs {
     pub fn run(self) -> Result<()> {
+        if let Some(profile) = &*env::MISE_ENV {
+            info!("profile: {profile}");
+        }
+        let locked = &Settings::policy()?.locked;
+        if !locked.is_empty() {
+            info!(
+                "policy: {} enforces {}",
+                display_path(&*env::MISE_POLICY_FILE),
+                locked.join(", ")
+            );
+        }
         let rows = CONFIG
             .config_files
             .values()
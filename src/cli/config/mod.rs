@@ -1,6 +1,6 @@
 use crate::config::{load_config_paths, DEFAULT_CONFIG_FILENAMES};
 use clap::Subcommand;
-use eyre::Result;
+use eyre::{eyre, Result};
 use once_cell::sync::Lazy;
 use std::path::PathBuf;
 
@@ -8,6 +8,7 @@ mod generate;
 mod get;
 mod ls;
 mod set;
+mod validate;
 
 /// Manage config files
 #[derive(Debug, clap::Args)]
@@ -27,6 +28,7 @@ enum Commands {
     Get(get::ConfigGet),
     Ls(ls::ConfigLs),
     Set(set::ConfigSet),
+    Validate(validate::ConfigValidate),
 }
 
 impl Commands {
@@ -36,6 +38,7 @@ impl Commands {
             Self::Get(cmd) => cmd.run(),
             Self::Ls(cmd) => cmd.run(),
             Self::Set(cmd) => cmd.run(),
+            Self::Validate(cmd) => cmd.run(),
         }
     }
 }
@@ -64,3 +67,9 @@ fn top_toml_config() -> Option<PathBuf> {
         .find(|p| p.to_string_lossy().ends_with(".toml"))
         .map(|p| p.to_path_buf())
 }
+
+/// splits a dotted key like `tools."npm:prettier"` into its segments
+fn parse_key(key: &str) -> Result<Vec<String>> {
+    let keys = toml_edit::Key::parse(key).map_err(|e| eyre!("invalid key {key}: {e}"))?;
+    Ok(keys.into_iter().map(|k| k.get().to_string()).collect())
+}
@@ -1,14 +1,19 @@
-use crate::cli::config::top_toml_config;
+use crate::cli::config::{parse_key, top_toml_config};
+use crate::cli::settings::set::parse_setting;
 use crate::config::config_file::mise_toml::MiseToml;
+use crate::file::display_path;
 use clap::ValueEnum;
-use eyre::bail;
+use eyre::{bail, eyre};
 use std::path::PathBuf;
 
-/// Display the value of a setting in a mise.toml file
+/// Set the value of a setting in a mise.toml file
+///
+/// Comments and formatting of the file are preserved. The file is only
+/// written if the result is still a valid mise.toml.
 #[derive(Debug, clap::Args)]
 #[clap(after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
 pub struct ConfigSet {
-    /// The path of the config to display
+    /// The path of the config to set, e.g.: `tools.node` or `tools."npm:prettier"`
     pub key: String,
 
     /// The value to set the key to
@@ -20,6 +25,10 @@ pub struct ConfigSet {
     #[clap(short, long)]
     pub file: Option<PathBuf>,
 
+    /// The type of the value
+    ///
+    /// By default the type is inferred, settings use the type of the setting
+    /// while tools and env vars are always strings
     #[clap(value_enum, short, long, default_value_t)]
     pub type_: TomlValueTypes,
 }
@@ -28,6 +37,8 @@ pub struct ConfigSet {
 pub enum TomlValueTypes {
     #[default]
     #[value()]
+    Infer,
+    #[value()]
     String,
     #[value()]
     Integer,
@@ -35,35 +46,35 @@ pub enum TomlValueTypes {
     Float,
     #[value()]
     Bool,
+    /// comma-separated list of strings
+    #[value()]
+    List,
 }
 
 impl ConfigSet {
     pub fn run(self) -> eyre::Result<()> {
-        let mut file = self.file;
+        let mut file = self.file.clone();
         if file.is_none() {
             file = top_toml_config();
         }
         if let Some(file) = file {
             let mut config: toml_edit::DocumentMut = std::fs::read_to_string(&file)?.parse()?;
-            let mut container = config.as_item_mut();
-            let parts = self.key.split('.').collect::<Vec<&str>>();
-            for key in parts.iter().take(parts.len() - 1) {
-                container = container
-                    .as_table_mut()
-                    .unwrap()
+            let parts = parse_key(&self.key)?;
+            let (last_key, parents) = parts
+                .split_last()
+                .ok_or_else(|| eyre!("key must not be empty"))?;
+            let value = self.parse_value(&parts)?;
+
+            let mut container: &mut dyn toml_edit::TableLike = config.as_table_mut();
+            for key in parents {
+                let item = container
                     .entry(key)
                     .or_insert(toml_edit::Item::Table(toml_edit::Table::new()));
+                container = item
+                    .as_table_like_mut()
+                    .ok_or_else(|| eyre!("{key} is not a table in {}", display_path(&file)))?;
             }
-            let last_key = parts.last().unwrap();
-
-            let value = match self.type_ {
-                TomlValueTypes::String => toml_edit::value(self.value),
-                TomlValueTypes::Integer => toml_edit::value(self.value.parse::<i64>()?),
-                TomlValueTypes::Float => toml_edit::value(self.value.parse::<f64>()?),
-                TomlValueTypes::Bool => toml_edit::value(self.value.parse::<bool>()?),
-            };
-
-            container.as_table_mut().unwrap().insert(last_key, value);
+            container.insert(last_key, toml_edit::Item::Value(value));
 
             let raw = config.to_string();
             MiseToml::from_str(&raw, &file)?;
@@ -73,6 +84,36 @@ impl ConfigSet {
         }
         Ok(())
     }
+
+    fn parse_value(&self, key: &[String]) -> eyre::Result<toml_edit::Value> {
+        let value = self.value.as_str();
+        Ok(match self.type_ {
+            TomlValueTypes::Infer => match key {
+                [section, setting @ ..] if section == "settings" && !setting.is_empty() => {
+                    parse_setting(&setting.join("."), value)?
+                }
+                [section, ..] if section == "tools" || section == "env" => value.into(),
+                _ => infer_value(value),
+            },
+            TomlValueTypes::String => value.into(),
+            TomlValueTypes::Integer => value.parse::<i64>()?.into(),
+            TomlValueTypes::Float => value.parse::<f64>()?.into(),
+            TomlValueTypes::Bool => value.parse::<bool>()?.into(),
+            TomlValueTypes::List => value.split(',').map(|s| s.trim().to_string()).collect(),
+        })
+    }
+}
+
+fn infer_value(value: &str) -> toml_edit::Value {
+    if let Ok(b) = value.parse::<bool>() {
+        b.into()
+    } else if let Ok(i) = value.parse::<i64>() {
+        i.into()
+    } else if let Ok(f) = value.parse::<f64>() {
+        f.into()
+    } else {
+        value.into()
+    }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -81,12 +122,17 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise config set tools.python 3.12</bold>
     $ <bold>mise config set settings.always_keep_download true</bold>
     $ <bold>mise config set env.TEST_ENV_VAR ABC</bold>
+    $ <bold>mise config set 'tools."npm:prettier"' 3</bold>
+    $ <bold>mise config set settings.disable_tools --type list node,python</bold>
 "#
 );
 
 #[cfg(test)]
 mod tests {
-    use crate::test::reset;
+    use insta::assert_snapshot;
+
+    use crate::test::{cli_run, reset};
+    use crate::{env, file};
 
     #[test]
     fn test_config_set() {
@@ -103,4 +149,39 @@ mod tests {
         assert_cli_snapshot!("config", "set", "settings.jobs", "--type", "integer", "4", @"");
         assert_cli_snapshot!("config", "get", "settings.jobs", @"4");
     }
+
+    #[test]
+    fn test_config_set_infer() {
+        reset();
+        assert_cli_snapshot!("config", "set", "settings.jobs", "2", @"");
+        assert_cli_snapshot!("config", "set", "settings.disable_tools", "node,python", @"");
+        assert_cli_snapshot!("config", "set", "tools.\"npm:prettier\"", "3", @"");
+        assert_cli_snapshot!("config", "get", "tools.\"npm:prettier\"", @"3");
+        let raw = file::read_to_string(&*env::MISE_GLOBAL_CONFIG_FILE).unwrap();
+        assert!(raw.contains("jobs = 2\n"), "{raw}");
+        assert!(
+            raw.contains(r#"disable_tools = ["node", "python"]"#),
+            "{raw}"
+        );
+        assert!(raw.contains(r#""npm:prettier" = "3""#), "{raw}");
+        reset();
+    }
+
+    #[test]
+    fn test_config_set_invalid() {
+        reset();
+        let err = cli_run(
+            &["mise", "config", "set", "tools", "1"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .unwrap_err();
+        assert_snapshot!(err, @r#"
+        TOML parse error at line 1, column 9
+          |
+        1 | tools = "1"
+          |         ^^^
+        invalid type: string "1", expected a map
+        "#);
+    }
 }
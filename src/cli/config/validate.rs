@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Result};
+use itertools::Itertools;
+use serde_derive::Serialize;
+
+use crate::backend;
+use crate::backend::BackendType;
+use crate::cli::args::BackendArg;
+use crate::config::config_file::mise_toml::MiseToml;
+use crate::config::config_file::{self, ConfigFile};
+use crate::config::{load_config_paths, Config, Settings, DEFAULT_CONFIG_FILENAMES};
+use crate::file;
+use crate::file::display_path;
+use crate::shorthands::{get_shorthands, Shorthands};
+use crate::task::Deps;
+use crate::toolset::ToolRequest;
+
+/// Checks the config files for mistakes
+///
+/// Every config file that applies to the current directory is parsed and checked for
+/// unknown keys, malformed tool versions, tools mise doesn't know about, templates with
+/// invalid syntax, and tasks that depend on tasks that don't exist or on each other.
+/// Nothing is installed or fetched from the network.
+///
+/// Exits with a non-zero status if any problem is found.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct ConfigValidate {
+    /// Output the problems in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Problem {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    message: String,
+}
+
+impl ConfigValidate {
+    pub fn run(self) -> Result<()> {
+        let paths = load_config_paths(&DEFAULT_CONFIG_FILENAMES);
+        let shorthands = get_shorthands(&Settings::get());
+        let mut problems = vec![];
+        for path in &paths {
+            for message in validate_file(path, &shorthands) {
+                problems.push(Problem {
+                    path: Some(path.clone()),
+                    message,
+                });
+            }
+        }
+        // tasks can depend on tasks from other files so these need the whole config
+        match Config::try_get() {
+            Ok(config) => problems.extend(validate_tasks(&config)?),
+            Err(err) if problems.is_empty() => problems.push(Problem {
+                path: None,
+                message: format!("{err:#}"),
+            }),
+            Err(_) => {}
+        }
+
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&problems)?);
+        } else {
+            for problem in &problems {
+                match &problem.path {
+                    Some(path) => miseprintln!("{}: {}", display_path(path), problem.message),
+                    None => miseprintln!("{}", problem.message),
+                }
+            }
+        }
+        if !problems.is_empty() {
+            bail!(
+                "found {} problem(s) in {} config file(s)",
+                problems.len(),
+                paths.len()
+            );
+        }
+        if !self.json {
+            info!("{} config file(s) are valid", paths.len());
+        }
+        Ok(())
+    }
+}
+
+fn validate_file(path: &Path, shorthands: &Shorthands) -> Vec<String> {
+    let mut problems = vec![];
+    let cf: Box<dyn ConfigFile> = if path.extension().is_some_and(|e| e == "toml") {
+        let body = match file::read_to_string(path) {
+            Ok(body) => body,
+            Err(err) => return vec![format!("{err:#}")],
+        };
+        let (cf, unknown) = match MiseToml::from_str_with_unknown(&body, path) {
+            Ok(cf) => cf,
+            Err(err) => return vec![format!("{err:#}")],
+        };
+        problems.extend(unknown.into_iter().map(|p| format!("unknown field: {p}")));
+        let template_errors = cf.template_errors();
+        let has_template_errors = !template_errors.is_empty();
+        problems.extend(template_errors);
+        // the tools can't be read if their templates don't parse
+        if has_template_errors {
+            return problems;
+        }
+        Box::new(cf)
+    } else {
+        match config_file::parse(path) {
+            Ok(cf) => cf,
+            Err(err) => return vec![format!("{err:#}")],
+        }
+    };
+    let trs = match cf.to_tool_request_set() {
+        Ok(trs) => trs,
+        Err(err) => {
+            problems.push(format!("{err:#}"));
+            return problems;
+        }
+    };
+    let plugins = cf.plugins().unwrap_or_default();
+    for (ba, requests, _) in trs.iter() {
+        if !is_known_tool(ba, &plugins, shorthands) {
+            problems.push(format!("unknown tool: {ba}"));
+        }
+        for tr in requests {
+            if let Some(msg) = malformed_version(tr) {
+                problems.push(msg);
+            }
+        }
+    }
+    problems
+}
+
+/// tools are looked up in the registry and installed plugins, not on the network
+fn is_known_tool(
+    ba: &BackendArg,
+    plugins: &HashMap<String, String>,
+    shorthands: &Shorthands,
+) -> bool {
+    if !matches!(ba.backend_type, BackendType::Asdf | BackendType::Core) {
+        return true;
+    }
+    backend::list().iter().any(|b| b.id() == ba.short)
+        || plugins.contains_key(&ba.short)
+        || shorthands.contains_key(&ba.short)
+}
+
+fn malformed_version(tr: &ToolRequest) -> Option<String> {
+    let version = match tr {
+        ToolRequest::Version { version, .. } => version,
+        ToolRequest::Prefix { prefix, .. } => prefix,
+        ToolRequest::Ref { ref_, .. } => ref_,
+        _ => return None,
+    };
+    if version.is_empty() || version.contains(char::is_whitespace) {
+        return Some(format!(
+            "malformed version for {}: {:?}",
+            tr.backend(),
+            tr.version()
+        ));
+    }
+    None
+}
+
+fn validate_tasks(config: &Config) -> Result<Vec<Problem>> {
+    let mut problems = vec![];
+    let tasks = config.tasks()?;
+    for task in tasks.values() {
+        if let Err(err) = task.resolve_depends(config) {
+            problems.push(Problem {
+                path: Some(task.config_source.clone()),
+                message: format!("task {} depends on a {err}", task.name),
+            });
+        }
+    }
+    // the graph can only be built if every dependency exists
+    if problems.is_empty() {
+        let graph = Deps::graph(config, tasks.values().cloned().collect())?;
+        let mut seen = HashSet::new();
+        for cycle in Deps::cycles(&graph) {
+            if !seen.insert(cycle.clone()) {
+                continue;
+            }
+            let path = tasks.get(&cycle[0]).map(|t| t.config_source.clone());
+            problems.push(Problem {
+                path,
+                message: format!(
+                    "circular dependency between tasks: {}",
+                    cycle.iter().join(", ")
+                ),
+            });
+        }
+    }
+    Ok(problems)
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise config validate</bold>
+    ~/src/myproj/mise.toml: unknown field: tool
+    ~/src/myproj/mise.toml: task test depends on a task not found: biuld
+    mise ERROR found 2 problem(s) in 2 config file(s)
+
+    $ <bold>mise config validate --json</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    #[test]
+    fn test_config_validate() {
+        reset();
+        let out = assert_cli!("config", "validate", "--json");
+        assert_eq!(out, "[]");
+    }
+}
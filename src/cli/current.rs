@@ -1,11 +1,16 @@
+use std::path::PathBuf;
+
 use console::style;
 use eyre::{bail, Result};
+use indexmap::IndexMap;
+use serde_derive::Serialize;
 
 use crate::backend;
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
+use crate::cli::JSONTools;
 use crate::config::Config;
-use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::toolset::{ToolSource, ToolVersion, Toolset, ToolsetBuilder};
 
 /// Shows current active and installed runtime versions
 ///
@@ -18,6 +23,10 @@ pub struct Current {
     /// e.g.: ruby, node, cargo:eza, npm:prettier, etc.
     #[clap()]
     plugin: Option<BackendArg>,
+
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
 }
 
 impl Current {
@@ -50,7 +59,22 @@ impl Current {
             .into_iter()
             .find(|(p, _)| p.id() == tool.id())
         {
+            Some((_, versions)) if self.json => {
+                let versions = versions
+                    .iter()
+                    .map(|tv| JSONCurrent::new(&ts, tool, tv))
+                    .collect::<Vec<_>>();
+                let out = JSONTools::new(IndexMap::from([(tool.id().to_string(), versions)]));
+                miseprintln!("{}", serde_json::to_string_pretty(&out)?);
+            }
             Some((_, versions)) => {
+                let source = versions
+                    .first()
+                    .and_then(|tv| ts.versions.get(&tv.backend))
+                    .map(|tvl| &tvl.source);
+                if let Some(source @ ToolSource::Environment(..)) = source {
+                    info!("{} is set by {source}", tool.id());
+                }
                 miseprintln!(
                     "{}",
                     versions
@@ -71,6 +95,9 @@ impl Current {
     }
 
     fn all(&self, ts: Toolset) -> Result<()> {
+        if self.json {
+            return self.all_json(ts);
+        }
         for (plugin, versions) in ts.list_versions_by_plugin() {
             if versions.is_empty() {
                 continue;
@@ -96,6 +123,44 @@ impl Current {
         }
         Ok(())
     }
+
+    fn all_json(&self, ts: Toolset) -> Result<()> {
+        let mut out: IndexMap<String, Vec<JSONCurrent>> = IndexMap::new();
+        for (plugin, versions) in ts.list_versions_by_plugin() {
+            if versions.is_empty() {
+                continue;
+            }
+            let versions = versions
+                .iter()
+                .map(|tv| JSONCurrent::new(&ts, plugin.as_ref(), tv))
+                .collect();
+            out.insert(plugin.id().to_string(), versions);
+        }
+        miseprintln!("{}", serde_json::to_string_pretty(&JSONTools::new(out))?);
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct JSONCurrent {
+    version: String,
+    requested_version: String,
+    install_path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<IndexMap<String, String>>,
+    installed: bool,
+}
+
+impl JSONCurrent {
+    fn new(ts: &Toolset, tool: &dyn Backend, tv: &ToolVersion) -> Self {
+        Self {
+            version: tv.version.clone(),
+            requested_version: tv.request.version(),
+            install_path: tv.install_path(),
+            source: ts.versions.get(&tv.backend).map(|tvl| tvl.source.as_json()),
+            installed: tool.is_version_installed(tv, true),
+        }
+    }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -113,6 +178,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     # can output multiple versions
     $ <bold>mise current python</bold>
     3.11.0 3.10.0
+
+    $ <bold>mise current --json</bold>
+    {"schema_version": 1, "tools": {"node": [{"version": "20.0.0", "requested_version": "20", ...}]}}
 "#
 );
 
@@ -151,4 +219,28 @@ mod tests {
 
         env::remove_var("MISE_DUMMY_VERSION");
     }
+
+    #[test]
+    fn test_current_json() {
+        reset();
+        assert_cli_snapshot!("current", "tiny", "--json", @r###"
+        {
+          "schema_version": 1,
+          "tools": {
+            "tiny": [
+              {
+                "version": "3.1.0",
+                "requested_version": "3",
+                "install_path": "~/data/installs/tiny/3.1.0",
+                "source": {
+                  "type": ".tool-versions",
+                  "path": "~/cwd/.test-tool-versions"
+                },
+                "installed": true
+              }
+            ]
+          }
+        }
+        "###);
+    }
 }
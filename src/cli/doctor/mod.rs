@@ -0,0 +1,796 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::process::exit;
+use std::time::Duration;
+
+use console::{pad_str, style, Alignment};
+use indoc::formatdoc;
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde_json::json;
+
+use crate::backend::BackendType;
+use crate::build_time::built_info;
+use crate::cache::{self, PruneOptions};
+use crate::cli::version;
+use crate::cli::version::VERSION;
+use crate::config::{self, config_file, Config, Settings, DEFAULT_CONFIG_FILENAMES};
+use crate::file::display_path;
+use crate::git::Git;
+use crate::plugins::core::CORE_PLUGINS;
+use crate::plugins::PluginType;
+use crate::shell::ShellType;
+use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::ui::{info, prompt, style};
+use crate::{backend, cmd, dirs, duration, env, file, registry, shims};
+
+mod network;
+
+/// Check mise installation for possible problems
+///
+/// With --fix, mise offers to fix the problems it knows how to: missing or unused shims, stale
+/// cache files, broken symlinks in the installs directory and a missing activation line in the
+/// shell rc file. The rc file is backed up before it is changed.
+///
+/// With --json, the result of every check is printed with its id, status (ok, warn or error),
+/// message and a hint on how to resolve it. The ids are also shown when --fix prompts for a fix.
+#[derive(Debug, clap::Args)]
+#[clap(
+    visible_alias = "dr",
+    verbatim_doc_comment,
+    after_long_help = AFTER_LONG_HELP,
+    args_conflicts_with_subcommands = true
+)]
+pub struct Doctor {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
+    /// Fix the problems that can be fixed automatically, prompting before each one
+    #[clap(long)]
+    fix: bool,
+
+    /// Apply every fix without prompting
+    #[clap(long, short, requires = "fix")]
+    yes: bool,
+
+    /// Output the result of each check in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+
+    /// Exit with 0 even if there are errors
+    #[clap(long)]
+    no_fail: bool,
+
+    #[clap(skip)]
+    checks: Vec<Check>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Commands {
+    Network(network::DoctorNetwork),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Status {
+    Ok,
+    Warn,
+    Error,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Warn => write!(f, "warn"),
+            Status::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// the result of a check, a check can have several results such as one per untrusted config file
+#[derive(Debug)]
+struct Check {
+    id: &'static str,
+    status: Status,
+    message: String,
+    /// how to resolve the problem
+    hint: Option<String>,
+    fix: Option<Fix>,
+}
+
+impl Check {
+    fn new(id: &'static str, status: Status, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            status,
+            message: message.into(),
+            hint: None,
+            fix: None,
+        }
+    }
+
+    fn error(id: &'static str, message: impl Into<String>) -> Self {
+        Self::new(id, Status::Error, message)
+    }
+
+    fn warn(id: &'static str, message: impl Into<String>) -> Self {
+        Self::new(id, Status::Warn, message)
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    fn fix(mut self, fix: Option<Fix>) -> Self {
+        self.fix = fix;
+        self
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let strip = |s: &str| console::strip_ansi_codes(s).to_string();
+        json!({
+            "id": self.id,
+            "status": self.status.to_string(),
+            "message": strip(&self.message),
+            "hint": self.hint.as_deref().map(strip),
+            "fix": self.fix.as_ref().map(|f| f.to_string()),
+        })
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(hint) = &self.hint {
+            write!(f, "\n{hint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Doctor {
+    pub fn run(mut self) -> eyre::Result<()> {
+        if let Some(Commands::Network(cmd)) = self.command.take() {
+            return cmd.run();
+        }
+        if !self.json {
+            info::inline_section("version", &*VERSION)?;
+            #[cfg(unix)]
+            info::inline_section("activated", yn(env::is_activated()))?;
+            info::inline_section("shims_on_path", yn(shims_on_path()))?;
+
+            info::section("build_info", build_info())?;
+            info::section("shell", shell())?;
+            info::section("dirs", mise_dirs())?;
+            if let Some(age) = registry_cache_age() {
+                info::inline_section("registry_cache", age)?;
+            }
+        }
+
+        self.analyze_trust();
+        match Config::try_get() {
+            Ok(config) => {
+                self.pass("config", "config loaded");
+                self.analyze_config(config)?;
+            }
+            Err(err) => self.push(Check::error(
+                "config",
+                format!("failed to load config: {err}"),
+            )),
+        }
+
+        self.analyze_plugins();
+        self.analyze_installs();
+
+        self.section("env_vars", mise_env_vars())?;
+        self.analyze_settings()?;
+        self.analyze_cache();
+
+        if let Some(latest) = version::check_for_new_version(duration::HOURLY) {
+            let msg = format!(
+                "new mise version {latest} available, currently on {}",
+                *version::V
+            );
+            let cmd = style::nyellow("mise self-update");
+            let hint = format!("run {cmd} or update mise with the tool that installed it");
+            self.push(Check::error("mise_version", msg).hint(hint));
+        }
+        self.pass("mise_version", "mise is up to date");
+
+        if self.fix {
+            self.apply_fixes()?;
+        }
+
+        let failed = !self.no_fail && self.checks.iter().any(|c| c.status == Status::Error);
+        if self.json {
+            let checks = self.checks.iter().map(|c| c.to_json()).collect_vec();
+            miseprintln!(
+                "{}",
+                serde_json::to_string_pretty(&json!({ "checks": checks }))?
+            );
+        } else {
+            self.print_checks()?;
+        }
+        if failed {
+            exit(1);
+        }
+
+        Ok(())
+    }
+
+    fn print_checks(&self) -> eyre::Result<()> {
+        let warnings = self
+            .checks
+            .iter()
+            .filter(|c| c.status == Status::Warn)
+            .collect_vec();
+        if warnings.is_empty() {
+            miseprintln!("No warnings found");
+        } else {
+            let warnings_plural = if warnings.len() == 1 { "" } else { "s" };
+            let warning_summary = format!("{} warning{warnings_plural} found:", warnings.len());
+            miseprintln!("{}\n", style(warning_summary).yellow().bold());
+            for (i, check) in warnings.iter().enumerate() {
+                let num = style::nyellow(format!("{}.", i + 1));
+                miseprintln!("{num} {}\n", info::indent_by(check, "   ").trim_start());
+            }
+        }
+
+        let errors = self
+            .checks
+            .iter()
+            .filter(|c| c.status == Status::Error)
+            .collect_vec();
+        if errors.is_empty() {
+            miseprintln!("No problems found");
+        } else {
+            let errors_plural = if errors.len() == 1 { "" } else { "s" };
+            let error_summary = format!("{} problem{errors_plural} found:", errors.len());
+            miseprintln!("{}\n", style(error_summary).red().bold());
+            for (i, check) in errors.iter().enumerate() {
+                let num = style::nred(format!("{}.", i + 1));
+                miseprintln!("{num} {}\n", info::indent_by(check, "   ").trim_start());
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, check: Check) {
+        self.checks.push(check);
+    }
+
+    /// records that check `id` passed unless it found a problem
+    fn pass(&mut self, id: &'static str, message: &str) {
+        if !self.checks.iter().any(|c| c.id == id) {
+            self.push(Check::new(id, Status::Ok, message));
+        }
+    }
+
+    /// prints an info section unless the output is JSON
+    fn section<S: Display>(&self, header: &str, body: S) -> eyre::Result<()> {
+        if !self.json {
+            info::section(header, body)?;
+        }
+        Ok(())
+    }
+
+    fn analyze_trust(&mut self) {
+        for path in config::load_config_paths(&DEFAULT_CONFIG_FILENAMES) {
+            if !config_file::is_trusted(&path) {
+                let path = display_path(&path);
+                let cmd = style::nyellow(format!("mise trust {path}"));
+                self.push(
+                    Check::warn("trust", format!("{path} is not trusted"))
+                        .hint(format!("run {cmd} to trust it")),
+                );
+            }
+        }
+        self.pass("trust", "all config files are trusted");
+    }
+
+    fn analyze_settings(&mut self) -> eyre::Result<()> {
+        match Settings::try_get() {
+            Ok(settings) => {
+                self.section("settings", settings)?;
+                self.pass("settings", "settings loaded");
+            }
+            Err(err) => self.push(Check::error(
+                "settings",
+                format!("failed to load settings: {err}"),
+            )),
+        }
+        Ok(())
+    }
+    fn analyze_config(&mut self, config: impl AsRef<Config>) -> eyre::Result<()> {
+        let config = config.as_ref();
+
+        self.section("config_files", render_config_files(config))?;
+        self.section("backends", render_backends())?;
+        self.section("plugins", render_plugins())?;
+
+        for backend in backend::list() {
+            if let Some(plugin) = backend.plugin() {
+                if !plugin.is_installed() {
+                    let cmd = style::nyellow(format!("mise plugins install {}", plugin.name()));
+                    self.push(
+                        Check::error(
+                            "plugins_installed",
+                            format!("plugin {} is not installed", &plugin.name()),
+                        )
+                        .hint(format!("run {cmd} to install it")),
+                    );
+                    continue;
+                }
+            }
+        }
+        self.pass("plugins_installed", "all plugins are installed");
+
+        if !env::is_activated() && !shims_on_path() {
+            let shims = style::ncyan(display_path(*dirs::SHIMS));
+            if cfg!(windows) {
+                self.push(
+                    Check::error("activation", "mise shims are not on PATH")
+                        .hint(format!("Add this directory to PATH: {shims}")),
+                );
+            } else {
+                let cmd = style::nyellow("mise help activate");
+                let url = style::nunderline("https://mise.jdx.dev");
+                let hint = formatdoc!(
+                    r#"run {cmd} or read documentation at {url} for activation instructions.
+                        Alternatively, add the shims directory {shims} to PATH.
+                        Using the shims directory is preferred for non-interactive setups."#
+                );
+                self.push(
+                    Check::error("activation", "mise is not activated")
+                        .hint(hint)
+                        .fix(activation_fix()),
+                );
+            }
+        }
+        self.pass("activation", "mise is activated");
+
+        match ToolsetBuilder::new().build(config) {
+            Ok(ts) => {
+                self.pass("toolset", "toolset loaded");
+                self.analyze_shims(&ts);
+                self.analyze_toolset(&ts)?;
+            }
+            Err(err) => self.push(Check::error(
+                "toolset",
+                format!("failed to load toolset: {}", err),
+            )),
+        }
+
+        Ok(())
+    }
+
+    fn analyze_toolset(&mut self, ts: &Toolset) -> eyre::Result<()> {
+        let tools = ts
+            .list_current_versions()
+            .into_iter()
+            .map(|(f, tv)| match f.is_version_installed(&tv, true) {
+                true => (tv.to_string(), style::nstyle("")),
+                false => (tv.to_string(), style::ndim("(missing)")),
+            })
+            .collect_vec();
+        let max_tool_len = tools
+            .iter()
+            .map(|(t, _)| t.len())
+            .max()
+            .unwrap_or(0)
+            .min(20);
+        let tools = tools
+            .into_iter()
+            .map(|(t, s)| format!("{}  {s}", pad_str(&t, max_tool_len, Alignment::Left, None)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.section("toolset", tools)?;
+        Ok(())
+    }
+
+    fn analyze_shims(&mut self, toolset: &Toolset) {
+        let start_ms = std::time::Instant::now();
+        let mise_bin = file::which("mise").unwrap_or(env::MISE_BIN.clone());
+
+        if let Ok((missing, extra)) = shims::get_shim_diffs(mise_bin, toolset) {
+            let cmd = style::nyellow("mise reshim");
+
+            if !missing.is_empty() {
+                let problem = formatdoc!(
+                    "shims are missing
+                     Missing shims: {missing}",
+                    missing = missing.into_iter().join(", ")
+                );
+                self.push(
+                    Check::error("shims_missing", problem)
+                        .hint(format!("run {cmd} to create them"))
+                        .fix(Some(Fix::Reshim)),
+                );
+            }
+
+            if !extra.is_empty() {
+                let problem = formatdoc!(
+                    "unused shims are present
+                     Unused shims: {extra}",
+                    extra = extra.into_iter().join(", ")
+                );
+                self.push(
+                    Check::error("shims_unused", problem)
+                        .hint(format!("run {cmd} to remove them"))
+                        .fix(Some(Fix::Reshim)),
+                );
+            }
+            self.pass("shims_missing", "no shims are missing");
+            self.pass("shims_unused", "no unused shims");
+        }
+        trace!("Shim analysis took {:?}", start_ms.elapsed());
+    }
+
+    fn analyze_plugins(&mut self) {
+        for plugin in backend::list() {
+            let is_core = CORE_PLUGINS.contains_key(plugin.id());
+            let plugin_type = plugin.get_plugin_type();
+
+            if is_core && plugin_type == PluginType::Asdf {
+                let cmd = style::nyellow(format!("mise plugins uninstall {}", plugin.id()));
+                self.push(
+                    Check::warn(
+                        "core_plugins",
+                        format!("plugin {} overrides a core plugin", &plugin.id()),
+                    )
+                    .hint(format!("run {cmd} to use the core plugin")),
+                );
+            }
+        }
+        self.pass("core_plugins", "no plugins override core plugins");
+    }
+
+    fn analyze_installs(&mut self) {
+        let broken = file::dir_subdirs(&dirs::INSTALLS)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|tool| file::ls(&dirs::INSTALLS.join(tool)).unwrap_or_default())
+            .filter(|p| p.is_symlink() && !p.exists())
+            .collect_vec();
+        if !broken.is_empty() {
+            let problem = formatdoc!(
+                "broken symlinks in {installs}
+                 Broken symlinks: {broken}",
+                installs = display_path(*dirs::INSTALLS),
+                broken = broken.iter().map(display_path).join(", ")
+            );
+            let cmd = style::nyellow("mise doctor --fix");
+            self.push(
+                Check::warn("broken_symlinks", problem)
+                    .hint(format!("run {cmd} to remove them"))
+                    .fix(Some(Fix::RemoveSymlinks(broken))),
+            );
+        }
+        self.pass(
+            "broken_symlinks",
+            "no broken symlinks in the installs directory",
+        );
+    }
+
+    fn analyze_cache(&mut self) {
+        let Ok(settings) = Settings::try_get() else {
+            return;
+        };
+        let Some(age) = settings.cache_prune_age_duration() else {
+            return;
+        };
+        if !dirs::CACHE.exists() {
+            return;
+        }
+        let opts = PruneOptions {
+            dry_run: true,
+            verbose: false,
+            age: Some(age),
+            max_size: None,
+        };
+        match cache::prunable(&cache::dirs(), &opts) {
+            Ok(stale) if !stale.is_empty() => {
+                let cmd = style::nyellow("mise cache prune");
+                let size: u64 = stale.iter().map(|e| e.size).sum();
+                let problem = format!(
+                    "{} stale cache files ({})",
+                    stale.len(),
+                    file::format_bytes(size as f64)
+                );
+                self.push(
+                    Check::warn("stale_cache", problem)
+                        .hint(format!("run {cmd} to remove them"))
+                        .fix(Some(Fix::PruneCache(age))),
+                );
+            }
+            Ok(_) => self.pass("stale_cache", "no stale cache files"),
+            Err(err) => debug!("failed to check cache for stale files: {err:#}"),
+        }
+    }
+
+    fn apply_fixes(&mut self) -> eyre::Result<()> {
+        let yes = self.yes || Settings::try_get().is_ok_and(|s| s.yes);
+        // several problems can share a fix, e.g. missing and unused shims
+        let mut applied: Vec<Fix> = vec![];
+        for check in &mut self.checks {
+            let Some(fix) = check.fix.take() else {
+                continue;
+            };
+            if !applied.contains(&fix) {
+                if !yes && !prompt::confirm(format!("{fix} ({})?", check.id))? {
+                    check.fix = Some(fix);
+                    continue;
+                }
+                if let Err(err) = fix.apply() {
+                    check.status = Status::Error;
+                    check.message = format!("failed to {fix}: {err:#}");
+                    check.hint = None;
+                    continue;
+                }
+                info!("fixed: {fix}");
+                applied.push(fix.clone());
+            }
+            check.status = Status::Ok;
+            check.message = format!("fixed: {fix}");
+            check.hint = None;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Fix {
+    Reshim,
+    PruneCache(Duration),
+    RemoveSymlinks(Vec<PathBuf>),
+    AddActivation { rc: PathBuf, line: String },
+}
+
+impl Fix {
+    pub(crate) fn apply(&self) -> eyre::Result<()> {
+        match self {
+            Fix::Reshim => {
+                let config = Config::try_get()?;
+                let ts = ToolsetBuilder::new().build(&config)?;
+                shims::reshim(&ts)?;
+            }
+            Fix::PruneCache(age) => {
+                let opts = PruneOptions {
+                    dry_run: false,
+                    verbose: false,
+                    age: Some(*age),
+                    max_size: None,
+                };
+                cache::prune(&cache::dirs(), &opts)?;
+            }
+            Fix::RemoveSymlinks(paths) => {
+                for path in paths {
+                    file::remove_file(path)?;
+                }
+            }
+            Fix::AddActivation { rc, line } => {
+                // running --fix again must not add the line twice
+                let body = match rc.exists() {
+                    true => file::read_to_string(rc)?,
+                    false => String::new(),
+                };
+                if body.contains(line.as_str()) {
+                    return Ok(());
+                }
+                if rc.exists() {
+                    let backup = PathBuf::from(format!("{}.mise.bak", rc.display()));
+                    file::copy(rc, &backup)?;
+                    info!(
+                        "backed up {} to {}",
+                        display_path(rc),
+                        display_path(&backup)
+                    );
+                }
+                let sep = match body.is_empty() || body.ends_with('\n') {
+                    true => "",
+                    false => "\n",
+                };
+                file::create_dir_all(rc.parent().unwrap())?;
+                file::write(rc, format!("{body}{sep}{line}\n"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for Fix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fix::Reshim => write!(f, "run mise reshim"),
+            Fix::PruneCache(_) => write!(f, "remove stale cache files"),
+            Fix::RemoveSymlinks(paths) => write!(f, "remove {} broken symlinks", paths.len()),
+            Fix::AddActivation { rc, line } => write!(f, "add `{line}` to {}", display_path(rc)),
+        }
+    }
+}
+
+/// the rc file and line that activate mise for the current shell, if mise isn't already in it
+pub(crate) fn activation_fix() -> Option<Fix> {
+    let mise = match file::which("mise") {
+        Some(_) => "mise".to_string(),
+        None => env::MISE_BIN.display().to_string(),
+    };
+    let (rc, line) = match ShellType::load()? {
+        ShellType::Bash => (
+            env::HOME.join(".bashrc"),
+            format!(r#"eval "$({mise} activate bash)""#),
+        ),
+        ShellType::Zsh => (
+            env::var_path("ZDOTDIR")
+                .unwrap_or(env::HOME.to_path_buf())
+                .join(".zshrc"),
+            format!(r#"eval "$({mise} activate zsh)""#),
+        ),
+        ShellType::Fish => (
+            env::XDG_CONFIG_HOME.join("fish/config.fish"),
+            format!("{mise} activate fish | source"),
+        ),
+        _ => return None,
+    };
+    // the user may have activated mise in a way we don't recognize, only fix it if there is no
+    // mention of mise activate at all
+    if file::read_to_string(&rc)
+        .is_ok_and(|body| body.contains("activate") && body.contains("mise"))
+    {
+        return None;
+    }
+    Some(Fix::AddActivation { rc, line })
+}
+
+pub(crate) fn shims_on_path() -> bool {
+    env::PATH.contains(&dirs::SHIMS.to_path_buf())
+}
+
+fn yn(b: bool) -> String {
+    if b {
+        style("yes").green().to_string()
+    } else {
+        style("no").red().to_string()
+    }
+}
+
+fn mise_dirs() -> String {
+    [
+        ("data", &*dirs::DATA),
+        ("config", &*dirs::CONFIG),
+        ("cache", &*dirs::CACHE),
+        ("state", &*dirs::STATE),
+        ("shims", &*dirs::SHIMS),
+    ]
+    .iter()
+    .map(|(k, p)| format!("{k}: {}", display_path(p)))
+    .join("\n")
+}
+
+/// how old the registry downloaded from `registry_url` is, `None` if that isn't set
+fn registry_cache_age() -> Option<String> {
+    Settings::try_get().ok()?.registry_url.as_ref()?;
+    Some(match registry::cache_age() {
+        Some(age) => {
+            let age = Duration::from_secs(age.as_secs());
+            format!("{} old", humantime::format_duration(age))
+        }
+        None => "not downloaded".to_string(),
+    })
+}
+
+fn mise_env_vars() -> String {
+    let vars = env::vars()
+        .filter(|(k, _)| k.starts_with("MISE_"))
+        .collect::<Vec<(String, String)>>();
+    if vars.is_empty() {
+        return "(none)".to_string();
+    }
+    vars.iter().map(|(k, v)| format!("{k}={v}")).join("\n")
+}
+
+fn render_config_files(config: &Config) -> String {
+    config
+        .config_files
+        .keys()
+        .rev()
+        .map(display_path)
+        .join("\n")
+}
+
+fn render_backends() -> String {
+    let mut s = vec![];
+    let backends = backend::list_backend_types()
+        .into_iter()
+        .filter(|f| *f != BackendType::Asdf);
+    for b in backends {
+        s.push(format!("{}", b));
+    }
+    s.join("\n")
+}
+
+fn render_plugins() -> String {
+    let plugins = backend::list()
+        .into_iter()
+        .filter(|b| {
+            b.plugin()
+                .is_some_and(|p| p.is_installed() && b.get_type() == BackendType::Asdf)
+        })
+        .collect::<Vec<_>>();
+    let max_plugin_name_len = plugins
+        .iter()
+        .map(|p| p.id().len())
+        .max()
+        .unwrap_or(0)
+        .min(40);
+    plugins
+        .into_par_iter()
+        .map(|p| {
+            let padded_name = pad_str(p.id(), max_plugin_name_len, Alignment::Left, None);
+            let extra = match p.get_plugin_type() {
+                PluginType::Asdf => {
+                    let git = Git::new(dirs::PLUGINS.join(p.id()));
+                    match git.get_remote_url() {
+                        Some(url) => {
+                            let sha = git
+                                .current_sha_short()
+                                .unwrap_or_else(|_| "(unknown)".to_string());
+                            format!("{url}#{sha}")
+                        }
+                        None => "".to_string(),
+                    }
+                }
+                PluginType::Core => "(core)".to_string(),
+            };
+            format!("{padded_name}  {}", style::ndim(extra))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn build_info() -> String {
+    let mut s = vec![];
+    s.push(format!("Target: {}", built_info::TARGET));
+    s.push(format!("Features: {}", built_info::FEATURES_STR));
+    s.push(format!("Built: {}", built_info::BUILT_TIME_UTC));
+    s.push(format!("Rust Version: {}", built_info::RUSTC_VERSION));
+    s.push(format!("Profile: {}", built_info::PROFILE));
+    s.join("\n")
+}
+
+fn shell() -> String {
+    match ShellType::load().map(|s| s.to_string()) {
+        Some(shell) => {
+            let shell_cmd = if env::SHELL.ends_with(shell.as_str()) {
+                &*env::SHELL
+            } else {
+                &shell
+            };
+            let version = cmd!(shell_cmd, "--version")
+                .read()
+                .unwrap_or_else(|e| format!("failed to get shell version: {}", e));
+            format!("{shell_cmd}\n{version}")
+        }
+        None => "(unknown)".to_string(),
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise doctor</bold>
+    [WARN] plugin node is not installed
+
+    $ <bold>mise doctor --fix</bold>
+    $ <bold>mise doctor --fix --yes</bold>
+
+    # check that the hosts mise downloads from can be reached
+    $ <bold>mise doctor network</bold>
+
+    # fail a CI job on any error
+    $ <bold>mise doctor --json</bold>
+    {"checks": [{"id": "activation", "status": "ok", "message": "mise is activated", ...}, ...]}
+"#
+);
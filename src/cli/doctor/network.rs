@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use eyre::{bail, Report};
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde_derive::Serialize;
+use tabled::{Table, Tabled};
+
+use crate::config::Settings;
+use crate::env;
+use crate::file::display_path;
+use crate::http::{proxy_description, HTTP};
+use crate::ui::table;
+
+/// Check that the hosts mise fetches from can be reached
+///
+/// Sends a request to each endpoint with the same client mise uses for everything else so the
+/// proxy, CA certificate and timeout settings apply. Any response counts as reachable, the
+/// latency is the time until the response headers arrived. Failures say whether it was DNS,
+/// connecting, TLS, the proxy or a timeout that failed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct DoctorNetwork {
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+#[tabled(rename_all = "PascalCase")]
+struct Endpoint {
+    #[tabled(rename = "Endpoint")]
+    url: String,
+    status: String,
+    /// the http status of the response
+    #[tabled(skip)]
+    http_status: Option<u16>,
+    #[tabled(display_with = "display_latency")]
+    latency_ms: Option<u128>,
+    #[tabled(display_with = "display_option")]
+    proxy: Option<String>,
+    #[tabled(skip)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct NetworkReport {
+    endpoints: Vec<Endpoint>,
+    ca_certs: Option<String>,
+    tls_verify: bool,
+}
+
+impl DoctorNetwork {
+    pub fn run(self) -> eyre::Result<()> {
+        let endpoints = endpoints()
+            .into_par_iter()
+            .map(|url| probe(&url))
+            .collect::<Vec<_>>();
+        let report = NetworkReport {
+            ca_certs: env::MISE_CA_CERTS.as_ref().map(display_path),
+            tls_verify: !*env::MISE_TLS_DANGER_ACCEPT_INVALID_CERTS,
+            endpoints,
+        };
+        let failed = report
+            .endpoints
+            .iter()
+            .filter(|e| e.error.is_some())
+            .count();
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            if let Some(path) = &report.ca_certs {
+                miseprintln!("ca certs: {path}");
+            }
+            if !report.tls_verify {
+                miseprintln!("tls verification: disabled");
+            }
+            let errors = report
+                .endpoints
+                .iter()
+                .filter_map(|e| Some(format!("{}: {}", e.url, e.error.as_ref()?)))
+                .collect_vec();
+            let mut table = Table::new(report.endpoints);
+            table::default_style(&mut table, false);
+            miseprintln!("{table}");
+            for error in errors {
+                miseprintln!("{error}");
+            }
+        }
+        if failed > 0 {
+            bail!("{failed} of the endpoints could not be reached");
+        }
+        Ok(())
+    }
+}
+
+/// the hosts mise talks to with the current settings
+fn endpoints() -> Vec<String> {
+    let settings = Settings::get();
+    let mut urls = vec![
+        "https://api.github.com/rate_limit".to_string(),
+        "https://github.com/".to_string(),
+        "https://objects.githubusercontent.com/".to_string(),
+    ];
+    if settings.use_versions_host {
+        // same as fetch_remote_versions_from_mise
+        match settings.paranoid {
+            true => urls.push("https://mise-versions.jdx.dev/".into()),
+            false => urls.push("http://mise-versions.jdx.dev/".into()),
+        }
+    }
+    if let Some(url) = &settings.registry_url {
+        urls.push(url.clone());
+    }
+    urls
+}
+
+fn probe(url: &str) -> Endpoint {
+    let proxy = proxy_description(url);
+    match HTTP.probe(url) {
+        Ok((status, latency)) => Endpoint {
+            url: url.to_string(),
+            status: "ok".into(),
+            http_status: Some(status.as_u16()),
+            latency_ms: Some(latency.as_millis()),
+            proxy,
+            error: None,
+        },
+        Err(err) => Endpoint {
+            url: url.to_string(),
+            status: classify(&err).into(),
+            http_status: None,
+            latency_ms: None,
+            error: Some(describe(&err)),
+            proxy,
+        },
+    }
+}
+
+/// which part of getting a response failed
+fn classify(err: &Report) -> &'static str {
+    let chain = describe(err).to_lowercase();
+    let reqwest = err.downcast_ref::<reqwest::Error>();
+    if reqwest.is_some_and(|e| e.is_timeout()) {
+        "timeout"
+    } else if chain.contains("dns error") || chain.contains("failed to lookup address") {
+        "dns error"
+    } else if chain.contains("certificate") || chain.contains("tls") || chain.contains("ssl") {
+        "tls error"
+    } else if chain.contains("proxy") || chain.contains("socks") {
+        "proxy error"
+    } else if reqwest.is_some_and(|e| e.is_connect()) {
+        "connect error"
+    } else {
+        "error"
+    }
+}
+
+/// the error and its causes on one line, reqwest puts the useful part in the sources. Causes
+/// that are already part of the message before them are left out.
+fn describe(err: &Report) -> String {
+    let mut msgs: Vec<String> = vec![];
+    for msg in err.chain().map(|e| e.to_string()) {
+        if !msgs.last().is_some_and(|prev| prev.ends_with(&msg)) {
+            msgs.push(msg);
+        }
+    }
+    msgs.join(": ")
+}
+
+fn display_latency(latency: &Option<u128>) -> String {
+    match latency {
+        Some(ms) => humantime::format_duration(Duration::from_millis(*ms as u64)).to_string(),
+        None => "-".into(),
+    }
+}
+
+fn display_option(s: &Option<String>) -> String {
+    s.clone().unwrap_or_else(|| "-".into())
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise doctor network</bold>
+    Endpoint                                Status     Latency  Proxy
+    https://api.github.com/rate_limit       ok         85ms     -
+    https://github.com/                     ok         112ms    -
+    https://objects.githubusercontent.com/  dns error  -        -
+
+    $ <bold>mise doctor network --json</bold>
+    {"endpoints": [{"url": "https://api.github.com/rate_limit", "status": "ok", "http_status": 200, "latency_ms": 85, ...}, ...], ...}
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test::reset;
+
+    #[test]
+    fn test_probe() {
+        reset();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            use std::io::{Read, Write};
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0; 4096]);
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        });
+        let endpoint = probe(&url);
+        assert_eq!(endpoint.status, "ok");
+        assert_eq!(endpoint.http_status, Some(404));
+        assert!(endpoint.error.is_none());
+
+        let endpoint = probe("http://nothere.invalid/");
+        assert_eq!(endpoint.status, "dns error");
+        assert!(endpoint.error.is_some());
+    }
+}
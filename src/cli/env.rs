@@ -1,9 +1,16 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use eyre::Result;
+use serde_derive::Serialize;
 
 use crate::cli::args::ToolArg;
 use crate::config::Config;
+use crate::env::PATH_KEY;
+use crate::redactions::Redactor;
 use crate::shell::{get_shell, ShellType};
 use crate::toolset::{InstallOptions, Toolset, ToolsetBuilder};
+use crate::ui::style;
 
 /// Exports env vars to activate mise a single time
 ///
@@ -23,6 +30,32 @@ pub struct Env {
     /// Shell type to generate environment variables for
     #[clap(long, short, overrides_with = "json")]
     shell: Option<ShellType>,
+
+    /// Show the env vars that are added, removed or changed compared to the current environment
+    ///
+    /// PATH is shown entry by entry. Values of env vars listed in `redactions` are masked.
+    #[clap(long, conflicts_with_all = ["json", "shell"])]
+    diff: bool,
+
+    /// Output the changes to apply to the current environment as JSON
+    ///
+    /// For wrappers that apply them to their own process: `{"ADD": {...}, "REMOVE": [...],
+    /// "PATH": [...]}` where ADD has the vars to set, REMOVE the vars to unset and PATH the
+    /// entries of the new PATH. Values are only masked with `--redacted`.
+    #[clap(long, conflicts_with_all = ["json", "shell", "diff"])]
+    export_json: bool,
+
+    /// Only output these env vars, e.g.: `--only PATH,GOPATH`
+    ///
+    /// Names mise doesn't set are skipped with a warning.
+    #[clap(long, value_name = "VARS", value_delimiter = ',', conflicts_with_all = ["diff", "export_json"])]
+    only: Vec<String>,
+
+    /// Mask the values of env vars listed in `redactions` with `***`
+    ///
+    /// This is the default when stdout is a terminal. The output can't be used with eval then.
+    #[clap(long)]
+    redacted: bool,
 }
 
 impl Env {
@@ -32,7 +65,11 @@ impl Env {
         ts.install_arg_versions(&config, &InstallOptions::new())?;
         ts.notify_if_versions_missing();
 
-        if self.json {
+        if self.diff {
+            self.output_diff(&config, ts)
+        } else if self.export_json {
+            self.output_export_json(&config, ts)
+        } else if self.json {
             self.output_json(&config, ts)
         } else {
             self.output_shell(&config, ts)
@@ -40,7 +77,7 @@ impl Env {
     }
 
     fn output_json(&self, config: &Config, ts: Toolset) -> Result<()> {
-        let env = ts.env_with_path(config)?;
+        let env = self.redact(config, self.filter(ts.env_with_path(config)?));
         miseprintln!("{}", serde_json::to_string_pretty(&env)?);
         Ok(())
     }
@@ -48,13 +85,185 @@ impl Env {
     fn output_shell(&self, config: &Config, ts: Toolset) -> Result<()> {
         let default_shell = get_shell(Some(ShellType::Bash)).unwrap();
         let shell = get_shell(self.shell).unwrap_or(default_shell);
-        for (k, v) in ts.env_with_path(config)? {
+        let env = ts.env_with_path(config)?;
+        let removed = config
+            .env_remove()?
+            .iter()
+            .filter(|k| !env.contains_key(*k))
+            .filter(|k| self.only.is_empty() || self.only.contains(k))
+            .cloned()
+            .collect::<Vec<_>>();
+        for (k, v) in self.redact(config, self.filter(env)) {
             let k = k.to_string();
             let v = v.to_string();
             miseprint!("{}", shell.set_env(&k, &v))?;
         }
+        for k in removed {
+            miseprint!("{}", shell.unset_env(&k))?;
+        }
+        Ok(())
+    }
+
+    fn output_diff(&self, config: &Config, ts: Toolset) -> Result<()> {
+        let env = ts.env_with_path(config)?;
+        let current: BTreeMap<String, String> = std::env::vars().collect();
+        let redactor = config.redactor(&env);
+        let removed = removed_vars(config, &current)?;
+        for line in diff_env(&current, &env, &removed, &redactor) {
+            miseprintln!("{line}");
+        }
+        Ok(())
+    }
+
+    fn output_export_json(&self, config: &Config, ts: Toolset) -> Result<()> {
+        let env = ts.env_with_path(config)?;
+        let current: BTreeMap<String, String> = std::env::vars().collect();
+        let removed = removed_vars(config, &current)?;
+        let mut changes = env_changes(&current, &env, removed);
+        if self.redacted {
+            let redactor = config.redactor(&env);
+            for v in changes.add.values_mut() {
+                *v = redactor.redact(v);
+            }
+        }
+        miseprintln!("{}", serde_json::to_string_pretty(&changes)?);
         Ok(())
     }
+
+    /// the vars named with `--only`, or all of them without it
+    fn filter(&self, env: BTreeMap<String, String>) -> BTreeMap<String, String> {
+        if self.only.is_empty() {
+            return env;
+        }
+        for k in self.only.iter().filter(|k| !env.contains_key(*k)) {
+            warn!("{k} is not set by mise, it is not included");
+        }
+        env.into_iter()
+            .filter(|(k, _)| self.only.contains(k))
+            .collect()
+    }
+
+    fn redact(&self, config: &Config, env: BTreeMap<String, String>) -> BTreeMap<String, String> {
+        if !self.redacted && !console::user_attended() {
+            return env;
+        }
+        let redactor = config.redactor(&env);
+        env.into_iter()
+            .map(|(k, v)| {
+                let v = redactor.redact(&v);
+                (k, v)
+            })
+            .collect()
+    }
+}
+
+/// the vars mise unsets that are set in `current`
+fn removed_vars(config: &Config, current: &BTreeMap<String, String>) -> Result<Vec<String>> {
+    Ok(config
+        .env_results()?
+        .env_remove
+        .iter()
+        .filter(|k| current.contains_key(*k))
+        .map(|k| k.to_string())
+        .collect())
+}
+
+/// what `mise env --export-json` prints
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct EnvChanges {
+    /// vars that are new or have a different value than in the current env, except PATH
+    add: BTreeMap<String, String>,
+    remove: Vec<String>,
+    /// the entries of the new PATH
+    path: Vec<PathBuf>,
+}
+
+fn env_changes(
+    current: &BTreeMap<String, String>,
+    env: &BTreeMap<String, String>,
+    remove: Vec<String>,
+) -> EnvChanges {
+    let add = env
+        .iter()
+        .filter(|(k, _)| *k != &*PATH_KEY)
+        .filter(|(k, v)| current.get(*k) != Some(v))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    let path = env
+        .get(&*PATH_KEY)
+        .or(current.get(&*PATH_KEY))
+        .map(|p| std::env::split_paths(p).collect())
+        .unwrap_or_default();
+    EnvChanges { add, remove, path }
+}
+
+/// lines describing how `env` differs from `current`, `removed` are the vars mise unsets
+fn diff_env(
+    current: &BTreeMap<String, String>,
+    env: &BTreeMap<String, String>,
+    removed: &[String],
+    redactor: &Redactor,
+) -> Vec<String> {
+    let mut lines = vec![];
+    for (k, v) in env {
+        let v = redactor.redact(v);
+        match current.get(k).map(|c| redactor.redact(c)) {
+            None => lines.push(style::ngreen(format!("+ {k}={v}")).to_string()),
+            Some(c) if c == v => {}
+            Some(_) if k == &*PATH_KEY => {}
+            Some(c) => lines.push(style::nyellow(format!("~ {k}={c} -> {v}")).to_string()),
+        }
+    }
+    for k in removed {
+        lines.push(style::nred(format!("- {k}")).to_string());
+    }
+    if let (Some(old), Some(new)) = (current.get(&*PATH_KEY), env.get(&*PATH_KEY)) {
+        if old != new {
+            let old = std::env::split_paths(old).collect::<Vec<_>>();
+            let new = std::env::split_paths(new).collect::<Vec<_>>();
+            lines.push(style::nyellow(format!("~ {}", &*PATH_KEY)).to_string());
+            for (op, p) in diff_paths(&old, &new) {
+                let p = p.display();
+                lines.push(match op {
+                    '+' => style::ngreen(format!("  + {p}")).to_string(),
+                    '-' => style::nred(format!("  - {p}")).to_string(),
+                    _ => style::ndim(format!("    {p}")).to_string(),
+                });
+            }
+        }
+    }
+    lines
+}
+
+/// an ordered diff of two lists of paths using their longest common subsequence, each entry is
+/// `+` if only in `new`, `-` if only in `old` or ` ` if in both
+fn diff_paths<'a, T: PartialEq>(old: &'a [T], new: &'a [T]) -> Vec<(char, &'a T)> {
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = vec![];
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push((' ', &old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push(('+', &new[j]));
+            j += 1;
+        } else {
+            out.push(('-', &old[i]));
+            i += 1;
+        }
+    }
+    out
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -64,13 +273,20 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>eval "$(mise env -s zsh)"</bold>
     $ <bold>mise env -s fish | source</bold>
     $ <bold>execx($(mise env -s xonsh))</bold>
+    $ <bold>eval (mise env -s elvish | slurp)</bold>
+    $ <bold>mise env -s pwsh | Out-String | Invoke-Expression</bold>
+    $ <bold>mise env --only PATH,GOPATH</bold>   # just these vars
+    $ <bold>mise env --diff</bold>   # show what would change in the current shell
+    $ <bold>mise env --export-json</bold>   # the changes as JSON for wrappers to apply
 "#
 );
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::env;
 
+    use insta::assert_snapshot;
     use pretty_assertions::assert_str_eq;
     use test_log::test;
 
@@ -125,6 +341,17 @@ mod tests {
         assert_str_eq!(grep(stdout, "JDXCODE"), "export JDXCODE_TINY=2.1.0");
     }
 
+    #[test]
+    fn test_env_shells() {
+        reset();
+        let stdout = assert_cli!("env", "tiny@2", "-s", "elvish");
+        assert_str_eq!(grep(stdout, "JDXCODE"), "set-env 'JDXCODE_TINY' '2.1.0'");
+        let stdout = assert_cli!("env", "tiny@2", "-s", "pwsh");
+        assert_str_eq!(grep(stdout, "JDXCODE"), "$Env:JDXCODE_TINY = '2.1.0'");
+        let stdout = assert_cli!("env", "tiny@2", "-s", "nu");
+        assert_str_eq!(grep(stdout, "JDXCODE"), "set,JDXCODE_TINY,\"2.1.0\"");
+    }
+
     #[test]
     fn test_env_default_shell() {
         reset();
@@ -138,4 +365,64 @@ mod tests {
         reset();
         assert_cli_snapshot!("env", "-J");
     }
+
+    #[test]
+    fn test_env_only() {
+        reset();
+        let stdout = assert_cli!("env", "tiny@2", "-s", "bash", "--only", "JDXCODE_TINY,NOPE");
+        assert_str_eq!(stdout.trim(), "export JDXCODE_TINY=2.1.0");
+        let stdout = assert_cli!("env", "tiny@2", "-J", "--only", "JDXCODE_TINY");
+        assert_snapshot!(stdout, @r#"
+        {
+          "JDXCODE_TINY": "2.1.0"
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_env_diff() {
+        reset();
+        let stdout = assert_cli!("env", "tiny@2", "--diff");
+        assert_str_eq!(grep(stdout, "JDXCODE"), "+ JDXCODE_TINY=2.1.0");
+    }
+
+    #[test]
+    fn test_env_changes() {
+        let current = BTreeMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("SAME".to_string(), "1".to_string()),
+            ("CHANGED".to_string(), "old".to_string()),
+            ("UNSET".to_string(), "x".to_string()),
+        ]);
+        let mut env = current.clone();
+        env.insert("PATH".into(), "/mise/bin:/usr/bin".into());
+        env.insert("CHANGED".into(), "new".into());
+        env.insert("NEW".into(), "a b".into());
+        env.remove("UNSET");
+        let changes = super::env_changes(&current, &env, vec!["UNSET".into()]);
+        assert_snapshot!(serde_json::to_string(&changes).unwrap(), @r###"{"ADD":{"CHANGED":"new","NEW":"a b"},"REMOVE":["UNSET"],"PATH":["/mise/bin","/usr/bin"]}"###);
+    }
+
+    #[test]
+    fn test_env_export_json() {
+        reset();
+        let stdout = assert_cli!("env", "tiny@2", "--export-json");
+        let changes: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(changes["ADD"]["JDXCODE_TINY"], "2.1.0");
+        assert_eq!(
+            changes["PATH"][0],
+            dirs::INSTALLS.join("tiny/2/bin").to_string_lossy().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_diff_paths() {
+        let old = ["/a", "/b", "/c"];
+        let new = ["/x", "/a", "/c", "/y"];
+        let diff = super::diff_paths(&old, &new)
+            .into_iter()
+            .map(|(op, p)| format!("{op}{p}"))
+            .collect::<Vec<_>>();
+        assert_eq!(diff, vec!["+/x", " /a", "-/b", " /c", "+/y"]);
+    }
 }
@@ -24,6 +24,11 @@ use crate::toolset::{InstallOptions, ToolsetBuilder};
 /// includes "node 20" but you run `mise exec python@3.11`; it will still load node@20.
 ///
 /// The "--" separates runtimes from the commands to pass along to the subprocess.
+/// Everything after the first "--" is passed to the subprocess verbatim, including
+/// flags like `-v` or `--help` and any further "--".
+///
+/// With `-C <DIR>` mise changes to DIR before loading config so the tools and env are the ones
+/// DIR would use, not the current directory's.
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "x", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Exec {
@@ -63,12 +68,21 @@ impl Exec {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: false,
+            ..Default::default()
         };
         ts.install_arg_versions(&config, &opts)?;
         ts.notify_if_versions_missing();
 
         let (program, args) = parse_command(&env::SHELL, &self.command, &self.c);
         let env = ts.env_with_path(&config)?;
+        // the command inherits mise's environment so vars `[env]` unsets are removed from it
+        for k in config
+            .env_remove()?
+            .iter()
+            .filter(|k| !env.contains_key(*k))
+        {
+            env::remove_var(k);
+        }
 
         self.exec(program, args, env)
     }
@@ -96,7 +110,7 @@ impl Exec {
         U: IntoIterator,
         U::Item: Into<OsString>,
     {
-        let cwd = crate::dirs::CWD.clone().unwrap_or_default();
+        let cwd = crate::dirs::cwd().unwrap_or_default();
         let program = program.to_executable();
         let path = env.get(&*env::PATH_KEY).map(OsString::from);
         let program = which::which_in(program, path, cwd)?;
@@ -189,4 +203,11 @@ mod tests {
         assert_cli!("exec", "-C", "/tmp", "--", "pwd");
         env::set_current_dir(cwd).unwrap();
     }
+
+    #[test]
+    fn test_exec_cd_missing() {
+        reset();
+        let err = assert_cli_err!("exec", "-C", "/does/not/exist", "--", "pwd");
+        assert_snapshot!(err, @"--cd /does/not/exist: no such directory");
+    }
 }
@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use eyre::bail;
+use itertools::Itertools;
+
+use crate::cli::version;
+use crate::config::{Config, Settings};
+use crate::file;
+use crate::file::display_path;
+use crate::git::Git;
+use crate::hash;
+use crate::http::HTTP;
+use crate::toolset::ToolsetBuilder;
+
+/// platforms mise publishes release tarballs for, as `os-arch`
+const PLATFORMS: &[&str] = &[
+    "linux-x64",
+    "linux-arm64",
+    "linux-armv7",
+    "macos-x64",
+    "macos-arm64",
+];
+
+/// [experimental] Generate a script that installs mise and the project's tools
+///
+/// The script downloads a pinned version of mise, checks it against the sha256 from the
+/// release's SHASUMS256.txt and then runs `mise install` for the exact tool versions the
+/// project uses right now. It only needs bash, curl and tar so it can be committed and run
+/// on a fresh machine or CI runner. Running it again doesn't download mise if the pinned
+/// version is already installed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Bootstrap {
+    /// the version of mise to install, defaults to the current version
+    #[clap(long, short = 'V', value_name = "VERSION")]
+    version: Option<String>,
+    /// write to ./bootstrap and make it executable, fails if it already exists
+    #[clap(long, short)]
+    write: bool,
+}
+
+impl Bootstrap {
+    pub fn run(self) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("generate bootstrap")?;
+        let output = self.generate()?;
+        if self.write {
+            let path = Git::get_root()?.join("bootstrap");
+            if path.exists() {
+                bail!("{} already exists", display_path(&path));
+            }
+            file::write(&path, &output)?;
+            file::make_executable(&path)?;
+            miseprintln!("Wrote to {}", display_path(&path));
+        } else {
+            miseprintln!("{output}");
+        }
+        Ok(())
+    }
+
+    fn generate(&self) -> eyre::Result<String> {
+        let config = Config::try_get()?;
+        let root = Git::get_root()?;
+        let version = match &self.version {
+            Some(v) => v.trim_start_matches('v').to_string(),
+            None => version::V.to_string(),
+        };
+        let shasums = HTTP.get_text(format!("{}/SHASUMS256.txt", base_url(&version)))?;
+        let checksums = PLATFORMS
+            .iter()
+            .filter_map(|p| {
+                let asset = format!("mise-v{version}-{p}.tar.gz");
+                hash::find_shasum(&shasums, &asset).map(|c| (p.to_string(), c))
+            })
+            .collect_vec();
+        if checksums.is_empty() {
+            bail!("no checksums for mise v{version} release tarballs in SHASUMS256.txt");
+        }
+        let tools = project_versions(&config, &root)?;
+        Ok(script(&version, &checksums, &tools))
+    }
+}
+
+fn base_url(version: &str) -> String {
+    format!("https://github.com/jdx/mise/releases/download/v{version}")
+}
+
+/// the resolved versions of the tools in the project, these are the lockfile versions if there is one
+fn project_versions(config: &Config, root: &Path) -> eyre::Result<Vec<String>> {
+    let ts = ToolsetBuilder::new().build(config)?;
+    Ok(ts
+        .versions
+        .values()
+        .filter(|tvl| tvl.source.path().is_some_and(|p| p.starts_with(root)))
+        .flat_map(|tvl| tvl.versions.iter().map(|tv| tv.to_string()))
+        .collect())
+}
+
+fn script(version: &str, checksums: &[(String, String)], tools: &[String]) -> String {
+    let base_url = base_url(version);
+    let checksums = checksums
+        .iter()
+        .map(|(platform, checksum)| format!("  {platform}) echo \"{checksum}\" ;;"))
+        .join("\n");
+    let install = match tools {
+        [] => r#""$MISE_INSTALL_PATH" install"#.to_string(),
+        tools => format!(r#""$MISE_INSTALL_PATH" install {}"#, tools.join(" ")),
+    };
+    format!(
+        r#"#!/usr/bin/env bash
+# generated by `mise generate bootstrap`
+set -euo pipefail
+
+MISE_VERSION="{version}"
+MISE_INSTALL_PATH="${{MISE_INSTALL_PATH:-$HOME/.local/bin/mise}}"
+
+mise_platform() {{
+  local os arch
+  case "$(uname -s)" in
+  Linux) os="linux" ;;
+  Darwin) os="macos" ;;
+  *) echo "mise: unsupported OS: $(uname -s)" >&2 && exit 1 ;;
+  esac
+  case "$(uname -m)" in
+  x86_64 | amd64) arch="x64" ;;
+  aarch64 | arm64) arch="arm64" ;;
+  armv7l) arch="armv7" ;;
+  *) echo "mise: unsupported architecture: $(uname -m)" >&2 && exit 1 ;;
+  esac
+  echo "$os-$arch"
+}}
+
+mise_checksum() {{
+  case "$1" in
+{checksums}
+  *) echo "mise: no mise $MISE_VERSION release for $1" >&2 && exit 1 ;;
+  esac
+}}
+
+mise_sha256() {{
+  if command -v sha256sum >/dev/null 2>&1; then
+    sha256sum "$1" | cut -d' ' -f1
+  else
+    shasum -a 256 "$1" | cut -d' ' -f1
+  fi
+}}
+
+install_mise() {{
+  if [ -x "$MISE_INSTALL_PATH" ] && "$MISE_INSTALL_PATH" version 2>/dev/null | grep -q "^$MISE_VERSION "; then
+    return
+  fi
+  local platform checksum asset
+  platform="$(mise_platform)"
+  checksum="$(mise_checksum "$platform")"
+  asset="mise-v$MISE_VERSION-$platform.tar.gz"
+  tmp="$(mktemp -d)"
+  trap 'rm -rf "$tmp"' EXIT
+  curl -fsSL "{base_url}/$asset" -o "$tmp/$asset"
+  if [ "$(mise_sha256 "$tmp/$asset")" != "$checksum" ]; then
+    echo "mise: checksum mismatch for $asset" >&2
+    exit 1
+  fi
+  tar -xzf "$tmp/$asset" -C "$tmp"
+  mkdir -p "$(dirname "$MISE_INSTALL_PATH")"
+  mv "$tmp/mise/bin/mise" "$MISE_INSTALL_PATH"
+}}
+
+install_mise
+{install}
+"#
+    )
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise generate bootstrap --write</bold>
+    $ <bold>./bootstrap</bold>
+    $ <bold>mise generate bootstrap --version 2024.9.0 > bin/setup</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let checksums = vec![
+            ("linux-x64".to_string(), "abc123".to_string()),
+            ("macos-arm64".to_string(), "def456".to_string()),
+        ];
+        let tools = vec!["node@20.1.0".to_string(), "tiny@3.1.0".to_string()];
+        let script = script("2024.1.0", &checksums, &tools);
+        assert!(script.contains(r#"MISE_VERSION="2024.1.0""#));
+        assert!(script.contains(r#"  linux-x64) echo "abc123" ;;"#));
+        assert!(script.contains(r#"  macos-arm64) echo "def456" ;;"#));
+        assert!(script.contains(
+            r#"curl -fsSL "https://github.com/jdx/mise/releases/download/v2024.1.0/$asset""#
+        ));
+        assert_snapshot!(script.lines().last().unwrap(), @r#""$MISE_INSTALL_PATH" install node@20.1.0 tiny@3.1.0"#);
+    }
+}
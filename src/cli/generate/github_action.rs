@@ -1,13 +1,16 @@
+use itertools::Itertools;
 use xx::file;
 
-use crate::config::Settings;
+use crate::cli::generate::{cache_key_files, project_tools};
+use crate::config::{Config, Settings};
 use crate::file::display_path;
 use crate::git::Git;
 
 /// [experimental] Generate a GitHub Action workflow file
 ///
 /// This command generates a GitHub Action workflow file that runs a mise task like `mise run ci`
-/// when you push changes to your repository.
+/// when you push changes to your repository. Installed tools are cached with a key derived from
+/// the project's mise config files and lockfiles.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct GithubAction {
@@ -40,9 +43,19 @@ impl GithubAction {
     }
 
     fn generate(&self) -> eyre::Result<String> {
-        let branch = Git::new(Git::get_root()?).current_branch()?;
+        let config = Config::try_get()?;
+        let root = Git::get_root()?;
+        let branch = Git::new(root.clone()).current_branch()?;
         let name = &self.name;
         let task = &self.task;
+        let hash_files = cache_key_files(&config, &root)
+            .iter()
+            .map(|f| format!("'{f}'"))
+            .join(", ");
+        let tools = match project_tools(&config, &root)? {
+            tools if tools.is_empty() => String::new(),
+            tools => format!("      # installs {}\n", tools.join(", ")),
+        };
         Ok(format!(
             r#"name: {name}
 
@@ -66,7 +79,13 @@ jobs:
     timeout-minutes: 10
     steps:
       - uses: actions/checkout@v4
-      - uses: jdx/mise-action@v2
+      - uses: actions/cache@v4
+        with:
+          path: ~/.local/share/mise
+          key: mise-${{{{ runner.os }}}}-${{{{ hashFiles({hash_files}) }}}}
+{tools}      - uses: jdx/mise-action@v2
+        with:
+          cache: false
       - run: mise run {task}
 "#
         ))
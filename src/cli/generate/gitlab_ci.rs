@@ -0,0 +1,130 @@
+use eyre::bail;
+use itertools::Itertools;
+use xx::file;
+
+use crate::cli::generate::{cache_key_files, project_tools};
+use crate::config::{Config, Settings};
+use crate::file::display_path;
+use crate::git::Git;
+
+/// [experimental] Generate a GitLab CI job
+///
+/// This command generates a GitLab CI job that installs mise and the project's tools and then
+/// runs a mise task like `mise run ci`. Installed tools are cached with a key derived from the
+/// project's mise config files and lockfiles.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct GitlabCi {
+    /// the name of the job to generate
+    #[clap(long, short, default_value = "ci")]
+    name: String,
+    /// The task to run in the job
+    #[clap(long, short, default_value = "ci")]
+    task: String,
+    /// write to .gitlab-ci.yml, fails if it already exists
+    #[clap(long, short)]
+    write: bool,
+}
+
+impl GitlabCi {
+    pub fn run(self) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("generate gitlab-ci")?;
+        let output = self.generate()?;
+        if self.write {
+            let path = Git::get_root()?.join(".gitlab-ci.yml");
+            if path.exists() {
+                bail!(
+                    "{} already exists, add the job to it instead",
+                    display_path(&path)
+                );
+            }
+            file::write(&path, &output)?;
+            miseprintln!("Wrote to {}", display_path(&path));
+        } else {
+            miseprintln!("{output}");
+        }
+        Ok(())
+    }
+
+    fn generate(&self) -> eyre::Result<String> {
+        let config = Config::try_get()?;
+        let root = Git::get_root()?;
+        let name = &self.name;
+        let task = &self.task;
+        // gitlab only allows up to 2 files in a cache key
+        let key_files = cache_key_files(&config, &root)
+            .into_iter()
+            .take(2)
+            .map(|f| format!("        - {f}"))
+            .join("\n");
+        let tools = match project_tools(&config, &root)? {
+            tools if tools.is_empty() => String::new(),
+            tools => format!("    # installs {}\n", tools.join(", ")),
+        };
+        Ok(format!(
+            r#"{name}:
+  image: debian:bookworm-slim
+  variables:
+    MISE_DATA_DIR: $CI_PROJECT_DIR/.mise
+    MISE_EXPERIMENTAL: "true"
+  cache:
+    key:
+      files:
+{key_files}
+    paths:
+      - .mise
+  before_script:
+    - apt-get update && apt-get install -y curl git
+    - curl https://mise.run | sh
+    - export PATH="$HOME/.local/bin:$MISE_DATA_DIR/shims:$PATH"
+{tools}    - mise install
+  script:
+    - mise run {task}
+"#
+        ))
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise generate gitlab-ci --task=ci >> .gitlab-ci.yml</bold>
+    $ <bold>mise generate gitlab-ci --write</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use crate::test::{cleanup, reset, setup_git_repo};
+
+    #[test]
+    fn test_gitlab_ci() {
+        reset();
+        setup_git_repo();
+        assert_cli_snapshot!("generate", "gitlab-ci", "-t", "test", @r#"
+        ci:
+          image: debian:bookworm-slim
+          variables:
+            MISE_DATA_DIR: $CI_PROJECT_DIR/.mise
+            MISE_EXPERIMENTAL: "true"
+          cache:
+            key:
+              files:
+                - .test-tool-versions
+            paths:
+              - .mise
+          before_script:
+            - apt-get update && apt-get install -y curl git
+            - curl https://mise.run | sh
+            - export PATH="$HOME/.local/bin:$MISE_DATA_DIR/shims:$PATH"
+            # installs tiny@3
+            - mise install
+          script:
+            - mise run test
+        "#);
+        cleanup();
+    }
+}
@@ -1,7 +1,16 @@
+use std::path::Path;
+
 use clap::Subcommand;
+use itertools::Itertools;
+
+use crate::config::Config;
+use crate::mise_lock;
+use crate::toolset::ToolsetBuilder;
 
+mod bootstrap;
 mod git_pre_commit;
 mod github_action;
+mod gitlab_ci;
 mod task_docs;
 
 /// [experimental] Generate files for various tools/services
@@ -14,16 +23,20 @@ pub struct Generate {
 
 #[derive(Debug, Subcommand)]
 enum Commands {
+    Bootstrap(bootstrap::Bootstrap),
     GitPreCommit(git_pre_commit::GitPreCommit),
     GithubAction(github_action::GithubAction),
+    GitlabCi(gitlab_ci::GitlabCi),
     TaskDocs(task_docs::TaskDocs),
 }
 
 impl Commands {
     pub fn run(self) -> eyre::Result<()> {
         match self {
+            Self::Bootstrap(cmd) => cmd.run(),
             Self::GitPreCommit(cmd) => cmd.run(),
             Self::GithubAction(cmd) => cmd.run(),
+            Self::GitlabCi(cmd) => cmd.run(),
             Self::TaskDocs(cmd) => cmd.run(),
         }
     }
@@ -34,3 +47,35 @@ impl Generate {
         self.command.run()
     }
 }
+
+/// config files and lockfiles in the project relative to `root`, CI caches are keyed on these
+fn cache_key_files(config: &Config, root: &Path) -> Vec<String> {
+    let files = config
+        .config_files
+        .keys()
+        .flat_map(|p| [Some(p.to_path_buf()), mise_lock::path_for_config(p)])
+        .flatten()
+        .filter(|p| p.is_file())
+        .filter_map(|p| p.strip_prefix(root).ok().map(relative_path))
+        .unique()
+        .collect_vec();
+    if files.is_empty() {
+        return vec!["mise.toml".into(), mise_lock::LOCKFILE_NAME.into()];
+    }
+    files
+}
+
+/// the tools requested by config files in the project, e.g.: `node@20`
+fn project_tools(config: &Config, root: &Path) -> eyre::Result<Vec<String>> {
+    let ts = ToolsetBuilder::new().build(config)?;
+    Ok(ts
+        .versions
+        .values()
+        .filter(|tvl| tvl.source.path().is_some_and(|p| p.starts_with(root)))
+        .flat_map(|tvl| tvl.requests.iter().map(|tr| tr.to_string()))
+        .collect())
+}
+
+fn relative_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
@@ -28,7 +28,7 @@ pub struct TaskDocs {
 impl TaskDocs {
     pub fn run(self) -> eyre::Result<()> {
         SETTINGS.ensure_experimental("generate task-docs")?;
-        let tasks = CONFIG.load_tasks_in_dir(dirs::CWD.as_ref().unwrap())?;
+        let tasks = CONFIG.load_tasks_in_dir(&dirs::cwd().unwrap())?;
         let mut out = vec![];
         for task in &tasks {
             out.push(task.render_markdown()?);
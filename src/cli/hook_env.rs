@@ -1,4 +1,4 @@
-use std::env::{join_paths, split_paths};
+use std::env::join_paths;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 
@@ -12,7 +12,7 @@ use crate::env::{PATH_KEY, TERM_WIDTH, __MISE_DIFF};
 use crate::env_diff::{EnvDiff, EnvDiffOperation};
 use crate::shell::{get_shell, ShellType};
 use crate::toolset::{Toolset, ToolsetBuilder};
-use crate::{env, hook_env};
+use crate::{env, hook_env, http};
 
 /// [internal] called by activate hook to update env vars directory change
 #[derive(Debug, clap::Args)]
@@ -33,30 +33,40 @@ pub struct HookEnv {
 
 impl HookEnv {
     pub fn run(self) -> Result<()> {
+        // this runs on every prompt so it can't wait on the network
+        http::set_budget(http::INTERACTIVE_BUDGET);
         let config = Config::try_get()?;
         let watch_files = config.watch_files()?;
         if hook_env::should_exit_early(&watch_files) {
             return Ok(());
         }
-        let ts = ToolsetBuilder::new().build(&config)?;
+        let ts = ToolsetBuilder::new()
+            .with_resolve_cache(true)
+            .build(&config)?;
         let shell = get_shell(self.shell).expect("no shell provided, use `--shell=zsh`");
         miseprint!("{}", hook_env::clear_old_env(&*shell))?;
         let mut env = ts.env(&config)?;
         let env_path = env.remove(&*PATH_KEY);
         let mut diff = EnvDiff::new(&env::PRISTINE_ENV, env);
+        // recorded as old values without a new one so they are unset now and restored when
+        // leaving the directory
+        for k in config.env_remove()? {
+            if let Some(v) = env::PRISTINE_ENV.get(k) {
+                if !diff.new.contains_key(k) {
+                    diff.old.insert(k.clone(), v.clone());
+                }
+            }
+        }
         let mut patches = diff.to_patches();
 
-        let mut paths = config.path_dirs()?.clone();
-        if let Some(p) = env_path {
-            paths.extend(split_paths(&p).collect_vec());
-        }
-        paths.extend(ts.list_paths()); // load the active runtime paths
+        let paths = ts.list_final_paths(&config, env_path.as_deref())?;
         diff.path.clone_from(&paths); // update __MISE_DIFF with the new paths for the next run
 
         let settings = Settings::try_get()?;
         patches.extend(self.build_path_operations(&settings, &paths, &__MISE_DIFF.path)?);
         patches.push(self.build_diff_operation(&diff)?);
-        patches.push(self.build_watch_operation(&watch_files)?);
+        patches.push(self.build_watch_operation(&watch_files, ts.dir_scoped)?);
+        patches.extend(self.build_session_operation());
 
         let output = hook_env::build_env_commands(&*shell, &patches);
         miseprint!("{output}")?;
@@ -163,13 +173,20 @@ impl HookEnv {
     fn build_watch_operation(
         &self,
         watch_files: impl IntoIterator<Item = impl AsRef<Path>>,
+        dir_scoped: bool,
     ) -> Result<EnvDiffOperation> {
-        let watches = hook_env::build_watches(watch_files)?;
+        let watches = hook_env::build_watches(watch_files, dir_scoped)?;
         Ok(EnvDiffOperation::Add(
             "__MISE_WATCH".into(),
             hook_env::serialize_watches(&watches)?,
         ))
     }
+
+    /// keeps the session id, subshells inherit it so they share the cached `[env]` run values
+    fn build_session_operation(&self) -> Option<EnvDiffOperation> {
+        let session = env::__MISE_SESSION.clone()?;
+        Some(EnvDiffOperation::Add("__MISE_SESSION".into(), session))
+    }
 }
 
 fn patch_to_status(patch: EnvDiffOperation) -> String {
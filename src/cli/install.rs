@@ -1,11 +1,21 @@
-use eyre::Result;
+use eyre::{bail, ensure, Result, WrapErr};
 use itertools::Itertools;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use crate::cli::args::{BackendArg, ToolArg};
-use crate::config::Config;
-use crate::toolset::{InstallOptions, ToolRequest, ToolVersion, ToolVersionOptions, Toolset};
+use crate::config::config_file::mise_toml::MiseToml;
+use crate::config::config_file::ConfigFile;
+use crate::config::{Config, Settings};
+use crate::http::HTTP;
+use crate::install_manifest::{self, Verification};
+use crate::install_plan::InstallPlan;
+use crate::toolset::{
+    InstallOptions, ToolRequest, ToolVersion, ToolVersionOptions, Toolset, ToolsetBuilder,
+};
 use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::{prompt, style};
+use crate::{dirs, env};
 
 /// Install a tool version
 ///
@@ -16,6 +26,8 @@ use crate::ui::multi_progress_report::MultiProgressReport;
 /// Alternatively, run `mise exec <TOOL>@<VERSION> -- <COMMAND>` to execute a tool without creating config files.
 ///
 /// Tools will be installed in parallel. To disable, set `--jobs=1` or `MISE_JOBS=1`
+/// This includes multiple versions of the same tool. If some of them fail to install the rest
+/// are still installed and mise exits with a non-zero status.
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "i", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Install {
@@ -28,6 +40,31 @@ pub struct Install {
     #[clap(long, short, requires = "tool")]
     force: bool,
 
+    /// Check installed versions against the checksums recorded when they were installed
+    /// instead of installing anything
+    ///
+    /// Checksums are recorded when the `lockfile` setting is enabled, versions installed without
+    /// it are reported as unverifiable. Exits with a non-zero status if any file was modified,
+    /// added or removed.
+    #[clap(long, conflicts_with_all = ["force", "raw"])]
+    verify: bool,
+
+    /// Install the tools of a mise.toml fetched from this url
+    ///
+    /// The tools are installed but not added to any config file. The config is shown before
+    /// anything is installed and has to be confirmed, unless `--yes` is set.
+    #[clap(long, value_name = "URL", conflicts_with_all = ["tool", "verify"])]
+    from: Option<String>,
+
+    /// Continue an install that was interrupted or failed in this directory
+    ///
+    /// Before installing, mise saves what it is going to install to the cache dir and marks
+    /// each tool as done once it is installed. This installs the tools that weren't done using
+    /// the versions that were resolved the first time. Tools that were done are skipped unless
+    /// their files no longer match the checksums recorded when they were installed.
+    #[clap(long, conflicts_with_all = ["tool", "verify", "from", "force"])]
+    resume: bool,
+
     /// Number of jobs to run in parallel
     /// [default: 4]
     #[clap(long, short, env = "MISE_JOBS", verbatim_doc_comment)]
@@ -38,6 +75,26 @@ pub struct Install {
     #[clap(long, overrides_with = "jobs")]
     raw: bool,
 
+    /// Show the output of install commands as it happens instead of a progress bar
+    ///
+    /// Each line is prefixed with the tool it comes from. Unlike --raw, tools still install in
+    /// parallel. Also set with the `raw_log` setting.
+    #[clap(long, conflicts_with = "raw")]
+    raw_log: bool,
+
+    /// Retry the install of a tool this many times if it fails
+    ///
+    /// The whole install is retried, partial downloads and installs are removed between
+    /// attempts. Errors that won't go away like a missing version or a checksum mismatch are
+    /// not retried.
+    #[clap(
+        long,
+        value_name = "N",
+        env = "MISE_INSTALL_RETRIES",
+        default_value_t = 0
+    )]
+    retries: usize,
+
     /// Show installation output
     ///
     /// This argument will print plugin output such as download, configuration, and compilation output.
@@ -48,6 +105,17 @@ pub struct Install {
 impl Install {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
+        if self.verify {
+            return self.verify(&config);
+        }
+        if let Some(url) = &self.from {
+            self.install_from(&config, url)?;
+            return Ok(());
+        }
+        if self.resume {
+            self.resume(&config)?;
+            return Ok(());
+        }
         match &self.tool {
             Some(runtime) => self.install_runtimes(&config, runtime)?,
             None => self.install_missing_runtimes(&config)?,
@@ -65,7 +133,56 @@ impl Install {
             warn!("specify a version with `mise install <PLUGIN>@<VERSION>`");
             return Ok(vec![]);
         }
-        ts.install_versions(config, tool_versions, &mpr, &self.install_opts())
+        self.install_planned(config, &mut ts, tool_versions, &mpr)
+    }
+
+    fn resume(&self, config: &Config) -> Result<Vec<ToolVersion>> {
+        let Some(plan) = InstallPlan::load()? else {
+            bail!("no install to resume in this directory");
+        };
+        let versions = plan.remaining()?;
+        if versions.is_empty() {
+            info!("all runtimes are installed");
+            plan.finish()?;
+            return Ok(vec![]);
+        }
+        let mpr = MultiProgressReport::get();
+        let mut ts = Toolset::from(config.get_tool_request_set()?.clone());
+        self.run_plan(config, &mut ts, versions, &mpr, plan)
+    }
+
+    /// saves what is about to be installed so it can be resumed with `--resume`
+    fn install_planned(
+        &self,
+        config: &Config,
+        ts: &mut Toolset,
+        versions: Vec<ToolRequest>,
+        mpr: &MultiProgressReport,
+    ) -> Result<Vec<ToolVersion>> {
+        let plan = InstallPlan::new(&versions)?;
+        self.run_plan(config, ts, versions, mpr, plan)
+    }
+
+    fn run_plan(
+        &self,
+        config: &Config,
+        ts: &mut Toolset,
+        versions: Vec<ToolRequest>,
+        mpr: &MultiProgressReport,
+        plan: InstallPlan,
+    ) -> Result<Vec<ToolVersion>> {
+        let plan = Arc::new(plan);
+        let opts = InstallOptions {
+            plan: Some(plan.clone()),
+            ..self.install_opts()
+        };
+        let versions = ts
+            .install_versions(config, versions, mpr, &opts)
+            .inspect_err(|_| {
+                info!("run `mise install --resume` to install the rest");
+            })?;
+        plan.finish()?;
+        Ok(versions)
     }
 
     fn install_opts(&self) -> InstallOptions {
@@ -74,6 +191,8 @@ impl Install {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: true,
+            retries: self.retries,
+            ..Default::default()
         }
     }
 
@@ -84,7 +203,7 @@ impl Install {
     ) -> Result<Vec<ToolRequest>> {
         let mut requests = vec![];
         for ta in ToolArg::double_tool_condition(runtimes)? {
-            let default_opts = ToolVersionOptions::new();
+            let default_opts = ToolVersionOptions::default();
             match ta.tvr {
                 // user provided an explicit version
                 // TODO: this should install using options from config if the version matches
@@ -117,6 +236,74 @@ impl Install {
         Ok(requests)
     }
 
+    fn verify(&self, config: &Config) -> Result<()> {
+        let ts = ToolsetBuilder::new().build(config)?;
+        let tools: Option<HashSet<BackendArg>> = self
+            .tool
+            .as_ref()
+            .map(|tools| tools.iter().map(|ta| ta.backend.clone()).collect());
+        let versions = ts
+            .list_installed_versions()?
+            .into_iter()
+            .filter(|(_, tv)| tools.as_ref().map_or(true, |t| t.contains(&tv.backend)))
+            .collect_vec();
+        let versions = versions.into_iter().map(|(_, tv)| tv).collect_vec();
+        let mut mismatches = 0;
+        for (tv, verification) in versions.iter().zip(install_manifest::verify_all(&versions)) {
+            match verification? {
+                Verification::Ok => miseprintln!("{tv}: ok"),
+                Verification::Unverifiable => miseprintln!("{tv}: unverifiable"),
+                Verification::Mismatch(files) => {
+                    mismatches += 1;
+                    miseprintln!("{tv}: mismatch");
+                    for file in files {
+                        miseprintln!("  {file}");
+                    }
+                }
+            }
+        }
+        ensure!(
+            mismatches == 0,
+            "{mismatches} installed version(s) do not match their recorded checksums"
+        );
+        Ok(())
+    }
+
+    fn install_from(&self, config: &Config, url: &str) -> Result<Vec<ToolVersion>> {
+        // get_text fails if the server returns an html page instead of the file
+        let body = HTTP.get_text(url)?;
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_else(|| url.to_string());
+        // the config isn't written anywhere, templates see the current directory as its root
+        let path = dirs::cwd()
+            .unwrap_or_default()
+            .join(&*env::MISE_DEFAULT_CONFIG_FILENAME);
+        let cf = MiseToml::from_str(&body, &path)
+            .wrap_err_with(|| format!("failed to parse config from {url}"))?;
+        // versions can be templates which may run commands so they're only rendered once the
+        // config is trusted
+        info_unprefix!("{}\n{}", style::edim(format!("# {url}")), body.trim_end());
+        let settings = Settings::get();
+        if !settings.yes && !prompt::confirm(format!("install the tools from {host}?"))? {
+            bail!("config from {host} was not trusted, use --yes to install without prompting");
+        }
+        let trs = cf.to_tool_request_set()?;
+        if trs.tools.is_empty() {
+            warn!("{url} does not contain any tools");
+            return Ok(vec![]);
+        }
+        let versions = trs.missing_tools().into_iter().cloned().collect_vec();
+        if versions.is_empty() {
+            info!("all runtimes are installed");
+            return Ok(vec![]);
+        }
+        let mpr = MultiProgressReport::get();
+        let mut ts = Toolset::from(trs.clone());
+        self.install_planned(config, &mut ts, versions, &mpr)
+    }
+
     fn install_missing_runtimes(&self, config: &Config) -> eyre::Result<Vec<ToolVersion>> {
         let trs = config.get_tool_request_set()?;
         let versions = trs.missing_tools().into_iter().cloned().collect_vec();
@@ -126,7 +313,7 @@ impl Install {
         }
         let mpr = MultiProgressReport::get();
         let mut ts = Toolset::from(trs.clone());
-        ts.install_versions(config, versions, &mpr, &self.install_opts())
+        self.install_planned(config, &mut ts, versions, &mpr)
     }
 }
 
@@ -135,8 +322,12 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     $ <bold>mise install node@20.0.0</bold>  # install specific node version
     $ <bold>mise install node@20</bold>      # install fuzzy node version
+    $ <bold>mise install node@18 node@20</bold>  # install multiple versions at once
     $ <bold>mise install node</bold>         # install version specified in .tool-versions or .mise.toml
     $ <bold>mise install</bold>              # installs everything specified in .tool-versions or .mise.toml
+    $ <bold>mise install --verify</bold>     # check installed files against their recorded checksums
+    $ <bold>mise install --resume</bold>     # continue an install that was interrupted
+    $ <bold>mise install --from https://example.com/mise.toml</bold>  # install the tools of a remote config
 "#
 );
 
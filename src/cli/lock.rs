@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use eyre::Result;
+
+use crate::backend;
+use crate::cli::args::BackendArg;
+use crate::config::Config;
+use crate::file::display_path;
+use crate::mise_lock::{self, LockedTool, Lockfile};
+use crate::toolset::ToolsetBuilder;
+
+/// Writes the resolved versions of the current tools to mise.lock
+///
+/// Each config file gets a mise.lock next to it with the exact version that every tool it
+/// requests resolved to. `mise install` uses the locked versions when the `lockfile` setting
+/// is enabled and records the url and checksum of what it downloads.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Lock {
+    /// Only lock these tools
+    #[clap(value_name = "TOOL")]
+    tool: Vec<BackendArg>,
+
+    /// Resolve versions again instead of keeping what is already locked
+    ///
+    /// Locked checksums are dropped for tools whose version changes.
+    #[clap(long, short)]
+    update: bool,
+}
+
+impl Lock {
+    pub fn run(self) -> Result<()> {
+        let config = Config::try_get()?;
+        let ts = ToolsetBuilder::new().build(&config)?;
+        let mut lockfiles: BTreeMap<PathBuf, Lockfile> = BTreeMap::new();
+        for (fa, tvl) in &ts.versions {
+            if !self.tool.is_empty() && !self.tool.contains(fa) {
+                continue;
+            }
+            let Some(path) = mise_lock::path_for(&tvl.source) else {
+                continue;
+            };
+            if !lockfiles.contains_key(&path) {
+                lockfiles.insert(path.clone(), Lockfile::read(&path)?);
+            }
+            let lockfile = lockfiles.get_mut(&path).unwrap();
+            let backend = backend::get(fa);
+            // only the first request is pinned, extra versions are for fallback PATH entries
+            let (Some(tr), Some(tv)) = (tvl.requests.first(), tvl.versions.first()) else {
+                continue;
+            };
+            let tv = match self.update {
+                true => tr.resolve(backend.as_ref(), true)?,
+                false => tv.clone(),
+            };
+            let tool = lockfile.tools.entry(fa.short.clone()).or_default();
+            if tool.version != tv.version {
+                *tool = LockedTool::default();
+            }
+            tool.requested = tr.version();
+            tool.version = tv.version.clone();
+        }
+        for (path, lockfile) in lockfiles {
+            if lockfile == Lockfile::read(&path)? {
+                continue;
+            }
+            lockfile.write(&path)?;
+            info!("updated {}", display_path(&path));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise lock</bold>
+    $ <bold>mise lock --update node</bold>
+    $ <bold>cat mise.lock</bold>
+    [tools.node]
+    requested = "20"
+    version = "20.1.0"
+
+    [tools.node.platforms.linux-x64]
+    url = "https://nodejs.org/dist/v20.1.0/node-v20.1.0-linux-x64.tar.gz"
+    checksum = "sha256:..."
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::test::reset;
+    use crate::{dirs, file};
+
+    #[test]
+    fn test_lock() {
+        reset();
+        let path = dirs::cwd().unwrap().join("mise.lock");
+        assert_cli!("lock", "tiny");
+        let body = file::read_to_string(&path).unwrap();
+        file::remove_file(&path).unwrap();
+        assert_snapshot!(body, @r###"
+        [tools.tiny]
+        requested = "3"
+        version = "3.1.0"
+        "###);
+    }
+}
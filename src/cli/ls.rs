@@ -13,6 +13,7 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
+use crate::cli::JSONTools;
 use crate::config::Config;
 use crate::toolset::{ToolSource, ToolVersion, Toolset};
 use crate::ui::table;
@@ -114,26 +115,20 @@ impl Ls {
     }
 
     fn display_json(&self, runtimes: Vec<RuntimeRow>) -> Result<()> {
-        if let Some(plugins) = &self.plugin {
-            // only runtimes for 1 plugin
-            let runtimes: Vec<JSONToolVersion> = runtimes
-                .into_iter()
-                .filter(|(p, _, _)| plugins.contains(p.fa()))
-                .map(|row| row.into())
-                .collect();
-            miseprintln!("{}", serde_json::to_string_pretty(&runtimes)?);
-            return Ok(());
-        }
-
         let mut plugins = JSONOutput::new();
         for (plugin_name, runtimes) in &runtimes
             .into_iter()
+            // the tools asked for are listed like all of them are, keyed by tool
+            .filter(|(p, _, _)| self.plugin.as_ref().map_or(true, |f| f.contains(p.fa())))
             .chunk_by(|(p, _, _)| p.id().to_string())
         {
             let runtimes = runtimes.map(|row| row.into()).collect();
             plugins.insert(plugin_name.clone(), runtimes);
         }
-        miseprintln!("{}", serde_json::to_string_pretty(&plugins)?);
+        miseprintln!(
+            "{}",
+            serde_json::to_string_pretty(&JSONTools::new(plugins))?
+        );
         Ok(())
     }
 
@@ -374,17 +369,20 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     $ <bold>mise ls --json</bold>
     {
-      "node": [
-        {
-          "version": "20.0.0",
-          "install_path": "/Users/jdx/.mise/installs/node/20.0.0",
-          "source": {
-            "type": ".mise.toml",
-            "path": "/Users/jdx/.mise.toml"
+      "schema_version": 1,
+      "tools": {
+        "node": [
+          {
+            "version": "20.0.0",
+            "install_path": "/Users/jdx/.mise/installs/node/20.0.0",
+            "source": {
+              "type": ".mise.toml",
+              "path": "/Users/jdx/.mise.toml"
+            }
           }
-        }
-      ],
-      "python": [...]
+        ],
+        "python": [...]
+      }
     }
 "#
 );
@@ -1,12 +1,16 @@
+use std::cmp::Reverse;
 use std::sync::Arc;
 
 use eyre::Result;
+use indexmap::IndexMap;
 use itertools::Itertools;
-use rayon::prelude::*;
+use versions::Versioning;
 
 use crate::backend;
 use crate::backend::Backend;
 use crate::cli::args::ToolArg;
+use crate::cli::JSONTools;
+use crate::config::Config;
 use crate::toolset::{ToolRequest, ToolVersion};
 use crate::ui::multi_progress_report::MultiProgressReport;
 
@@ -23,13 +27,42 @@ pub struct LsRemote {
     pub plugin: Option<ToolArg>,
 
     /// Show all installed plugins and versions
-    #[clap(long, verbatim_doc_comment, conflicts_with_all = ["plugin", "prefix"])]
+    #[clap(long, verbatim_doc_comment, conflicts_with_all = ["plugin", "prefix", "prefix_flag"])]
     pub all: bool,
 
     /// The version prefix to use when querying the latest version
     /// same as the first argument after the "@"
     #[clap(verbatim_doc_comment)]
     pub prefix: Option<String>,
+
+    /// Only show versions that start with this prefix
+    /// same as the PREFIX argument
+    #[clap(
+        long = "prefix",
+        value_name = "PREFIX",
+        verbatim_doc_comment,
+        conflicts_with = "prefix"
+    )]
+    pub prefix_flag: Option<String>,
+
+    /// Only show the newest N versions
+    #[clap(long, short = 'n', value_name = "N", verbatim_doc_comment)]
+    pub limit: Option<usize>,
+
+    /// Sort the versions by version number, newest last with asc
+    /// By default versions are in the order the backend lists them, which is usually oldest first
+    #[clap(long, value_name = "ORDER", verbatim_doc_comment)]
+    pub sort: Option<SortOrder>,
+
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortOrder {
+    Asc,
+    Desc,
 }
 
 impl LsRemote {
@@ -49,20 +82,42 @@ impl LsRemote {
                     Some(tvr) => Some(ToolVersion::resolve(plugin.as_ref(), tvr, false)?.version),
                     None => None,
                 },
-                _ => self.prefix.clone(),
+                _ => self.prefix(),
             },
-            _ => self.prefix.clone(),
+            _ => self.prefix(),
+        };
+        let prefix = match prefix {
+            Some(prefix) => {
+                let prefix = Config::get().resolve_alias(plugin.as_ref(), &prefix)?;
+                Some(
+                    prefix
+                        .strip_prefix("prefix:")
+                        .unwrap_or(&prefix)
+                        .to_string(),
+                )
+            }
+            None => None,
         };
 
-        let versions = plugin.list_remote_versions()?;
         let versions = match prefix {
-            Some(prefix) => versions
+            Some(glob) if glob.contains('*') => plugin.list_versions_matching(&glob)?,
+            Some(range) if backend::version_constraint(&range).is_some() => {
+                plugin.list_versions_matching(&range)?
+            }
+            Some(prefix) => plugin
+                .list_remote_versions()?
                 .into_iter()
                 .filter(|v| v.starts_with(&prefix))
                 .collect(),
-            None => versions,
+            None => plugin.list_remote_versions()?,
         };
+        let versions = self.sort_and_limit(versions);
 
+        if self.json {
+            let out = JSONTools::new(IndexMap::from([(plugin.id().to_string(), versions)]));
+            miseprintln!("{}", serde_json::to_string_pretty(&out)?);
+            return Ok(());
+        }
         for version in versions {
             miseprintln!("{}", version);
         }
@@ -71,16 +126,28 @@ impl LsRemote {
     }
 
     fn run_all(self) -> Result<()> {
-        let versions = backend::list()
-            .into_par_iter()
-            .map(|p| {
-                let versions = p.list_remote_versions()?;
-                Ok((p, versions))
-            })
-            .collect::<Result<Vec<_>>>()?
+        let versions = backend::fetch_each(backend::list(), |p| p.list_remote_versions())
             .into_iter()
+            .filter_map(|(p, versions)| match versions {
+                Ok(versions) => Some((p, self.sort_and_limit(versions))),
+                Err(err) => {
+                    warn!("failed to fetch versions for {p}: {err:#}");
+                    None
+                }
+            })
             .sorted_by_cached_key(|(p, _)| p.id().to_string())
             .collect::<Vec<_>>();
+        if self.json {
+            let versions = versions
+                .into_iter()
+                .map(|(p, v)| (p.id().to_string(), v))
+                .collect::<IndexMap<_, _>>();
+            miseprintln!(
+                "{}",
+                serde_json::to_string_pretty(&JSONTools::new(versions))?
+            );
+            return Ok(());
+        }
         for (plugin, versions) in versions {
             for v in versions {
                 miseprintln!("{}@{v}", plugin);
@@ -89,6 +156,34 @@ impl LsRemote {
         Ok(())
     }
 
+    fn prefix(&self) -> Option<String> {
+        self.prefix.clone().or_else(|| self.prefix_flag.clone())
+    }
+
+    /// sorts by version number if --sort is set, `Versioning` can parse any version so this also
+    /// works for tools that don't use semver. Versions that compare equal keep their order.
+    fn sort_and_limit(&self, versions: Vec<String>) -> Vec<String> {
+        let mut versions = match self.sort {
+            Some(SortOrder::Asc) => versions
+                .into_iter()
+                .sorted_by_cached_key(|v| Versioning::new(v))
+                .collect(),
+            Some(SortOrder::Desc) => versions
+                .into_iter()
+                .sorted_by_cached_key(|v| Reverse(Versioning::new(v)))
+                .collect(),
+            None => versions,
+        };
+        if let Some(limit) = self.limit {
+            if self.sort == Some(SortOrder::Desc) {
+                versions.truncate(limit);
+            } else {
+                versions.drain(..versions.len().saturating_sub(limit));
+            }
+        }
+        versions
+    }
+
     fn get_plugin(&self) -> Result<Option<Arc<dyn Backend>>> {
         match &self.plugin {
             Some(tool_arg) => {
@@ -118,6 +213,21 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise ls-remote node 20</bold>
     20.0.0
     20.1.0
+
+    $ <bold>mise ls-remote "node@20.*"</bold>
+    20.0.0
+    20.1.0
+
+    $ <bold>mise ls-remote "node@>=20.1,<<21"</bold>
+    20.1.0
+
+    $ <bold>mise ls-remote node 20 --json</bold>
+    {"schema_version": 1, "tools": {"node": ["20.0.0", "20.1.0"]}}
+
+    # the 2 newest versions, newest first
+    $ <bold>mise ls-remote node --limit 2 --sort desc</bold>
+    22.1.0
+    22.0.0
 "#
 );
 
@@ -134,4 +244,41 @@ mod tests {
         assert_cli_snapshot!("list-remote", "dummy@2");
         assert_cli_snapshot!("list-remote", "dummy@sub-1:2");
     }
+
+    #[test]
+    fn test_ls_remote_glob() {
+        assert_cli_snapshot!("list-remote", "dummy@1.*", @r###"
+        1.0.0
+        1.1.0
+        "###);
+    }
+
+    #[test]
+    fn test_ls_remote_constraint() {
+        assert_cli_snapshot!("list-remote", "dummy@>=1.1,<2.0", @"1.1.0");
+    }
+
+    #[test]
+    fn test_ls_remote_limit() {
+        assert_cli_snapshot!("list-remote", "dummy", "--limit", "2", @r###"
+        1.1.0
+        2.0.0
+        "###);
+        assert_cli_snapshot!("list-remote", "dummy", "--prefix", "1", "--sort", "desc", "-n", "1", @"1.1.0");
+    }
+
+    #[test]
+    fn test_ls_remote_json() {
+        assert_cli_snapshot!("list-remote", "dummy", "1", "--json", @r###"
+        {
+          "schema_version": 1,
+          "tools": {
+            "dummy": [
+              "1.0.0",
+              "1.1.0"
+            ]
+          }
+        }
+        "###);
+    }
 }
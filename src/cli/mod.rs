@@ -1,6 +1,8 @@
 use clap::{FromArgMatches, Subcommand};
 use color_eyre::Result;
+use indexmap::IndexMap;
 use indoc::indoc;
+use serde_derive::Serialize;
 
 use crate::config::Settings;
 use crate::{logger, migrate, shims};
@@ -10,6 +12,7 @@ mod alias;
 pub mod args;
 mod asdf;
 pub mod backends;
+mod bin;
 mod bin_paths;
 mod cache;
 mod completion;
@@ -30,10 +33,12 @@ mod install;
 mod latest;
 mod link;
 mod local;
+mod lock;
 mod ls;
 mod ls_remote;
 mod outdated;
 mod plugins;
+mod prompt;
 mod prune;
 mod registry;
 #[cfg(debug_assertions)]
@@ -45,6 +50,7 @@ mod run;
 mod self_update;
 mod set;
 mod settings;
+mod setup;
 mod shell;
 mod sync;
 mod tasks;
@@ -61,12 +67,30 @@ mod r#which;
 
 pub struct Cli {}
 
+/// the `--json` output of commands that list something for each tool. `schema_version` is bumped
+/// whenever a field is removed or changes meaning, adding a field doesn't bump it.
+#[derive(Serialize)]
+pub struct JSONTools<T> {
+    schema_version: u32,
+    tools: IndexMap<String, T>,
+}
+
+impl<T> JSONTools<T> {
+    pub fn new(tools: IndexMap<String, T>) -> Self {
+        Self {
+            schema_version: 1,
+            tools,
+        }
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     Activate(activate::Activate),
     Alias(alias::Alias),
     Asdf(asdf::Asdf),
     Backends(backends::Backends),
+    Bin(bin::Bin),
     BinPaths(bin_paths::BinPaths),
     Cache(cache::Cache),
     Completion(completion::Completion),
@@ -86,10 +110,12 @@ pub enum Commands {
     Latest(latest::Latest),
     Link(link::Link),
     Local(local::Local),
+    Lock(lock::Lock),
     Ls(ls::Ls),
     LsRemote(ls_remote::LsRemote),
     Outdated(outdated::Outdated),
     Plugins(plugins::Plugins),
+    Prompt(prompt::Prompt),
     Prune(prune::Prune),
     Registry(registry::Registry),
     Reshim(reshim::Reshim),
@@ -97,6 +123,7 @@ pub enum Commands {
     SelfUpdate(self_update::SelfUpdate),
     Set(set::Set),
     Settings(settings::Settings),
+    Setup(setup::Setup),
     Shell(shell::Shell),
     Sync(sync::Sync),
     Tasks(tasks::Tasks),
@@ -125,6 +152,7 @@ impl Commands {
             Self::Alias(cmd) => cmd.run(),
             Self::Asdf(cmd) => cmd.run(),
             Self::Backends(cmd) => cmd.run(),
+            Self::Bin(cmd) => cmd.run(),
             Self::BinPaths(cmd) => cmd.run(),
             Self::Cache(cmd) => cmd.run(),
             Self::Completion(cmd) => cmd.run(),
@@ -144,10 +172,12 @@ impl Commands {
             Self::Latest(cmd) => cmd.run(),
             Self::Link(cmd) => cmd.run(),
             Self::Local(cmd) => cmd.run(),
+            Self::Lock(cmd) => cmd.run(),
             Self::Ls(cmd) => cmd.run(),
             Self::LsRemote(cmd) => cmd.run(),
             Self::Outdated(cmd) => cmd.run(),
             Self::Plugins(cmd) => cmd.run(),
+            Self::Prompt(cmd) => cmd.run(),
             Self::Prune(cmd) => cmd.run(),
             Self::Registry(cmd) => cmd.run(),
             Self::Reshim(cmd) => cmd.run(),
@@ -155,6 +185,7 @@ impl Commands {
             Self::SelfUpdate(cmd) => cmd.run(),
             Self::Set(cmd) => cmd.run(),
             Self::Settings(cmd) => cmd.run(),
+            Self::Setup(cmd) => cmd.run(),
             Self::Shell(cmd) => cmd.run(),
             Self::Sync(cmd) => cmd.run(),
             Self::Tasks(cmd) => cmd.run(),
@@ -193,6 +224,9 @@ impl Cli {
                 .arg(args::ProfileArg::arg())
                 .arg(args::DebugArg::arg())
                 .arg(args::LogLevelArg::arg())
+                .arg(args::NoCacheArg::arg())
+                .arg(args::NoCacheWriteArg::arg())
+                .arg(args::OfflineArg::arg())
                 .arg(args::QuietArg::arg())
                 .arg(args::TraceArg::arg())
                 .arg(args::VerboseArg::arg())
@@ -213,6 +247,8 @@ impl Cli {
                     .get_matches_from(args)
             });
         Settings::add_cli_matches(&matches);
+        // surfaces errors like a missing --cd dir before anything calls Settings::get()
+        Settings::try_get()?;
         logger::init();
         migrate::run();
         if let Err(err) = crate::cache::auto_prune() {
@@ -1,10 +1,17 @@
 use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::process::exit;
 
 use crate::cli::args::ToolArg;
+use crate::cli::upgrade::Upgrade;
+use crate::cli::JSONTools;
 use crate::config::Config;
 use crate::toolset::{OutdatedInfo, ToolsetBuilder};
+use crate::ui;
 use crate::ui::table;
-use eyre::Result;
+use console::style;
+use demand::DemandOption;
+use eyre::{bail, Result};
 use indexmap::IndexMap;
 
 /// Shows outdated tool versions
@@ -30,9 +37,20 @@ pub struct Outdated {
     #[clap(short = 'J', long, verbatim_doc_comment)]
     pub json: bool,
 
+    /// Exit with status 1 if any tools are outdated
+    #[clap(long, verbatim_doc_comment)]
+    pub exit_code: bool,
+
     /// Don't show table header
     #[clap(long)]
     pub no_header: bool,
+
+    /// Pick which outdated tools to upgrade from a menu
+    ///
+    /// The selected tools are installed and bumped to the latest version in the config
+    /// that sets them. This implies --bump, major version bumps are marked in the menu.
+    #[clap(long, short, verbatim_doc_comment, conflicts_with_all = ["json", "exit_code"])]
+    pub interactive: bool,
 }
 
 impl Outdated {
@@ -46,18 +64,54 @@ impl Outdated {
             .collect::<HashSet<_>>();
         ts.versions
             .retain(|_, tvl| tool_set.is_empty() || tool_set.contains(&tvl.backend));
-        let outdated = ts.list_outdated_versions(self.bump);
-        if outdated.is_empty() {
-            info!("All tools are up to date");
-        } else if self.json {
+        let outdated = ts.list_outdated_versions(self.bump || self.interactive);
+        if self.interactive {
+            return self.upgrade_interactive(&config, outdated);
+        }
+        let any_outdated = !outdated.is_empty();
+        if self.json {
             self.display_json(outdated)?;
+        } else if !any_outdated {
+            info!("All tools are up to date");
         } else {
             self.display(outdated)?;
         }
+        if self.exit_code && any_outdated {
+            exit(1);
+        }
 
         Ok(())
     }
 
+    fn upgrade_interactive(&self, config: &Config, outdated: Vec<OutdatedInfo>) -> Result<()> {
+        if outdated.is_empty() {
+            info!("All tools are up to date");
+            return Ok(());
+        }
+        if !console::user_attended() || !std::io::stdin().is_terminal() {
+            bail!("mise outdated --interactive requires a terminal, use `mise upgrade --bump [TOOL]...` instead");
+        }
+        let _ctrlc = ui::ctrlc::handle_ctrlc()?;
+        let mut ms = demand::MultiSelect::new("mise outdated")
+            .description("Select tools to upgrade")
+            .filterable(true)
+            .min(1);
+        for o in outdated {
+            let label = upgrade_label(&o);
+            ms = ms.option(DemandOption::new(o).label(&label));
+        }
+        let selected = ms.run()?;
+        let upgrade = Upgrade {
+            tool: self.tool.clone(),
+            dry_run: false,
+            interactive: false,
+            jobs: None,
+            bump: true,
+            raw: false,
+        };
+        upgrade.upgrade(config, selected)
+    }
+
     fn display(&self, outdated: Vec<OutdatedInfo>) -> Result<()> {
         let mut table = tabled::Table::new(outdated);
         table::default_style(&mut table, self.no_header);
@@ -70,11 +124,35 @@ impl Outdated {
         for o in outdated {
             map.insert(o.name.to_string(), o);
         }
-        miseprintln!("{}", serde_json::to_string_pretty(&map)?);
+        miseprintln!("{}", serde_json::to_string_pretty(&JSONTools::new(map))?);
         Ok(())
     }
 }
 
+fn upgrade_label(o: &OutdatedInfo) -> String {
+    let current = o.current.as_deref().unwrap_or("MISSING");
+    let label = format!("{} {current} -> {} ({})", o.name, o.latest, o.source);
+    if is_major_bump(current, &o.latest) {
+        format!("{label} {}", style("major").yellow().bold())
+    } else {
+        label
+    }
+}
+
+/// true if the first number of the version changes, e.g.: 20.1.0 -> 22.0.0
+fn is_major_bump(current: &str, latest: &str) -> bool {
+    let major = |v: &str| {
+        v.trim_start_matches(|c: char| !c.is_ascii_digit())
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .and_then(|m| m.parse::<u64>().ok())
+    };
+    match (major(current), major(latest)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    }
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -88,14 +166,24 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     node    20         20.0.0   20.1.0
 
     $ <bold>mise outdated --json</bold>
-    {"python": {"requested": "3.11", "current": "3.11.0", "latest": "3.11.1"}, ...}
+    {"schema_version": 1, "tools": {"python": {"requested": "3.11", "current": "3.11.0", "latest": "3.11.1"}, ...}}
+
+    # fail in CI if anything is out of date
+    $ <bold>mise outdated --exit-code</bold>
+
+    # choose which tools to upgrade
+    $ <bold>mise outdated --interactive</bold>
 "#
 );
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use crate::test::{change_installed_version, reset};
 
+    use super::is_major_bump;
+
     #[test]
     fn test_outdated() {
         reset();
@@ -124,4 +212,27 @@ mod tests {
         assert_cli!("use", "tiny@2");
         assert_cli_snapshot!("outdated", "tiny", "--json", "--bump");
     }
+
+    #[test]
+    fn test_is_major_bump() {
+        assert_eq!(is_major_bump("20.1.0", "22.0.0"), true);
+        assert_eq!(is_major_bump("20.1.0", "20.2.0"), false);
+        assert_eq!(is_major_bump("v1.2.3", "v2.0.0"), true);
+        assert_eq!(is_major_bump("temurin-17.0.1", "temurin-21.0.0"), true);
+        assert_eq!(is_major_bump("MISSING", "3.1.0"), false);
+    }
+
+    #[test]
+    fn test_outdated_interactive_no_tty() {
+        reset();
+        change_installed_version("tiny", "3.1.0", "3.0.0");
+        let err = crate::test::cli_run(
+            &["mise", "outdated", "tiny", "-i"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .unwrap_err();
+        change_installed_version("tiny", "3.0.0", "3.1.0");
+        assert!(err.to_string().contains("requires a terminal"), "{err}");
+    }
 }
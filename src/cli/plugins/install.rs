@@ -6,6 +6,7 @@ use url::Url;
 
 use crate::backend::unalias_backend;
 use crate::config::{Config, Settings};
+use crate::git::Git;
 use crate::plugins::asdf_plugin::AsdfPlugin;
 use crate::plugins::core::CORE_PLUGINS;
 use crate::plugins::Plugin;
@@ -140,6 +141,7 @@ fn get_name_and_url(name: &str, git_url: &Option<String>) -> Result<(String, Opt
 }
 
 fn get_name_from_url(url: &str) -> Result<String> {
+    let (url, _) = Git::split_url_and_ref(url);
     if let Ok(url) = Url::parse(url.trim_end_matches('/')) {
         if let Some(segments) = url.path_segments() {
             let last = segments.last().unwrap_or_default();
@@ -167,6 +169,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # install the node plugin using a specific ref
     $ <bold>mise plugins install node https://github.com/mise-plugins/rtx-nodejs.git#v1.0.0</bold>
+
+    # pin the node plugin to a commit, mise refuses to use it if the checkout moves
+    $ <bold>mise plugins install node https://github.com/mise-plugins/rtx-nodejs.git#a1b2c3d</bold>
 "#
 );
 
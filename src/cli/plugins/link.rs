@@ -6,12 +6,14 @@ use console::style;
 use path_absolutize::Absolutize;
 
 use crate::backend::unalias_backend;
-use crate::file::{make_symlink, remove_all};
-use crate::{dirs, file};
+use crate::config::Config;
+use crate::file::{display_path, make_symlink, remove_all};
+use crate::{backend, dirs, file};
 
 /// Symlinks a plugin into mise
 ///
-/// This is used for developing a plugin.
+/// This is used for developing a plugin. Changes to the local directory take effect
+/// immediately and `mise plugins unlink` removes the link without touching the directory.
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "ln", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct PluginsLink {
@@ -22,6 +24,7 @@ pub struct PluginsLink {
 
     /// The local path to the plugin
     /// e.g.: ./mise-node
+    /// The name can also come after the path, e.g.: `mise plugins link ./mise-node node`
     #[clap(value_hint = ValueHint::DirPath, verbatim_doc_comment)]
     path: Option<PathBuf>,
 
@@ -33,6 +36,10 @@ pub struct PluginsLink {
 impl PluginsLink {
     pub fn run(self) -> Result<()> {
         let (name, path) = match self.path {
+            // `link ./mise-node node`
+            Some(path) if !path.is_dir() && Path::new(&self.name).is_dir() => {
+                (path.to_string_lossy().to_string(), PathBuf::from(self.name))
+            }
             Some(path) => (self.name, path),
             None => {
                 let path = PathBuf::from(PathBuf::from(&self.name).absolutize()?);
@@ -53,12 +60,30 @@ impl PluginsLink {
                 ));
             }
         }
+        if Config::get().get_shorthands().contains_key(name)
+            || backend::list().iter().any(|b| b.id() == name)
+        {
+            warn!(
+                "{} is a registered plugin, the link to {} is used instead until it is unlinked",
+                style(&name).blue().for_stderr(),
+                display_path(&path)
+            );
+        }
         file::create_dir_all(*dirs::PLUGINS)?;
         make_symlink(&path, &symlink)?;
         Ok(())
     }
 }
 
+/// the directory a plugin made with `mise plugins link` points to
+pub fn link_target(name: &str) -> Option<PathBuf> {
+    let path = dirs::PLUGINS.join(name);
+    if !path.is_symlink() {
+        return None;
+    }
+    file::resolve_symlink(&path).ok()
+}
+
 fn get_name_from_path(path: &Path) -> String {
     let name = path.file_name().unwrap().to_str().unwrap();
     let name = name.strip_prefix("asdf-").unwrap_or(name);
@@ -74,23 +99,36 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # infer plugin name as "node"
     $ <bold>mise plugins link ./mise-node</bold>
+
+    # the name can also come after the path
+    $ <bold>mise plugins link ./my-plugin node</bold>
 "#
 );
 
 #[cfg(test)]
 mod tests {
-    use crate::test::reset;
     use test_log::test;
 
+    use super::link_target;
+    use crate::test::reset;
+
     #[test]
     fn test_plugin_link() {
         reset();
         assert_cli_snapshot!("plugin", "link", "-f", "tiny-link", "../data/plugins/tiny", @"");
+        let stdout = assert_cli!("plugins", "ls");
+        assert!(stdout.contains("tiny-link (linked to "));
+        let target = link_target("tiny-link").unwrap();
+        assert_cli_snapshot!("plugin", "unlink", "tiny-link", @"mise unlinked tiny-link");
         assert_cli_snapshot!("plugins", "ls", @r#"
         dummy
         tiny
-        tiny-link
         "#);
-        assert_cli_snapshot!("plugin", "uninstall", "tiny-link", @"");
+        assert!(target.join("bin").exists());
+        let err = assert_cli_err!("plugin", "unlink", "tiny");
+        assert_eq!(
+            err.to_string(),
+            "tiny is not a linked plugin, remove it with `mise plugins uninstall tiny`"
+        );
     }
 }
@@ -2,7 +2,9 @@ use eyre::Result;
 use rayon::prelude::*;
 use tabled::{Table, Tabled};
 
+use crate::cli::plugins::link::link_target;
 use crate::config::Config;
+use crate::file::display_path;
 use crate::plugins;
 use crate::plugins::asdf_plugin::AsdfPlugin;
 use crate::plugins::PluginType;
@@ -93,8 +95,11 @@ impl PluginsLs {
             miseprintln!("{table}");
         } else {
             hint!("registry", "see available plugins with", "mise registry");
-            for tool in tools.values() {
-                miseprintln!("{tool}");
+            for (name, tool) in &tools {
+                match link_target(name) {
+                    Some(target) => miseprintln!("{tool} (linked to {})", display_path(target)),
+                    None => miseprintln!("{tool}"),
+                }
             }
         }
         Ok(())
@@ -7,7 +7,9 @@ mod install;
 mod link;
 mod ls;
 mod ls_remote;
+mod test;
 mod uninstall;
+mod unlink;
 mod update;
 
 #[derive(Debug, clap::Args)]
@@ -51,7 +53,9 @@ enum Commands {
     Link(link::PluginsLink),
     Ls(ls::PluginsLs),
     LsRemote(ls_remote::PluginsLsRemote),
+    Test(test::PluginsTest),
     Uninstall(uninstall::PluginsUninstall),
+    Unlink(unlink::PluginsUnlink),
     Update(update::Update),
 }
 
@@ -62,7 +66,9 @@ impl Commands {
             Self::Link(cmd) => cmd.run(),
             Self::Ls(cmd) => cmd.run(config),
             Self::LsRemote(cmd) => cmd.run(config),
+            Self::Test(cmd) => cmd.run(),
             Self::Uninstall(cmd) => cmd.run(),
+            Self::Unlink(cmd) => cmd.run(),
             Self::Update(cmd) => cmd.run(),
         }
     }
@@ -0,0 +1,333 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use clap::ValueHint;
+use color_eyre::eyre::{bail, ensure, Result};
+use console::style;
+use itertools::Itertools;
+use path_absolutize::Absolutize;
+use serde_derive::Deserialize;
+
+use crate::config::Settings;
+use crate::file;
+use crate::file::display_path;
+use crate::plugins::{Script, ScriptManager};
+
+/// Runs a plugin's hooks to check that they work
+///
+/// This is for plugin authors. Each hook (list-all, latest-stable, download, install,
+/// list-bin-paths) is run in order against a temporary install directory and is reported
+/// with its timing. If a hook fails its stderr is shown, use -v to show the output of every hook.
+/// Nothing is written to the mise installs directory.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsTest {
+    /// The local path to the plugin
+    /// e.g.: ./mise-node
+    #[clap(value_hint = ValueHint::DirPath, verbatim_doc_comment)]
+    path: PathBuf,
+
+    /// The version to download and install
+    /// Defaults to the version from --fixtures, then latest-stable, then the last version from list-all
+    #[clap(long, verbatim_doc_comment)]
+    version: Option<String>,
+
+    /// A toml file with what the hooks are expected to return
+    ///
+    /// e.g.:
+    ///     version = "1.0.0"               # version to install
+    ///     versions = ["1.0.0", "1.1.0"]   # must be listed by list-all
+    ///     latest_stable = "1.1.0"         # must be returned by latest-stable
+    ///     bins = ["tool"]                 # must exist in the bin paths after install
+    #[clap(long, value_hint = ValueHint::FilePath, verbatim_doc_comment)]
+    fixtures: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fixtures {
+    version: Option<String>,
+    #[serde(default)]
+    versions: Vec<String>,
+    latest_stable: Option<String>,
+    #[serde(default)]
+    bins: Vec<String>,
+}
+
+impl PluginsTest {
+    pub fn run(self) -> Result<()> {
+        let path = self.path.absolutize()?.to_path_buf();
+        let sm = ScriptManager::new(path.clone());
+        ensure!(
+            sm.script_exists(&Script::ListAll),
+            "{} is not a plugin, bin/list-all not found",
+            display_path(&path)
+        );
+        let fixtures: Fixtures = match &self.fixtures {
+            Some(f) => toml::from_str(&file::read_to_string(f)?)?,
+            None => Fixtures::default(),
+        };
+        let mut harness = Harness {
+            verbose: Settings::get().verbose,
+            ran: 0,
+            failed: vec![],
+        };
+
+        let versions = harness.hook(&sm, Script::ListAll, true, |out| {
+            let versions = out.split_whitespace().collect_vec();
+            ensure!(!versions.is_empty(), "no versions returned");
+            Ok(format!("{} versions", versions.len()))
+        })?;
+        let Some(versions) = versions else {
+            return harness.finish();
+        };
+        if !fixtures.versions.is_empty() {
+            harness.check("versions", || {
+                let listed = versions.split_whitespace().collect_vec();
+                let missing = fixtures
+                    .versions
+                    .iter()
+                    .filter(|v| !listed.contains(&v.as_str()))
+                    .collect_vec();
+                ensure!(
+                    missing.is_empty(),
+                    "expected versions not listed: {}",
+                    missing.iter().join(", ")
+                );
+                Ok(fixtures.versions.join(", "))
+            })?;
+        }
+        let latest = harness.hook(&sm, Script::LatestStable, false, |out| {
+            let latest = out.trim();
+            ensure!(!latest.is_empty(), "no version returned");
+            if let Some(expected) = &fixtures.latest_stable {
+                ensure!(latest == expected, "expected {expected}, got {latest}");
+            }
+            Ok(latest.to_string())
+        })?;
+
+        let version = self
+            .version
+            .clone()
+            .or(fixtures.version.clone())
+            .or(latest.map(|v| v.trim().to_string()))
+            .or(versions.split_whitespace().last().map(String::from))
+            .unwrap();
+        let tmp = tempfile::tempdir()?;
+        let download = tmp.path().join("download");
+        let install = tmp.path().join("install");
+        file::create_dir_all(&download)?;
+        file::create_dir_all(&install)?;
+        let mut sm = sm;
+        for prefix in ["ASDF", "RTX", "MISE"] {
+            sm = sm
+                .with_env(format!("{prefix}_DOWNLOAD_PATH"), &download)
+                .with_env(format!("{prefix}_INSTALL_PATH"), &install)
+                .with_env(format!("{prefix}_INSTALL_TYPE"), "version")
+                .with_env(format!("{prefix}_INSTALL_VERSION"), &version);
+        }
+
+        harness.hook(&sm, Script::Download, false, |_| Ok(version.clone()))?;
+        let installed = harness.hook(&sm, Script::Install, true, |_| {
+            ensure!(
+                !file::ls(&install)?.is_empty(),
+                "nothing was written to the install path"
+            );
+            Ok(version.clone())
+        })?;
+        if installed.is_none() {
+            return harness.finish();
+        }
+        let bin_paths = harness
+            .hook(&sm, Script::ListBinPaths, false, |out| {
+                let missing = out
+                    .split_whitespace()
+                    .filter(|p| !install.join(p).is_dir())
+                    .collect_vec();
+                ensure!(
+                    missing.is_empty(),
+                    "bin paths not found in the install path: {}",
+                    missing.join(", ")
+                );
+                Ok(out.split_whitespace().join(" "))
+            })?
+            .map(|out| out.split_whitespace().map(String::from).collect_vec())
+            .unwrap_or_else(|| vec!["bin".into()]);
+        if !fixtures.bins.is_empty() {
+            harness.check("bins", || {
+                let missing = fixtures
+                    .bins
+                    .iter()
+                    .filter(|b| !bin_paths.iter().any(|p| install.join(p).join(b).is_file()))
+                    .collect_vec();
+                ensure!(
+                    missing.is_empty(),
+                    "not found in {}: {}",
+                    bin_paths.join(", "),
+                    missing.iter().join(", ")
+                );
+                Ok(fixtures.bins.join(", "))
+            })?;
+        }
+        harness.finish()
+    }
+}
+
+struct Harness {
+    verbose: bool,
+    ran: usize,
+    failed: Vec<String>,
+}
+
+impl Harness {
+    /// runs `script` capturing its output, then `check` on its stdout which returns a
+    /// summary to show. Returns stdout if the hook succeeded.
+    fn hook<F>(
+        &mut self,
+        sm: &ScriptManager,
+        script: Script,
+        required: bool,
+        check: F,
+    ) -> Result<Option<String>>
+    where
+        F: FnOnce(&str) -> Result<String>,
+    {
+        let name = script.to_string();
+        if !sm.script_exists(&script) {
+            if required {
+                self.fail(&name, None, &format!("bin/{name} not found"), "")?;
+            } else {
+                miseprintln!("{} {name} skipped, no bin/{name}", style("-").dim());
+            }
+            return Ok(None);
+        }
+        self.ran += 1;
+        let cmd = sm.cmd(&script).stdout_capture().stderr_capture();
+        let start = Instant::now();
+        let output = match sm.output(&script, cmd) {
+            Ok(output) => output,
+            Err(err) => {
+                self.fail(&name, Some(start.elapsed()), &format!("{err:#}"), "")?;
+                return Ok(None);
+            }
+        };
+        let elapsed = start.elapsed();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        if !output.status.success() {
+            let msg = match output.status.code() {
+                Some(code) => format!("exited with code {code}"),
+                None => "was killed".to_string(),
+            };
+            self.fail(&name, Some(elapsed), &msg, &stderr)?;
+            return Ok(None);
+        }
+        match check(&stdout) {
+            Ok(summary) => {
+                miseprintln!(
+                    "{} {name} {summary} {}",
+                    style("✓").green(),
+                    style(format!("({})", format_duration(elapsed))).dim()
+                );
+                if self.verbose {
+                    print_output("stdout", &stdout)?;
+                    print_output("stderr", &stderr)?;
+                }
+                Ok(Some(stdout))
+            }
+            Err(err) => {
+                self.fail(&name, Some(elapsed), &format!("{err:#}"), &stderr)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn check<F>(&mut self, name: &str, check: F) -> Result<()>
+    where
+        F: FnOnce() -> Result<String>,
+    {
+        self.ran += 1;
+        match check() {
+            Ok(summary) => miseprintln!("{} {name} {summary}", style("✓").green()),
+            Err(err) => self.fail(name, None, &format!("{err:#}"), "")?,
+        }
+        Ok(())
+    }
+
+    fn fail(
+        &mut self,
+        name: &str,
+        elapsed: Option<Duration>,
+        msg: &str,
+        stderr: &str,
+    ) -> Result<()> {
+        let elapsed = elapsed
+            .map(|e| format!(" {}", style(format!("({})", format_duration(e))).dim()))
+            .unwrap_or_default();
+        miseprintln!("{} {name} {msg}{elapsed}", style("✗").red());
+        print_output("stderr", stderr)?;
+        self.failed.push(name.to_string());
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        if !self.failed.is_empty() {
+            bail!(
+                "{} of {} hooks failed: {}",
+                self.failed.len(),
+                self.ran.max(self.failed.len()),
+                self.failed.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+fn print_output(label: &str, output: &str) -> Result<()> {
+    let output = output.trim_end();
+    if output.is_empty() {
+        return Ok(());
+    }
+    miseprintln!("  {}", style(format!("{label}:")).dim());
+    for line in output.lines() {
+        miseprintln!("    {line}");
+    }
+    Ok(())
+}
+
+fn format_duration(dur: Duration) -> String {
+    if dur < Duration::from_secs(1) {
+        format!("{:.0?}", dur)
+    } else {
+        format!("{:.2?}", dur)
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+    $ <bold>mise plugins test ./mise-node</bold>
+    ✓ list-all 1203 versions (310ms)
+    ✓ latest-stable 22.9.0 (402ms)
+    - download skipped, no bin/download
+    ✓ install 22.9.0 (4.12s)
+    ✓ list-bin-paths bin (8ms)
+
+    # check the hooks return what is expected
+    $ <bold>mise plugins test ./mise-node --fixtures fixtures.toml</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use crate::test::reset;
+
+    #[test]
+    fn test_plugin_test() {
+        reset();
+        let stdout = assert_cli!("plugins", "test", "../data/plugins/tiny");
+        assert!(stdout.contains("list-all 9 versions"), "{stdout}");
+        assert!(stdout.contains("install 3.1.0"), "{stdout}");
+    }
+}
@@ -0,0 +1,42 @@
+use eyre::{bail, Result};
+
+use crate::backend::unalias_backend;
+use crate::cli::plugins::link::link_target;
+use crate::dirs;
+use crate::file;
+use crate::ui::style;
+
+/// Removes a plugin symlinked with `mise plugins link`
+///
+/// Only the link is removed, the local directory it points to is left as it is.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsUnlink {
+    /// Linked plugin(s) to remove
+    #[clap(required = true, verbatim_doc_comment)]
+    plugin: Vec<String>,
+}
+
+impl PluginsUnlink {
+    pub fn run(self) -> Result<()> {
+        for name in &self.plugin {
+            let name = unalias_backend(name);
+            if link_target(name).is_none() {
+                bail!(
+                    "{name} is not a linked plugin, remove it with `mise plugins uninstall {name}`"
+                );
+            }
+            file::remove_file(dirs::PLUGINS.join(name))?;
+            info!("unlinked {}", style::eblue(name));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise plugins link node ./mise-node</bold>
+    $ <bold>mise plugins unlink node</bold>
+"#
+);
@@ -82,6 +82,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise plugins update</bold>            # update all plugins
     $ <bold>mise plugins update node</bold>       # update only node
     $ <bold>mise plugins update node#beta</bold>  # specify a ref
+    $ <bold>mise plugins update node#a1b2c3d</bold>  # move a pinned plugin to another commit
 "#
 );
 
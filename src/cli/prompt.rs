@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use eyre::Result;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::cache::CacheManagerBuilder;
+use crate::config::{self, Config, Settings, DEFAULT_CONFIG_FILENAMES};
+use crate::hash::hash_to_str;
+use crate::tera::{get_tera, BASE_CONTEXT};
+use crate::toolset::ToolsetBuilder;
+use crate::{dirs, hook_env, http};
+
+/// Prints a short summary of the active tool versions for a shell prompt
+///
+/// Nothing is printed outside of a directory with a mise config file, tools that are only set
+/// in the global config aren't shown. This never uses the network and the output is cached until a
+/// config file changes or a tool is installed so it is fast enough to run on every prompt.
+///
+/// The output is rendered from the `prompt_format` setting.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Prompt {}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PromptCache {
+    output: String,
+    /// the files the output depends on and their modified times when it was rendered
+    watch_files: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl PromptCache {
+    /// stale if a watched file changed or a config file was added since it was rendered
+    fn is_fresh(&self, config_paths: &[PathBuf]) -> bool {
+        config_paths
+            .iter()
+            .all(|p| self.watch_files.contains_key(p))
+            && self
+                .watch_files
+                .iter()
+                .all(|(p, modified)| mtime(p).is_some_and(|m| m == *modified))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PromptTool {
+    name: String,
+    version: String,
+    request: String,
+    installed: bool,
+}
+
+impl Prompt {
+    pub fn run(self) -> Result<()> {
+        http::set_budget(http::INTERACTIVE_BUDGET);
+        let Some(cwd) = &dirs::cwd() else {
+            return Ok(());
+        };
+        let config_paths = config::load_config_paths(&DEFAULT_CONFIG_FILENAMES);
+        if config_paths.iter().all(|p| config::is_global_config(p)) {
+            return Ok(());
+        }
+        let format = Settings::get().prompt_format.clone();
+        let cache = CacheManagerBuilder::new(
+            dirs::CACHE
+                .join("prompt")
+                .join(format!("{}.msgpack.z", hash_to_str(cwd))),
+        )
+        .with_cache_key(hook_env::get_mise_env_vars_hashed())
+        .with_cache_key(format.clone())
+        .build::<PromptCache>();
+        let cached = cache.get_or_try_init(|| render(&format))?;
+        if cached.is_fresh(&config_paths) {
+            return print(&cached.output);
+        }
+        trace!("prompt cache is stale");
+        let fresh = render(&format)?;
+        if let Err(err) = cache.write(&fresh) {
+            debug!("failed to write prompt cache: {err:#}");
+        }
+        print(&fresh.output)
+    }
+}
+
+fn render(format: &str) -> Result<PromptCache> {
+    let config = Config::try_get()?;
+    let ts = ToolsetBuilder::new().build(&config)?;
+    let tools: Vec<PromptTool> = ts
+        .list_current_versions()
+        .into_iter()
+        .map(|(b, tv)| PromptTool {
+            name: b.id().to_string(),
+            installed: b.is_version_installed(&tv, true),
+            request: tv.request.version(),
+            version: tv.version,
+        })
+        .collect();
+    let mut ctx = BASE_CONTEXT.clone();
+    ctx.insert("tools", &tools);
+    let output = get_tera(None).render_str(format, &ctx)?.trim().to_string();
+    // the data dir is touched whenever a tool is installed or uninstalled
+    let watch_files = config
+        .config_files
+        .keys()
+        .cloned()
+        .chain([dirs::DATA.to_path_buf()])
+        .filter_map(|p| mtime(&p).map(|m| (p, m)))
+        .collect();
+    Ok(PromptCache {
+        output,
+        watch_files,
+    })
+}
+
+fn mtime(path: &std::path::Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
+}
+
+fn print(output: &str) -> Result<()> {
+    if !output.is_empty() {
+        miseprintln!("{output}");
+    }
+    Ok(())
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+  $ <bold>mise prompt</bold>
+  node@20.17.0 python@3.12.5
+
+  # bash
+  PS1='$(mise prompt) \$ '
+
+  # zsh
+  setopt prompt_subst
+  PROMPT='$(mise prompt) %# '
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    #[test]
+    fn test_prompt() {
+        reset();
+        assert_cli_snapshot!("prompt", @"tiny@3.1.0 dummy@ref:master");
+    }
+}
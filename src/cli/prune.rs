@@ -3,11 +3,15 @@ use std::sync::Arc;
 
 use console::style;
 use eyre::Result;
+use itertools::Itertools;
+use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
 use crate::config::tracking::Tracker;
 use crate::config::{Config, Settings};
+use crate::file;
+use crate::mise_lock::{self, Lockfile};
 use crate::toolset::{ToolVersion, Toolset, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::prompt;
@@ -20,6 +24,7 @@ use super::trust::Trust;
 /// Versions which are no longer the latest specified in any of those configs are deleted.
 /// Versions installed only with environment variables (`MISE_<PLUGIN>_VERSION`) will be deleted,
 /// as will versions only referenced on the command line (`mise exec <PLUGIN>@<VERSION>`).
+/// Versions pinned in a mise.lock next to any of those configs are kept.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Prune {
@@ -38,6 +43,10 @@ pub struct Prune {
     /// Prune only unused versions of tools
     #[clap(long)]
     pub tools: bool,
+
+    /// Keep the N newest unused versions of each tool
+    #[clap(long, value_name = "N")]
+    pub keep_last: Option<usize>,
 }
 
 impl Prune {
@@ -81,6 +90,32 @@ impl Prune {
             for (_, tv) in ts.list_current_versions() {
                 to_delete.remove(&tv.to_string());
             }
+            if let Some(path) = mise_lock::path_for_config(cf.get_path()) {
+                for (short, locked) in Lockfile::read(&path)?.tools {
+                    to_delete.retain(|_, (_, tv)| {
+                        tv.backend.short != short || tv.version != locked.version
+                    });
+                }
+            }
+        }
+
+        if let Some(n) = self.keep_last {
+            let keep = to_delete
+                .values()
+                .map(|(_, tv)| tv)
+                .into_group_map_by(|tv| tv.backend.short.clone())
+                .into_values()
+                .flat_map(|tvs| {
+                    tvs.into_iter()
+                        .sorted_by_cached_key(|tv| Versioning::new(&tv.version))
+                        .rev()
+                        .take(n)
+                        .map(|tv| tv.to_string())
+                })
+                .collect_vec();
+            for key in keep {
+                to_delete.remove(&key);
+            }
         }
 
         self.delete(to_delete.into_values().collect())
@@ -89,6 +124,7 @@ impl Prune {
     fn delete(&self, to_delete: Vec<(Arc<dyn Backend>, ToolVersion)>) -> Result<()> {
         let settings = Settings::try_get()?;
         let mpr = MultiProgressReport::get();
+        let mut reclaimed = 0;
         for (p, tv) in to_delete {
             let mut prefix = tv.style();
             if self.dry_run {
@@ -96,10 +132,18 @@ impl Prune {
             }
             let pr = mpr.add(&prefix);
             if self.dry_run || settings.yes || prompt::confirm(format!("remove {} ?", &tv))? {
+                reclaimed += file::dir_size(&tv.install_path());
                 p.uninstall_version(&tv, pr.as_ref(), self.dry_run)?;
                 pr.finish();
             }
         }
+        if reclaimed > 0 {
+            let size = file::format_bytes(reclaimed as f64);
+            match self.dry_run {
+                true => info!("would reclaim {size} {}", style("[dryrun]").bold()),
+                false => info!("reclaimed {size}"),
+            }
+        }
         Ok(())
     }
 }
@@ -110,6 +154,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise prune --dry-run</bold>
     rm -rf ~/.local/share/mise/versions/node/20.0.0
     rm -rf ~/.local/share/mise/versions/node/20.0.1
+
+    # keep the 2 newest unused versions of each tool around
+    $ <bold>mise prune --keep-last 2</bold>
 "#
 );
 
@@ -126,4 +173,14 @@ mod tests {
         assert_cli!("prune");
         assert_cli!("install");
     }
+
+    #[test]
+    fn test_prune_keep_last() {
+        reset();
+        assert_cli!("install", "tiny@1");
+        assert_cli_snapshot!("prune", "--dry-run", "--tools", "tiny", @"mise would reclaim 61 B [dryrun]");
+        assert_cli_snapshot!("prune", "--dry-run", "--tools", "--keep-last", "1", "tiny", @"");
+        assert_cli!("prune", "tiny");
+        assert_cli!("install");
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use clap::Subcommand;
 use eyre::Result;
 use tabled::{Table, Tabled};
 
@@ -7,31 +8,32 @@ use crate::config::{settings, Config};
 use crate::registry::REGISTRY;
 use crate::ui::table;
 
+mod search;
+mod update;
+
 /// [experimental] List available tools
 #[derive(Debug, clap::Args)]
 #[clap(after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
-pub struct Registry {}
+pub struct Registry {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Search(search::RegistrySearch),
+    Update(update::RegistryUpdate),
+}
 
 impl Registry {
     pub fn run(self) -> Result<()> {
         settings::ensure_experimental("registry")?;
-        let mut tools = BTreeMap::new();
-
-        for (plugin, url) in Config::get().get_shorthands() {
-            let re = regex!(r#"^https://github.com/(.+?/.+?)(.git)?$"#);
-            let full = if let Some(caps) = re.captures(url) {
-                format!("asdf:{}", &caps[1])
-            } else {
-                format!("asdf:{}", url)
-            };
-            tools.insert(plugin.to_string(), full);
+        match self.command {
+            Some(Commands::Search(cmd)) => return cmd.run(),
+            Some(Commands::Update(cmd)) => return cmd.run(),
+            None => {}
         }
-
-        for (short, full) in REGISTRY.iter() {
-            tools.insert(short.to_string(), full.to_string());
-        }
-
-        let data = tools.into_iter().map(|x| x.into()).collect::<Vec<Row>>();
+        let data = tools().into_iter().map(|x| x.into()).collect::<Vec<Row>>();
         let mut table = Table::new(data);
         table::default_style(&mut table, false);
         miseprintln!("{table}");
@@ -39,6 +41,25 @@ impl Registry {
     }
 }
 
+/// every tool that can be used by its short name, from the shorthands and the registry
+fn tools() -> BTreeMap<String, String> {
+    let mut tools = BTreeMap::new();
+    for (plugin, url) in Config::get().get_shorthands() {
+        let re = regex!(r#"^https://github.com/(.+?/.+?)(.git)?$"#);
+        let full = if let Some(caps) = re.captures(url) {
+            format!("asdf:{}", &caps[1])
+        } else {
+            format!("asdf:{}", url)
+        };
+        tools.insert(plugin.to_string(), full);
+    }
+
+    for (short, full) in REGISTRY.iter() {
+        tools.insert(short.to_string(), full.to_string());
+    }
+    tools
+}
+
 #[derive(Tabled, Eq, PartialEq, Ord, PartialOrd)]
 #[tabled(rename_all = "PascalCase")]
 struct Row {
@@ -59,6 +80,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     node    core:node
     poetry  asdf:mise-plugins/mise-poetry
     ubi     cargo:ubi
+
+    $ <bold>mise registry search poet</bold>
+    $ <bold>mise registry update</bold>   # download the registry at registry_url again
 "#
 );
 
@@ -0,0 +1,150 @@
+use eyre::Result;
+use itertools::Itertools;
+use serde_derive::Serialize;
+use tabled::{Table, Tabled};
+
+use crate::cli::registry::tools;
+use crate::plugins::core::CORE_PLUGINS;
+use crate::ui::table;
+
+/// Search the registry for a tool
+///
+/// Tools are matched by name, the closest matches are listed first: an exact match, then names
+/// starting with the query, names containing it, tools whose backend contains it and finally
+/// names that contain the letters of the query in order.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct RegistrySearch {
+    /// The name, or part of the name, of the tool
+    query: String,
+
+    /// Only show tools that use this backend
+    /// e.g.: asdf, cargo, core, ubi, vfox
+    #[clap(long, short, verbatim_doc_comment)]
+    backend: Option<String>,
+
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+#[tabled(rename_all = "PascalCase")]
+struct Row {
+    short: String,
+    backend: String,
+    full: String,
+}
+
+impl RegistrySearch {
+    pub fn run(self) -> Result<()> {
+        let query = self.query.to_lowercase();
+        // core tools aren't in the registry but they are what most people look for and they are
+        // used instead of a plugin with the same name
+        let core = CORE_PLUGINS
+            .keys()
+            .map(|id| (id.to_string(), format!("core:{id}")));
+        let rows = core
+            .chain(tools())
+            .unique_by(|(short, _)| short.clone())
+            .filter_map(|(short, full)| {
+                let backend = full.split_once(':').map(|(b, _)| b).unwrap_or_default();
+                if self.backend.as_ref().is_some_and(|b| b != backend) {
+                    return None;
+                }
+                let rank = rank(&query, &short, &full)?;
+                let row = Row {
+                    backend: backend.to_string(),
+                    short,
+                    full,
+                };
+                Some((rank, row))
+            })
+            .sorted_by(|(a, ra), (b, rb)| {
+                (a, ra.short.len(), &ra.short).cmp(&(b, rb.short.len(), &rb.short))
+            })
+            .map(|(_, row)| row)
+            .collect_vec();
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&rows)?);
+            return Ok(());
+        }
+        if rows.is_empty() {
+            warn!("no tools in the registry match {}", self.query);
+            return Ok(());
+        }
+        let mut table = Table::new(rows);
+        table::default_style(&mut table, false);
+        miseprintln!("{table}");
+        Ok(())
+    }
+}
+
+/// how closely a tool matches the query, lower is closer and None is no match
+fn rank(query: &str, short: &str, full: &str) -> Option<u8> {
+    let short = short.to_lowercase();
+    if short == query {
+        Some(0)
+    } else if short.starts_with(query) {
+        Some(1)
+    } else if short.contains(query) {
+        Some(2)
+    } else if full.to_lowercase().contains(query) {
+        Some(3)
+    } else if is_subsequence(query, &short) {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+fn is_subsequence(query: &str, s: &str) -> bool {
+    let mut chars = s.chars();
+    query.chars().all(|q| chars.any(|c| c == q))
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise registry search poet</bold>
+    poetry  asdf  asdf:mise-plugins/mise-poetry
+
+    $ <bold>mise registry search --backend cargo bin</bold>
+    cargo-binstall  cargo  cargo:cargo-binstall
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test::reset;
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(rank("node", "node", "core:node"), Some(0));
+        assert_eq!(rank("no", "nodejs", "asdf:asdf-vm/asdf-nodejs"), Some(1));
+        assert_eq!(rank("js", "nodejs", "asdf:asdf-vm/asdf-nodejs"), Some(2));
+        assert_eq!(
+            rank("asdf-vm", "nodejs", "asdf:asdf-vm/asdf-nodejs"),
+            Some(3)
+        );
+        assert_eq!(rank("ndjs", "nodejs", "asdf:asdf-vm/asdf-nodejs"), Some(4));
+        assert_eq!(rank("python", "nodejs", "asdf:asdf-vm/asdf-nodejs"), None);
+    }
+
+    #[test]
+    fn test_registry_search() {
+        reset();
+        let out = assert_cli!("registry", "search", "--json", "poetry");
+        let rows: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        assert_eq!(rows[0]["short"], "poetry");
+        assert_eq!(rows[0]["backend"], "asdf");
+        let out = assert_cli!("registry", "search", "--json", "-b", "core", "node");
+        let rows: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        assert_eq!(rows[0]["full"], "core:node");
+        let out = assert_cli!("registry", "search", "--json", "-b", "cargo", "poetry");
+        assert_eq!(out, "[]");
+    }
+}
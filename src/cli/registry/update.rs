@@ -0,0 +1,39 @@
+use eyre::{bail, Result};
+
+use crate::config::Settings;
+use crate::registry;
+
+/// Download the registry at `registry_url` again
+///
+/// Normal commands read the registry from the cache and only download it when the cache is
+/// missing, corrupt or older than `registry_cache_ttl`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct RegistryUpdate {}
+
+impl RegistryUpdate {
+    pub fn run(self) -> Result<()> {
+        let settings = Settings::get();
+        let Some(url) = &settings.registry_url else {
+            bail!("registry_url is not set, there is no registry to update");
+        };
+        let tools = registry::update(url)?;
+        info!("updated the registry from {url}, {} tools", tools.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    #[test]
+    fn test_registry_update_without_url() {
+        reset();
+        let err = assert_cli_err!("registry", "update");
+        assert_eq!(
+            err.to_string(),
+            "registry_url is not set, there is no registry to update"
+        );
+    }
+}
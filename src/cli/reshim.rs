@@ -33,7 +33,14 @@ impl Reshim {
         let config = Config::try_get()?;
         let ts = ToolsetBuilder::new().build(&config)?;
 
-        shims::reshim(&ts)
+        let stats = shims::reshim(&ts)?;
+        // kept quiet when nothing changed since this is often run from shell functions
+        if stats.added == 0 && stats.removed == 0 {
+            debug!("shims are up to date");
+        } else {
+            info!("added {} shims, removed {}", stats.added, stats.removed);
+        }
+        Ok(())
     }
 }
 
@@ -41,6 +48,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
     $ <bold>mise reshim</bold>
+    mise added 2 shims, removed 0
     $ <bold>~/.local/share/mise/shims/node -v</bold>
     v20.0.0
 "#
@@ -1,18 +1,19 @@
-use std::collections::{BTreeMap, HashSet};
-use std::io::Write;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{IsTerminal, Write};
 use std::iter::once;
 use std::path::{Path, PathBuf};
 use std::process::{exit, Stdio};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 
 use clap::ValueHint;
 use console::Color;
 use demand::{DemandOption, Select};
 use duct::IntoExecutablePath;
 use either::Either;
-use eyre::{bail, ensure, eyre, Result};
+use eyre::{bail, ensure, eyre, Result, WrapErr};
 use glob::glob;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
@@ -21,7 +22,7 @@ use super::args::ToolArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings, CONFIG};
 use crate::errors::Error;
-use crate::errors::Error::ScriptFailed;
+use crate::errors::Error::{ScriptFailed, ScriptTimedOut, TaskTimedOut};
 use crate::file::display_path;
 use crate::task::{Deps, GetMatchingExt, Task};
 use crate::toolset::{InstallOptions, ToolsetBuilder};
@@ -66,11 +67,17 @@ pub struct Run {
     #[clap(allow_hyphen_values = true)]
     pub args: Vec<String>,
 
+    /// Arguments after "--" are passed verbatim to the last task, including ":::" and "--"
+    #[clap(last = true, hide = true)]
+    pub args_last: Vec<String>,
+
     /// Change to this directory before executing the command
     #[clap(short = 'C', long, value_hint = ValueHint::DirPath, long)]
     pub cd: Option<PathBuf>,
 
     /// Don't actually run the tasks(s), just print them in order of execution
+    /// Shows the directory, the env vars set by the task and the rendered commands
+    /// Tools are not installed and sources are not checked or updated
     #[clap(long, short = 'n', verbatim_doc_comment)]
     pub dry_run: bool,
 
@@ -106,10 +113,40 @@ pub struct Run {
     #[clap(long, short, verbatim_doc_comment)]
     pub raw: bool,
 
+    /// Connect stdin of the tasks to the terminal
+    /// Defaults to true if mise is run from a terminal and tasks run one at a time
+    #[clap(long, verbatim_doc_comment, overrides_with = "no_stdin")]
+    pub stdin: bool,
+
+    /// Don't connect stdin of the tasks to the terminal, they read from /dev/null instead
+    #[clap(long, verbatim_doc_comment, overrides_with = "stdin")]
+    pub no_stdin: bool,
+
     /// Shows elapsed time after each tasks
     #[clap(long, alias = "timing", verbatim_doc_comment)]
     pub timings: bool,
 
+    /// Also write the output of each task to `<dir>/<task>.log` with timestamps
+    /// Configure with `task_log_dir` config or `MISE_TASK_LOG_DIR` env var
+    #[clap(long, value_hint = ValueHint::DirPath, verbatim_doc_comment)]
+    pub log_dir: Option<PathBuf>,
+
+    /// Append to the log files instead of truncating them each run
+    #[clap(long, verbatim_doc_comment)]
+    pub append: bool,
+
+    /// Keep starting tasks after one fails, only the tasks that depend on it are skipped
+    /// The failed and skipped tasks are listed at the end and mise exits with a failure
+    /// Without this no more tasks are started once one fails
+    #[clap(long, short = 'k', visible_alias = "keep-going", verbatim_doc_comment)]
+    pub continue_on_error: bool,
+
+    /// Stop tasks that don't set `timeout` if they run longer than this, e.g.: 30m
+    /// They get SIGTERM (or their `timeout_signal`) and are killed if still running 10s later
+    /// Configure with `task_timeout` config or `MISE_TASK_TIMEOUT` env var
+    #[clap(long, value_name = "DURATION", value_parser = humantime::parse_duration, verbatim_doc_comment)]
+    pub timeout: Option<Duration>,
+
     #[clap(skip)]
     pub is_linear: bool,
 }
@@ -120,12 +157,25 @@ impl Run {
         let settings = Settings::try_get()?;
         settings.ensure_experimental("`mise run`")?;
         let task_list = self.get_task_lists(&config)?;
+        for task in &task_list {
+            if task.args.iter().any(|a| a == "--help" || a == "-h") {
+                if let Some(help) = task.render_help(self.cd.clone())? {
+                    miseprintln!("{}", help.trim_end());
+                    return Ok(());
+                }
+            }
+        }
         self.parallelize_tasks(&config, task_list)
     }
 
     fn get_task_lists(&self, config: &Config) -> Result<Vec<Task>> {
-        once(&self.task)
-            .chain(self.args.iter())
+        // clap keeps "--" in args if it comes after a task argument
+        let (args, args_last) = match self.args.iter().position(|a| a == "--") {
+            Some(i) => (&self.args[..i], &self.args[i + 1..]),
+            None => (&self.args[..], &self.args_last[..]),
+        };
+        let mut task_args = once(&self.task)
+            .chain(args.iter())
             .map(|s| vec![s.to_string()])
             .coalesce(|a, b| {
                 if b == vec![":::".to_string()] {
@@ -136,6 +186,12 @@ impl Run {
                     Ok(a.into_iter().chain(b).collect_vec())
                 }
             })
+            .collect_vec();
+        if let Some(last) = task_args.last_mut() {
+            last.extend(args_last.iter().cloned());
+        }
+        task_args
+            .into_iter()
             .flat_map(|args| args.split_first().map(|(t, a)| (t.clone(), a.to_vec())))
             .map(|(t, args)| {
                 let tasks = config
@@ -162,20 +218,24 @@ impl Run {
     }
 
     fn parallelize_tasks(mut self, config: &Config, tasks: Vec<Task>) -> Result<()> {
-        let mut ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
-
-        ts.install_arg_versions(config, &InstallOptions::new())?;
-        ts.notify_if_versions_missing();
-        let mut env = ts.env_with_path(config)?;
-        if let Some(root) = &config.project_root {
-            env.insert("MISE_PROJECT_ROOT".into(), root.display().to_string());
-            env.insert("root".into(), root.display().to_string());
-        }
+        let env = self.tools_env(config, &self.tool, true)?;
 
         let tasks = Deps::new(config, tasks)?;
         for task in tasks.all() {
             self.validate_task(task)?;
         }
+        // tools for each task are installed before anything runs so a missing one fails early
+        let mut task_envs = HashMap::new();
+        for task in tasks.all().filter(|t| !t.tools.is_empty()) {
+            let tools = task_tool_args(task)?
+                .into_iter()
+                .chain(self.tool.iter().cloned())
+                .collect_vec();
+            let env = self
+                .tools_env(config, &tools, false)
+                .wrap_err_with(|| format!("failed to install the tools for task {}", task.name))?;
+            task_envs.insert(task.name.clone(), env);
+        }
 
         let num_tasks = tasks.all().count();
         self.is_linear = tasks.is_linear();
@@ -187,19 +247,40 @@ impl Run {
             .num_threads(self.jobs() + 1)
             .build()?;
         let exit_status = Mutex::new(None);
+        let failed = Mutex::new(vec![]);
+        let skipped = Mutex::new(vec![]);
         pool.scope(|s| {
             let run = |task: &Task| {
                 let t = task.clone();
                 s.spawn(|_| {
                     let task = t;
+                    // a task failed so the ones that haven't started yet are left out
+                    if !self.continue_on_error && !failed.lock().unwrap().is_empty() {
+                        skipped.lock().unwrap().push(task.name.clone());
+                        tasks.lock().unwrap().remove(&task);
+                        return;
+                    }
                     trace!("running tasks: {task}");
-                    if let Err(err) = self.run_task(config, &env, &task) {
+                    let env = task_envs.get(&task.name).unwrap_or(&env);
+                    if let Err(err) = self.run_task(config, env, &task) {
                         error!("{err}");
                         if let Some(ScriptFailed(_, Some(status))) = err.downcast_ref::<Error>() {
                             *exit_status.lock().unwrap() = status.code();
                         } else {
                             *exit_status.lock().unwrap() = Some(1);
                         }
+                        failed.lock().unwrap().push(task.name.clone());
+                        // tasks that don't depend on the failed one still run
+                        let dependents = tasks.lock().unwrap().remove_failed(&task);
+                        if !dependents.is_empty() && self.continue_on_error {
+                            let names = dependents.iter().map(|t| &t.name).join(", ");
+                            warn!("skipping {names} since {} failed", task.name);
+                        }
+                        skipped
+                            .lock()
+                            .unwrap()
+                            .extend(dependents.into_iter().map(|t| t.name));
+                        return;
                     }
                     let mut tasks = tasks.lock().unwrap();
                     tasks.remove(&task);
@@ -207,9 +288,6 @@ impl Run {
             };
             let rx = tasks.lock().unwrap().subscribe();
             while let Some(task) = rx.recv().unwrap() {
-                if exit_status.lock().unwrap().is_some() {
-                    break;
-                }
                 run(&task);
             }
         });
@@ -218,6 +296,25 @@ impl Run {
             let msg = format!("finished in {}", format_duration(timer.elapsed()));
             info!("{}", style::edim(msg));
         };
+        let failed = failed.into_inner().unwrap();
+        let skipped = skipped.into_inner().unwrap();
+        if self.continue_on_error && !failed.is_empty() {
+            let ran = num_tasks - skipped.len();
+            error!(
+                "{} of {ran} tasks failed: {}",
+                failed.len(),
+                failed.iter().join(", ")
+            );
+            if !skipped.is_empty() {
+                warn!("skipped: {}", skipped.iter().sorted().join(", "));
+            }
+        } else if !skipped.is_empty() {
+            let failed = failed.iter().join(", ");
+            warn!(
+                "{failed} failed so {} tasks were not run, use --continue-on-error to run the tasks that don't depend on it",
+                skipped.len()
+            );
+        }
 
         if let Some(status) = *exit_status.lock().unwrap() {
             debug!("exiting with status: {status}");
@@ -227,17 +324,47 @@ impl Run {
         Ok(())
     }
 
+    /// the env with `tools` installed and on PATH, `tools` override the versions in the config
+    fn tools_env(
+        &self,
+        config: &Config,
+        tools: &[ToolArg],
+        notify_missing: bool,
+    ) -> Result<BTreeMap<String, String>> {
+        let mut ts = ToolsetBuilder::new().with_args(tools).build(config)?;
+        if !self.dry_run {
+            ts.install_arg_versions(config, &InstallOptions::new())?;
+        }
+        if notify_missing {
+            ts.notify_if_versions_missing();
+        }
+        let mut env = ts.env_with_path(config)?;
+        if let Some(root) = &config.project_root {
+            env.insert("MISE_PROJECT_ROOT".into(), root.display().to_string());
+            env.insert("root".into(), root.display().to_string());
+        }
+        Ok(env)
+    }
+
     fn run_task(&self, config: &Config, env: &BTreeMap<String, String>, task: &Task) -> Result<()> {
-        let prefix = style::estyle(task.prefix()).fg(get_color()).to_string();
-        if !self.force && self.sources_are_fresh(config, task) {
+        let prefix = match self.dry_run {
+            // printed to stdout
+            true => task.prefix(),
+            false => style::estyle(task.prefix()).fg(get_color()).to_string(),
+        };
+        if !self.force && !self.dry_run && self.sources_are_fresh(config, task) {
             info_unprefix_trunc!("{prefix} sources up-to-date, skipping");
             return Ok(());
         }
 
-        let string_env = task.env.iter().filter_map(|(k, v)| match &v.0 {
-            Either::Left(v) => Some((k, v)),
-            _ => None,
-        });
+        let string_env = task
+            .env
+            .iter()
+            .filter_map(|(k, v)| match &v.0 {
+                Either::Left(v) => Some((k, v)),
+                _ => None,
+            })
+            .collect_vec();
         let rm_env = task
             .env
             .iter()
@@ -246,18 +373,50 @@ impl Run {
             .collect::<HashSet<_>>();
         let env: BTreeMap<String, String> = env
             .iter()
-            .chain(string_env)
+            .chain(string_env.iter().copied())
             .filter(|(k, _)| !rm_env.contains(k))
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
 
+        if self.dry_run {
+            if let Some(dir) = self.task_dir(task)? {
+                miseprintln!("{prefix} cd {}", display_path(dir));
+            }
+            let redactor = CONFIG.redactor(&env);
+            for (k, v) in &string_env {
+                let v = redactor.redact(&shell_words::quote(v));
+                miseprintln!("{prefix} export {k}={v}");
+            }
+            for k in rm_env.iter().sorted() {
+                miseprintln!("{prefix} unset {k}");
+            }
+        }
+
+        if let (Some(path), false) = (self.log_path(task), self.dry_run) {
+            file::create_dir_all(path.parent().unwrap())?;
+            if !self.append {
+                File::create(&path)
+                    .wrap_err_with(|| format!("failed to create {}", display_path(&path)))?;
+            }
+        }
+
         let timer = std::time::Instant::now();
+        // the timeout is for the whole task, each script gets what's left of it
+        let timeout = self.timeout(task)?;
+        let deadline = match timeout {
+            Some(_) if self.raw(task) => {
+                warn!("{prefix} raw tasks run directly in the terminal, they can't time out");
+                None
+            }
+            Some(timeout) => Some((timer + timeout, timeout)),
+            None => None,
+        };
 
         if let Some(file) = &task.file {
-            self.exec_file(file, task, &env, &prefix)?;
+            self.exec_file(file, task, &env, &prefix, deadline)?;
         } else {
             for (script, args) in task.render_run_scripts_with_args(self.cd.clone(), &task.args)? {
-                self.exec_script(&script, &args, task, &env, &prefix)?;
+                self.exec_script(&script, &args, task, &env, &prefix, deadline)?;
             }
         }
 
@@ -269,7 +428,9 @@ impl Run {
             );
         }
 
-        self.save_checksum(task)?;
+        if !self.dry_run {
+            self.save_checksum(task)?;
+        }
 
         Ok(())
     }
@@ -281,9 +442,19 @@ impl Run {
         task: &Task,
         env: &BTreeMap<String, String>,
         prefix: &str,
+        deadline: Option<(Instant, Duration)>,
     ) -> Result<()> {
         let script = script.trim_start();
-        let cmd = style::ebold(format!("$ {script}")).bright().to_string();
+        let cmd = CONFIG.redactor(env).redact(&format!("$ {script}"));
+        if self.dry_run {
+            let args = match args.is_empty() {
+                true => String::new(),
+                false => format!(" {}", shell_words::join(args)),
+            };
+            miseprintln!("{prefix} {cmd}{args}");
+            return Ok(());
+        }
+        let cmd = style::ebold(cmd).bright().to_string();
         info_unprefix_trunc!("{prefix} {cmd}");
 
         if script.starts_with("#!") {
@@ -295,19 +466,19 @@ impl Run {
             drop(tmp);
             file::make_executable(&file)?;
             let filename = file.display().to_string();
-            self.exec(&filename, args, task, env, prefix)
+            self.exec(&filename, args, task, env, prefix, deadline)
         } else {
             #[cfg(windows)]
             {
                 let script = format!("{} {}", script, args.join(" "));
                 let args = vec!["/c".to_string(), script];
-                self.exec("cmd", &args, task, env, prefix)
+                self.exec("cmd", &args, task, env, prefix, deadline)
             }
             #[cfg(unix)]
             {
                 let script = format!("{} {}", script, shell_words::join(args));
                 let args = vec!["-c".to_string(), script];
-                self.exec("sh", &args, task, env, prefix)
+                self.exec("sh", &args, task, env, prefix, deadline)
             }
         }
     }
@@ -318,6 +489,7 @@ impl Run {
         task: &Task,
         env: &BTreeMap<String, String>,
         prefix: &str,
+        deadline: Option<(Instant, Duration)>,
     ) -> Result<()> {
         let mut env = env.clone();
         let command = file.to_string_lossy().to_string();
@@ -327,17 +499,28 @@ impl Run {
             let args = once(command.clone()).chain(args.clone()).collect_vec();
             let po = usage::parse(&spec, &args).map_err(|err| eyre!(err))?;
             for (k, v) in po.as_env() {
+                if self.dry_run {
+                    let v = CONFIG.redactor(&env).redact(&shell_words::quote(&v));
+                    miseprintln!("{prefix} export {k}={v}");
+                }
                 env.insert(k, v);
             }
         }
 
-        let cmd = format!("{} {}", display_path(file), args.join(" "));
-        let cmd = style::ebold(format!("$ {cmd}")).bright().to_string();
+        let cmd = format!("$ {} {}", display_path(file), args.join(" "));
+        let cmd = CONFIG.redactor(&env).redact(&cmd);
+        if self.dry_run {
+            miseprintln!("{prefix} {}", cmd.trim_end());
+            return Ok(());
+        }
+        let cmd = style::ebold(cmd).bright().to_string();
         info_unprefix_trunc!("{prefix} {cmd}");
 
-        self.exec(&command, &args, task, &env, prefix)
+        self.exec(&command, &args, task, &env, prefix, deadline)
     }
 
+    /// runs one script of `task`, `deadline` is when the task's timeout runs out along with the
+    /// timeout itself for the error
     fn exec(
         &self,
         program: &str,
@@ -345,33 +528,73 @@ impl Run {
         task: &Task,
         env: &BTreeMap<String, String>,
         prefix: &str,
+        deadline: Option<(Instant, Duration)>,
     ) -> Result<()> {
         let program = program.to_executable();
-        let mut cmd = CmdLineRunner::new(program.clone()).args(args).envs(env);
-        cmd.with_pass_signals();
+        let mut cmd = CmdLineRunner::new(program.clone())
+            .args(args)
+            .envs(env)
+            .redactor(CONFIG.redactor(env));
+        cmd.with_pass_signals().with_forward_stdout();
+        let log_path = self.log_path(task);
         match &self.output(task)? {
             TaskOutput::Prefix => cmd = cmd.prefix(format!("{prefix} ")),
-            TaskOutput::Interleave => {
-                cmd = cmd
-                    .stdin(Stdio::inherit())
-                    .stdout(Stdio::inherit())
-                    .stderr(Stdio::inherit())
+            // the output is read by line to write it to the log as well
+            TaskOutput::Interleave if log_path.is_some() => {}
+            TaskOutput::Interleave => cmd = cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit()),
+        }
+        if let Some(path) = &log_path {
+            if self.raw(task) {
+                warn!("{prefix} raw tasks write directly to the terminal, they can't be logged");
+            } else {
+                let log = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .wrap_err_with(|| format!("failed to open {}", display_path(path)))?;
+                cmd = cmd.log_file(log);
             }
         }
+        if self.inherit_stdin(console::user_attended() && std::io::stdin().is_terminal()) {
+            cmd = cmd.stdin(Stdio::inherit());
+        }
         if self.raw(task) {
             cmd.with_raw();
         }
-        if let Some(cd) = &self.cd.as_ref().or(task.dir.as_ref()) {
-            cmd = cmd.current_dir(cd);
+        if let Some(dir) = self.task_dir(task)? {
+            cmd = cmd.current_dir(dir);
         }
-        if self.dry_run {
-            return Ok(());
+        if let Some(umask) = task.umask()? {
+            cmd = cmd.umask(umask);
+        }
+        if let Some((deadline, timeout)) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(TaskTimedOut(task.name.clone(), timeout).into());
+            }
+            cmd = cmd
+                .timeout(Some(remaining))
+                .timeout_signal(task.timeout_signal()?, TIMEOUT_GRACE);
+        }
+        if let Err(err) = cmd.execute() {
+            if let (Some(ScriptTimedOut(..)), Some((_, timeout))) =
+                (err.downcast_ref::<Error>(), deadline)
+            {
+                return Err(TaskTimedOut(task.name.clone(), timeout).into());
+            }
+            return Err(err);
         }
-        cmd.execute()?;
         trace!("{prefix} exited successfully");
         Ok(())
     }
 
+    fn task_dir(&self, task: &Task) -> Result<Option<PathBuf>> {
+        match &self.cd {
+            Some(cd) => Ok(Some(cd.clone())),
+            None => task.dir(),
+        }
+    }
+
     fn output(&self, task: &Task) -> Result<TaskOutput> {
         let settings = Settings::get();
         if self.prefix {
@@ -387,12 +610,53 @@ impl Run {
         }
     }
 
+    /// parallel tasks read from /dev/null since they can't share the terminal. Prefixing
+    /// only affects stdout/stderr so it works either way.
+    fn inherit_stdin(&self, terminal: bool) -> bool {
+        if self.no_stdin {
+            false
+        } else if self.stdin {
+            true
+        } else {
+            terminal && (self.jobs() == 1 || self.is_linear)
+        }
+    }
+
+    /// the file the output of `task` is written to, named after the task with `:` and path
+    /// separators replaced since they can't be in a file name everywhere
+    fn log_path(&self, task: &Task) -> Option<PathBuf> {
+        let dir = match &self.log_dir {
+            Some(dir) => dir.clone(),
+            None => Settings::get().task_log_dir.clone()?,
+        };
+        let name = task.name.replace([':', '/', '\\'], "_");
+        Some(dir.join(format!("{name}.log")))
+    }
+
+    /// the task's own `timeout`, otherwise --timeout or the `task_timeout` setting
+    fn timeout(&self, task: &Task) -> Result<Option<Duration>> {
+        if let Some(timeout) = task.timeout()? {
+            return Ok(Some(timeout));
+        }
+        if let Some(timeout) = self.timeout {
+            return Ok(Some(timeout));
+        }
+        match &Settings::get().task_timeout {
+            Some(timeout) => Ok(Some(
+                humantime::parse_duration(timeout)
+                    .wrap_err_with(|| format!("invalid task_timeout setting: {timeout}"))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     fn raw(&self, task: &Task) -> bool {
         self.raw || task.raw || Settings::get().raw
     }
 
     fn jobs(&self) -> usize {
-        if self.raw {
+        // a dry run prints the tasks in the order they'd run
+        if self.raw || self.dry_run {
             1
         } else {
             self.jobs.unwrap_or(Settings::get().jobs)
@@ -421,6 +685,14 @@ impl Run {
     }
 
     fn validate_task(&self, task: &Task) -> Result<()> {
+        task.dir()?;
+        task.umask()?;
+        self.timeout(task)?;
+        task.timeout_signal()?;
+        if task.file.is_none() {
+            // fails with the usage of the task if its args are missing or invalid
+            task.render_run_scripts_with_args(self.cd.clone(), &task.args)?;
+        }
         if let Some(path) = &task.file {
             if !file::is_executable(path) {
                 let dp = display_path(path);
@@ -437,8 +709,9 @@ impl Run {
 
     fn sources_are_fresh(&self, config: &Config, task: &Task) -> bool {
         let run = || -> Result<bool> {
-            let sources = self.get_last_modified(&self.cwd(config, task), &task.sources)?;
-            let outputs = self.get_last_modified(&self.cwd(config, task), &task.outputs)?;
+            let cwd = self.cwd(config, task)?;
+            let sources = self.get_last_modified(&cwd, &task.sources)?;
+            let outputs = self.get_last_modified(&cwd, &task.outputs)?;
             trace!("sources: {sources:?}, outputs: {outputs:?}",);
             match (sources, outputs) {
                 (Some(sources), Some(outputs)) => Ok(sources < outputs),
@@ -471,13 +744,14 @@ impl Run {
         Ok(last_mod)
     }
 
-    fn cwd(&self, config: &Config, task: &Task) -> PathBuf {
-        self.cd
-            .as_ref()
-            .or(task.dir.as_ref())
-            .cloned()
+    fn cwd(&self, config: &Config, task: &Task) -> Result<PathBuf> {
+        let dir = match &self.cd {
+            Some(cd) => Some(cd.clone()),
+            None => task.dir()?,
+        };
+        Ok(dir
             .or_else(|| config.project_root.clone())
-            .unwrap_or_else(|| env::current_dir().unwrap().clone())
+            .unwrap_or_else(|| env::current_dir().unwrap().clone()))
     }
 
     fn save_checksum(&self, task: &Task) -> Result<()> {
@@ -489,8 +763,15 @@ impl Run {
     }
 }
 
+fn task_tool_args(task: &Task) -> Result<Vec<ToolArg>> {
+    task.tools
+        .iter()
+        .map(|(tool, version)| format!("{tool}@{version}").parse())
+        .collect()
+}
+
 fn err_no_task(name: &str) -> Result<()> {
-    if let Some(cwd) = &*dirs::CWD {
+    if let Some(cwd) = &dirs::cwd() {
         let includes = CONFIG.task_includes_for_dir(cwd);
         let path = includes
             .iter()
@@ -583,6 +864,10 @@ fn last_modified_file(files: impl IntoIterator<Item = PathBuf>) -> Result<Option
         .max())
 }
 
+/// how long a task that timed out has to exit after it got its `timeout_signal` before it is
+/// killed
+const TIMEOUT_GRACE: Duration = Duration::from_secs(10);
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -1,19 +1,35 @@
+use std::process::exit;
+
+use std::path::Path;
+
 use color_eyre::eyre::bail;
 use color_eyre::Result;
 use console::style;
-use self_update::backends::github::{ReleaseList, Update};
+use self_update::backends::github::ReleaseList;
 use self_update::update::Release;
-use self_update::{cargo_crate_version, Status};
+use self_update::version::bump_is_greater;
+use self_update::{cargo_crate_version, Extract, Status};
+use versions::Versioning;
 
-use crate::cli::version::{ARCH, OS};
+use crate::cli::version::{self, ARCH, OS};
 use crate::config::Settings;
-use crate::{cmd, env};
+use crate::http::HTTP;
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::prompt;
+use crate::{cmd, env, file, hash};
+
+/// the only key release archives are accepted from, see docs/faq.md
+static ZIPSIGN_KEY: &[u8; zipsign_api::PUBLIC_KEY_LENGTH] = include_bytes!("../../zipsign.pub");
 
 /// Updates mise itself
 ///
 /// Uses the GitHub Releases API to find the latest release and binary.
 /// By default, this will also update any installed plugins.
 /// Uses the `GITHUB_API_TOKEN` environment variable if set for higher rate limits.
+/// The download is checked against the release's SHASUMS256.txt and its zipsign signature
+/// before the current binary is replaced.
+///
+/// Use `--check` to only report whether a newer version is available, e.g.: in scripts.
 #[derive(Debug, Default, clap::Args)]
 #[clap(verbatim_doc_comment)]
 pub struct SelfUpdate {
@@ -29,12 +45,21 @@ pub struct SelfUpdate {
     #[clap(long, short)]
     yes: bool,
 
+    /// Only check if a newer version is available, exits with 1 if there is one
+    ///
+    /// Nothing is updated. Uses the cached version when offline.
+    #[clap(long, conflicts_with_all = ["force", "version"])]
+    check: bool,
+
     /// Update to a specific version
     version: Option<String>,
 }
 
 impl SelfUpdate {
     pub fn run(self) -> Result<()> {
+        if self.check {
+            return self.check();
+        }
         if !Self::is_available() && !self.force {
             bail!("mise is installed via a package manager, cannot update");
         }
@@ -53,6 +78,24 @@ impl SelfUpdate {
         Ok(())
     }
 
+    fn check(&self) -> Result<()> {
+        let Some(latest) = version::fetch_latest_version() else {
+            bail!("failed to check for a new version of mise");
+        };
+        let current = &*version::V;
+        match Versioning::new(&latest) {
+            Some(latest) if *current < latest => {
+                miseprintln!("mise {latest} is available (current: {current})");
+                exit(1);
+            }
+            Some(_) => {
+                miseprintln!("mise {current} is up to date");
+                Ok(())
+            }
+            None => bail!("invalid latest version: {latest}"),
+        }
+    }
+
     fn fetch_releases(&self) -> Result<Vec<Release>> {
         let mut releases = ReleaseList::configure();
         if let Some(token) = &*env::GITHUB_API_TOKEN {
@@ -73,41 +116,53 @@ impl SelfUpdate {
 
     fn do_update(&self) -> Result<Status> {
         let settings = Settings::try_get();
-        let v = self
-            .version
-            .clone()
-            .map_or_else(|| self.latest_version(), Ok)
-            .map(|v| format!("v{}", v))?;
-        let target = format!("{}-{}", *OS, *ARCH);
-        let mut update = Update::configure();
-        if let Some(token) = &*env::GITHUB_API_TOKEN {
-            update.auth_token(token);
-        }
-        if self.force || self.version.is_some() {
-            update.target_version_tag(&v);
+        let current = cargo_crate_version!();
+        let v = match &self.version {
+            Some(v) => v.trim_start_matches('v').to_string(),
+            None => self.latest_version()?,
+        };
+        if !self.force && self.version.is_none() && !bump_is_greater(current, &v)? {
+            return Ok(Status::UpToDate(current.to_string()));
         }
+        let tag = format!("v{v}");
         #[cfg(windows)]
-        let target = format!("mise-{v}-{target}.zip");
+        let asset = format!("mise-{tag}-{}-{}.zip", *OS, *ARCH);
         #[cfg(not(windows))]
-        let target = format!("mise-{v}-{target}.tar.gz");
+        let asset = format!("mise-{tag}-{}-{}.tar.gz", *OS, *ARCH);
         #[cfg(windows)]
         let bin_path_in_archive = "mise/bin/mise.exe";
         #[cfg(not(windows))]
         let bin_path_in_archive = "mise/bin/mise";
-        let status = update
-            .repo_owner("jdx")
-            .repo_name("mise")
-            .bin_name("mise")
-            .verifying_keys([*include_bytes!("../../zipsign.pub")])
-            .show_download_progress(true)
-            .current_version(cargo_crate_version!())
-            .target(&target)
-            .bin_path_in_archive(bin_path_in_archive)
-            .target(&target)
-            .no_confirm(settings.is_ok_and(|s| s.yes) || self.yes)
-            .build()?
-            .update()?;
-        Ok(status)
+
+        let yes = settings.is_ok_and(|s| s.yes) || self.yes;
+        if !yes && !prompt::confirm(format!("Update mise {current} to {v}?"))? {
+            bail!("update cancelled");
+        }
+
+        // everything is verified in a temp dir first, a failure leaves the current binary alone
+        let base_url = format!("https://github.com/jdx/mise/releases/download/{tag}");
+        let shasums = HTTP.get_text(format!("{base_url}/SHASUMS256.txt"))?;
+        let Some(checksum) = hash::find_shasum(&shasums, &asset) else {
+            bail!("no checksum for {asset} in SHASUMS256.txt, refusing to update");
+        };
+        let tmp = tempfile::tempdir()?;
+        let archive = tmp.path().join(&asset);
+        let mpr = MultiProgressReport::get();
+        let pr = mpr.add("mise");
+        pr.set_message(format!("downloading {asset}"));
+        HTTP.download_file_with_checksum(
+            format!("{base_url}/{asset}"),
+            &archive,
+            &format!("sha256:{checksum}"),
+            Some(pr.as_ref()),
+        )?;
+        pr.set_message("verifying signature".into());
+        verify_signature(&archive, ZIPSIGN_KEY)?;
+        pr.set_message("extracting".into());
+        Extract::from_source(&archive).extract_file(tmp.path(), bin_path_in_archive)?;
+        pr.finish_with_message(format!("verified {asset}"));
+        self_update::self_replace::self_replace(tmp.path().join(bin_path_in_archive))?;
+        Ok(Status::Updated(v))
     }
 
     pub fn is_available() -> bool {
@@ -119,3 +174,36 @@ impl SelfUpdate {
             .unwrap_or_default()
     }
 }
+
+/// checks the zipsign signature embedded in a release archive
+fn verify_signature(archive: &Path, key: &[u8; zipsign_api::PUBLIC_KEY_LENGTH]) -> Result<()> {
+    let context = archive.file_name().unwrap().to_string_lossy().to_string();
+    let keys = zipsign_api::verify::collect_keys([Ok(*key)])?;
+    let mut f = file::open(archive)?;
+    #[cfg(windows)]
+    let verified = zipsign_api::verify::verify_zip(&mut f, &keys, Some(context.as_bytes()));
+    #[cfg(not(windows))]
+    let verified = zipsign_api::verify::verify_tar(&mut f, &keys, Some(context.as_bytes()));
+    if let Err(err) = verified {
+        bail!("signature verification failed for {context}, refusing to update: {err}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_signature_unsigned() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("mise-v1.0.0-linux-x64.tar.gz");
+        file::write(&archive, "not signed").unwrap();
+        let err = verify_signature(&archive, ZIPSIGN_KEY).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("signature verification failed for mise-v1.0.0-linux-x64.tar.gz"));
+    }
+}
@@ -14,6 +14,11 @@ pub struct SettingsLs {
     /// Only display key names for each setting
     #[clap(long, verbatim_doc_comment)]
     pub keys: bool,
+
+    /// Show the config file or env var each setting is set by
+    /// settings without one are the default
+    #[clap(long, verbatim_doc_comment, conflicts_with = "keys")]
+    pub sources: bool,
 }
 
 impl SettingsLs {
@@ -26,10 +31,32 @@ impl SettingsLs {
         if self.keys {
             return self.print_keys(&settings);
         }
+        if self.sources {
+            return self.print_sources(&settings);
+        }
         miseprintln!("{}", settings);
         Ok(())
     }
 
+    fn print_sources(&self, settings: &toml::Table) -> Result<()> {
+        let sources = Settings::sources()?;
+        for (k, v) in settings {
+            let entries = match v {
+                toml::Value::Table(t) => {
+                    t.iter().map(|(sub, v)| (format!("{k}.{sub}"), v)).collect()
+                }
+                _ => vec![(k.to_string(), v)],
+            };
+            for (k, v) in entries {
+                match sources.get(&k) {
+                    Some(source) => miseprintln!("{k} = {v}  # {source}"),
+                    None => miseprintln!("{k} = {v}"),
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn print_keys(&self, settings: &toml::Table) -> Result<()> {
         for (k, v) in settings {
             miseprintln!("{k}");
@@ -48,6 +75,10 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     $ <bold>mise settings</bold>
     legacy_version_file = false
+
+    $ <bold>mise settings ls --sources</bold>
+    jobs = 3  # ~/src/proj/mise.local.toml
+    legacy_version_file = false
 "#
 );
 
@@ -65,33 +96,62 @@ mod tests {
         always_keep_install = true
         asdf = true
         asdf_compat = false
+        build_heavy_tools = []
         cache_prune_age = "0"
         cargo_binstall = true
         color = true
         disable_default_shorthands = false
         disable_hints = []
         disable_tools = []
+        download_buffer_size = "1MiB"
+        download_cache_max_size = "2GiB"
+        download_fsync = true
+        env_file_missing = "warn"
         experimental = true
+        github_use_gh_cli = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
         go_repo = "https://github.com/golang/go"
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        http_cache = true
+        http_concurrency = 4
+        http_connect_timeout = 10
+        http_expect_continue = false
+        http_host_bandwidth = []
+        http_host_timeout = []
+        http_ipv4 = false
+        http_ipv6 = false
+        http_max_redirects = 10
+        http_max_response_size = "100MiB"
+        http_pool_idle_timeout = 90
+        http_retries = 3
+        http_retry_after_max = 60
         http_timeout = 30
         jobs = 2
         legacy_version_file = true
         legacy_version_file_disable_tools = []
         libgit2 = true
+        lockfile = false
+        no_cache = false
+        no_cache_write = false
         not_found_auto_install = true
+        offline = false
         paranoid = false
+        pin = false
         pipx_uvx = false
         plugin_autoupdate_last_check_duration = "20m"
+        prompt_format = "{% for tool in tools %}{{ tool.name }}@{{ tool.version }} {% endfor %}"
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        raw_log = false
+        registry_cache_ttl = "1d"
+        resolve_cache = true
         trusted_config_paths = []
+        url_rewrite = []
         use_versions_host = true
         verbose = true
         vfox = false
@@ -122,33 +182,61 @@ mod tests {
         always_keep_install
         asdf
         asdf_compat
+        build_heavy_tools
         cache_prune_age
         cargo_binstall
         color
         disable_default_shorthands
         disable_hints
         disable_tools
+        download_buffer_size
+        download_cache_max_size
+        download_fsync
+        env_file_missing
         experimental
+        github_use_gh_cli
         go_default_packages_file
         go_download_mirror
         go_repo
         go_set_gopath
         go_set_goroot
         go_skip_checksum
+        http_cache
+        http_concurrency
+        http_connect_timeout
+        http_expect_continue
+        http_host_bandwidth
+        http_host_timeout
+        http_ipv4
+        http_ipv6
+        http_max_redirects
+        http_max_response_size
+        http_pool_idle_timeout
+        http_retries
+        http_retry_after_max
         http_timeout
         jobs
         legacy_version_file
         legacy_version_file_disable_tools
         libgit2
+        lockfile
+        no_cache
+        no_cache_write
         node
         not_found_auto_install
+        offline
         paranoid
+        pin
         pipx_uvx
         plugin_autoupdate_last_check_duration
+        prompt_format
         python_default_packages_file
         python_pyenv_repo
         quiet
         raw
+        raw_log
+        registry_cache_ttl
+        resolve_cache
         ruby
         ruby.default_packages_file
         ruby.ruby_build_repo
@@ -159,6 +247,7 @@ mod tests {
         status.show_env
         status.show_tools
         trusted_config_paths
+        url_rewrite
         use_versions_host
         verbose
         vfox
@@ -3,7 +3,7 @@ use eyre::Result;
 
 mod get;
 mod ls;
-mod set;
+pub(crate) mod set;
 mod unset;
 
 #[derive(Debug, clap::Args)]
@@ -15,6 +15,10 @@ pub struct Settings {
     /// Only display key names for each setting
     #[clap(long, verbatim_doc_comment)]
     keys: bool,
+
+    /// Show the config file or env var each setting is set by
+    #[clap(long, verbatim_doc_comment, conflicts_with = "keys")]
+    sources: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -38,9 +42,10 @@ impl Commands {
 
 impl Settings {
     pub fn run(self) -> Result<()> {
-        let cmd = self
-            .command
-            .unwrap_or(Commands::Ls(ls::SettingsLs { keys: self.keys }));
+        let cmd = self.command.unwrap_or(Commands::Ls(ls::SettingsLs {
+            keys: self.keys,
+            sources: self.sources,
+        }));
 
         cmd.run()
     }
@@ -2,6 +2,8 @@ use eyre::{eyre, Result};
 use toml_edit::DocumentMut;
 
 use crate::config::settings::SettingsFile;
+use crate::config::Settings;
+use crate::file::display_path;
 use crate::{env, file};
 
 /// Add/update a setting
@@ -19,65 +21,8 @@ pub struct SettingsSet {
 
 impl SettingsSet {
     pub fn run(self) -> Result<()> {
-        let value: toml_edit::Value = match self.setting.as_str() {
-            "activate_aggressive" => parse_bool(&self.value)?,
-            "all_compile" => parse_bool(&self.value)?,
-            "always_keep_download" => parse_bool(&self.value)?,
-            "always_keep_install" => parse_bool(&self.value)?,
-            "asdf" => parse_bool(&self.value)?,
-            "asdf_compat" => parse_bool(&self.value)?,
-            "cargo_binstall" => parse_bool(&self.value)?,
-            "color" => parse_bool(&self.value)?,
-            "disable_default_shorthands" => parse_bool(&self.value)?,
-            "disable_hints" => self.value.split(',').map(|s| s.to_string()).collect(),
-            "disable_tools" => self.value.split(',').map(|s| s.to_string()).collect(),
-            "experimental" => parse_bool(&self.value)?,
-            "go_default_packages_file" => self.value.into(),
-            "go_download_mirror" => self.value.into(),
-            "go_repo" => self.value.into(),
-            "go_set_gobin" => parse_bool(&self.value)?,
-            "go_set_gopath" => parse_bool(&self.value)?,
-            "go_set_goroot" => parse_bool(&self.value)?,
-            "go_skip_checksum" => parse_bool(&self.value)?,
-            "http_timeout" => parse_i64(&self.value)?,
-            "jobs" => parse_i64(&self.value)?,
-            "legacy_version_file" => parse_bool(&self.value)?,
-            "legacy_version_file_disable_tools" => {
-                self.value.split(',').map(|s| s.to_string()).collect()
-            }
-            "libgit2" => parse_bool(&self.value)?,
-            "node.compile" => parse_bool(&self.value)?,
-            "node.flavor" => self.value.into(),
-            "node.mirror_url" => self.value.into(),
-            "not_found_auto_install" => parse_bool(&self.value)?,
-            "paranoid" => parse_bool(&self.value)?,
-            "pipx_uvx" => parse_bool(&self.value)?,
-            "plugin_autoupdate_last_check_duration" => self.value.into(),
-            "python_compile" => parse_bool(&self.value)?,
-            "python_default_packages_file" => self.value.into(),
-            "python_pyenv_repo" => self.value.into(),
-            "python_venv_auto_create" => parse_bool(&self.value)?,
-            "quiet" => parse_bool(&self.value)?,
-            "raw" => parse_bool(&self.value)?,
-            "ruby.apply_patches" => self.value.into(),
-            "ruby.default_packages_file" => self.value.into(),
-            "ruby.ruby_build_repo" => self.value.into(),
-            "ruby.ruby_build_opts" => self.value.into(),
-            "ruby.ruby_install" => parse_bool(&self.value)?,
-            "ruby.ruby_install_repo" => self.value.into(),
-            "ruby.ruby_install_opts" => self.value.into(),
-            "ruby.verbose_install" => parse_bool(&self.value)?,
-            "shorthands_file" => self.value.into(),
-            "status.missing_tools" => self.value.into(),
-            "status.show_env" => parse_bool(&self.value)?,
-            "status.show_tools" => parse_bool(&self.value)?,
-            "task_output" => self.value.into(),
-            "trusted_config_paths" => self.value.split(':').map(|s| s.to_string()).collect(),
-            "verbose" => parse_bool(&self.value)?,
-            "vfox" => parse_bool(&self.value)?,
-            "yes" => parse_bool(&self.value)?,
-            _ => return Err(eyre!("Unknown setting: {}", self.setting)),
-        };
+        ensure_not_locked(&self.setting)?;
+        let value = parse_setting(&self.setting, &self.value)?;
 
         let path = &*env::MISE_GLOBAL_CONFIG_FILE;
         file::create_dir_all(path.parent().unwrap())?;
@@ -106,6 +51,102 @@ impl SettingsSet {
     }
 }
 
+/// parses a value from the command line into the type `setting` uses
+pub fn parse_setting(setting: &str, value: &str) -> Result<toml_edit::Value> {
+    let value: toml_edit::Value = match setting {
+        "activate_aggressive" => parse_bool(value)?,
+        "all_compile" => parse_bool(value)?,
+        "always_keep_download" => parse_bool(value)?,
+        "always_keep_install" => parse_bool(value)?,
+        "asdf" => parse_bool(value)?,
+        "asdf_compat" => parse_bool(value)?,
+        "build_heavy_tools" => value.split(',').map(|s| s.to_string()).collect(),
+        "build_jobs" => parse_i64(value)?,
+        "cargo_binstall" => parse_bool(value)?,
+        "checksum_jobs" => parse_i64(value)?,
+        "color" => parse_bool(value)?,
+        "disable_default_shorthands" => parse_bool(value)?,
+        "disable_hints" => value.split(',').map(|s| s.to_string()).collect(),
+        "disable_tools" => value.split(',').map(|s| s.to_string()).collect(),
+        "download_buffer_size" => value.into(),
+        "download_cache_max_size" => value.into(),
+        "download_fsync" => parse_bool(value)?,
+        "env_file_missing" => value.into(),
+        "experimental" => parse_bool(value)?,
+        "github_use_gh_cli" => parse_bool(value)?,
+        "go_default_packages_file" => value.into(),
+        "go_download_mirror" => value.into(),
+        "go_repo" => value.into(),
+        "go_set_gobin" => parse_bool(value)?,
+        "go_set_gopath" => parse_bool(value)?,
+        "go_set_goroot" => parse_bool(value)?,
+        "go_skip_checksum" => parse_bool(value)?,
+        "http_cache" => parse_bool(value)?,
+        "http_concurrency" => parse_i64(value)?,
+        "http_connect_timeout" => parse_i64(value)?,
+        "http_expect_continue" => parse_bool(value)?,
+        "http_host_bandwidth" => value.split(',').map(|s| s.to_string()).collect(),
+        "http_host_timeout" => value.split(',').map(|s| s.to_string()).collect(),
+        "http_ipv4" => parse_bool(value)?,
+        "http_ipv6" => parse_bool(value)?,
+        "http_max_redirects" => parse_i64(value)?,
+        "http_max_response_size" => value.into(),
+        "http_pool_idle_timeout" => parse_i64(value)?,
+        "http_pool_max_idle_per_host" => parse_i64(value)?,
+        "http_retries" => parse_i64(value)?,
+        "http_retry_after_max" => parse_i64(value)?,
+        "http_timeout" => parse_i64(value)?,
+        "http_user_agent" => value.into(),
+        "jobs" => parse_i64(value)?,
+        "legacy_version_file" => parse_bool(value)?,
+        "legacy_version_file_disable_tools" => value.split(',').map(|s| s.to_string()).collect(),
+        "libgit2" => parse_bool(value)?,
+        "lockfile" => parse_bool(value)?,
+        "no_cache" => parse_bool(value)?,
+        "no_cache_write" => parse_bool(value)?,
+        "node.compile" => parse_bool(value)?,
+        "node.flavor" => value.into(),
+        "node.mirror_url" => value.into(),
+        "not_found_auto_install" => parse_bool(value)?,
+        "offline" => parse_bool(value)?,
+        "paranoid" => parse_bool(value)?,
+        "pin" => parse_bool(value)?,
+        "pipx_uvx" => parse_bool(value)?,
+        "plugin_autoupdate_last_check_duration" => value.into(),
+        "prompt_format" => value.into(),
+        "python_compile" => parse_bool(value)?,
+        "python_default_packages_file" => value.into(),
+        "python_pyenv_repo" => value.into(),
+        "python_venv_auto_create" => parse_bool(value)?,
+        "quiet" => parse_bool(value)?,
+        "raw" => parse_bool(value)?,
+        "raw_log" => parse_bool(value)?,
+        "registry_cache_ttl" => value.into(),
+        "registry_url" => value.into(),
+        "resolve_cache" => parse_bool(value)?,
+        "ruby.apply_patches" => value.into(),
+        "ruby.default_packages_file" => value.into(),
+        "ruby.ruby_build_repo" => value.into(),
+        "ruby.ruby_build_opts" => value.into(),
+        "ruby.ruby_install" => parse_bool(value)?,
+        "ruby.ruby_install_repo" => value.into(),
+        "ruby.ruby_install_opts" => value.into(),
+        "ruby.verbose_install" => parse_bool(value)?,
+        "shorthands_file" => value.into(),
+        "status.missing_tools" => value.into(),
+        "status.show_env" => parse_bool(value)?,
+        "status.show_tools" => parse_bool(value)?,
+        "task_output" => value.into(),
+        "trusted_config_paths" => value.split(':').map(|s| s.to_string()).collect(),
+        "url_rewrite" => value.split(',').map(|s| s.to_string()).collect(),
+        "verbose" => parse_bool(value)?,
+        "vfox" => parse_bool(value)?,
+        "yes" => parse_bool(value)?,
+        _ => return Err(eyre!("Unknown setting: {}", setting)),
+    };
+    Ok(value)
+}
+
 fn parse_bool(value: &str) -> Result<toml_edit::Value> {
     match value.to_lowercase().as_str() {
         "1" | "true" | "yes" | "y" => Ok(true.into()),
@@ -121,6 +162,17 @@ fn parse_i64(value: &str) -> Result<toml_edit::Value> {
     }
 }
 
+/// settings locked by the policy file can't be changed by users
+pub fn ensure_not_locked(setting: &str) -> Result<()> {
+    if Settings::policy()?.locked.iter().any(|k| k == setting) {
+        return Err(eyre!(
+            "{setting} is locked by the policy in {}",
+            display_path(&*env::MISE_POLICY_FILE)
+        ));
+    }
+    Ok(())
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -152,33 +204,62 @@ pub mod tests {
         always_keep_install = true
         asdf = true
         asdf_compat = false
+        build_heavy_tools = []
         cache_prune_age = "0"
         cargo_binstall = true
         color = true
         disable_default_shorthands = false
         disable_hints = []
         disable_tools = []
+        download_buffer_size = "1MiB"
+        download_cache_max_size = "2GiB"
+        download_fsync = true
+        env_file_missing = "warn"
         experimental = true
+        github_use_gh_cli = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
         go_repo = "https://github.com/golang/go"
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        http_cache = true
+        http_concurrency = 4
+        http_connect_timeout = 10
+        http_expect_continue = false
+        http_host_bandwidth = []
+        http_host_timeout = []
+        http_ipv4 = false
+        http_ipv6 = false
+        http_max_redirects = 10
+        http_max_response_size = "100MiB"
+        http_pool_idle_timeout = 90
+        http_retries = 3
+        http_retry_after_max = 60
         http_timeout = 30
         jobs = 2
         legacy_version_file = false
         legacy_version_file_disable_tools = []
         libgit2 = true
+        lockfile = false
+        no_cache = false
+        no_cache_write = false
         not_found_auto_install = true
+        offline = false
         paranoid = false
+        pin = false
         pipx_uvx = false
         plugin_autoupdate_last_check_duration = "1"
+        prompt_format = "{% for tool in tools %}{{ tool.name }}@{{ tool.version }} {% endfor %}"
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        raw_log = false
+        registry_cache_ttl = "1d"
+        resolve_cache = true
         trusted_config_paths = []
+        url_rewrite = []
         use_versions_host = true
         verbose = true
         vfox = false
@@ -1,6 +1,7 @@
 use eyre::Result;
 use toml_edit::DocumentMut;
 
+use crate::cli::settings::set::ensure_not_locked;
 use crate::config::settings::SettingsFile;
 use crate::{env, file};
 
@@ -16,6 +17,7 @@ pub struct SettingsUnset {
 
 impl SettingsUnset {
     pub fn run(self) -> Result<()> {
+        ensure_not_locked(&self.setting)?;
         let path = env::MISE_CONFIG_DIR.join("config.toml");
         let raw = file::read_to_string(&path)?;
         let mut config: DocumentMut = raw.parse()?;
@@ -56,33 +58,62 @@ mod tests {
         always_keep_install = true
         asdf = true
         asdf_compat = false
+        build_heavy_tools = []
         cache_prune_age = "0"
         cargo_binstall = true
         color = true
         disable_default_shorthands = false
         disable_hints = []
         disable_tools = []
+        download_buffer_size = "1MiB"
+        download_cache_max_size = "2GiB"
+        download_fsync = true
+        env_file_missing = "warn"
         experimental = true
+        github_use_gh_cli = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
         go_repo = "https://github.com/golang/go"
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        http_cache = true
+        http_concurrency = 4
+        http_connect_timeout = 10
+        http_expect_continue = false
+        http_host_bandwidth = []
+        http_host_timeout = []
+        http_ipv4 = false
+        http_ipv6 = false
+        http_max_redirects = 10
+        http_max_response_size = "100MiB"
+        http_pool_idle_timeout = 90
+        http_retries = 3
+        http_retry_after_max = 60
         http_timeout = 30
         jobs = 4
         legacy_version_file = true
         legacy_version_file_disable_tools = []
         libgit2 = true
+        lockfile = false
+        no_cache = false
+        no_cache_write = false
         not_found_auto_install = true
+        offline = false
         paranoid = false
+        pin = false
         pipx_uvx = false
         plugin_autoupdate_last_check_duration = "20m"
+        prompt_format = "{% for tool in tools %}{{ tool.name }}@{{ tool.version }} {% endfor %}"
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        raw_log = false
+        registry_cache_ttl = "1d"
+        resolve_cache = true
         trusted_config_paths = []
+        url_rewrite = []
         use_versions_host = true
         verbose = true
         vfox = false
@@ -0,0 +1,155 @@
+use std::fmt::{Display, Formatter};
+
+use console::style;
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::cli::doctor::{activation_fix, shims_on_path, Fix};
+use crate::config::{Config, Settings};
+use crate::env;
+use crate::file::display_path;
+use crate::shell::ShellType;
+use crate::toolset::{InstallOptions, Toolset};
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::prompt;
+
+/// Install the project's tools and activate mise in one step
+///
+/// Installs the tools in the config files of the current directory like `mise install`, then
+/// adds `mise activate` to the rc file of the current shell if mise isn't activated yet, prompting
+/// first unless --yes is set. The rc file is backed up before it is changed.
+///
+/// It is safe to run again: what is already set up is left alone and reported as such. A summary
+/// of each step and what to do next is printed at the end, mise exits with a non-zero status if
+/// a step failed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Setup {
+    /// Number of jobs to run in parallel
+    /// [default: 4]
+    #[clap(long, short, env = "MISE_JOBS", verbatim_doc_comment)]
+    jobs: Option<usize>,
+
+    /// Change the shell rc file without prompting
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// the outcome of a step of the setup
+enum Step {
+    Changed(String),
+    Unchanged(String),
+    Skipped(String),
+    Failed(String),
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Changed(msg) => write!(f, "{} {msg}", style("changed").green()),
+            Step::Unchanged(msg) => write!(f, "{} {msg}", style("ok").green()),
+            Step::Skipped(msg) => write!(f, "{} {msg}", style("skipped").yellow()),
+            Step::Failed(msg) => write!(f, "{} {msg}", style("failed").red()),
+        }
+    }
+}
+
+impl Setup {
+    pub fn run(self) -> Result<()> {
+        let config = Config::try_get()?;
+        let mut next = vec![];
+        let tools = self
+            .install(&config)
+            .unwrap_or_else(|e| Step::Failed(format!("{e:#}")));
+        let activation = self
+            .activate(&mut next)
+            .unwrap_or_else(|e| Step::Failed(format!("{e:#}")));
+        if matches!(tools, Step::Failed(_)) {
+            next.push("fix the install error and run `mise setup` again".to_string());
+        } else {
+            next.push("run `mise ls` to see the tools of this project".to_string());
+        }
+        miseprintln!("tools:      {tools}");
+        miseprintln!("activation: {activation}");
+        miseprintln!("\nnext steps:");
+        for step in &next {
+            miseprintln!("  {step}");
+        }
+        if matches!(tools, Step::Failed(_)) || matches!(activation, Step::Failed(_)) {
+            bail!("setup failed");
+        }
+        Ok(())
+    }
+
+    fn install(&self, config: &Config) -> Result<Step> {
+        let trs = config.get_tool_request_set()?;
+        let versions = trs.missing_tools().into_iter().cloned().collect_vec();
+        if versions.is_empty() {
+            return Ok(Step::Unchanged("all tools are already installed".into()));
+        }
+        let mpr = MultiProgressReport::get();
+        let mut ts = Toolset::from(trs.clone());
+        let opts = InstallOptions {
+            jobs: self.jobs,
+            latest_versions: true,
+            ..Default::default()
+        };
+        let installed = ts.install_versions(config, versions, &mpr, &opts)?;
+        Ok(Step::Changed(format!(
+            "installed {}",
+            installed.iter().map(|tv| tv.style()).join(", ")
+        )))
+    }
+
+    fn activate(&self, next: &mut Vec<String>) -> Result<Step> {
+        if env::is_activated() {
+            return Ok(Step::Unchanged("mise is activated in this shell".into()));
+        }
+        if shims_on_path() {
+            return Ok(Step::Unchanged("mise shims are on PATH".into()));
+        }
+        if ShellType::load().is_none() {
+            next.push("see `mise help activate` to activate mise in your shell".into());
+            return Ok(Step::Skipped("unknown shell".into()));
+        }
+        let Some(fix) = activation_fix() else {
+            next.push("open a new shell to activate mise".into());
+            return Ok(Step::Unchanged(
+                "mise activate is already in your shell rc file".into(),
+            ));
+        };
+        let Fix::AddActivation { rc, line } = &fix else {
+            unreachable!("activation_fix only adds activation");
+        };
+        let yes = self.yes || Settings::try_get().is_ok_and(|s| s.yes);
+        if !yes && !prompt::confirm(format!("{fix}?"))? {
+            next.push(format!(
+                "add `{line}` to {} to activate mise",
+                display_path(rc)
+            ));
+            return Ok(Step::Skipped("not added to your shell rc file".into()));
+        }
+        fix.apply()?;
+        next.push(format!(
+            "open a new shell or run `source {}` to activate mise",
+            display_path(rc)
+        ));
+        Ok(Step::Changed(format!(
+            "added `{line}` to {}",
+            display_path(rc)
+        )))
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise setup</bold>
+    tools:      changed installed node@20.1.0, python@3.12.1
+    activation: changed added `eval "$(mise activate bash)"` to ~/.bashrc
+
+    next steps:
+      open a new shell or run `source ~/.bashrc` to activate mise
+      run `mise ls` to see the tools of this project
+"#
+);
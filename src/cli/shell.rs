@@ -6,7 +6,7 @@ use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::env;
 use crate::shell::get_shell;
-use crate::toolset::{InstallOptions, ToolSource, ToolsetBuilder};
+use crate::toolset::{version_env_key, InstallOptions, ToolSource, ToolsetBuilder};
 
 /// Sets a tool version for the current session
 ///
@@ -32,8 +32,11 @@ pub struct Shell {
     #[clap(long, overrides_with = "jobs")]
     raw: bool,
 
-    /// Removes a previously set version
-    #[clap(long, short)]
+    /// Removes the version set with `mise shell` for these tools
+    ///
+    /// The versions from the config files are used again, tools that weren't set in this
+    /// session are left alone.
+    #[clap(long, short, verbatim_doc_comment)]
     unset: bool,
 }
 
@@ -43,6 +46,9 @@ impl Shell {
         if !env::is_activated() {
             err_inactive()?;
         }
+        if self.unset {
+            return self.unset();
+        }
 
         let mut ts = ToolsetBuilder::new().with_args(&self.tool).build(&config)?;
         let opts = InstallOptions {
@@ -50,6 +56,7 @@ impl Shell {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: false,
+            ..Default::default()
         };
         ts.install_arg_versions(&config, &opts)?;
         ts.notify_if_versions_missing();
@@ -59,18 +66,28 @@ impl Shell {
         for (p, tv) in ts.list_current_installed_versions() {
             let source = &ts.versions.get(p.fa()).unwrap().source;
             if matches!(source, ToolSource::Argument) {
-                let k = format!("MISE_{}_VERSION", p.id().to_uppercase());
-                let op = if self.unset {
-                    shell.unset_env(&k)
-                } else {
-                    shell.set_env(&k, &tv.version)
-                };
-                miseprintln!("{op}");
+                let k = version_env_key(p.id());
+                miseprintln!("{}", shell.set_env(&k, &tv.version));
             }
         }
 
         Ok(())
     }
+
+    /// the session versions are only kept in `MISE_<TOOL>_VERSION` so once that is unset
+    /// hook-env goes back to the versions from the config files on the next prompt
+    fn unset(&self) -> Result<()> {
+        let shell = get_shell(None).expect("no shell detected");
+        for ta in &self.tool {
+            let k = version_env_key(&ta.backend.short);
+            if env::var_os(&k).is_some() {
+                miseprintln!("{}", shell.unset_env(&k));
+            } else {
+                info!("{} is not set in this shell session", ta.backend);
+            }
+        }
+        Ok(())
+    }
 }
 
 fn err_inactive() -> Result<()> {
@@ -89,6 +106,10 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise shell node@20</bold>
     $ <bold>node -v</bold>
     v20.0.0
+
+    $ <bold>mise shell --unset node</bold>
+    $ <bold>node -v</bold>
+    v22.0.0
 "#
 );
 
@@ -108,6 +129,11 @@ mod tests {
         env::set_var("__MISE_DIFF", "");
         env::set_var("MISE_SHELL", "zsh");
         assert_cli_snapshot!("shell", "tiny@1.0.1");
+        env::set_var("MISE_TINY_VERSION", "1.0.1");
+        assert_cli_snapshot!("shell", "--unset", "tiny", @"unset MISE_TINY_VERSION");
+        env::remove_var("MISE_TINY_VERSION");
+        let out = assert_cli!("shell", "--unset", "tiny");
+        assert_eq!(out, "");
         env::remove_var("__MISE_DIFF");
         env::remove_var("MISE_SHELL");
     }
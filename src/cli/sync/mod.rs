@@ -1,8 +1,15 @@
+use std::path::{Path, PathBuf};
+
 use clap::Subcommand;
 use eyre::Result;
 
+use crate::config::Config;
+use crate::file::display_path;
+use crate::{file, plugins};
+
 mod node;
 mod python;
+mod ruby;
 
 #[derive(Debug, clap::Args)]
 #[clap(about = "Add tool versions from external tools to mise")]
@@ -15,6 +22,7 @@ pub struct Sync {
 enum Commands {
     Node(node::SyncNode),
     Python(python::SyncPython),
+    Ruby(ruby::SyncRuby),
 }
 
 impl Commands {
@@ -22,6 +30,7 @@ impl Commands {
         match self {
             Self::Node(cmd) => cmd.run(),
             Self::Python(cmd) => cmd.run(),
+            Self::Ruby(cmd) => cmd.run(),
         }
     }
 }
@@ -31,3 +40,61 @@ impl Sync {
         self.command.run()
     }
 }
+
+/// symlinks each version `source` installed under `source_dir` into the installs of `plugin`
+///
+/// Symlinks an earlier sync made to versions that are gone are removed and versions that are
+/// already synced or installed by mise are left as they are, so syncing again changes nothing.
+fn sync_versions(
+    plugin: &str,
+    source: &str,
+    source_dir: &Path,
+    mut versions: Vec<(String, PathBuf)>,
+    dry_run: bool,
+) -> Result<()> {
+    if !source_dir.is_dir() {
+        warn!(
+            "no {source} installs found, {} does not exist",
+            display_path(source_dir)
+        );
+    }
+    versions.sort();
+    let tool = plugins::get(plugin);
+    let installs = &tool.fa().installs_path;
+    let mut changed = false;
+    for link in file::ls(installs)? {
+        if !link.is_symlink() {
+            continue;
+        }
+        let dir = link.file_name().unwrap().to_string_lossy();
+        let target = file::resolve_symlink(&link)?;
+        if target.starts_with(source_dir) && !versions.iter().any(|(_, p)| *p == target) {
+            match dry_run {
+                true => miseprintln!("Would remove {plugin}@{dir} synced from {source}"),
+                false => {
+                    file::remove_file(&link)?;
+                    miseprintln!("Removed {plugin}@{dir} synced from {source}");
+                }
+            }
+            changed = true;
+        }
+    }
+    for (v, path) in versions {
+        let link = installs.join(&v);
+        if link.is_symlink() && file::resolve_symlink(&link)? == path {
+            miseprintln!("{plugin}@{v} is already synced from {source}");
+        } else if link.exists() {
+            info!("Skipping {plugin}@{v} from {source} because it already exists in mise");
+        } else if dry_run {
+            miseprintln!("Would sync {plugin}@{v} from {source}");
+        } else {
+            tool.create_symlink(&v, &path)?;
+            miseprintln!("Synced {plugin}@{v} from {source}");
+            changed = true;
+        }
+    }
+    if changed && !dry_run {
+        Config::try_get()?.rebuild_shims_and_runtime_symlinks()?;
+    }
+    Ok(())
+}
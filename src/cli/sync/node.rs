@@ -1,11 +1,10 @@
 use std::path::PathBuf;
 
 use eyre::Result;
-use itertools::sorted;
 
-use crate::config::Config;
+use crate::cli::sync::sync_versions;
 use crate::env::{NODENV_ROOT, NVM_DIR};
-use crate::{cmd, dirs, file, plugins};
+use crate::{cmd, file};
 
 /// Symlinks all tool versions from an external tool into mise
 ///
@@ -15,6 +14,10 @@ use crate::{cmd, dirs, file, plugins};
 pub struct SyncNode {
     #[clap(flatten)]
     _type: SyncNodeType,
+
+    /// Show what would be synced without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
 }
 
 #[derive(Debug, clap::Args)]
@@ -35,84 +38,40 @@ pub struct SyncNodeType {
 
 impl SyncNode {
     pub fn run(self) -> Result<()> {
-        let config = Config::try_get()?;
         if self._type.brew {
-            self.run_brew(&config)?;
+            let brew_prefix = PathBuf::from(cmd!("brew", "--prefix").read()?).join("opt");
+            let versions = file::dir_subdirs(&brew_prefix)?
+                .into_iter()
+                .filter_map(|entry| {
+                    let v = entry.strip_prefix("node@")?.to_string();
+                    Some((v, brew_prefix.join(&entry)))
+                })
+                .collect();
+            sync_versions("node", "Homebrew", &brew_prefix, versions, self.dry_run)
         } else if self._type.nvm {
-            self.run_nvm(&config)?;
-        } else if self._type.nodenv {
-            self.run_nodenv(&config)?;
-        }
-        Ok(())
-    }
-
-    fn run_brew(self, config: &Config) -> Result<()> {
-        let tool = plugins::get("node");
-
-        let brew_prefix = PathBuf::from(cmd!("brew", "--prefix").read()?).join("opt");
-        let installed_versions_path = dirs::INSTALLS.join("node");
-
-        file::remove_symlinks_with_target_prefix(&installed_versions_path, &brew_prefix)?;
-
-        let subdirs = file::dir_subdirs(&brew_prefix)?;
-        for entry in sorted(subdirs) {
-            if !entry.starts_with("node@") {
-                continue;
-            }
-            let v = entry.trim_start_matches("node@");
-            tool.create_symlink(v, &brew_prefix.join(&entry))?;
-            miseprintln!("Synced node@{} from Homebrew", v);
-        }
-
-        config.rebuild_shims_and_runtime_symlinks()
-    }
-
-    fn run_nvm(self, config: &Config) -> Result<()> {
-        let tool = plugins::get("node");
-
-        let nvm_versions_path = NVM_DIR.join("versions").join("node");
-        let installed_versions_path = dirs::INSTALLS.join("node");
-
-        let removed =
-            file::remove_symlinks_with_target_prefix(&installed_versions_path, &nvm_versions_path)?;
-        if !removed.is_empty() {
-            debug!("Removed symlinks: {removed:?}");
-        }
-
-        let mut created = vec![];
-        let subdirs = file::dir_subdirs(&nvm_versions_path)?;
-        for entry in sorted(subdirs) {
-            let v = entry.trim_start_matches('v');
-            let symlink = tool.create_symlink(v, &nvm_versions_path.join(&entry))?;
-            if let Some(symlink) = symlink {
-                created.push(symlink);
-                miseprintln!("Synced node@{} from nvm", v);
-            } else {
-                info!("Skipping node@{v} from nvm because it already exists in mise");
-            }
-        }
-        if !created.is_empty() {
-            debug!("Created symlinks: {created:?}");
-        }
-
-        config.rebuild_shims_and_runtime_symlinks()
-    }
-
-    fn run_nodenv(self, config: &Config) -> Result<()> {
-        let tool = plugins::get("node");
-
-        let nodenv_versions_path = NODENV_ROOT.join("versions");
-        let installed_versions_path = dirs::INSTALLS.join("node");
-
-        file::remove_symlinks_with_target_prefix(&installed_versions_path, &nodenv_versions_path)?;
-
-        let subdirs = file::dir_subdirs(&nodenv_versions_path)?;
-        for v in sorted(subdirs) {
-            tool.create_symlink(&v, &nodenv_versions_path.join(&v))?;
-            miseprintln!("Synced node@{} from nodenv", v);
+            let nvm_versions_path = NVM_DIR.join("versions").join("node");
+            let versions = file::dir_subdirs(&nvm_versions_path)?
+                .into_iter()
+                .map(|entry| {
+                    let v = entry.trim_start_matches('v').to_string();
+                    (v, nvm_versions_path.join(&entry))
+                })
+                .collect();
+            sync_versions("node", "nvm", &nvm_versions_path, versions, self.dry_run)
+        } else {
+            let nodenv_versions_path = NODENV_ROOT.join("versions");
+            let versions = file::dir_subdirs(&nodenv_versions_path)?
+                .into_iter()
+                .map(|v| (v.clone(), nodenv_versions_path.join(&v)))
+                .collect();
+            sync_versions(
+                "node",
+                "nodenv",
+                &nodenv_versions_path,
+                versions,
+                self.dry_run,
+            )
         }
-
-        config.rebuild_shims_and_runtime_symlinks()
     }
 }
 
@@ -1,9 +1,8 @@
 use eyre::Result;
-use itertools::sorted;
 
-use crate::config::Config;
+use crate::cli::sync::sync_versions;
 use crate::env::PYENV_ROOT;
-use crate::{dirs, file, plugins};
+use crate::file;
 
 /// Symlinks all tool versions from an external tool into mise
 ///
@@ -14,28 +13,26 @@ pub struct SyncPython {
     /// Get tool versions from pyenv
     #[clap(long, required = true)]
     pyenv: bool,
+
+    /// Show what would be synced without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
 }
 
 impl SyncPython {
     pub fn run(self) -> Result<()> {
-        let config = Config::try_get()?;
-        let python = plugins::get("python");
-
         let pyenv_versions_path = PYENV_ROOT.join("versions");
-        let installed_python_versions_path = dirs::INSTALLS.join("python");
-
-        file::remove_symlinks_with_target_prefix(
-            &installed_python_versions_path,
+        let versions = file::dir_subdirs(&pyenv_versions_path)?
+            .into_iter()
+            .map(|v| (v.clone(), pyenv_versions_path.join(&v)))
+            .collect();
+        sync_versions(
+            "python",
+            "pyenv",
             &pyenv_versions_path,
-        )?;
-
-        let subdirs = file::dir_subdirs(&pyenv_versions_path)?;
-        for v in sorted(subdirs) {
-            python.create_symlink(&v, &pyenv_versions_path.join(&v))?;
-            miseprintln!("Synced python@{} from pyenv", v);
-        }
-
-        config.rebuild_shims_and_runtime_symlinks()
+            versions,
+            self.dry_run,
+        )
     }
 }
 
@@ -0,0 +1,46 @@
+use eyre::Result;
+
+use crate::cli::sync::sync_versions;
+use crate::env::RBENV_ROOT;
+use crate::file;
+
+/// Symlinks all ruby tool versions from an external tool into mise
+///
+/// For example, use this to import all rbenv installs into mise
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct SyncRuby {
+    /// Get tool versions from rbenv
+    #[clap(long, required = true)]
+    rbenv: bool,
+
+    /// Show what would be synced without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+}
+
+impl SyncRuby {
+    pub fn run(self) -> Result<()> {
+        let rbenv_versions_path = RBENV_ROOT.join("versions");
+        let versions = file::dir_subdirs(&rbenv_versions_path)?
+            .into_iter()
+            .map(|v| (v.clone(), rbenv_versions_path.join(&v)))
+            .collect();
+        sync_versions(
+            "ruby",
+            "rbenv",
+            &rbenv_versions_path,
+            versions,
+            self.dry_run,
+        )
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>rbenv install 3.3.0</bold>
+    $ <bold>mise sync ruby --rbenv</bold>
+    $ <bold>mise use -g ruby@3.3.0</bold> - uses rbenv-provided ruby
+"#
+);
@@ -1,9 +1,14 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
 use console::style;
 use eyre::{eyre, Result};
 use itertools::Itertools;
-use petgraph::dot::Dot;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
 
 use crate::config::{Config, Settings};
+use crate::file::display_path;
 use crate::task::{Deps, Task};
 use crate::ui::style::{self};
 use crate::ui::tree::print_tree;
@@ -23,8 +28,13 @@ pub struct TasksDeps {
     pub hidden: bool,
 
     /// Display dependencies in DOT format
+    /// tasks that are part of a circular dependency are colored red
     #[clap(long, alias = "dot", verbatim_doc_comment)]
     pub dot: bool,
+
+    /// With --dot, group tasks into a subgraph per config file or task directory
+    #[clap(long, requires = "dot", verbatim_doc_comment)]
+    pub group_by_source: bool,
 }
 
 impl TasksDeps {
@@ -106,31 +116,72 @@ impl TasksDeps {
     /// Example:
     /// ```
     /// digraph {
-    ///  1 [label = "task1"]
-    ///  2 [label = "task2"]
-    ///  3 [label = "task3"]
-    ///  4 [label = "task4"]
-    ///  5 [label = "task5"]
-    ///  1 -> 2 [ ]
-    ///  2 -> 3 [ ]
-    ///  1 -> 4 [ ]
+    ///     0 [ label = "task1"]
+    ///     1 [ label = "task2\nruns the tests"]
+    ///     2 [ label = "task3"]
+    ///     0 -> 1 [ ]
+    ///     1 -> 2 [ ]
     /// }
     /// ```
-    //
+    ///
+    /// unlike running the tasks this does not fail on circular dependencies so they can be seen
     fn print_deps_dot(&self, config: &Config, tasks: Vec<Task>) -> Result<()> {
-        let deps = Deps::new(config, tasks)?;
-        miseprintln!(
-            "{:?}",
-            Dot::with_attr_getters(
-                &deps.graph,
-                &[
-                    petgraph::dot::Config::NodeNoLabel,
-                    petgraph::dot::Config::EdgeNoLabel
-                ],
-                &|_, _| String::new(),
-                &|_, nr| format!("label = \"{}\"", nr.1.name),
-            ),
-        );
+        let graph = Deps::graph(config, tasks)?;
+        let cycles: HashSet<NodeIndex> = petgraph::algo::tarjan_scc(&graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1)
+            .flatten()
+            .collect();
+        let node = |idx: NodeIndex| {
+            let task = &graph[idx];
+            let mut label = task.name.clone();
+            if !task.description.is_empty() {
+                label = format!("{label}\n{}", task.description);
+            }
+            let color = if cycles.contains(&idx) {
+                ", color = red"
+            } else {
+                ""
+            };
+            format!("{} [ label = {}{color}]", idx.index(), dot_quote(&label))
+        };
+
+        let mut out = String::from("digraph {\n");
+        if self.group_by_source {
+            let mut groups: BTreeMap<String, Vec<NodeIndex>> = BTreeMap::new();
+            for idx in graph.node_indices() {
+                let task = &graph[idx];
+                // file tasks each have their own source so they are grouped by directory
+                let source = match &task.file {
+                    Some(file) => file.parent().unwrap_or(file),
+                    None => &task.config_source,
+                };
+                groups.entry(display_path(source)).or_default().push(idx);
+            }
+            for (i, (source, nodes)) in groups.into_iter().enumerate() {
+                writeln!(out, "    subgraph cluster_{i} {{")?;
+                writeln!(out, "        label = {}", dot_quote(&source))?;
+                for idx in nodes {
+                    writeln!(out, "        {}", node(idx))?;
+                }
+                writeln!(out, "    }}")?;
+            }
+        } else {
+            for idx in graph.node_indices() {
+                writeln!(out, "    {}", node(idx))?;
+            }
+        }
+        for edge in graph.edge_references() {
+            let (a, b) = (edge.source(), edge.target());
+            let color = if cycles.contains(&a) && cycles.contains(&b) {
+                "color = red "
+            } else {
+                ""
+            };
+            writeln!(out, "    {} -> {} [ {color}]", a.index(), b.index())?;
+        }
+        out.push('}');
+        miseprintln!("{out}");
         Ok(())
     }
 
@@ -145,6 +196,15 @@ impl TasksDeps {
     }
 }
 
+/// quotes a DOT string, `\n` starts a new line in a label
+fn dot_quote(s: &str) -> String {
+    let s = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{s}\"")
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -156,6 +216,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # Show dependencies in DOT format
     $ <bold>mise tasks deps --dot</bold>
+
+    # Render the graph with Graphviz, grouped by the file each task is defined in
+    $ <bold>mise tasks deps --dot --group-by-source | dot -Tsvg > tasks.svg</bold>
 "#
 );
 
@@ -196,7 +259,7 @@ mod tests {
         assert_cli_snapshot!("tasks", "deps", "--dot", @r###"
         digraph {
             0 [ label = "configtask"]
-            1 [ label = "filetask"]
+            1 [ label = "filetask\nThis is a test build script"]
             2 [ label = "lint"]
             3 [ label = "test"]
             1 -> 2 [ ]
@@ -205,4 +268,26 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn test_tasks_deps_dot_group_by_source() {
+        reset();
+        assert_cli_snapshot!("tasks", "deps", "--dot", "--group-by-source", @r###"
+        digraph {
+            subgraph cluster_0 {
+                label = "~/config/config.toml"
+                0 [ label = "configtask"]
+                2 [ label = "lint"]
+                3 [ label = "test"]
+            }
+            subgraph cluster_1 {
+                label = "~/cwd/.mise/tasks"
+                1 [ label = "filetask\nThis is a test build script"]
+            }
+            1 -> 2 [ ]
+            1 -> 3 [ ]
+        }
+        "###
+        );
+    }
 }
@@ -1,12 +1,19 @@
-use eyre::Result;
+use std::path::Path;
+
+use eyre::{bail, Result};
 
 use crate::config::{Config, Settings};
+use crate::file::display_path;
 use crate::task::Task;
-use crate::{env, file};
+use crate::{cmd, env, file};
 
 /// [experimental] Edit a tasks with $EDITOR
 ///
-/// The tasks will be created as a standalone script if it does not already exist.
+/// Opens the file the task is defined in: the script of a file task or the config file of a
+/// toml task. For toml tasks the editor is started at the line of the task's table if it is one
+/// mise knows how to pass a line to, e.g.: vim, nvim, nano, emacs, helix or vscode.
+///
+/// With --create, a task that doesn't exist is created as a standalone script.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct TasksEdit {
@@ -14,6 +21,10 @@ pub struct TasksEdit {
     #[clap()]
     task: String,
 
+    /// Create the task as a script in .mise/tasks if it doesn't exist
+    #[clap(long, short)]
+    create: bool,
+
     /// Display the path to the tasks instead of editing it
     #[clap(long, short, verbatim_doc_comment)]
     path: bool,
@@ -25,42 +36,152 @@ impl TasksEdit {
         let settings = Settings::try_get()?;
         settings.ensure_experimental("`mise tasks edit`")?;
 
-        let task = config
-            .tasks_with_aliases()?
-            .remove(&self.task)
-            .cloned()
-            .map_or_else(
-                || {
-                    let path = config
-                        .project_root
-                        .as_ref()
-                        .unwrap_or(&env::current_dir()?)
-                        .join(".mise")
-                        .join("tasks")
-                        .join(&self.task);
-                    Task::from_path(&path)
-                },
-                Ok,
-            )?;
+        let task = match config.tasks_with_aliases()?.remove(&self.task) {
+            Some(task) => task.clone(),
+            None => {
+                let path = config
+                    .project_root
+                    .as_ref()
+                    .unwrap_or(&env::current_dir()?)
+                    .join(".mise")
+                    .join("tasks")
+                    .join(&self.task);
+                if !self.create {
+                    bail!(
+                        "no task named `{}`, run `mise tasks edit --create {}` to create it at {}",
+                        self.task,
+                        self.task,
+                        display_path(&path)
+                    );
+                }
+                if !path.exists() {
+                    file::create_dir_all(path.parent().unwrap())?;
+                    file::write(&path, "#!/usr/bin/env bash\n")?;
+                    file::make_executable(&path)?;
+                }
+                Task::from_path(&path)?
+            }
+        };
         let file = &task.config_source;
-        if !file.exists() {
-            file::create(file)?;
-            file::make_executable(file)?;
-        }
         if self.path {
             miseprintln!("{}", file.display());
         } else {
-            cmd!(&*env::EDITOR, &file).run()?;
+            let line = toml_task_line(file, &task.name);
+            let args = editor_args(&env::EDITOR, file, line)?;
+            cmd::cmd(&args[0], &args[1..]).run()?;
         }
 
         Ok(())
     }
 }
 
+/// the line of the task's table in a config or toml task file, `None` for scripts or if it
+/// can't be found
+fn toml_task_line(path: &Path, name: &str) -> Option<usize> {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+        return None;
+    }
+    let body = file::read_to_string(path).ok()?;
+    let doc = toml_edit::ImDocument::parse(body.as_str()).ok()?;
+    // config files have the task under [tasks], task files have it at the top level
+    let table = match doc.get("tasks").and_then(|t| t.as_table_like()) {
+        Some(tasks) if tasks.contains_key(name) => tasks,
+        _ => doc.as_table(),
+    };
+    let (key, _) = table.get_key_value(name)?;
+    let start = key.span()?.start;
+    Some(body[..start].matches('\n').count() + 1)
+}
+
+/// the command line to open `path` in `editor`, at `line` if the editor supports it
+fn editor_args(editor: &str, path: &Path, line: Option<usize>) -> Result<Vec<String>> {
+    let mut args = shell_words::split(editor)?;
+    let Some(program) = args.first() else {
+        bail!("$EDITOR is empty");
+    };
+    let program = Path::new(program)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let path = path.display().to_string();
+    match (line, program.as_str()) {
+        (
+            Some(line),
+            "vi" | "vim" | "nvim" | "nano" | "emacs" | "emacsclient" | "kak" | "micro",
+        ) => {
+            args.push(format!("+{line}"));
+            args.push(path);
+        }
+        (Some(line), "code" | "codium" | "cursor") => {
+            args.push("--goto".into());
+            args.push(format!("{path}:{line}"));
+        }
+        (Some(line), "hx" | "helix" | "subl" | "zed") => args.push(format!("{path}:{line}")),
+        _ => args.push(path),
+    }
+    Ok(args)
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
     $ <bold>mise tasks edit build</bold>
     $ <bold>mise tasks edit test</bold>
+    $ <bold>mise tasks edit --create lint</bold>
 "#
 );
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_toml_task_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mise.toml");
+        file::write(
+            &path,
+            "[env]\nFOO = \"bar\"\n\n[tasks.build]\nrun = \"make\"\n\n[tasks]\ntest = \"cargo test\"\n",
+        )
+        .unwrap();
+        assert_eq!(toml_task_line(&path, "build"), Some(4));
+        assert_eq!(toml_task_line(&path, "test"), Some(8));
+        assert_eq!(toml_task_line(&path, "lint"), None);
+
+        let path = dir.path().join("tasks.toml");
+        file::write(&path, "[lint]\nrun = \"eslint\"\n").unwrap();
+        assert_eq!(toml_task_line(&path, "lint"), Some(1));
+
+        let path = dir.path().join("build");
+        file::write(&path, "#!/usr/bin/env bash\n[lint]\n").unwrap();
+        assert_eq!(toml_task_line(&path, "lint"), None);
+    }
+
+    #[test]
+    fn test_editor_args() {
+        let path = Path::new("mise.toml");
+        assert_eq!(
+            editor_args("nvim", path, Some(4)).unwrap(),
+            vec!["nvim", "+4", "mise.toml"]
+        );
+        assert_eq!(
+            editor_args("/usr/bin/code --wait", path, Some(4)).unwrap(),
+            vec!["/usr/bin/code", "--wait", "--goto", "mise.toml:4"]
+        );
+        assert_eq!(
+            editor_args("hx", path, Some(4)).unwrap(),
+            vec!["hx", "mise.toml:4"]
+        );
+        assert_eq!(
+            editor_args("nano", path, None).unwrap(),
+            vec!["nano", "mise.toml"]
+        );
+        assert_eq!(
+            editor_args("ed", path, Some(4)).unwrap(),
+            vec!["ed", "mise.toml"]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use eyre::{bail, Result};
+use itertools::Itertools;
 use serde_json::json;
 
 use crate::config::settings::SETTINGS;
@@ -60,6 +61,14 @@ impl TasksInfo {
         if !task.depends.is_empty() {
             info::inline_section("Depends on", task.depends.join(", "))?;
         }
+        if !task.tools.is_empty() {
+            let tools = task
+                .tools
+                .iter()
+                .map(|(t, v)| format!("{t}@{v}"))
+                .join(", ");
+            info::inline_section("Tools", tools)?;
+        }
         if let Some(dir) = &task.dir {
             info::inline_section("Directory", display_path(dir))?;
         }
@@ -94,6 +103,7 @@ impl TasksInfo {
             "source": task.config_source,
             "depends": task.depends.join(", "),
             "env": task.env,
+            "tools": task.tools,
             "dir": task.dir,
             "hide": task.hide,
             "raw": task.raw,
@@ -125,6 +135,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
       "source": "~/src/myproj/mise.toml",
       "depends": [],
       "env": {},
+      "tools": {},
       "dir": null,
       "hide": false,
       "raw": false,
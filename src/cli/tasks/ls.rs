@@ -45,6 +45,10 @@ pub struct TasksLs {
 
     #[clap(long, hide = true)]
     pub usage: bool,
+
+    /// Print task names for shell completion, one per line
+    #[clap(long, hide = true)]
+    pub complete: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
@@ -63,6 +67,9 @@ pub enum SortOrder {
 
 impl TasksLs {
     pub fn run(self) -> Result<()> {
+        if self.complete {
+            return self.display_complete();
+        }
         let config = Config::try_get()?;
         let settings = Settings::try_get()?;
         settings.ensure_experimental("`mise tasks ls`")?;
@@ -97,6 +104,22 @@ impl TasksLs {
         Ok(())
     }
 
+    /// called on every <TAB> so this skips the experimental check and prints nothing if the
+    /// config can't be loaded rather than writing errors into the prompt. Descriptions are left
+    /// out since usage can't show them for `run` completions yet.
+    fn display_complete(&self) -> Result<()> {
+        let Ok(tasks) = Config::try_get().and_then(|c| c.tasks().cloned()) else {
+            return Ok(());
+        };
+        for task in tasks.values().filter(|t| !t.hide) {
+            miseprintln!("{}", task.name);
+            for alias in &task.aliases {
+                miseprintln!("{alias}");
+            }
+        }
+        Ok(())
+    }
+
     fn display_usage(&self, tasks: Vec<Task>) -> Result<()> {
         let mut usage = usage::Spec::default();
         for task in tasks {
@@ -201,6 +224,18 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_task_ls_complete() {
+        reset();
+        assert_cli_snapshot!("tasks", "--complete", @r###"
+        configtask
+        filetask
+        ft
+        lint
+        test
+        "###);
+    }
+
     #[test]
     fn test_task_ls_json() {
         reset();
@@ -1,8 +1,10 @@
 use std::fs::read_dir;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::ValueHint;
-use eyre::Result;
+use eyre::{bail, Result};
+use itertools::Itertools;
+use walkdir::WalkDir;
 
 use crate::config;
 use crate::config::{config_file, Settings, DEFAULT_CONFIG_FILENAMES};
@@ -18,14 +20,21 @@ use crate::file::{display_path, remove_file};
 /// - environment variables
 /// - templates
 /// - `path:` plugin versions
+///
+/// A hash of the file is recorded when it is trusted. If the file is changed
+/// by something other than mise it needs to be trusted again, as does a file
+/// that is moved. mise says where it was trusted before when it asks.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Trust {
     /// The config file to trust
-    #[clap(value_hint = ValueHint::FilePath, verbatim_doc_comment)]
+    ///
+    /// With --all this is a directory and every config file beneath it is trusted
+    #[clap(value_hint = ValueHint::AnyPath, verbatim_doc_comment)]
     config_file: Option<String>,
 
     /// Trust all config files in the current directory and its parents
+    /// or, if a directory is given, all config files beneath it
     #[clap(long, short, verbatim_doc_comment)]
     all: bool,
 
@@ -44,6 +53,9 @@ impl Trust {
         if self.show {
             return self.show();
         }
+        if let Some(dir) = self.config_file.as_ref().filter(|_| self.all) {
+            return self.trust_dir(Path::new(dir));
+        }
         if self.untrust {
             self.untrust()
         } else if self.all {
@@ -82,10 +94,7 @@ impl Trust {
         let path = path.canonicalize()?;
         info!("untrusted {}", path.display());
 
-        let trusted_via_settings = settings
-            .trusted_config_paths
-            .iter()
-            .any(|p| path.starts_with(p));
+        let trusted_via_settings = settings.trusted_config_paths().any(|p| path.starts_with(p));
         if trusted_via_settings {
             warn!("{path:?} is trusted via settings so it will still be trusted.");
         }
@@ -109,6 +118,27 @@ impl Trust {
         Ok(())
     }
 
+    /// trusts (or untrusts) every config file beneath `dir`
+    fn trust_dir(&self, dir: &Path) -> Result<()> {
+        if !dir.is_dir() {
+            bail!("{} is not a directory", display_path(dir));
+        }
+        let paths = config_files_in(dir);
+        if paths.is_empty() {
+            warn!("No config files found in {}", display_path(dir));
+        }
+        for path in paths {
+            if self.untrust {
+                config_file::untrust(&path)?;
+                info!("untrusted {}", path.display());
+            } else {
+                config_file::trust(&path)?;
+                info!("trusted {}", path.display());
+            }
+        }
+        Ok(())
+    }
+
     fn get_next_trusted(&self) -> Option<PathBuf> {
         config::load_config_paths(&DEFAULT_CONFIG_FILENAMES)
             .into_iter()
@@ -140,6 +170,22 @@ impl Trust {
     }
 }
 
+/// config files beneath `dir`, skipping directories that hold dependencies or vcs data
+fn config_files_in(dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| !matches!(e.file_name().to_str(), Some(".git" | "node_modules")))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            let path = e.path().strip_prefix(dir).unwrap_or(e.path());
+            DEFAULT_CONFIG_FILENAMES.iter().any(|f| path.ends_with(f))
+        })
+        .filter_map(|e| e.path().canonicalize().ok())
+        .sorted()
+        .collect()
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
     # trusts ~/some_dir/.mise.toml
@@ -147,6 +193,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # trusts .mise.toml in the current or parent directory
     $ <bold>mise trust</bold>
+
+    # trusts every config file in ~/src
+    $ <bold>mise trust --all ~/src</bold>
 "#
 );
 
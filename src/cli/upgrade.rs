@@ -16,20 +16,20 @@ pub struct Upgrade {
     /// e.g.: node@20 python@3.10
     /// If not specified, all current tools will be upgraded
     #[clap(value_name = "TOOL@VERSION", verbatim_doc_comment)]
-    tool: Vec<ToolArg>,
+    pub tool: Vec<ToolArg>,
 
     /// Just print what would be done, don't actually do it
     #[clap(long, short = 'n', verbatim_doc_comment)]
-    dry_run: bool,
+    pub dry_run: bool,
 
     /// Display multiselect menu to choose which tools to upgrade
     #[clap(long, short, verbatim_doc_comment, conflicts_with = "tool")]
-    interactive: bool,
+    pub interactive: bool,
 
     /// Number of jobs to run in parallel
     /// [default: 4]
     #[clap(long, short, env = "MISE_JOBS", verbatim_doc_comment)]
-    jobs: Option<usize>,
+    pub jobs: Option<usize>,
 
     /// Upgrades to the latest version available, bumping the version in mise.toml
     ///
@@ -39,12 +39,12 @@ pub struct Upgrade {
     /// It keeps the same precision as what was there before, so if you instead had `node = "20"`, it
     /// would change your config to `node = "22"`.
     #[clap(long, short = 'l', verbatim_doc_comment)]
-    bump: bool,
+    pub bump: bool,
 
     /// Directly pipe stdin/stdout/stderr from plugin to user
     /// Sets --jobs=1
     #[clap(long, overrides_with = "jobs")]
-    raw: bool,
+    pub raw: bool,
 }
 
 impl Upgrade {
@@ -70,7 +70,7 @@ impl Upgrade {
         Ok(())
     }
 
-    fn upgrade(&self, config: &Config, outdated: Vec<OutdatedInfo>) -> Result<()> {
+    pub fn upgrade(&self, config: &Config, outdated: Vec<OutdatedInfo>) -> Result<()> {
         let mpr = MultiProgressReport::get();
         let mut ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
 
@@ -129,6 +129,7 @@ impl Upgrade {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: true,
+            ..Default::default()
         };
         let new_versions = outdated.iter().map(|o| o.tool_request.clone()).collect();
         ts.install_versions(config, new_versions, &mpr, &opts)?;
@@ -1,19 +1,22 @@
 use std::path::{Path, PathBuf};
 
 use console::style;
-use eyre::Result;
+use eyre::{bail, Result};
 use itertools::Itertools;
 
 use crate::cli::args::{BackendArg, ToolArg};
+use crate::cli::prune::Prune;
 use crate::config::config_file::ConfigFile;
 use crate::config::{config_file, is_global_config, Config, Settings, LOCAL_CONFIG_FILENAMES};
 use crate::env::{
     MISE_DEFAULT_CONFIG_FILENAME, MISE_DEFAULT_TOOL_VERSIONS_FILENAME, MISE_GLOBAL_CONFIG_FILE,
 };
 use crate::file::display_path;
-use crate::toolset::{InstallOptions, ToolRequest, ToolSource, ToolVersion, ToolsetBuilder};
+use crate::toolset::{
+    InstallOptions, ToolRequest, ToolSource, ToolVersion, Toolset, ToolsetBuilder,
+};
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::{env, file};
+use crate::{dirs, env, file};
 
 /// Install tool version and add it to config
 ///
@@ -40,7 +43,7 @@ pub struct Use {
 
     /// Save fuzzy version to config file
     /// e.g.: `mise use --fuzzy node@20` will save 20 as the version
-    /// this is the default behavior unless MISE_ASDF_COMPAT=1
+    /// this is the default behavior unless MISE_PIN=1 or MISE_ASDF_COMPAT=1
     #[clap(long, verbatim_doc_comment, overrides_with = "pin")]
     fuzzy: bool,
 
@@ -63,9 +66,16 @@ pub struct Use {
     raw: bool,
 
     /// Remove the plugin(s) from config file
-    #[clap(long, value_name = "PLUGIN", aliases = ["rm", "unset"])]
+    ///
+    /// Unless --global, --env or --path is used, each one is removed from the config file that
+    /// currently sets it
+    #[clap(long, value_name = "PLUGIN", aliases = ["rm", "unset"], verbatim_doc_comment)]
     remove: Vec<BackendArg>,
 
+    /// With --remove, also uninstall the versions of the removed tools no other config uses
+    #[clap(long, requires = "remove")]
+    prune: bool,
+
     /// Specify a path to a config file or directory
     /// If a directory is specified, it will look for .mise.toml (default) or .tool-versions
     #[clap(short, long, overrides_with_all = & ["global", "env"], value_hint = clap::ValueHint::FilePath
@@ -74,7 +84,7 @@ pub struct Use {
 
     /// Save exact version to config file
     /// e.g.: `mise use --pin node@20` will save 20.0.0 as the version
-    /// Set MISE_ASDF_COMPAT=1 to make this the default behavior
+    /// Set MISE_PIN=1 to make this the default behavior
     #[clap(long, verbatim_doc_comment, overrides_with = "fuzzy")]
     pin: bool,
 }
@@ -102,12 +112,13 @@ impl Use {
                 jobs: self.jobs,
                 raw: self.raw,
                 latest_versions: false,
+                ..Default::default()
             },
         )?;
 
         let mut cf = self.get_config_file()?;
         let settings = Settings::try_get()?;
-        let pin = self.pin || (settings.asdf_compat && !self.fuzzy);
+        let pin = self.pin || ((settings.pin || settings.asdf_compat) && !self.fuzzy);
 
         for (fa, tvl) in &versions.iter().chunk_by(|tv| &tv.backend) {
             let versions: Vec<String> = tvl
@@ -126,14 +137,66 @@ impl Use {
         if self.global {
             self.warn_if_hidden(&config, cf.get_path());
         }
-        for plugin_name in &self.remove {
-            cf.remove_plugin(plugin_name)?;
+        let mut removed_from = vec![];
+        let mut modified = !self.tool.is_empty() || self.remove.is_empty();
+        for fa in &self.remove {
+            match self.remove_target(&ts, fa)? {
+                Some(path) if path != cf.get_path() => {
+                    let mut cf = config_file::parse(&path)?;
+                    cf.remove_plugin(fa)?;
+                    cf.save()?;
+                    removed_from.push((fa, path));
+                }
+                _ => {
+                    cf.remove_plugin(fa)?;
+                    modified = true;
+                }
+            }
+        }
+        if modified {
+            cf.save()?;
+            self.render_success_message(cf.as_ref(), &versions)?;
+        }
+        for (fa, path) in removed_from {
+            info!("removed {fa} from {}", display_path(path));
+        }
+        if self.prune {
+            let prune = Prune {
+                plugin: Some(self.remove.clone()),
+                dry_run: false,
+                configs: false,
+                tools: true,
+                keep_last: None,
+            };
+            prune.run()?;
         }
-        cf.save()?;
-        self.render_success_message(cf.as_ref(), &versions)?;
         Ok(())
     }
 
+    /// the config file to remove `fa` from if it isn't the one tools are added to, which is the
+    /// one that sets it unless a config file was chosen with a flag
+    fn remove_target(&self, ts: &Toolset, fa: &BackendArg) -> Result<Option<PathBuf>> {
+        if self.global || self.env.is_some() || self.path.is_some() || env::MISE_ENV.is_some() {
+            return Ok(None);
+        }
+        let Some(tvl) = ts.versions.get(fa) else {
+            return Ok(None);
+        };
+        match &tvl.source {
+            ToolSource::MiseToml(path) | ToolSource::ToolVersions(path) => {
+                let readonly = path.metadata().is_ok_and(|m| m.permissions().readonly());
+                if readonly || path.starts_with(*dirs::SYSTEM) {
+                    bail!(
+                        "{fa} is set in {} which can't be modified, use --path to remove it from another config file",
+                        display_path(path)
+                    );
+                }
+                Ok(Some(path.clone()))
+            }
+            source => bail!("{fa} is set by {source}, not a config file mise can modify"),
+        }
+    }
+
     fn get_config_file(&self) -> Result<Box<dyn ConfigFile>> {
         let path = if let Some(env) = &*env::MISE_ENV {
             config_file_from_dir(&env::current_dir()?.join(format!(".mise.{}.toml", env)))
@@ -226,6 +289,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # sets .mise.staging.toml (which is used if MISE_ENV=staging)
     $ <bold>mise use --env staging node@20</bold>
+
+    # removes node from the config file that sets it and uninstalls versions no longer used
+    $ <bold>mise use --remove node --prune</bold>
 "#
 );
 
@@ -233,6 +299,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 mod tests {
     use insta::assert_snapshot;
 
+    use crate::config::Settings;
     use crate::test::reset;
     use crate::{dirs, env, file};
 
@@ -310,6 +377,52 @@ mod tests {
         let _ = file::remove_file(&cf_path);
     }
 
+    #[test]
+    fn test_use_remove_from_source() {
+        reset();
+        let cf_path = env::current_dir().unwrap().join(".test.mise.toml");
+        let tv_path = env::current_dir().unwrap().join(".test-tool-versions");
+        file::write(&cf_path, "# my tools\n[tools]\ndummy = \"1\" # keep this\n").unwrap();
+        file::write(&tv_path, "tiny 3\ndummy 1\n").unwrap();
+
+        assert_cli_snapshot!("use", "--rm", "tiny", @"mise removed tiny from ~/cwd/.test-tool-versions");
+        assert_snapshot!(file::read_to_string(&cf_path).unwrap(), @r###"
+        # my tools
+        [tools]
+        dummy = "1" # keep this
+        "###);
+        assert_snapshot!(file::read_to_string(&tv_path).unwrap(), @"dummy 1");
+
+        let _ = file::remove_file(&cf_path);
+    }
+
+    #[test]
+    fn test_use_pin_setting() {
+        reset();
+        let cf_path = env::current_dir().unwrap().join(".test.mise.toml");
+        file::write(&cf_path, "# my tools\n[tools]\ntiny = \"2\" # keep this\n").unwrap();
+        env::set_var("MISE_PIN", "1");
+        Settings::reset(None);
+
+        assert_cli_snapshot!("use", "tiny@3", @"mise ~/cwd/.test.mise.toml tools: tiny@3.1.0");
+        assert_snapshot!(file::read_to_string(&cf_path).unwrap(), @r###"
+        # my tools
+        [tools]
+        tiny = "3.1.0" # keep this
+        "###);
+
+        assert_cli_snapshot!("use", "--fuzzy", "tiny@2", @"mise ~/cwd/.test.mise.toml tools: tiny@2.1.0");
+        assert_snapshot!(file::read_to_string(&cf_path).unwrap(), @r###"
+        # my tools
+        [tools]
+        tiny = "2" # keep this
+        "###);
+
+        env::remove_var("MISE_PIN");
+        Settings::reset(None);
+        let _ = file::remove_file(&cf_path);
+    }
+
     #[test]
     fn test_use_local_tool_versions_reuse() {
         reset();
@@ -7,7 +7,6 @@ use versions::Versioning;
 
 use crate::build_time::{git_sha, BUILD_TIME};
 use crate::cli::self_update::SelfUpdate;
-#[cfg(not(test))]
 use crate::config::Settings;
 use crate::file::modified_duration;
 use crate::{dirs, duration, env, file};
@@ -92,18 +91,35 @@ pub fn check_for_new_version(cache_duration: Duration) -> Option<String> {
     None
 }
 
+/// fetches the latest version without using the daily cache, unless mise is offline in which
+/// case the cached version is returned if there is one
+pub fn fetch_latest_version() -> Option<String> {
+    #[cfg(not(test))]
+    if Settings::get().offline {
+        return file::read_to_string(dirs::CACHE.join("latest-version"))
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+    }
+    get_latest_version(Duration::ZERO)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
 fn get_latest_version(duration: Duration) -> Option<String> {
     let version_file_path = dirs::CACHE.join("latest-version");
     if let Ok(metadata) = modified_duration(&version_file_path) {
-        if metadata < duration {
+        if metadata < duration && Settings::get().use_cache() {
             if let Ok(version) = file::read_to_string(&version_file_path) {
                 return Some(version);
             }
         }
     }
-    let _ = file::create_dir_all(*dirs::CACHE);
     let version = get_latest_version_call();
-    let _ = file::write(version_file_path, version.clone().unwrap_or_default());
+    if Settings::get().write_cache() {
+        let _ = file::create_dir_all(*dirs::CACHE);
+        let _ = file::write(version_file_path, version.clone().unwrap_or_default());
+    }
     version
 }
 
@@ -6,6 +6,7 @@ use eyre::{eyre, Result};
 use crate::cli::args::BackendArg;
 use crate::cmd;
 use crate::config::{Config, Settings};
+use crate::task::Task;
 use crate::toolset::ToolsetBuilder;
 
 /// [experimental] Run a tasks watching for changes
@@ -24,6 +25,25 @@ pub struct Watch {
     /// Defaults to sources from the tasks(s)
     #[clap(short, long, verbatim_doc_comment)]
     glob: Vec<String>,
+
+    /// Files to ignore
+    /// Added to the default ignores: .git/, target/, and node_modules/
+    #[clap(short, long, verbatim_doc_comment)]
+    ignore: Vec<String>,
+
+    /// Don't ignore .git/, target/, and node_modules/
+    #[clap(long, verbatim_doc_comment)]
+    no_default_ignore: bool,
+
+    /// Wait this long after a change before running the tasks so that a
+    /// batch of changes only triggers one run
+    #[clap(long, default_value = "500ms", verbatim_doc_comment)]
+    debounce: String,
+
+    /// Let a running task finish instead of stopping and restarting it
+    /// when files change
+    #[clap(long, verbatim_doc_comment)]
+    no_restart: bool,
     // /// Change to this directory before executing the command
     // #[clap(short = 'C', long, value_hint = ValueHint::DirPath, long)]
     // pub cd: Option<PathBuf>,
@@ -91,6 +111,20 @@ impl Watch {
                     .ok_or_else(|| eyre!("Tasks not found: {t}"))
             })
             .collect::<Result<Vec<_>>>()?;
+        let args = self.watchexec_args(&tasks);
+        info!("$ watchexec {}", args.join(" "));
+        let mut cmd = cmd::cmd("watchexec", &args);
+        for (k, v) in ts.env_with_path(&config)? {
+            cmd = cmd.env(k, v);
+        }
+        if let Some(root) = &config.project_root {
+            cmd = cmd.dir(root);
+        }
+        cmd.run()?;
+        Ok(())
+    }
+
+    fn watchexec_args(&self, tasks: &[&Task]) -> Vec<String> {
         let mut args = vec![];
         let globs = if self.glob.is_empty() {
             tasks
@@ -100,28 +134,32 @@ impl Watch {
         } else {
             self.glob.clone()
         };
-        if !globs.is_empty() {
-            args.push("-f".to_string());
-            args.extend(itertools::intersperse(globs, "-f".to_string()).collect::<Vec<_>>());
+        for glob in globs {
+            args.extend(["-f".to_string(), glob]);
+        }
+        let default_ignores = DEFAULT_IGNORES
+            .iter()
+            .filter(|_| !self.no_default_ignore)
+            .map(|i| i.to_string());
+        for ignore in default_ignores.chain(self.ignore.clone()) {
+            args.extend(["-i".to_string(), ignore]);
+        }
+        args.extend(["--debounce".to_string(), self.debounce.clone()]);
+        if !self.no_restart {
+            // stops the running task with SIGTERM before starting it again
+            args.push("--restart".to_string());
         }
         args.extend(self.args.clone());
         args.extend(["--".to_string(), "mise".to_string(), "run".to_string()]);
         for arg in itertools::intersperse(tasks.iter().map(|t| t.name.as_str()), ":::") {
             args.push(arg.to_string());
         }
-        info!("$ watchexec {}", args.join(" "));
-        let mut cmd = cmd::cmd("watchexec", &args);
-        for (k, v) in ts.env_with_path(&config)? {
-            cmd = cmd.env(k, v);
-        }
-        if let Some(root) = &config.project_root {
-            cmd = cmd.dir(root);
-        }
-        cmd.run()?;
-        Ok(())
+        args
     }
 }
 
+static DEFAULT_IGNORES: &[&str] = &["**/.git/**", "**/target/**", "**/node_modules/**"];
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
     $ <bold>mise watch -t build</bold>
@@ -132,7 +170,53 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     Runs the "build" tasks but specify the files to watch with a glob pattern.
     This overrides the "sources" from the tasks definition.
 
+    $ <bold>mise watch -t build --ignore "dist/**" --debounce 2s</bold>
+    Don't rerun when files in dist/ change and wait for changes to settle for 2 seconds.
+
     $ <bold>mise run -t build --clear</bold>
     Extra arguments are passed to watchexec. See `watchexec --help` for details.
 "#
 );
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn watch() -> Watch {
+        Watch {
+            task: vec!["build".into()],
+            args: vec![],
+            glob: vec![],
+            ignore: vec![],
+            no_default_ignore: false,
+            debounce: "500ms".into(),
+            no_restart: false,
+        }
+    }
+
+    #[test]
+    fn test_watchexec_args() {
+        let task = Task {
+            name: "build".into(),
+            sources: vec!["src/**/*.rs".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            watch().watchexec_args(&[&task]).join(" "),
+            "-f src/**/*.rs -i **/.git/** -i **/target/** -i **/node_modules/** --debounce 500ms --restart -- mise run build"
+        );
+        let watch = Watch {
+            ignore: vec!["dist/**".into()],
+            no_default_ignore: true,
+            debounce: "2s".into(),
+            no_restart: true,
+            ..watch()
+        };
+        assert_eq!(
+            watch.watchexec_args(&[&task]).join(" "),
+            "-f src/**/*.rs -i dist/** --debounce 2s -- mise run build"
+        );
+    }
+}
@@ -1,8 +1,14 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
 use eyre::{bail, Result};
+use indexmap::IndexMap;
+use serde_derive::Serialize;
 
 use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::dirs::SHIMS;
+use crate::file::display_path;
 use crate::toolset::{Toolset, ToolsetBuilder};
 
 /// Shows the path that a bin name points to
@@ -25,11 +31,28 @@ pub struct Which {
     /// e.g.: `mise which npm --tool=node@20`
     #[clap(short, long, value_name = "TOOL@VERSION", verbatim_doc_comment)]
     pub tool: Option<ToolArg>,
+
+    /// Show the path for every installed version that provides the bin
+    /// the active one is marked along with the config that selected it
+    #[clap(long, short, conflicts_with_all = ["plugin", "version"], verbatim_doc_comment)]
+    pub all: bool,
+
+    /// Output in JSON format, only used with --all
+    #[clap(long, short = 'J', requires = "all")]
+    pub json: bool,
+
+    /// Follow every symlink from the shim to the file that actually runs
+    /// each hop is shown followed by the final path, if a symlink is broken it shows where
+    #[clap(long, short = 'L', conflicts_with_all = ["plugin", "version"], verbatim_doc_comment)]
+    pub resolve_symlinks: bool,
 }
 
 impl Which {
     pub fn run(self) -> Result<()> {
         let ts = self.get_toolset()?;
+        if self.all {
+            return self.all(&ts);
+        }
 
         match ts.which(&self.bin_name) {
             Some((p, tv)) => {
@@ -37,6 +60,10 @@ impl Which {
                     miseprintln!("{}", tv.version);
                 } else if self.plugin {
                     miseprintln!("{p}");
+                } else if self.resolve_symlinks {
+                    let path = p.which(&tv, &self.bin_name)?.unwrap();
+                    self.print_shim(&format!("{}@{}", p.id(), tv.version))?;
+                    print_chain(&resolve_symlinks(&path)?)?;
                 } else {
                     let path = p.which(&tv, &self.bin_name)?;
                     miseprintln!("{}", path.unwrap().display());
@@ -44,6 +71,9 @@ impl Which {
                 Ok(())
             }
             None => {
+                if self.resolve_symlinks {
+                    self.find_broken(&ts)?;
+                }
                 if self.has_shim(&self.bin_name) {
                     bail!("{} is a mise bin however it is not currently active. Use `mise use` to activate it in this directory.", self.bin_name)
                 } else {
@@ -55,6 +85,58 @@ impl Which {
             }
         }
     }
+    fn all(&self, ts: &Toolset) -> Result<()> {
+        let active = ts
+            .which(&self.bin_name)
+            .map(|(p, tv)| (p.id().to_string(), tv.version));
+        let mut out = vec![];
+        for (p, tv) in ts.list_installed_versions()? {
+            let Some(path) = p.which(&tv, &self.bin_name)? else {
+                continue;
+            };
+            let is_active = active
+                .as_ref()
+                .is_some_and(|(id, v)| id == p.id() && v == &tv.version);
+            let source = ts
+                .versions
+                .get(&tv.backend)
+                .filter(|_| is_active)
+                .map(|tvl| tvl.source.clone());
+            let path = match self.resolve_symlinks {
+                true => resolve_symlinks(&path)?.target,
+                false => path,
+            };
+            out.push((p.id().to_string(), tv.version, path, source));
+        }
+        if out.is_empty() {
+            bail!(
+                "{} is not provided by any installed tool. Perhaps you need to install it first.",
+                self.bin_name
+            );
+        }
+        if self.json {
+            let out = out
+                .into_iter()
+                .map(|(tool, version, path, source)| JSONWhich {
+                    tool,
+                    version,
+                    path,
+                    active: source.is_some(),
+                    source: source.map(|s| s.as_json()),
+                })
+                .collect::<Vec<_>>();
+            miseprintln!("{}", serde_json::to_string_pretty(&out)?);
+            return Ok(());
+        }
+        for (tool, version, path, source) in out {
+            let path = display_path(&path);
+            match source {
+                Some(source) => miseprintln!("{tool}@{version}  {path}  (active, set by {source})"),
+                None => miseprintln!("{tool}@{version}  {path}"),
+            }
+        }
+        Ok(())
+    }
     fn get_toolset(&self) -> Result<Toolset> {
         let config = Config::try_get()?;
         let mut tsb = ToolsetBuilder::new();
@@ -67,6 +149,134 @@ impl Which {
     fn has_shim(&self, shim: &str) -> bool {
         SHIMS.join(shim).exists()
     }
+    /// shims are symlinks to mise (or copies of it) which then run the tool's bin
+    fn print_shim(&self, tool: &str) -> Result<()> {
+        let shim = SHIMS.join(&self.bin_name);
+        if fs::symlink_metadata(&shim).is_err() {
+            return Ok(());
+        }
+        let chain = resolve_symlinks(&shim)?;
+        for (link, target) in &chain.hops {
+            miseprintln!("{} -> {}", display_path(link), display_path(target));
+        }
+        if chain.broken {
+            warn!(
+                "the shim is broken, {} does not exist",
+                display_path(&chain.target)
+            );
+        } else {
+            miseprintln!("{} runs {tool}", display_path(&chain.target));
+        }
+        Ok(())
+    }
+    /// bins that are broken symlinks don't count as existing so aren't found by `ts.which`
+    fn find_broken(&self, ts: &Toolset) -> Result<()> {
+        for (p, tv) in ts.list_current_installed_versions() {
+            for bin_path in p.list_bin_paths(&tv)? {
+                let path = bin_path.join(&self.bin_name);
+                if fs::symlink_metadata(&path).is_ok() {
+                    self.print_shim(&format!("{}@{}", p.id(), tv.version))?;
+                    print_chain(&resolve_symlinks(&path)?)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct SymlinkChain {
+    /// each symlink that was followed and where it points, this may be a parent directory
+    hops: Vec<(PathBuf, PathBuf)>,
+    target: PathBuf,
+    /// `target` does not exist
+    broken: bool,
+}
+
+/// follows `path` one symlink at a time, including symlinked parent directories
+fn resolve_symlinks(path: &Path) -> Result<SymlinkChain> {
+    let mut hops = vec![];
+    let mut current = path.to_path_buf();
+    // the same limit as linux' ELOOP
+    for _ in 0..40 {
+        let mut prefix = PathBuf::new();
+        let mut link = None;
+        for c in current.components() {
+            prefix.push(c);
+            match fs::symlink_metadata(&prefix) {
+                Ok(m) if m.file_type().is_symlink() => {
+                    link = Some(prefix);
+                    break;
+                }
+                Ok(_) => {}
+                Err(_) => {
+                    return Ok(SymlinkChain {
+                        hops,
+                        target: current,
+                        broken: true,
+                    })
+                }
+            }
+        }
+        let Some(link) = link else {
+            return Ok(SymlinkChain {
+                hops,
+                target: current,
+                broken: false,
+            });
+        };
+        let target = normalize(&link.parent().unwrap().join(fs::read_link(&link)?));
+        let rest = current.strip_prefix(&link)?;
+        current = match rest.as_os_str().is_empty() {
+            true => target.clone(),
+            false => target.join(rest),
+        };
+        hops.push((link, target));
+    }
+    bail!("too many levels of symlinks: {}", display_path(path))
+}
+
+/// removes `..` from a path that has no symlinks before them
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn print_chain(chain: &SymlinkChain) -> Result<()> {
+    for (link, target) in &chain.hops {
+        miseprintln!("{} -> {}", display_path(link), display_path(target));
+    }
+    if chain.broken {
+        match chain.hops.last() {
+            Some((link, _)) => bail!(
+                "{} is a broken symlink, {} does not exist",
+                display_path(link),
+                display_path(&chain.target)
+            ),
+            None => bail!("{} does not exist", display_path(&chain.target)),
+        }
+    }
+    miseprintln!("{}", chain.target.display());
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct JSONWhich {
+    tool: String,
+    version: String,
+    path: PathBuf,
+    active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<IndexMap<String, String>>,
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -78,14 +288,29 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     node
     $ <bold>mise which node --version</bold>
     20.0.0
+    $ <bold>mise which npx --all</bold>
+    node@18.0.0  ~/.local/share/mise/installs/node/18.0.0/bin/npx
+    node@20.0.0  ~/.local/share/mise/installs/node/20.0.0/bin/npx  (active, set by ~/mise.toml)
+    $ <bold>mise which npx --resolve-symlinks</bold>
+    ~/.local/share/mise/shims/npx -> ~/.local/bin/mise
+    ~/.local/bin/mise runs node@20.0.0
+    ~/.local/share/mise/installs/node/20.0.0/bin/npx -> ~/.local/share/mise/installs/node/20.0.0/lib/node_modules/npm/bin/npx-cli.js
+    /home/username/.local/share/mise/installs/node/20.0.0/lib/node_modules/npm/bin/npx-cli.js
 "#
 );
 
 #[cfg(test)]
 mod tests {
-    use crate::test::reset;
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
     use test_log::test;
 
+    use crate::file;
+    use crate::test::reset;
+
+    use super::{resolve_symlinks, SymlinkChain};
+
     #[test]
     fn test_which() {
         reset();
@@ -122,4 +347,49 @@ mod tests {
         assert_cli!("install", "dummy@1.0.1");
         assert_cli_snapshot!("which", "dummy", "--tool=dummy@1.0.1");
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_symlinks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        file::create_dir_all(root.join("1.0.0/lib")).unwrap();
+        file::write(root.join("1.0.0/lib/tool.js"), "").unwrap();
+        file::create_dir_all(root.join("1.0.0/bin")).unwrap();
+        file::make_symlink(Path::new("../lib/tool.js"), &root.join("1.0.0/bin/tool")).unwrap();
+        file::make_symlink(Path::new("1.0.0"), &root.join("1")).unwrap();
+
+        let chain = resolve_symlinks(&root.join("1/bin/tool")).unwrap();
+        assert_eq!(
+            chain,
+            SymlinkChain {
+                hops: vec![
+                    (root.join("1"), root.join("1.0.0")),
+                    (root.join("1.0.0/bin/tool"), root.join("1.0.0/lib/tool.js")),
+                ],
+                target: root.join("1.0.0/lib/tool.js"),
+                broken: false,
+            }
+        );
+
+        file::remove_file(root.join("1.0.0/lib/tool.js")).unwrap();
+        let chain = resolve_symlinks(&root.join("1/bin/tool")).unwrap();
+        assert!(chain.broken);
+        assert_eq!(chain.target, root.join("1.0.0/lib/tool.js"));
+    }
+
+    #[test]
+    fn test_which_all() {
+        reset();
+        assert_cli!("install", "dummy@1.0.1");
+        assert_cli!("use", "dummy@1.0.0");
+        let output = assert_cli!("which", "dummy", "--all");
+        assert!(output.contains(
+            "dummy@1.0.0  ~/data/installs/dummy/1.0.0/bin/dummy  (active, set by ~/cwd/.test-tool-versions)\n"
+        ));
+        assert!(output.contains("dummy@1.0.1  ~/data/installs/dummy/1.0.1/bin/dummy"));
+        assert_cli!("use", "dummy@ref:master");
+        assert_cli!("uninstall", "dummy@1.0.0");
+        assert_cli!("use", "--rm", "dummy");
+    }
 }
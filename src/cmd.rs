@@ -1,11 +1,13 @@
 use std::ffi::{OsStr, OsString};
 use std::fmt::{Display, Formatter};
+use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
-use std::sync::mpsc::channel;
+use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use color_eyre::Result;
 use duct::{Expression, IntoExecutablePath};
@@ -18,8 +20,9 @@ use signal_hook::iterator::Signals;
 use crate::config::Settings;
 use crate::env;
 use crate::env::PATH_KEY;
-use crate::errors::Error::ScriptFailed;
+use crate::errors::Error::{ScriptFailed, ScriptTimedOut};
 use crate::file::display_path;
+use crate::redactions::Redactor;
 use crate::ui::progress_report::SingleReport;
 
 /// Create a command with any number of of positional arguments
@@ -98,8 +101,13 @@ pub struct CmdLineRunner<'a> {
     pr: Option<&'a dyn SingleReport>,
     stdin: Option<String>,
     prefix: String,
+    redactor: Redactor,
     raw: bool,
     pass_signals: bool,
+    forward_stdout: bool,
+    timeout: Option<Duration>,
+    timeout_signal: Option<(i32, Duration)>,
+    log_file: Option<File>,
 }
 
 static OUTPUT_LOCK: Mutex<()> = Mutex::new(());
@@ -122,8 +130,13 @@ impl<'a> CmdLineRunner<'a> {
             pr: None,
             stdin: None,
             prefix: String::new(),
+            redactor: Redactor::default(),
             raw: false,
             pass_signals: false,
+            forward_stdout: false,
+            timeout: None,
+            timeout_signal: None,
+            log_file: None,
         }
     }
 
@@ -147,6 +160,12 @@ impl<'a> CmdLineRunner<'a> {
         self
     }
 
+    /// masks secrets in the output that is printed
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
     pub fn current_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
         self.cmd.current_dir(dir);
         self
@@ -220,11 +239,65 @@ impl<'a> CmdLineRunner<'a> {
         self
     }
 
+    /// sets the file mode creation mask of the child process, does nothing on windows
+    pub fn umask(mut self, mask: u32) -> Self {
+        #[cfg(unix)]
+        {
+            use nix::sys::stat::{self, Mode};
+            use std::os::unix::process::CommandExt;
+            let mode = Mode::from_bits_truncate(mask as _);
+            // umask is async-signal-safe so it can be called between fork and exec
+            unsafe {
+                self.cmd.pre_exec(move || {
+                    stat::umask(mode);
+                    Ok(())
+                });
+            }
+        }
+        #[cfg(windows)]
+        let _ = mask;
+        self
+    }
+
+    /// kills the command and everything it started if it runs longer than `timeout`. On unix
+    /// this runs it in its own process group so signals mise receives are forwarded to it.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        #[cfg(unix)]
+        if timeout.is_some() {
+            use std::os::unix::process::CommandExt;
+            self.cmd.process_group(0);
+        }
+        self.timeout = timeout;
+        self
+    }
+
+    /// on timeout sends `signal` to the process group first and only kills it if it is still
+    /// running `grace` later. Without this it is killed right away, on windows it always is.
+    pub fn timeout_signal(mut self, signal: i32, grace: Duration) -> Self {
+        self.timeout_signal = Some((signal, grace));
+        self
+    }
+
+    /// also writes each line of output to `file` with a timestamp, output can't be captured
+    /// with `with_raw`
+    pub fn log_file(mut self, file: File) -> Self {
+        self.log_file = Some(file);
+        self
+    }
+
     pub fn with_pass_signals(&mut self) -> &mut Self {
         self.pass_signals = true;
         self
     }
 
+    /// writes the command's stdout to mise's stdout, use this when it is the output of the
+    /// mise command like with tasks. Otherwise it goes to stderr with the rest of the
+    /// diagnostics so it can't end up in the output of something like `mise env`.
+    pub fn with_forward_stdout(&mut self) -> &mut Self {
+        self.forward_stdout = true;
+        self
+    }
+
     pub fn stdin_string(mut self, input: impl Into<String>) -> Self {
         self.cmd.stdin(Stdio::piped());
         self.stdin = Some(input.into());
@@ -278,7 +351,7 @@ impl<'a> CmdLineRunner<'a> {
         #[cfg(not(any(test, target_os = "windows")))]
         let mut sighandle = None;
         #[cfg(not(any(test, target_os = "windows")))]
-        if self.pass_signals {
+        if self.pass_signals || self.timeout.is_some() {
             let mut signals =
                 Signals::new([SIGINT, SIGTERM, SIGTERM, SIGHUP, SIGQUIT, SIGUSR1, SIGUSR2])?;
             sighandle = Some(signals.handle());
@@ -289,7 +362,6 @@ impl<'a> CmdLineRunner<'a> {
                 }
             });
         }
-        #[cfg(not(any(test, target_os = "windows")))]
         let id = cp.id();
         thread::spawn(move || {
             let status = cp.wait().unwrap();
@@ -301,7 +373,35 @@ impl<'a> CmdLineRunner<'a> {
         });
         let mut combined_output = vec![];
         let mut status = None;
-        for line in rx {
+        let mut deadline = self.timeout.map(|t| Instant::now() + t);
+        let mut timed_out = false;
+        loop {
+            let line = match deadline {
+                Some(d) => match rx.recv_timeout(d.saturating_duration_since(Instant::now())) {
+                    Ok(line) => line,
+                    Err(RecvTimeoutError::Timeout) => {
+                        deadline = None;
+                        match self.timeout_signal.filter(|_| !timed_out) {
+                            Some((sig, grace)) => {
+                                debug!("{self} timed out, sending signal {sig}");
+                                signal_process_group(id, sig);
+                                deadline = Some(Instant::now() + grace);
+                            }
+                            None => {
+                                debug!("{self} timed out, killing it");
+                                kill_process_group(id);
+                            }
+                        }
+                        timed_out = true;
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                },
+                None => match rx.recv() {
+                    Ok(line) => line,
+                    Err(_) => break,
+                },
+            };
             match line {
                 ChildProcessOutput::Stdout(line) => {
                     self.on_stdout(&line);
@@ -316,7 +416,8 @@ impl<'a> CmdLineRunner<'a> {
                 }
                 #[cfg(not(any(test, target_os = "windows")))]
                 ChildProcessOutput::Signal(sig) => {
-                    if sig != SIGINT {
+                    // a command in its own process group doesn't get the SIGINT from the terminal
+                    if sig != SIGINT || self.timeout.is_some() {
                         debug!("Received signal {}, {id}", sig);
                         let pid = nix::unistd::Pid::from_raw(id as i32);
                         let sig = nix::sys::signal::Signal::try_from(sig).unwrap();
@@ -325,6 +426,11 @@ impl<'a> CmdLineRunner<'a> {
                 }
             }
         }
+        if let (true, Some(timeout)) = (timed_out, self.timeout) {
+            // processes it started that closed their output wouldn't have kept the loop going
+            kill_process_group(id);
+            return Err(ScriptTimedOut(self.get_program(), timeout))?;
+        }
         let status = status.unwrap();
 
         if !status.success() {
@@ -343,11 +449,15 @@ impl<'a> CmdLineRunner<'a> {
     }
 
     fn on_stdout(&self, line: &str) {
+        let line = &self.redactor.redact(line);
+        self.log(line);
         let _lock = OUTPUT_LOCK.lock().unwrap();
         if let Some(pr) = self.pr {
             if !line.trim().is_empty() {
                 pr.set_message(line.into())
             }
+        } else if !self.forward_stdout {
+            self.print_stderr(line);
         } else if console::colors_enabled() {
             println!("{}{line}\x1b[0m", self.prefix);
         } else {
@@ -356,6 +466,8 @@ impl<'a> CmdLineRunner<'a> {
     }
 
     fn on_stderr(&self, line: &str) {
+        let line = &self.redactor.redact(line);
+        self.log(line);
         let _lock = OUTPUT_LOCK.lock().unwrap();
         match self.pr {
             Some(pr) => {
@@ -363,12 +475,24 @@ impl<'a> CmdLineRunner<'a> {
                     pr.println(line.into())
                 }
             }
-            None => {
-                if console::colors_enabled() {
-                    eprintln!("{}{line}\x1b[0m", self.prefix);
-                } else {
-                    eprintln!("{}{line}", self.prefix);
-                }
+            None => self.print_stderr(line),
+        }
+    }
+
+    fn print_stderr(&self, line: &str) {
+        if console::colors_enabled() {
+            eprintln!("{}{line}\x1b[0m", self.prefix);
+        } else {
+            eprintln!("{}{line}", self.prefix);
+        }
+    }
+
+    fn log(&self, line: &str) {
+        if let Some(mut file) = self.log_file.as_ref() {
+            let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+            let line = console::strip_ansi_codes(line);
+            if let Err(err) = writeln!(file, "{now} {line}") {
+                warn!("failed to write to log file: {err}");
             }
         }
     }
@@ -378,8 +502,9 @@ impl<'a> CmdLineRunner<'a> {
         match self.pr {
             Some(pr) => {
                 error!("{} failed", self.get_program());
-                if !settings.verbose && !output.trim().is_empty() {
-                    pr.println(output);
+                // with raw_log the output was already shown as it was printed
+                if !settings.verbose && !settings.raw_log && !output.trim().is_empty() {
+                    pr.println(self.redactor.redact(&output));
                 }
             }
             None => {
@@ -408,6 +533,45 @@ impl Display for CmdLineRunner<'_> {
     }
 }
 
+/// kills a child that was started in its own process group along with everything it started
+pub fn kill_process_group(pid: u32) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{killpg, Signal};
+        use nix::unistd::Pid;
+        if let Err(err) = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL) {
+            debug!("failed to kill process group {pid}: {err}");
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = cmd!("taskkill", "/F", "/T", "/PID", pid.to_string())
+            .stdout_null()
+            .stderr_null()
+            .run();
+    }
+}
+
+/// sends `signal` to a child that was started in its own process group and everything it
+/// started. Windows has no signals so it is killed instead.
+pub fn signal_process_group(pid: u32, signal: i32) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{killpg, Signal};
+        use nix::unistd::Pid;
+        let result =
+            Signal::try_from(signal).and_then(|sig| killpg(Pid::from_raw(pid as i32), sig));
+        if let Err(err) = result {
+            debug!("failed to send signal {signal} to process group {pid}: {err}");
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = signal;
+        kill_process_group(pid);
+    }
+}
+
 enum ChildProcessOutput {
     Stdout(String),
     Stderr(String),
@@ -418,13 +582,76 @@ enum ChildProcessOutput {
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, Instant};
+
     use pretty_assertions::assert_eq;
 
     use crate::cmd;
+    use crate::cmd::CmdLineRunner;
+    use crate::errors::Error;
+    use crate::errors::Error::ScriptTimedOut;
+    use crate::test::reset;
 
     #[test]
     fn test_cmd() {
         let output = cmd!("echo", "foo", "bar").read().unwrap();
         assert_eq!("foo bar", output);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout() {
+        reset();
+        let start = Instant::now();
+        // the background sleep keeps stdout open so this only returns if it is killed too
+        let err = CmdLineRunner::new("sh")
+            .args(["-c", "sleep 30 & sleep 30"])
+            .timeout(Some(Duration::from_millis(200)))
+            .execute()
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(ScriptTimedOut(program, _)) if program == "sh"
+        ));
+
+        CmdLineRunner::new("true")
+            .timeout(Some(Duration::from_secs(30)))
+            .execute()
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_signal() {
+        reset();
+        let sigterm = nix::sys::signal::SIGTERM as i32;
+        // exits on SIGTERM so the grace period isn't waited out
+        let start = Instant::now();
+        let err = CmdLineRunner::new("sh")
+            .args(["-c", "sleep 30"])
+            .timeout(Some(Duration::from_millis(200)))
+            .timeout_signal(sigterm, Duration::from_secs(30))
+            .execute()
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(ScriptTimedOut(..))
+        ));
+
+        // ignores SIGTERM so it is killed once the grace period is over
+        let start = Instant::now();
+        let err = CmdLineRunner::new("sh")
+            .args(["-c", "trap '' TERM; sleep 30"])
+            .timeout(Some(Duration::from_millis(200)))
+            .timeout_signal(sigterm, Duration::from_millis(200))
+            .execute()
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(ScriptTimedOut(..))
+        ));
+    }
 }
@@ -15,8 +15,8 @@ use versions::Versioning;
 
 use crate::cli::args::{BackendArg, ToolVersionType};
 use crate::config::config_file::toml::deserialize_arr;
-use crate::config::config_file::{trust_check, ConfigFile, TaskConfig};
-use crate::config::env_directive::EnvDirective;
+use crate::config::config_file::{retrust, trust_check, ConfigFile, TaskConfig};
+use crate::config::env_directive::{EnvDirective, EnvDirectiveOptions};
 use crate::config::settings::SettingsPartial;
 use crate::config::AliasMap;
 use crate::file::{create_dir_all, display_path};
@@ -53,6 +53,8 @@ pub struct MiseToml {
     tasks: Tasks,
     #[serde(default)]
     settings: SettingsPartial,
+    #[serde(default, deserialize_with = "deserialize_arr")]
+    redactions: Vec<String>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -87,10 +89,20 @@ impl MiseToml {
     }
 
     pub fn from_str(body: &str, path: &Path) -> eyre::Result<Self> {
+        let (rf, unknown) = Self::from_str_with_unknown(body, path)?;
+        for p in unknown {
+            warn!("unknown field in {}: {p}", display_path(path));
+        }
+        Ok(rf)
+    }
+
+    /// parses the config and returns the paths of the fields mise doesn't know about
+    pub fn from_str_with_unknown(body: &str, path: &Path) -> eyre::Result<(Self, Vec<String>)> {
         trace!("parsing: {}", display_path(path));
         let des = toml::Deserializer::new(body);
+        let mut unknown = vec![];
         let mut rf: MiseToml = serde_ignored::deserialize(des, |p| {
-            warn!("unknown field in {}: {p}", display_path(path));
+            unknown.push(p.to_string());
         })?;
         rf.context = BASE_CONTEXT.clone();
         rf.context
@@ -100,7 +112,65 @@ impl MiseToml {
             task.config_source.clone_from(&rf.path);
         }
         trace!("{}", rf.dump()?);
-        Ok(rf)
+        Ok((rf, unknown))
+    }
+
+    /// checks the syntax of the templates in env and tools without rendering them, so nothing
+    /// is run and the config doesn't have to be trusted
+    pub fn template_errors(&self) -> Vec<String> {
+        let mut templates = vec![];
+        for p in &self.env_file {
+            templates.push(("env_file".to_string(), p.to_string_lossy().to_string()));
+        }
+        for p in &self.env_path {
+            templates.push(("env._.path".to_string(), p.to_string_lossy().to_string()));
+        }
+        for ed in &self.env.0 {
+            match ed {
+                EnvDirective::Val(k, v, _) => templates.push((format!("env.{k}"), v.clone())),
+                EnvDirective::Run { key, run, .. } => {
+                    templates.push((format!("env.{key}"), run.clone()))
+                }
+                EnvDirective::File(p) => {
+                    templates.push(("env._.file".into(), p.to_string_lossy().to_string()))
+                }
+                EnvDirective::Path(p) => {
+                    templates.push(("env._.path".into(), p.to_string_lossy().to_string()))
+                }
+                EnvDirective::Source(p) => {
+                    templates.push(("env._.source".into(), p.to_string_lossy().to_string()))
+                }
+                EnvDirective::PythonVenv { path, .. } => templates.push((
+                    "env._.python.venv".into(),
+                    path.to_string_lossy().to_string(),
+                )),
+                EnvDirective::Rm(_) => {}
+            }
+        }
+        for (fa, tvp) in &self.tools {
+            for tool in &tvp.0 {
+                templates.push((format!("tools.{fa}"), tool.tt.to_string()));
+                for (k, v) in &tool.options.opts {
+                    templates.push((format!("tools.{fa}.{k}"), v.clone()));
+                }
+                for (k, v) in &tool.options.env {
+                    templates.push((format!("tools.{fa}.env.{k}"), v.clone()));
+                }
+            }
+        }
+        templates
+            .into_iter()
+            .filter_map(|(key, input)| {
+                let err = tera::Tera::default().add_raw_template(&key, &input).err()?;
+                let mut msg = format!("invalid template in {key}: {input}");
+                let mut source = std::error::Error::source(&err);
+                while let Some(err) = source {
+                    msg.push_str(&format!("\n{}", err.to_string().trim_end()));
+                    source = err.source();
+                }
+                Some(msg)
+            })
+            .collect()
     }
 
     fn doc(&self) -> eyre::Result<&DocumentMut> {
@@ -293,17 +363,24 @@ impl ConfigFile for MiseToml {
             .unwrap();
 
         // if a short name is used like "node", make sure we remove any long names like "core:node"
-        tools.remove(&fa.full.to_string());
+        if fa.full != fa.short {
+            tools.remove(&fa.full.to_string());
+        }
 
-        if versions.len() == 1 {
-            tools.insert(&fa.short, value(versions[0].clone()));
+        let mut new = if versions.len() == 1 {
+            Value::from(versions[0].clone())
         } else {
             let mut arr = Array::new();
             for v in versions {
                 arr.push(v);
             }
-            tools.insert(&fa.short, Item::Value(Value::Array(arr)));
+            Value::Array(arr)
+        };
+        // keep comments next to the old value
+        if let Some(old) = tools.get(&fa.short).and_then(|i| i.as_value()) {
+            *new.decor_mut() = old.decor().clone();
         }
+        tools.insert(&fa.short, Item::Value(new));
 
         Ok(())
     }
@@ -313,7 +390,8 @@ impl ConfigFile for MiseToml {
         if let Some(parent) = self.path.parent() {
             create_dir_all(parent)?;
         }
-        file::write(&self.path, contents)
+        file::write(&self.path, contents)?;
+        retrust(&self.path)
     }
 
     fn dump(&self) -> eyre::Result<String> {
@@ -330,7 +408,14 @@ impl ConfigFile for MiseToml {
                 }
                 let version = self.parse_template(&tool.tt.to_string())?;
                 let mut options = tool.options.clone();
-                for v in options.values_mut() {
+                for (k, v) in options.opts.iter_mut() {
+                    // these are rendered by the backend which knows the resolved version
+                    if k == "url" || k == "checksum_url" {
+                        continue;
+                    }
+                    *v = self.parse_template(v)?;
+                }
+                for v in options.env.values_mut() {
                     *v = self.parse_template(v)?;
                 }
                 let tvr = ToolRequest::new_opts(fa.clone(), &version, options)?;
@@ -362,6 +447,10 @@ impl ConfigFile for MiseToml {
     fn task_config(&self) -> &TaskConfig {
         &self.task_config
     }
+
+    fn redactions(&self) -> &[String] {
+        &self.redactions
+    }
 }
 
 impl Debug for MiseToml {
@@ -389,6 +478,9 @@ impl Debug for MiseToml {
         if self.task_config.includes.is_some() {
             d.field("task_config", &self.task_config);
         }
+        if !self.redactions.is_empty() {
+            d.field("redactions", &self.redactions);
+        }
         d.finish()
     }
 }
@@ -409,6 +501,7 @@ impl Clone for MiseToml {
             tasks: self.tasks.clone(),
             task_config: self.task_config.clone(),
             settings: self.settings.clone(),
+            redactions: self.redactions.clone(),
         }
     }
 }
@@ -483,6 +576,8 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                 source: Vec<PathBuf>,
                                 #[serde(default)]
                                 python: EnvDirectivePython,
+                                #[serde(default, deserialize_with = "deserialize_arr")]
+                                unset: Vec<String>,
                             }
 
                             impl<'de> de::Deserialize<'de> for EnvDirectivePythonVenv {
@@ -569,12 +664,24 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                     create: venv.create,
                                 });
                             }
+                            for key in directives.unset {
+                                env.push(EnvDirective::Rm(key));
+                            }
                         }
                         _ => {
                             enum Val {
                                 Int(i64),
                                 Str(String),
                                 Bool(bool),
+                                Map {
+                                    value: String,
+                                    opts: EnvDirectiveOptions,
+                                },
+                                Run {
+                                    run: String,
+                                    skip_on_error: bool,
+                                    opts: EnvDirectiveOptions,
+                                },
                             }
 
                             impl<'de> de::Deserialize<'de> for Val {
@@ -621,6 +728,71 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                         {
                                             Ok(Val::Str(v.to_string()))
                                         }
+
+                                        fn visit_map<M>(
+                                            self,
+                                            mut map: M,
+                                        ) -> Result<Self::Value, M::Error>
+                                        where
+                                            M: de::MapAccess<'de>,
+                                        {
+                                            let mut value = None;
+                                            let mut run = None;
+                                            let mut on_error = None;
+                                            let mut opts = EnvDirectiveOptions::default();
+                                            while let Some(key) = map.next_key::<String>()? {
+                                                match key.as_str() {
+                                                    "value" => {
+                                                        value = Some(map.next_value()?);
+                                                    }
+                                                    "run" => {
+                                                        run = Some(map.next_value()?);
+                                                    }
+                                                    "on_error" => {
+                                                        on_error =
+                                                            Some(map.next_value::<String>()?);
+                                                    }
+                                                    "tools" => {
+                                                        opts.tools = map.next_value()?;
+                                                    }
+                                                    _ => {
+                                                        return Err(de::Error::unknown_field(
+                                                            &key,
+                                                            &["value", "run", "on_error", "tools"],
+                                                        ));
+                                                    }
+                                                }
+                                            }
+                                            let skip_on_error = match on_error.as_deref() {
+                                                None | Some("error") => false,
+                                                Some("skip") => true,
+                                                Some(other) => {
+                                                    return Err(de::Error::unknown_variant(
+                                                        other,
+                                                        &["error", "skip"],
+                                                    ));
+                                                }
+                                            };
+                                            match (value, run) {
+                                                (Some(_), Some(_)) => Err(de::Error::custom(
+                                                    "env values cannot have both value and run",
+                                                )),
+                                                (None, Some(run)) => Ok(Val::Run {
+                                                    run,
+                                                    skip_on_error,
+                                                    opts,
+                                                }),
+                                                (_, None) if on_error.is_some() => {
+                                                    Err(de::Error::custom(
+                                                        "on_error can only be used with run",
+                                                    ))
+                                                }
+                                                (Some(value), None) => Ok(Val::Map { value, opts }),
+                                                (None, None) => {
+                                                    Err(de::Error::missing_field("value"))
+                                                }
+                                            }
+                                        }
                                     }
 
                                     deserializer.deserialize_any(ValVisitor)
@@ -630,10 +802,25 @@ impl<'de> de::Deserialize<'de> for EnvList {
                             let value = map.next_value::<Val>()?;
                             match value {
                                 Val::Int(i) => {
-                                    env.push(EnvDirective::Val(key, i.to_string()));
+                                    env.push((key, i).into());
                                 }
                                 Val::Str(s) => {
-                                    env.push(EnvDirective::Val(key, s));
+                                    env.push((key, s).into());
+                                }
+                                Val::Map { value, opts } => {
+                                    env.push(EnvDirective::Val(key, value, opts));
+                                }
+                                Val::Run {
+                                    run,
+                                    skip_on_error,
+                                    opts,
+                                } => {
+                                    env.push(EnvDirective::Run {
+                                        key,
+                                        run,
+                                        skip_on_error,
+                                        opts,
+                                    });
                                 }
                                 Val::Bool(_b) => env.push(EnvDirective::Rm(key)),
                             }
@@ -689,13 +876,15 @@ impl<'de> de::Deserialize<'de> for MiseTomlToolList {
             where
                 M: de::MapAccess<'de>,
             {
-                let mut options: BTreeMap<String, String> =
+                let options: BTreeMap<String, toml::Value> =
                     de::Deserialize::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let mut options = parse_tool_options(options)?;
                 let tt: ToolVersionType = options
+                    .opts
                     .remove("version")
-                    .or_else(|| options.remove("path").map(|p| format!("path:{p}")))
-                    .or_else(|| options.remove("prefix").map(|p| format!("prefix:{p}")))
-                    .or_else(|| options.remove("ref").map(|p| format!("ref:{p}")))
+                    .or_else(|| options.opts.remove("path").map(|p| format!("path:{p}")))
+                    .or_else(|| options.opts.remove("prefix").map(|p| format!("prefix:{p}")))
+                    .or_else(|| options.opts.remove("ref").map(|p| format!("ref:{p}")))
                     .ok_or_else(|| de::Error::custom("missing version"))?
                     .parse()
                     .map_err(de::Error::custom)?;
@@ -737,13 +926,15 @@ impl<'de> de::Deserialize<'de> for MiseTomlTool {
             where
                 M: de::MapAccess<'de>,
             {
-                let mut options: BTreeMap<String, String> =
+                let options: BTreeMap<String, toml::Value> =
                     de::Deserialize::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let mut options = parse_tool_options(options)?;
                 let tt: ToolVersionType = options
+                    .opts
                     .remove("version")
-                    .or_else(|| options.remove("path").map(|p| format!("path:{p}")))
-                    .or_else(|| options.remove("prefix").map(|p| format!("prefix:{p}")))
-                    .or_else(|| options.remove("ref").map(|p| format!("ref:{p}")))
+                    .or_else(|| options.opts.remove("path").map(|p| format!("path:{p}")))
+                    .or_else(|| options.opts.remove("prefix").map(|p| format!("prefix:{p}")))
+                    .or_else(|| options.opts.remove("ref").map(|p| format!("ref:{p}")))
                     .ok_or_else(|| de::Error::custom("missing version"))?
                     .parse()
                     .map_err(de::Error::custom)?;
@@ -755,6 +946,45 @@ impl<'de> de::Deserialize<'de> for MiseTomlTool {
     }
 }
 
+/// tool options are strings except for `env`, a table of env vars that are only set while the
+/// tool is active. Those are kept apart from the other options, see [ToolRequest::env].
+/// `only_in` may also be a list of globs, these are kept newline-separated, see
+/// [ToolRequest::only_in].
+fn parse_tool_options<E: de::Error>(
+    map: BTreeMap<String, toml::Value>,
+) -> Result<ToolVersionOptions, E> {
+    let mut options = ToolVersionOptions::default();
+    for (k, v) in map {
+        match v {
+            toml::Value::String(v) => {
+                options.opts.insert(k, v);
+            }
+            toml::Value::Table(env) if k == "env" => {
+                for (key, v) in env {
+                    let v = match v {
+                        toml::Value::String(v) => v,
+                        v @ (toml::Value::Integer(_) | toml::Value::Boolean(_)) => v.to_string(),
+                        v => return Err(de::Error::custom(format!("invalid env value {key}={v}"))),
+                    };
+                    options.env.insert(key, v);
+                }
+            }
+            toml::Value::Array(patterns) if k == "only_in" => {
+                let patterns = patterns
+                    .into_iter()
+                    .map(|p| match p {
+                        toml::Value::String(p) => Ok(p),
+                        p => Err(de::Error::custom(format!("invalid only_in pattern {p}"))),
+                    })
+                    .collect::<Result<Vec<_>, E>>()?;
+                options.opts.insert(k, patterns.join("\n"));
+            }
+            v => return Err(de::Error::custom(format!("invalid tool option {k}={v}"))),
+        }
+    }
+    Ok(options)
+}
+
 impl<'de> de::Deserialize<'de> for Tasks {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -898,7 +1128,6 @@ mod tests {
     use insta::{assert_debug_snapshot, assert_snapshot};
     use test_log::test;
 
-    use crate::dirs::CWD;
     use crate::test::{replace_path, reset};
 
     use super::*;
@@ -922,7 +1151,7 @@ mod tests {
     #[test]
     fn test_env() {
         reset();
-        let p = CWD.as_ref().unwrap().join(".test.mise.toml");
+        let p = dirs::cwd().unwrap().join(".test.mise.toml");
         file::write(
             &p,
             formatdoc! {r#"
@@ -947,6 +1176,48 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_env_tools() {
+        reset();
+        let cf = parse(formatdoc! {r#"
+        [env]
+        foo = "bar"
+        java_lib = {{value = "{{{{env.JAVA_HOME}}}}/lib", tools = true}}
+        "#});
+        let entries = cf.env_entries().unwrap();
+        assert_snapshot!(entries.iter().join("\n"), @r###"
+        foo=bar
+        java_lib={{env.JAVA_HOME}}/lib
+        "###);
+        assert_eq!(
+            entries.iter().map(|e| e.needs_tools()).collect_vec(),
+            [false, true]
+        );
+
+        let p = dirs::cwd().unwrap().join(".test.mise.toml");
+        file::write(&p, "[env]\nfoo = {tools = true}\n").unwrap();
+        let err = MiseToml::from_file(&p).unwrap_err();
+        file::remove_file(&p).unwrap();
+        assert!(format!("{err:?}").contains("missing field `value`"));
+    }
+
+    #[test]
+    fn test_env_run() {
+        reset();
+        let cf = parse(formatdoc! {r#"
+        [env]
+        token = {{run = "aws-token-helper", on_error = "skip"}}
+        "#});
+        let entries = cf.env_entries().unwrap();
+        assert_snapshot!(entries.iter().join("\n"), @"token=$(aws-token-helper)");
+
+        let p = dirs::cwd().unwrap().join(".test.mise.toml");
+        file::write(&p, "[env]\nfoo = {value = 'x', on_error = 'skip'}\n").unwrap();
+        let err = MiseToml::from_file(&p).unwrap_err();
+        file::remove_file(&p).unwrap();
+        assert!(format!("{err:?}").contains("on_error can only be used with run"));
+    }
+
     #[test]
     fn test_env_array_valid() {
         reset();
@@ -1062,10 +1333,27 @@ mod tests {
         assert_debug_snapshot!(env, @r###""dotenv .env\ndotenv .env2""###);
     }
 
+    #[test]
+    fn test_env_unset() {
+        reset();
+        let env = parse_env(formatdoc! {r#"
+            [env]
+            GOFLAGS = false
+            _.unset = ["CGO_ENABLED", "GOPATH"]
+            "#});
+        assert_debug_snapshot!(env, @r###""unset GOFLAGS\nunset CGO_ENABLED\nunset GOPATH""###);
+
+        let env = parse_env(formatdoc! {r#"
+            [env]
+            _.unset = "GOFLAGS"
+            "#});
+        assert_debug_snapshot!(env, @r###""unset GOFLAGS""###);
+    }
+
     #[test]
     fn test_set_alias() {
         reset();
-        let p = CWD.as_ref().unwrap().join(".test.mise.toml");
+        let p = dirs::cwd().unwrap().join(".test.mise.toml");
         file::write(
             &p,
             formatdoc! {r#"
@@ -1091,7 +1379,7 @@ mod tests {
     #[test]
     fn test_remove_alias() {
         reset();
-        let p = CWD.as_ref().unwrap().join(".test.mise.toml");
+        let p = dirs::cwd().unwrap().join(".test.mise.toml");
         file::write(
             &p,
             formatdoc! {r#"
@@ -1228,7 +1516,7 @@ mod tests {
     }
 
     fn parse(s: String) -> MiseToml {
-        let p = CWD.as_ref().unwrap().join(".test.mise.toml");
+        let p = dirs::cwd().unwrap().join(".test.mise.toml");
         file::write(&p, s).unwrap();
         let cfg = MiseToml::from_file(&p).unwrap();
         file::remove_file(&p).unwrap();
@@ -1236,6 +1524,71 @@ mod tests {
         cfg
     }
 
+    #[test]
+    fn test_tool_env() {
+        reset();
+        let cf = parse(formatdoc! {r#"
+        [tools]
+        go = {{ version = "1.22", arch = "x64", env = {{ GOFLAGS = "-mod=mod", CGO_ENABLED = 0 }} }}
+        node = "20"
+        "#});
+        let trs = cf.to_tool_request_set().unwrap();
+        let env = trs
+            .tools
+            .iter()
+            .map(|(fa, trs)| (fa.short.clone(), trs[0].env()))
+            .collect_vec();
+        assert_debug_snapshot!(env, @r###"
+        [
+            (
+                "go",
+                {
+                    "CGO_ENABLED": "0",
+                    "GOFLAGS": "-mod=mod",
+                },
+            ),
+            (
+                "node",
+                {},
+            ),
+        ]
+        "###);
+        // the env table isn't passed on to the backend with the other options
+        let go = &trs.tools.values().next().unwrap()[0];
+        assert_eq!(
+            go.options().opts,
+            BTreeMap::from([("arch".to_string(), "x64".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_tool_only_in() {
+        reset();
+        let cf = parse(formatdoc! {r#"
+        [tools]
+        go = {{ version = "1.22", only_in = ["services/**", "tools"] }}
+        node = {{ version = "20", only_in = "web" }}
+        python = "3.12"
+        "#});
+        let trs = cf.to_tool_request_set().unwrap();
+        let only_in = trs
+            .tools
+            .iter()
+            .map(|(fa, trs)| (fa.short.clone(), trs[0].only_in()))
+            .collect_vec();
+        assert_eq!(
+            only_in,
+            vec![
+                (
+                    "go".to_string(),
+                    vec!["services/**".to_string(), "tools".to_string()]
+                ),
+                ("node".to_string(), vec!["web".to_string()]),
+                ("python".to_string(), vec![]),
+            ]
+        );
+    }
+
     fn parse_env(toml: String) -> String {
         parse(toml).env_entries().unwrap().into_iter().join("\n")
     }
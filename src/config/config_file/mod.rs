@@ -85,6 +85,10 @@ pub trait ConfigFile: Debug + Send + Sync {
         static DEFAULT_TASK_CONFIG: Lazy<TaskConfig> = Lazy::new(TaskConfig::default);
         &DEFAULT_TASK_CONFIG
     }
+    /// names or patterns of env vars whose values are masked in output
+    fn redactions(&self) -> &[String] {
+        &[]
+    }
 }
 
 impl dyn ConfigFile {
@@ -206,8 +210,23 @@ pub fn trust_check(path: &Path) -> eyre::Result<()> {
         return Ok(());
     }
     if cmd != "hook-env" {
+        let reason = if trust_path(path).exists() {
+            "has changed since it was trusted".to_string()
+        } else {
+            match moved_from(path) {
+                Ok(Some(old)) => format!(
+                    "is not trusted, the same file was trusted at {} which no longer exists",
+                    display_path(old)
+                ),
+                Ok(None) => "is not trusted".to_string(),
+                Err(err) => {
+                    warn!("moved_from: {err}");
+                    "is not trusted".to_string()
+                }
+            }
+        };
         let ans = prompt::confirm_with_all(format!(
-            "{} {} is not trusted. Trust it?",
+            "{} {} {reason}. Trust it?",
             style::eyellow("mise"),
             style::epath(path)
         ))?;
@@ -240,21 +259,22 @@ pub fn is_trusted(path: &Path) -> bool {
             return true;
         }
     }
-    if settings.paranoid {
-        let trusted = trust_file_hash(path).unwrap_or_else(|e| {
-            warn!("trust_file_hash: {e}");
-            false
-        });
-        if !trusted {
-            return false;
-        }
-    } else if cfg!(test) || ci_info::is_ci() {
+    let hash_matches = trust_file_hash(path).unwrap_or_else(|e| {
+        warn!("trust_file_hash: {e}");
+        Some(false)
+    });
+    match hash_matches {
+        // the file was trusted with `mise trust` and hasn't changed since
+        Some(true) => {}
+        // the file changed since it was trusted so the user needs to look at it again
+        Some(false) => return false,
+        None if settings.paranoid => return false,
         // in tests/CI we trust everything
-        return true;
-    } else if !trust_path(path).exists() {
+        None if cfg!(test) || ci_info::is_ci() => return true,
+        None if trust_path(path).exists() => {}
         // the file isn't trusted, and we're not on a CI system where we generally assume we can
         // trust config files
-        return false;
+        None => return false,
     }
     cached.insert(canonicalized_path.to_path_buf());
     true
@@ -266,16 +286,33 @@ pub fn trust(path: &Path) -> eyre::Result<()> {
         file::create_dir_all(hashed_path.parent().unwrap())?;
         file::make_symlink_or_file(path.canonicalize()?.as_path(), &hashed_path)?;
     }
-    let trust_hash_path = hashed_path.with_extension("hash");
     let hash = file_hash_sha256(path)?;
-    file::write(trust_hash_path, hash)?;
+    file::write(trust_hash_path(&hashed_path), hash)?;
     Ok(())
 }
 
 pub fn untrust(path: &Path) -> eyre::Result<()> {
     let hashed_path = trust_path(path);
-    if hashed_path.exists() {
-        file::remove_file(hashed_path)?;
+    for p in [
+        trust_hash_path(&hashed_path),
+        hashed_path.with_extension("hash"),
+        hashed_path,
+    ] {
+        if p.exists() {
+            file::remove_file(p)?;
+        }
+    }
+    if let Ok(path) = path.canonicalize() {
+        IS_TRUSTED.lock().unwrap().remove(&path);
+    }
+    Ok(())
+}
+
+/// called after mise writes to a config file itself so that the change doesn't require trusting
+/// it again. Does nothing if the file wasn't trusted with `mise trust`.
+pub fn retrust(path: &Path) -> eyre::Result<()> {
+    if path.exists() && trust_file_hash(path)?.is_some() {
+        trust(path)?;
     }
     Ok(())
 }
@@ -310,15 +347,56 @@ fn trust_path(path: &Path) -> PathBuf {
     )
 }
 
-fn trust_file_hash(path: &Path) -> eyre::Result<bool> {
+/// the sha256 of the file's contents at the time it was trusted is stored next to the trust link
+fn trust_hash_path(trust_path: &Path) -> PathBuf {
+    let mut path = trust_path.as_os_str().to_os_string();
+    path.push(".hash");
+    PathBuf::from(path)
+}
+
+/// whether the file still has the contents it was trusted with, `None` if no hash was recorded
+fn trust_file_hash(path: &Path) -> eyre::Result<Option<bool>> {
     let trust_path = trust_path(path);
-    let trust_hash_path = trust_path.with_extension("hash");
-    if !trust_hash_path.exists() {
-        return Ok(false);
-    }
+    // older versions of mise replaced the extension of the trust link
+    let Some(trust_hash_path) = [
+        trust_hash_path(&trust_path),
+        trust_path.with_extension("hash"),
+    ]
+    .into_iter()
+    .find(|p| p.exists()) else {
+        return Ok(None);
+    };
     let hash = file::read_to_string(&trust_hash_path)?;
     let actual = file_hash_sha256(path)?;
-    Ok(hash == actual)
+    Ok(Some(hash == actual))
+}
+
+/// the path a config with the same contents as `path` was trusted at if that path no longer
+/// exists, i.e.: the config (or the directory it is in) may have been moved or renamed. This is
+/// only shown when asking to trust `path`, a config anywhere could have the same contents.
+fn moved_from(path: &Path) -> eyre::Result<Option<PathBuf>> {
+    if !dirs::TRUSTED_CONFIGS.is_dir() {
+        return Ok(None);
+    }
+    let actual = file_hash_sha256(path)?;
+    for entry in std::fs::read_dir(&*dirs::TRUSTED_CONFIGS)? {
+        let hash_path = entry?.path();
+        let Some(link) = hash_path
+            .to_str()
+            .and_then(|p| p.strip_suffix(".hash"))
+            .map(PathBuf::from)
+        else {
+            continue;
+        };
+        let Ok(old) = file::resolve_symlink(&link) else {
+            continue;
+        };
+        if old.exists() || file::read_to_string(&hash_path)?.trim() != actual {
+            continue;
+        }
+        return Ok(Some(old));
+    }
+    Ok(None)
 }
 
 fn detect_config_file_type(path: &Path) -> Option<ConfigFileType> {
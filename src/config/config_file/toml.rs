@@ -85,6 +85,22 @@ impl<'a> TomlParser<'a> {
             .transpose()
     }
 
+    pub fn parse_table(&self, key: &str) -> eyre::Result<Option<BTreeMap<String, String>>> {
+        self.table
+            .get(key)
+            .and_then(|value| value.as_table())
+            .map(|table| {
+                table
+                    .iter()
+                    .map(|(key, value)| match value.as_str() {
+                        Some(v) => Ok((key.clone(), self.render_tmpl(v)?)),
+                        None => Err(eyre::eyre!("invalid {key} value: {:?}", value)),
+                    })
+                    .collect::<eyre::Result<_>>()
+            })
+            .transpose()
+    }
+
     fn render_tmpl<T>(&self, tmpl: &str) -> eyre::Result<T>
     where
         T: From<String>,
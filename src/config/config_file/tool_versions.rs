@@ -62,15 +62,16 @@ impl ToolVersions {
             s.to_string()
         };
         for line in s.lines() {
-            if !line.trim_start().starts_with('#') {
+            let line = line.trim_start();
+            if !line.is_empty() && !line.starts_with('#') {
                 break;
             }
             cf.pre.push_str(line);
             cf.pre.push('\n');
         }
 
-        cf.plugins = Self::parse_plugins(&s);
-        cf.populate_toolset()?;
+        cf.plugins = Self::parse_plugins(&s, &path);
+        cf.populate_toolset();
         trace!("{cf}");
         Ok(cf)
     }
@@ -85,16 +86,20 @@ impl ToolVersions {
             })
     }
 
-    fn parse_plugins(input: &str) -> IndexMap<BackendArg, ToolVersionPlugin> {
+    fn parse_plugins(input: &str, path: &Path) -> IndexMap<BackendArg, ToolVersionPlugin> {
         let mut plugins: IndexMap<BackendArg, ToolVersionPlugin> = IndexMap::new();
-        for line in input.lines() {
-            if line.trim_start().starts_with('#') {
+        for (i, line) in input.lines().enumerate() {
+            let trimmed = line.trim();
+            // comments and blank lines stay attached to the tool above them so they survive a
+            // rewrite, anything before the first tool is kept in `pre`
+            if trimmed.is_empty() || trimmed.starts_with('#') {
                 if let Some(prev) = &mut plugins.values_mut().last() {
                     prev.post.push_str(line);
                     prev.post.push('\n');
                 }
                 continue;
             }
+            let raw = line;
             let (line, post) = line.split_once('#').unwrap_or((line, ""));
             let mut parts = line.split_whitespace();
             if let Some(plugin) = parts.next() {
@@ -102,14 +107,27 @@ impl ToolVersions {
                 // note that this method will cause the colons to be removed
                 // permanently if saving the file again, but I think that's fine
                 let orig_plugin = plugin.trim_end_matches(':');
+                if !is_valid_tool_name(orig_plugin) {
+                    warn!(
+                        "{}:{}: ignoring invalid tool name: {orig_plugin}",
+                        display_path(path),
+                        i + 1
+                    );
+                    // kept as-is so a rewrite doesn't lose it
+                    if let Some(prev) = &mut plugins.values_mut().last() {
+                        prev.post.push_str(raw);
+                        prev.post.push('\n');
+                    }
+                    continue;
+                }
                 let fa = orig_plugin.into();
 
                 let tvp = ToolVersionPlugin {
                     orig_name: orig_plugin.to_string(),
                     versions: parts.map(|v| v.to_string()).collect(),
-                    post: match post {
+                    post: match post.trim_end() {
                         "" => String::from("\n"),
-                        _ => [" #", post, "\n"].join(""),
+                        post => [" #", post, "\n"].join(""),
                     },
                 };
                 plugins.insert(fa, tvp);
@@ -124,18 +142,28 @@ impl ToolVersions {
             .push(version.to_string());
     }
 
-    fn populate_toolset(&mut self) -> eyre::Result<()> {
+    /// a bad entry only skips that version so the rest of the file still applies
+    fn populate_toolset(&mut self) {
         let source = ToolSource::ToolVersions(self.path.clone());
         for (plugin, tvp) in &self.plugins {
             for version in &tvp.versions {
-                let tvr = ToolRequest::new(plugin.clone(), version)?;
-                self.tools.add_version(tvr, &source)
+                match ToolRequest::new(plugin.clone(), version) {
+                    Ok(tvr) => self.tools.add_version(tvr, &source),
+                    Err(err) => warn!("{}: {plugin}: {err}", display_path(&self.path)),
+                }
             }
         }
-        Ok(())
     }
 }
 
+/// asdf plugin names plus the `backend:name` forms mise accepts, e.g. `npm:@scope/pkg`
+fn is_valid_tool_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:/@+".contains(c))
+}
+
 impl Display for ToolVersions {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let plugins = &self
@@ -172,7 +200,8 @@ impl ConfigFile for ToolVersions {
 
     fn save(&self) -> Result<()> {
         let s = self.dump()?;
-        file::write(&self.path, s)
+        file::write(&self.path, s)?;
+        config_file::retrust(&self.path)
     }
 
     fn dump(&self) -> eyre::Result<String> {
@@ -247,6 +276,24 @@ pub(crate) mod tests {
         assert_eq!(tv.dump().unwrap(), orig);
     }
 
+    #[test]
+    fn test_parse_blank_lines_and_crlf() {
+        reset();
+        let orig = "# intro\r\n\r\nnodejs 20.0.0 18 # lts\r\n\r\n# python\r\nbad!name 1\r\npython 3.11\r\n";
+        let path = env::current_dir().unwrap().join(".test-tool-versions");
+        let tv = ToolVersions::parse_str(orig, path).unwrap();
+        assert_snapshot!(tv.to_toolset().unwrap(), @"node@20.0.0 node@18, python@3.11");
+        assert_snapshot!(tv.dump().unwrap(), @r###"
+        # intro
+
+        nodejs 20.0.0 18 # lts
+
+        # python
+        bad!name 1
+        python 3.11
+        "###);
+    }
+
     #[test]
     fn test_parse_colon() {
         reset();
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use eyre::{bail, Result};
+
+/// parses a dotenv file. Single quoted values are taken literally, double quoted values support
+/// `\n`-style escapes and both may span multiple lines. `$VAR`, `${VAR}` and `${VAR:-default}` in
+/// double quoted and unquoted values expand to keys defined earlier in the file, falling back
+/// to `env` which holds the variables mise has already set.
+pub fn parse(input: &str, env: &HashMap<String, String>) -> Result<Vec<(String, String)>> {
+    let mut parser = Parser {
+        chars: input.chars().peekable(),
+        line: 1,
+        env,
+        vars: vec![],
+    };
+    parser.parse()?;
+    Ok(parser.vars)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    env: &'a HashMap<String, String>,
+    vars: Vec<(String, String)>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self) -> Result<()> {
+        loop {
+            self.skip_blank();
+            match self.chars.peek() {
+                None => return Ok(()),
+                Some('#') => self.skip_line(),
+                Some(_) => {
+                    let mut key = self.key()?;
+                    if key == "export" && self.skip_spaces() && self.chars.peek() != Some(&'=') {
+                        key = self.key()?;
+                    }
+                    self.skip_spaces();
+                    if self.next() != Some('=') {
+                        bail!("line {}: expected '=' after {key}", self.line);
+                    }
+                    self.skip_spaces();
+                    let value = self.value()?;
+                    self.vars.push((key, value));
+                }
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_blank(&mut self) {
+        while let Some(c) = self.chars.next_if(|c| c.is_whitespace()) {
+            if c == '\n' {
+                self.line += 1;
+            }
+        }
+    }
+
+    /// returns true if any spaces were skipped
+    fn skip_spaces(&mut self) -> bool {
+        let mut skipped = false;
+        while self.chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {
+            skipped = true;
+        }
+        skipped
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.next() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn key(&mut self) -> Result<String> {
+        let mut key = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '.')
+        {
+            key.push(c);
+        }
+        if !key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            bail!("line {}: invalid key", self.line);
+        }
+        Ok(key)
+    }
+
+    fn value(&mut self) -> Result<String> {
+        let start = self.line;
+        let value = match self.chars.peek() {
+            Some('\'') => {
+                self.next();
+                let mut value = String::new();
+                loop {
+                    match self.next() {
+                        Some('\'') => break,
+                        Some(c) => value.push(c),
+                        None => bail!("line {start}: unterminated single quote"),
+                    }
+                }
+                value
+            }
+            Some('"') => {
+                self.next();
+                let mut value = String::new();
+                loop {
+                    match self.next() {
+                        Some('"') => break,
+                        Some('\\') => match self.next() {
+                            Some('n') => value.push('\n'),
+                            Some('r') => value.push('\r'),
+                            Some('t') => value.push('\t'),
+                            Some(c @ ('"' | '\\' | '$' | '\'')) => value.push(c),
+                            Some(c) => {
+                                value.push('\\');
+                                value.push(c);
+                            }
+                            None => bail!("line {start}: unterminated double quote"),
+                        },
+                        Some('$') => self.substitute(&mut value)?,
+                        Some(c) => value.push(c),
+                        None => bail!("line {start}: unterminated double quote"),
+                    }
+                }
+                value
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(c) = self.chars.next_if(|c| *c != '\n') {
+                    match c {
+                        '#' if value.is_empty() || value.ends_with([' ', '\t']) => {
+                            self.skip_line();
+                            return Ok(value.trim_end().to_string());
+                        }
+                        '$' => self.substitute(&mut value)?,
+                        c => value.push(c),
+                    }
+                }
+                return Ok(value.trim_end().to_string());
+            }
+        };
+        // anything after the closing quote must be a comment
+        self.skip_spaces();
+        match self.chars.peek() {
+            None | Some('\n') | Some('\r') | Some('#') => self.skip_line(),
+            Some(_) => bail!(
+                "line {}: unexpected characters after quoted value",
+                self.line
+            ),
+        }
+        Ok(value)
+    }
+
+    /// expands the variable after a `$` into `out`
+    fn substitute(&mut self, out: &mut String) -> Result<()> {
+        let braced = self.chars.next_if_eq(&'{').is_some();
+        let mut name = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            name.push(c);
+        }
+        let mut default = None;
+        if braced {
+            if self.chars.next_if_eq(&':').is_some() {
+                if self.chars.next_if_eq(&'-').is_none() {
+                    bail!("line {}: expected ':-' in ${{{name}}}", self.line);
+                }
+                let mut d = String::new();
+                while let Some(c) = self.chars.next_if(|c| *c != '}' && *c != '\n') {
+                    d.push(c);
+                }
+                default = Some(d);
+            }
+            if self.chars.next_if_eq(&'}').is_none() {
+                bail!("line {}: unterminated ${{{name}", self.line);
+            }
+        } else if name.is_empty() {
+            out.push('$');
+            return Ok(());
+        }
+        let value = self
+            .vars
+            .iter()
+            .rev()
+            .find(|(k, _)| k == &name)
+            .map(|(_, v)| v)
+            .or_else(|| self.env.get(&name))
+            .filter(|v| default.is_none() || !v.is_empty());
+        match (value, default) {
+            (Some(v), _) => out.push_str(v),
+            (None, Some(d)) => out.push_str(&d),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn vars(input: &str) -> Vec<(String, String)> {
+        let env = HashMap::from([("HOME".to_string(), "/home/me".to_string())]);
+        parse(input, &env).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = indoc! {r#"
+            # comment
+            A=1
+            export B = two words # trailing comment
+            C='single $A
+            line2'
+            D="double $A\n${B}"
+            E=${HOME}/bin
+            F="${MISSING:-fallback}" # comment
+            G=a#b
+            H=
+        "#};
+        let expected = [
+            ("A", "1"),
+            ("B", "two words"),
+            ("C", "single $A\nline2"),
+            ("D", "double 1\ntwo words"),
+            ("E", "/home/me/bin"),
+            ("F", "fallback"),
+            ("G", "a#b"),
+            ("H", ""),
+        ];
+        let actual = vars(input);
+        assert_eq!(
+            actual
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let env = HashMap::new();
+        assert!(parse("A='unterminated", &env).is_err());
+        assert!(parse("A=\"unterminated", &env).is_err());
+        assert!(parse("1A=foo", &env).is_err());
+        assert!(parse("A='x' y", &env).is_err());
+        assert!(parse("A", &env).is_err());
+    }
+}
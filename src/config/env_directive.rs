@@ -1,24 +1,33 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use eyre::{eyre, Context};
+use eyre::{bail, eyre, Context};
 use indexmap::IndexMap;
+use once_cell::sync::Lazy;
 
-use crate::cmd::CmdLineRunner;
+use crate::cmd::{cmd, CmdLineRunner};
 use crate::config::config_file::trust_check;
+use crate::config::dotenv;
 use crate::config::{Config, Settings};
 use crate::env::PATH_KEY;
 use crate::env_diff::{EnvDiff, EnvDiffOperation};
 use crate::file::display_path;
 use crate::tera::{get_tera, BASE_CONTEXT};
 use crate::toolset::ToolsetBuilder;
-use crate::{dirs, env};
+use crate::{dirs, env, file, hash};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvDirectiveOptions {
+    /// render after the tools are resolved so templates can use the env vars they set
+    pub tools: bool,
+}
 
 #[derive(Debug, Clone)]
 pub enum EnvDirective {
     /// simple key/value pair
-    Val(String, String),
+    Val(String, String, EnvDirectiveOptions),
     /// remove a key
     Rm(String),
     /// dotenv file
@@ -27,6 +36,14 @@ pub enum EnvDirective {
     Path(PathBuf),
     /// run a bash script and apply the resulting env diff
     Source(PathBuf),
+    /// run a command and use its trimmed stdout as the value
+    Run {
+        key: String,
+        run: String,
+        /// leave the var unset instead of failing if the command fails
+        skip_on_error: bool,
+        opts: EnvDirectiveOptions,
+    },
     PythonVenv {
         path: PathBuf,
         create: bool,
@@ -35,7 +52,7 @@ pub enum EnvDirective {
 
 impl From<(String, String)> for EnvDirective {
     fn from((k, v): (String, String)) -> Self {
-        Self::Val(k, v)
+        Self::Val(k, v, Default::default())
     }
 }
 
@@ -45,14 +62,24 @@ impl From<(String, i64)> for EnvDirective {
     }
 }
 
+impl EnvDirective {
+    pub fn needs_tools(&self) -> bool {
+        matches!(
+            self,
+            EnvDirective::Val(_, _, opts) | EnvDirective::Run { opts, .. } if opts.tools
+        )
+    }
+}
+
 impl Display for EnvDirective {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            EnvDirective::Val(k, v) => write!(f, "{k}={v}"),
+            EnvDirective::Val(k, v, _) => write!(f, "{k}={v}"),
             EnvDirective::Rm(k) => write!(f, "unset {k}"),
             EnvDirective::File(path) => write!(f, "dotenv {}", display_path(path)),
             EnvDirective::Path(path) => write!(f, "path_add {}", display_path(path)),
             EnvDirective::Source(path) => write!(f, "source {}", display_path(path)),
+            EnvDirective::Run { key, run, .. } => write!(f, "{key}=$({run})"),
             EnvDirective::PythonVenv { path, create } => {
                 write!(f, "python venv path={}", display_path(path))?;
                 if *create {
@@ -74,9 +101,12 @@ pub struct EnvResults {
 }
 
 impl EnvResults {
+    /// `tools` is false until the toolset has been resolved, directives that need the env vars
+    /// from tools are skipped until then
     pub fn resolve(
         initial: &HashMap<String, String>,
         input: Vec<(EnvDirective, PathBuf)>,
+        tools: bool,
     ) -> eyre::Result<Self> {
         let settings = Settings::get();
         let mut ctx = BASE_CONTEXT.clone();
@@ -91,13 +121,22 @@ impl EnvResults {
             env_paths: Vec::new(),
             env_scripts: Vec::new(),
         };
+        let explicit = input
+            .iter()
+            .filter_map(|(d, source)| match d {
+                EnvDirective::Val(k, _, _) | EnvDirective::Run { key: k, .. } => {
+                    Some((source.clone(), k.clone()))
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
         for (directive, source) in input {
             let config_root = source
                 .parent()
                 .map(Path::to_path_buf)
-                .or_else(|| dirs::CWD.clone())
+                .or_else(dirs::cwd)
                 .unwrap_or_default();
-            ctx.insert("cwd", &*dirs::CWD);
+            ctx.insert("cwd", &dirs::cwd());
             ctx.insert("config_root", &config_root);
             let env_vars = env
                 .iter()
@@ -113,11 +152,44 @@ impl EnvResults {
                 }
             };
             match directive {
-                EnvDirective::Val(k, v) => {
-                    let v = r.parse_template(&ctx, &source, &v)?;
+                EnvDirective::Val(_, _, opts) if opts.tools && !tools => {}
+                EnvDirective::Val(k, v, _) => {
+                    let v = r.parse_template(&ctx, &source, &v).wrap_err_with(|| {
+                        eyre!(
+                            "failed to render env.{k} = '{v}' in {}",
+                            display_path(&source)
+                        )
+                    })?;
                     r.env_remove.remove(&k);
                     env.insert(k, (v, Some(source.clone())));
                 }
+                EnvDirective::Run { opts, .. } if opts.tools && !tools => {}
+                EnvDirective::Run {
+                    key,
+                    run,
+                    skip_on_error,
+                    ..
+                } => {
+                    trust_check(&source)?;
+                    let run = r.parse_template(&ctx, &source, &run).wrap_err_with(|| {
+                        eyre!(
+                            "failed to render env.{key} run = '{run}' in {}",
+                            display_path(&source)
+                        )
+                    })?;
+                    match run_env_command(&source, &key, &run, &config_root, &env_vars) {
+                        Ok(v) => {
+                            r.env_remove.remove(&key);
+                            env.insert(key, (v, Some(source.clone())));
+                        }
+                        Err(err) if skip_on_error => {
+                            debug!("skipping env.{key}, `{run}` failed: {err:#}");
+                        }
+                        Err(err) => {
+                            return Err(err.wrap_err(format!("failed to run `{run}` for env.{key}")))
+                        }
+                    }
+                }
                 EnvDirective::Rm(k) => {
                     env.shift_remove(&k);
                     r.env_remove.insert(k);
@@ -131,11 +203,25 @@ impl EnvResults {
                 EnvDirective::File(input) => {
                     trust_check(&source)?;
                     let s = r.parse_template(&ctx, &source, input.to_string_lossy().as_ref())?;
-                    for p in xx::file::glob(normalize_path(s.into()))? {
+                    let pattern = normalize_path(s.into());
+                    let files = xx::file::glob(&pattern)?;
+                    let is_glob = pattern.to_string_lossy().contains(['*', '?', '[']);
+                    if files.is_empty() && !is_glob {
+                        let msg = format!("dotenv file not found: {}", display_path(&pattern));
+                        match settings.env_file_missing.as_str() {
+                            "error" => bail!(msg),
+                            _ => warn!("{msg}"),
+                        }
+                    }
+                    for p in files {
                         r.env_files.push(p.clone());
                         let errfn = || eyre!("failed to parse dotenv file: {}", display_path(&p));
-                        for item in dotenvy::from_path_iter(&p).wrap_err_with(errfn)? {
-                            let (k, v) = item.wrap_err_with(errfn)?;
+                        let body = file::read_to_string(&p).wrap_err_with(errfn)?;
+                        for (k, v) in dotenv::parse(&body, &env_vars).wrap_err_with(errfn)? {
+                            // keys set directly in the same config file take precedence
+                            if explicit.contains(&(source.clone(), k.clone())) {
+                                continue;
+                            }
                             r.env_remove.remove(&k);
                             env.insert(k, (v, Some(p.clone())));
                         }
@@ -237,6 +323,78 @@ impl EnvResults {
     }
 }
 
+/// runs the command for an `[env]` value once per mise process, and once per shell session when
+/// mise is activated so each prompt and subshell doesn't run it again
+fn run_env_command(
+    source: &Path,
+    key: &str,
+    run: &str,
+    dir: &Path,
+    env_vars: &HashMap<String, String>,
+) -> eyre::Result<String> {
+    static RESULTS: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(Default::default);
+    let hash = hash::hash_to_str(&(source, key, run));
+    if let Some(v) = RESULTS.lock().unwrap().get(&hash) {
+        return Ok(v.clone());
+    }
+    let cache_dir = dirs::CACHE.join("env_run");
+    let cache_file = env::__MISE_SESSION
+        .as_ref()
+        .map(|session| cache_dir.join(format!("{session}-{hash}")));
+    if let Some(v) = cache_file
+        .as_ref()
+        .and_then(|f| file::read_to_string(f).ok())
+    {
+        trace!("using cached env.{key} from session");
+        RESULTS.lock().unwrap().insert(hash, v.clone());
+        return Ok(v);
+    }
+    let v = cmd("bash", ["-c", run])
+        .full_env(env_vars)
+        .dir(dir)
+        .read()?
+        .trim()
+        .to_string();
+    if let Some(cache_file) = cache_file {
+        // the value may be a secret, e.g.: a token, so only the user can read it
+        if let Err(err) =
+            file::create_dir_all(&cache_dir).and_then(|_| file::write_private(&cache_file, &v))
+        {
+            debug!("failed to cache env.{key}: {err:#}");
+        }
+        prune_env_run_cache(&cache_dir);
+    }
+    RESULTS.lock().unwrap().insert(hash, v.clone());
+    Ok(v)
+}
+
+/// removes the values cached for shell sessions that have exited
+fn prune_env_run_cache(dir: &Path) {
+    for path in file::ls(dir).unwrap_or_default() {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let alive = env::session_pid(&name).is_some_and(is_running);
+        if !alive {
+            if let Err(err) = file::remove_file(&path) {
+                debug!("failed to prune {}: {err:#}", display_path(&path));
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+    // a process of another user can't be signaled but is still running
+    !matches!(kill(Pid::from_raw(pid as i32), None), Err(Errno::ESRCH))
+}
+
+#[cfg(windows)]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
@@ -272,6 +430,7 @@ mod tests {
                     Default::default(),
                 ),
             ],
+            false,
         )
         .unwrap();
         assert_debug_snapshot!(
@@ -288,6 +447,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_error() {
+        reset();
+        let err = EnvResults::resolve(
+            &HashMap::new(),
+            vec![(
+                ("A".to_string(), "{{ env.MISSING }}".to_string()).into(),
+                PathBuf::from("/config/mise.toml"),
+            )],
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to render env.A = '{{ env.MISSING }}' in /config/mise.toml"
+        );
+    }
+
     #[test]
     fn test_venv_path() {
         reset();
@@ -310,6 +487,7 @@ mod tests {
                     Default::default(),
                 ),
             ],
+            false,
         )
         .unwrap();
         // expect order to be reversed as it processes directives from global to dir specific
@@ -323,4 +501,104 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn test_env_file() {
+        reset();
+        let path = dirs::cwd().unwrap().join(".test-dotenv");
+        file::write(&path, "A=from_file\nB=\"$A-${C}\"\n").unwrap();
+        let config = dirs::cwd().unwrap().join(".test.mise.toml");
+        let results = EnvResults::resolve(
+            &HashMap::new(),
+            vec![
+                (("C".to_string(), "c".to_string()).into(), config.clone()),
+                (EnvDirective::File(".test-dotenv".into()), config.clone()),
+                (("A".to_string(), "explicit".to_string()).into(), config),
+            ],
+            false,
+        )
+        .unwrap();
+        file::remove_file(&path).unwrap();
+        assert_debug_snapshot!(
+            results.env.into_iter().map(|(k, (v, _))| (k, v)).collect::<Vec<_>>(),
+            @r###"
+        [
+            (
+                "C",
+                "c",
+            ),
+            (
+                "B",
+                "from_file-c",
+            ),
+            (
+                "A",
+                "explicit",
+            ),
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn test_env_run() {
+        reset();
+        let config = dirs::cwd().unwrap().join(".test.mise.toml");
+        let run = |key: &str, run: &str, skip_on_error| EnvDirective::Run {
+            key: key.into(),
+            run: run.into(),
+            skip_on_error,
+            opts: Default::default(),
+        };
+        let results = EnvResults::resolve(
+            &HashMap::new(),
+            vec![
+                (("A".to_string(), "a".to_string()).into(), config.clone()),
+                (run("B", "echo \"  $A-b  \"", false), config.clone()),
+                (run("C", "echo c; exit 1", true), config.clone()),
+            ],
+            false,
+        )
+        .unwrap();
+        assert_debug_snapshot!(
+            results.env.into_iter().map(|(k, (v, _))| (k, v)).collect::<Vec<_>>(),
+            @r###"
+        [
+            (
+                "A",
+                "a",
+            ),
+            (
+                "B",
+                "a-b",
+            ),
+        ]
+        "###
+        );
+
+        let err = EnvResults::resolve(
+            &HashMap::new(),
+            vec![(run("D", "exit 1", false), config)],
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(format!("{err}"), "failed to run `exit 1` for env.D");
+    }
+
+    #[test]
+    fn test_prune_env_run_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        exited.wait().unwrap();
+        let running = format!("{}-1a-hash", std::process::id());
+        let exited = format!("{}-2b-hash", exited.id());
+        for name in [&running, &exited, &"3c-hash".to_string()] {
+            file::write(dir.path().join(name), "secret").unwrap();
+        }
+        prune_env_run_cache(dir.path());
+        assert_eq!(
+            file::ls(dir.path()).unwrap(),
+            vec![dir.path().join(running)]
+        );
+    }
 }
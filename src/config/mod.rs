@@ -2,9 +2,9 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::{Debug, Formatter};
 use std::iter::once;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, OnceLock, RwLock};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
-use eyre::{ensure, eyre, Context, Result};
+use eyre::{bail, eyre, Context, Result};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use once_cell::sync::{Lazy, OnceCell};
@@ -18,9 +18,11 @@ use crate::cli::version;
 use crate::config::config_file::legacy_version::LegacyVersionFile;
 use crate::config::config_file::mise_toml::MiseToml;
 use crate::config::config_file::ConfigFile;
-use crate::config::env_directive::EnvResults;
+use crate::config::env_directive::{EnvDirective, EnvResults};
 use crate::config::tracking::Tracker;
+use crate::errors::Error;
 use crate::file::display_path;
+use crate::redactions::Redactor;
 use crate::shorthands::{get_shorthands, Shorthands};
 use crate::task::Task;
 use crate::toolset::{ToolRequestSet, ToolRequestSetBuilder};
@@ -28,6 +30,7 @@ use crate::ui::style;
 use crate::{backend, dirs, env, file};
 
 pub mod config_file;
+mod dotenv;
 mod env_directive;
 pub mod settings;
 pub mod tracking;
@@ -43,6 +46,7 @@ pub struct Config {
     pub project_root: Option<PathBuf>,
     env: OnceCell<EnvResults>,
     env_with_sources: OnceCell<EnvWithSources>,
+    env_with_tool_env: Mutex<HashMap<BTreeMap<String, String>, IndexMap<String, String>>>,
     all_aliases: OnceLock<AliasMap>,
     repo_urls: HashMap<String, String>,
     shorthands: OnceLock<HashMap<String, String>>,
@@ -79,6 +83,8 @@ impl Config {
             .into_iter()
             .unique_by(|p| p.canonicalize().unwrap_or_else(|_| p.clone()))
             .collect_vec();
+        warn_if_profile_missing(&config_paths);
+        warn_about_policy()?;
         let config_files = load_all_config_files(&config_paths, &legacy_files)?;
 
         let config = Self {
@@ -111,28 +117,45 @@ impl Config {
     }
     pub fn env_with_sources(&self) -> eyre::Result<&EnvWithSources> {
         self.env_with_sources.get_or_try_init(|| {
-            let mut env = self.env_results()?.env.clone();
-            let settings = Settings::get();
-            for env_file in settings.env_files() {
-                match dotenvy::from_path_iter(&env_file) {
-                    Ok(iter) => {
-                        for item in iter {
-                            let (k, v) = item.unwrap_or_else(|err| {
-                                warn!("env_file: {err}");
-                                Default::default()
-                            });
-                            env.insert(k, (v, env_file.clone()));
-                        }
-                    }
-                    Err(err) => trace!("env_file: {err}"),
-                }
-            }
-            Ok(env)
+            let env = self.env_results()?.env.clone();
+            Ok(with_settings_env_files(env))
         })
     }
+    /// like [`Config::env`] but also renders the `[env]` values marked `tools = true`. These can
+    /// use the env vars set by tools in their templates, e.g. `{{env.JAVA_HOME}}`. Resolving
+    /// runs `_.source` scripts and `run` commands again so the result is cached for each
+    /// `tool_env`.
+    pub fn env_with_tool_env(
+        &self,
+        tool_env: &BTreeMap<String, String>,
+    ) -> eyre::Result<IndexMap<String, String>> {
+        let entries = self.env_entries()?;
+        if !entries.iter().any(|(d, _)| d.needs_tools()) {
+            return self.env();
+        }
+        if let Some(env) = self.env_with_tool_env.lock().unwrap().get(tool_env) {
+            return Ok(env.clone());
+        }
+        let mut initial = env::PRISTINE_ENV.clone();
+        initial.extend(tool_env.clone());
+        let env = EnvResults::resolve(&initial, entries, true)?.env;
+        let env: IndexMap<_, _> = with_settings_env_files(env)
+            .into_iter()
+            .map(|(k, (v, _))| (k, v))
+            .collect();
+        self.env_with_tool_env
+            .lock()
+            .unwrap()
+            .insert(tool_env.clone(), env.clone());
+        Ok(env)
+    }
     pub fn env_results(&self) -> eyre::Result<&EnvResults> {
         self.env.get_or_try_init(|| self.load_env())
     }
+    /// the vars `[env]` unsets, e.g.: with `GOFLAGS = false` or `_.unset = ["GOFLAGS"]`
+    pub fn env_remove(&self) -> eyre::Result<&BTreeSet<String>> {
+        Ok(&self.env_results()?.env_remove)
+    }
     pub fn path_dirs(&self) -> eyre::Result<&Vec<PathBuf>> {
         Ok(&self.env_results()?.env_paths)
     }
@@ -175,16 +198,37 @@ impl Config {
             .collect())
     }
 
+    /// follows aliases until the version isn't an alias anymore, e.g.: with `lts = "iron"` and
+    /// `iron = "20"`, `lts` resolves to `20`. User aliases take precedence over the backend's.
     pub fn resolve_alias(&self, backend: &dyn Backend, v: &str) -> Result<String> {
-        if let Some(plugin_aliases) = self.aliases.get(backend.fa()) {
-            if let Some(alias) = plugin_aliases.get(v) {
-                return Ok(alias.clone());
+        let user_aliases = self.aliases.get(backend.fa());
+        let mut backend_aliases = None;
+        let mut chain = vec![v.to_string()];
+        loop {
+            let v = chain.last().unwrap();
+            let alias = match user_aliases.and_then(|a| a.get(v)) {
+                Some(alias) => alias.clone(),
+                None => {
+                    let aliases = match &backend_aliases {
+                        Some(aliases) => aliases,
+                        None => backend_aliases.insert(backend.get_aliases()?),
+                    };
+                    match aliases.get(v) {
+                        Some(alias) => alias.clone(),
+                        None => return Ok(chain.pop().unwrap()),
+                    }
+                }
+            };
+            if chain.contains(&alias) {
+                chain.push(alias);
+                bail!(
+                    "circular alias for {}: {}",
+                    backend.id(),
+                    chain.join(" -> ")
+                );
             }
+            chain.push(alias);
         }
-        if let Some(alias) = backend.get_aliases()?.get(v) {
-            return Ok(alias.clone());
-        }
-        Ok(v.to_string())
     }
 
     fn load_all_aliases(&self) -> AliasMap {
@@ -241,6 +285,27 @@ impl Config {
             .collect())
     }
 
+    /// masks the values of the vars listed in `redactions` of any config file. These are looked
+    /// up in `env` as well as the environment mise was started with.
+    pub fn redactor(&self, env: &BTreeMap<String, String>) -> Redactor {
+        let patterns = self
+            .config_files
+            .values()
+            .flat_map(|cf| cf.redactions())
+            .cloned()
+            .unique()
+            .collect_vec();
+        if patterns.is_empty() {
+            return Redactor::default();
+        }
+        let env = env::PRISTINE_ENV
+            .iter()
+            .chain(env)
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        Redactor::new(&patterns, &env)
+    }
+
     pub fn task_includes_for_dir(&self, dir: &Path) -> Vec<PathBuf> {
         self.configs_at_root(dir)
             .iter()
@@ -420,18 +485,19 @@ impl Config {
         for cf in self.config_files.values() {
             if let Some(min) = cf.min_version() {
                 let cur = &*version::V;
-                ensure!(
-                    cur >= min,
-                    "mise version {} is required, but you are using {}",
-                    style::eyellow(min),
-                    style::eyellow(cur)
-                );
+                if cur < min {
+                    return Err(Error::MiseVersionRequired(min.clone(), cur.clone()).into());
+                }
             }
         }
         Ok(())
     }
 
     fn load_env(&self) -> eyre::Result<EnvResults> {
+        EnvResults::resolve(&env::PRISTINE_ENV, self.env_entries()?, false)
+    }
+
+    fn env_entries(&self) -> eyre::Result<Vec<(EnvDirective, PathBuf)>> {
         let entries = self
             .config_files
             .iter()
@@ -444,7 +510,7 @@ impl Config {
             .into_iter()
             .flatten()
             .collect();
-        EnvResults::resolve(&env::PRISTINE_ENV, entries)
+        Ok(entries)
     }
 
     pub fn watch_files(&self) -> eyre::Result<BTreeSet<PathBuf>> {
@@ -459,13 +525,32 @@ impl Config {
             .collect())
     }
 
-    #[cfg(test)]
     pub fn reset() {
         Settings::reset(None);
         _CONFIG.write().unwrap().take();
     }
 }
 
+/// adds the vars from the `env_file` setting, e.g. `MISE_ENV_FILE=.env`
+fn with_settings_env_files(mut env: EnvWithSources) -> EnvWithSources {
+    let settings = Settings::get();
+    for env_file in settings.env_files() {
+        match dotenvy::from_path_iter(&env_file) {
+            Ok(iter) => {
+                for item in iter {
+                    let (k, v) = item.unwrap_or_else(|err| {
+                        warn!("env_file: {err}");
+                        Default::default()
+                    });
+                    env.insert(k, (v, env_file.clone()));
+                }
+            }
+            Err(err) => trace!("env_file: {err}"),
+        }
+    }
+    env
+}
+
 fn get_project_root(config_files: &ConfigMap) -> Option<PathBuf> {
     config_files
         .values()
@@ -564,7 +649,7 @@ pub fn load_config_paths(config_filenames: &[String]) -> Vec<PathBuf> {
 
     // The current directory is not always available, e.g.
     // when a directory was deleted or inside FUSE mounts.
-    if let Some(current_dir) = &*dirs::CWD {
+    if let Some(current_dir) = &dirs::cwd() {
         config_files.extend(file::FindUp::new(current_dir, config_filenames));
     };
 
@@ -574,6 +659,48 @@ pub fn load_config_paths(config_filenames: &[String]) -> Vec<PathBuf> {
     config_files.into_iter().unique().collect()
 }
 
+/// an active profile without any config files is likely a typo
+fn warn_if_profile_missing(config_paths: &[PathBuf]) {
+    let Some(profile) = &*env::MISE_ENV else {
+        return;
+    };
+    let suffixes = [format!(".{profile}.toml"), format!(".{profile}.local.toml")];
+    let found = config_paths.iter().any(|p| {
+        p.file_name()
+            .and_then(|f| f.to_str())
+            .is_some_and(|f| suffixes.iter().any(|s| f.ends_with(s)))
+    });
+    if !found {
+        warn!("no config files found for profile {profile}, expected e.g.: mise.{profile}.toml");
+    }
+}
+
+/// users can't override settings the policy file locks, tell them why their value isn't used.
+/// This is said once per process and not on every prompt from hook-env.
+fn warn_about_policy() -> Result<()> {
+    static WARNED: OnceLock<()> = OnceLock::new();
+    let args = env::ARGS.read().unwrap();
+    if WARNED.set(()).is_err() || args.get(1).is_some_and(|cmd| cmd == "hook-env") {
+        return Ok(());
+    }
+    let path = display_path(&*env::MISE_POLICY_FILE);
+    if let Some(var) = env::var_path("MISE_POLICY_FILE") {
+        if var != *env::MISE_POLICY_FILE {
+            warn!(
+                "ignoring MISE_POLICY_FILE={} since it can be changed by users, using {path}",
+                display_path(&var)
+            );
+        }
+    }
+    for key in &Settings::policy()?.ignored {
+        warn!("{key} can't be set by the policy in {path}, ignoring it");
+    }
+    for (key, source) in Settings::policy_overrides()? {
+        warn!("{key} is locked by the policy in {path}, ignoring the value from {source}");
+    }
+    Ok(())
+}
+
 pub fn is_global_config(path: &Path) -> bool {
     global_config_files()
         .iter()
@@ -1,17 +1,17 @@
 use crate::config::{system_config_files, DEFAULT_CONFIG_FILENAMES};
-use crate::file::FindUp;
+use crate::file::{display_path, FindUp};
 use crate::{config, dirs, env, file};
 #[allow(unused_imports)]
 use confique::env::parse::{list_by_colon, list_by_comma};
 use confique::{Config, Partial};
-use eyre::{bail, Result};
-use once_cell::sync::Lazy;
+use eyre::{bail, Result, WrapErr};
+use once_cell::sync::{Lazy, OnceCell};
 use serde::ser::Error;
 use serde_derive::{Deserialize, Serialize};
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::iter::once;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
@@ -40,6 +40,27 @@ pub enum SettingsStatusMissingTools {
 
 pub type SettingsPartial = <Settings as Config>::Partial;
 
+/// the settings a policy file can lock. These decide where tools and their metadata come from and
+/// which config files are trusted, so an organization can enforce them on every machine
+pub const POLICY_KEYS: &[&str] = &[
+    "disable_default_shorthands",
+    "disable_tools",
+    "experimental",
+    "go_download_mirror",
+    "go_repo",
+    "node.mirror_url",
+    "paranoid",
+    "python_patch_url",
+    "python_pyenv_repo",
+    "registry_url",
+    "ruby.ruby_build_repo",
+    "ruby.ruby_install_repo",
+    "shorthands_file",
+    "trusted_config_paths",
+    "url_rewrite",
+    "use_versions_host",
+];
+
 static BASE_SETTINGS: RwLock<Option<Arc<Settings>>> = RwLock::new(None);
 static CLI_SETTINGS: Mutex<Option<SettingsPartial>> = Mutex::new(None);
 static DEFAULT_SETTINGS: Lazy<SettingsPartial> = Lazy::new(|| {
@@ -74,16 +95,20 @@ impl Settings {
             .env();
 
         let mut settings = sb.load()?;
-        if let Some(mut cd) = settings.cd {
+        if let Some(mut cd) = settings.cd.map(file::replace_path) {
             static ORIG_PATH: Lazy<std::io::Result<PathBuf>> = Lazy::new(env::current_dir);
             if cd.is_relative() {
                 cd = ORIG_PATH.as_ref()?.join(cd);
             }
+            if !cd.is_dir() {
+                bail!("--cd {}: no such directory", display_path(&cd));
+            }
             env::set_current_dir(cd)?;
         }
 
         // Reload settings after current directory option processed
         sb = Self::builder()
+            .preloaded(Self::policy_settings()?)
             .preloaded(CLI_SETTINGS.lock().unwrap().clone().unwrap_or_default())
             .env();
         for file in Self::all_settings_files() {
@@ -92,6 +117,7 @@ impl Settings {
         sb = sb.preloaded(DEFAULT_SETTINGS.clone());
 
         settings = sb.load()?;
+        settings.expand_paths();
         if settings.raw {
             settings.jobs = 1;
         }
@@ -134,6 +160,31 @@ impl Settings {
         *BASE_SETTINGS.write().unwrap() = Some(settings.clone());
         Ok(settings)
     }
+
+    /// expands `~` and env vars in the settings that are paths so e.g.: `$HOME/tools` works
+    fn expand_paths(&mut self) {
+        let paths = [
+            &mut self.cd,
+            &mut self.env_file,
+            &mut self.python_default_packages_file,
+            &mut self.python_patches_directory,
+            &mut self.shorthands_file,
+            &mut self.task_log_dir,
+        ];
+        for p in paths.into_iter().flatten() {
+            *p = file::replace_path(&*p);
+        }
+        self.go_default_packages_file = file::replace_path(&self.go_default_packages_file);
+        self.trusted_config_paths = self
+            .trusted_config_paths
+            .iter()
+            .map(file::replace_path)
+            .collect();
+        self.ruby.default_packages_file = file::replace_path(&self.ruby.default_packages_file)
+            .to_string_lossy()
+            .to_string();
+    }
+
     pub fn add_cli_matches(m: &clap::ArgMatches) {
         let mut s = SettingsPartial::empty();
         for arg in &*env::ARGS.read().unwrap() {
@@ -143,6 +194,9 @@ impl Settings {
             if arg == "--raw" {
                 s.raw = Some(true);
             }
+            if arg == "--raw-log" {
+                s.raw_log = Some(true);
+            }
         }
         if let Some(cd) = m.get_one::<PathBuf>("cd") {
             s.cd = Some(cd.clone());
@@ -150,6 +204,25 @@ impl Settings {
         if let Some(true) = m.get_one::<bool>("yes") {
             s.yes = Some(true);
         }
+        if let Some(true) = m.get_one::<bool>("no-cache") {
+            s.no_cache = Some(true);
+        }
+        if let Some(true) = m.get_one::<bool>("no-cache-write") {
+            s.no_cache_write = Some(true);
+        }
+        if let Some(true) = m.get_one::<bool>("offline") {
+            s.offline = Some(true);
+        }
+        // the prompt is rendered before every command so it must never wait on the network
+        if m.subcommand_name() == Some("prompt") {
+            s.offline = Some(true);
+        }
+        // so is version completion, it only uses the version lists that are cached
+        if let Some(("completion", m)) = m.subcommand() {
+            if m.subcommand_name() == Some("versions") {
+                s.offline = Some(true);
+            }
+        }
         if let Some(true) = m.get_one::<bool>("quiet") {
             s.quiet = Some(true);
         }
@@ -224,6 +297,118 @@ impl Settings {
             .collect()
     }
 
+    /// the env var or file each setting that isn't a default comes from, checked in the same
+    /// order settings are loaded so e.g.: mise.local.toml is shown over mise.toml
+    pub fn sources() -> Result<BTreeMap<String, String>> {
+        let policy = format!("{} (policy)", display_path(&*env::MISE_POLICY_FILE));
+        let mut sources: BTreeMap<_, _> = Self::policy()?
+            .locked
+            .iter()
+            .map(|key| (key.clone(), policy.clone()))
+            .collect();
+        for (key, source) in Self::user_sources(settings_keys(&Self::try_get()?.as_dict()?)) {
+            sources.entry(key).or_insert(source);
+        }
+        Ok(sources)
+    }
+
+    /// the settings users set that the policy file overrides, along with where they set them
+    pub fn policy_overrides() -> Result<Vec<(String, String)>> {
+        let locked = Self::policy()?.locked.clone();
+        let mut sources = Self::user_sources(locked.clone());
+        Ok(locked
+            .into_iter()
+            .filter_map(|key| sources.remove(&key).map(|source| (key, source)))
+            .collect())
+    }
+
+    /// where each setting is set outside of the policy file, `env_keys` are the settings to look
+    /// for env vars of
+    fn user_sources(env_keys: Vec<String>) -> BTreeMap<String, String> {
+        let mut sources = BTreeMap::new();
+        for key in env_keys {
+            let var = format!("MISE_{}", key.replace('.', "_").to_uppercase());
+            if env::var(&var).is_ok() {
+                sources.insert(key, format!("${var}"));
+            }
+        }
+        let settings_table = |p: PathBuf| {
+            let settings = read_table(&p)?.remove("settings")?;
+            Some((p, settings.as_table()?.clone()))
+        };
+        let config_files = config::load_config_paths(&DEFAULT_CONFIG_FILENAMES)
+            .into_iter()
+            .chain(once(env::MISE_GLOBAL_CONFIG_FILE.clone()))
+            .filter(|p| {
+                let filename = p.file_name().unwrap_or_default().to_string_lossy();
+                filename != *env::MISE_DEFAULT_TOOL_VERSIONS_FILENAME
+                    && filename != ".tool-versions"
+            })
+            .filter_map(settings_table)
+            .chain(
+                read_table(&env::MISE_SETTINGS_FILE).map(|t| (env::MISE_SETTINGS_FILE.clone(), t)),
+            )
+            .chain(system_config_files().into_iter().filter_map(settings_table));
+        for (path, settings) in config_files {
+            for key in settings_keys(&settings) {
+                sources.entry(key).or_insert_with(|| display_path(&path));
+            }
+        }
+        sources
+    }
+
+    /// the `[settings]` of the policy file, only keys in `POLICY_KEYS` are used. It is read once
+    /// per process since settings are loaded again whenever the config is reset.
+    pub fn policy() -> Result<&'static Policy> {
+        static POLICY: OnceCell<Policy> = OnceCell::new();
+        POLICY.get_or_try_init(Self::read_policy)
+    }
+
+    fn read_policy() -> Result<Policy> {
+        let path = &*env::MISE_POLICY_FILE;
+        let Some(raw) = path
+            .is_file()
+            .then(|| file::read_to_string(path))
+            .transpose()?
+        else {
+            return Ok(Policy::default());
+        };
+        let policy = (|| {
+            let mut table: toml::Table = toml::from_str(&raw)?;
+            let settings = match table.remove("settings") {
+                Some(toml::Value::Table(settings)) => settings,
+                Some(_) => bail!("settings must be a table"),
+                None => return Ok(Policy::default()),
+            };
+            let mut policy = Policy::default();
+            for key in settings_keys(&settings) {
+                if !POLICY_KEYS.contains(&key.as_str()) {
+                    policy.ignored.push(key);
+                    continue;
+                }
+                match key.split_once('.') {
+                    Some((table, sub)) => policy
+                        .settings
+                        .entry(table)
+                        .or_insert_with(|| toml::Table::new().into())
+                        .as_table_mut()
+                        .unwrap()
+                        .insert(sub.to_string(), settings[table][sub].clone()),
+                    None => policy.settings.insert(key.clone(), settings[&key].clone()),
+                };
+                policy.locked.push(key);
+            }
+            // make sure the values are valid before they're used
+            let _: SettingsPartial = policy.settings.clone().try_into()?;
+            Ok(policy)
+        })();
+        policy.wrap_err_with(|| format!("invalid policy file {}", display_path(path)))
+    }
+
+    fn policy_settings() -> Result<SettingsPartial> {
+        Ok(Self::policy()?.settings.clone().try_into()?)
+    }
+
     pub fn from_file(path: &PathBuf) -> Result<SettingsPartial> {
         let raw = file::read_to_string(path)?;
         let settings: SettingsPartial = toml::from_str(&raw)?;
@@ -259,7 +444,11 @@ impl Settings {
     }
 
     pub fn trusted_config_paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
-        self.trusted_config_paths.iter().map(file::replace_path)
+        self.trusted_config_paths
+            .iter()
+            // an empty entry would be a prefix of every path
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(file::replace_path)
     }
 
     pub fn global_tools_file(&self) -> PathBuf {
@@ -276,7 +465,7 @@ impl Settings {
 
     pub fn env_files(&self) -> Vec<PathBuf> {
         let mut files = vec![];
-        if let Some(cwd) = &*dirs::CWD {
+        if let Some(cwd) = &dirs::cwd() {
             if let Some(env_file) = &self.env_file {
                 let env_file = env_file.to_string_lossy().to_string();
                 for p in FindUp::new(cwd, &[env_file]) {
@@ -299,6 +488,36 @@ impl Settings {
         }
         Some(humantime::parse_duration(&self.cache_prune_age).unwrap())
     }
+
+    /// if cached data may be used. Offline mode only has cached data so it ignores `no_cache`.
+    pub fn use_cache(&self) -> bool {
+        self.offline || !self.no_cache
+    }
+
+    /// if fetched data should be written to the caches
+    pub fn write_cache(&self) -> bool {
+        !self.no_cache_write
+    }
+
+    pub fn registry_cache_ttl_duration(&self) -> Result<Option<Duration>> {
+        if self.registry_cache_ttl == "0" {
+            return Ok(None);
+        }
+        humantime::parse_duration(&self.registry_cache_ttl)
+            .map(Some)
+            .wrap_err_with(|| format!("invalid registry_cache_ttl: {}", self.registry_cache_ttl))
+    }
+}
+
+/// settings from the policy file, see `POLICY_KEYS`
+#[derive(Debug, Default)]
+pub struct Policy {
+    /// the table of settings the policy sets
+    pub settings: toml::Table,
+    /// the keys the policy locks, e.g.: `node.mirror_url`
+    pub locked: Vec<String>,
+    /// keys in the policy that can't be locked
+    pub ignored: Vec<String>,
 }
 
 impl Display for Settings {
@@ -332,3 +551,22 @@ impl SettingsStatus {
         SettingsStatusMissingTools::from_str(&self.missing_tools).unwrap()
     }
 }
+
+fn read_table(path: &Path) -> Option<toml::Table> {
+    if !path.is_file() {
+        return None;
+    }
+    let raw = file::read_to_string(path).ok()?;
+    toml::from_str(&raw).ok()
+}
+
+/// the keys in a settings table with subtables flattened, e.g.: `status.missing_tools`
+pub fn settings_keys(table: &toml::Table) -> Vec<String> {
+    table
+        .iter()
+        .flat_map(|(k, v)| match v {
+            toml::Value::Table(t) => t.keys().map(|sub| format!("{k}.{sub}")).collect(),
+            _ => vec![k.to_string()],
+        })
+        .collect()
+}
@@ -1,17 +1,20 @@
 use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 use once_cell::sync::Lazy;
 
 use crate::env;
 
 pub static HOME: Lazy<&Path> = Lazy::new(|| &env::HOME);
-pub static CWD: Lazy<Option<PathBuf>> = Lazy::new(|| env::current_dir().ok());
 pub static DATA: Lazy<&Path> = Lazy::new(|| &env::MISE_DATA_DIR);
 pub static CACHE: Lazy<&Path> = Lazy::new(|| &env::MISE_CACHE_DIR);
 pub static CONFIG: Lazy<&Path> = Lazy::new(|| &env::MISE_CONFIG_DIR);
 pub static STATE: Lazy<&Path> = Lazy::new(|| &env::MISE_STATE_DIR);
 pub static SYSTEM: Lazy<&Path> = Lazy::new(|| &env::MISE_SYSTEM_DIR);
 
+pub static DOWNLOAD_CACHE: Lazy<&Path> = Lazy::new(|| &env::MISE_DOWNLOAD_CACHE_DIR);
+pub static REMOTE_VERSIONS_CACHE: Lazy<&Path> = Lazy::new(|| &env::MISE_REMOTE_VERSIONS_CACHE_DIR);
+
 pub static PLUGINS: Lazy<&Path> = Lazy::new(|| &env::MISE_PLUGINS_DIR);
 pub static DOWNLOADS: Lazy<&Path> = Lazy::new(|| &env::MISE_DOWNLOADS_DIR);
 pub static INSTALLS: Lazy<&Path> = Lazy::new(|| &env::MISE_INSTALLS_DIR);
@@ -19,3 +22,21 @@ pub static SHIMS: Lazy<&Path> = Lazy::new(|| &env::MISE_SHIMS_DIR);
 
 pub static TRACKED_CONFIGS: Lazy<PathBuf> = Lazy::new(|| STATE.join("tracked-configs"));
 pub static TRUSTED_CONFIGS: Lazy<PathBuf> = Lazy::new(|| STATE.join("trusted-configs"));
+
+static CWD: RwLock<Option<Option<PathBuf>>> = RwLock::new(None);
+
+/// the directory config files are searched from. This is the current directory when it is
+/// first called unless it was set with [`set_cwd`]
+pub fn cwd() -> Option<PathBuf> {
+    if let Some(cwd) = &*CWD.read().unwrap() {
+        return cwd.clone();
+    }
+    let cwd = env::current_dir().ok();
+    *CWD.write().unwrap() = Some(cwd.clone());
+    cwd
+}
+
+/// uses `dir` in place of the current directory without changing the process's own
+pub fn set_cwd(dir: PathBuf) {
+    *CWD.write().unwrap() = Some(Some(dir));
+}
@@ -0,0 +1,213 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use eyre::{bail, Result};
+use filetime::{set_file_mtime, FileTime};
+use itertools::Itertools;
+use url::Url;
+
+use crate::config::Settings;
+use crate::env;
+use crate::file::{self, display_path, format_bytes};
+use crate::hash::{hash_sha256_to_str, Checksum};
+
+/// a content-addressed store of downloaded artifacts shared by every tool. Entries are keyed by
+/// the expected checksum when one is known, otherwise by the url. An entry is only ever added
+/// after the download completed (and matched its checksum) and is renamed into place so a
+/// partially written entry is never visible.
+pub struct DownloadCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl DownloadCache {
+    /// returns None if the cache is disabled with `download_cache_max_size = 0`
+    pub fn get() -> Option<Self> {
+        let max_size = Settings::get().download_cache_max_size.clone();
+        let max_size = match parse_size(&max_size) {
+            Ok(size) => size,
+            Err(err) => {
+                warn!("invalid download_cache_max_size {max_size}: {err}");
+                return None;
+            }
+        };
+        Self::new(env::MISE_DOWNLOAD_CACHE_DIR.clone(), max_size)
+    }
+
+    fn new(dir: PathBuf, max_size: u64) -> Option<Self> {
+        (max_size > 0).then_some(Self { dir, max_size })
+    }
+
+    /// local files are never cached since copying them is just as fast
+    pub fn key(url: &Url, checksum: Option<&Checksum>) -> Option<String> {
+        if url.scheme() == "file" {
+            return None;
+        }
+        Some(match checksum {
+            Some(c) => format!("{}-{}", c.algo, c.expected),
+            None => format!("url-{}", hash_sha256_to_str(url.as_str())),
+        })
+    }
+
+    /// links or copies the cached artifact to `dest`. Entries keyed by checksum are hashed again
+    /// and evicted if they no longer match.
+    pub fn restore(&self, key: &str, dest: &Path) -> Result<bool> {
+        let entry = self.dir.join(key);
+        if !entry.is_file() {
+            return Ok(false);
+        }
+        if dest.exists() {
+            file::remove_file(dest)?;
+        }
+        file::create_dir_all(dest.parent().unwrap())?;
+        if fs::hard_link(&entry, dest).is_err() {
+            file::copy(&entry, dest)?;
+        }
+        if let Some(checksum) = checksum_for_key(key) {
+            let mut checksum = Checksum::parse(&checksum)?;
+            checksum.update_file(dest)?;
+            if let Err(err) = checksum.ensure(&entry) {
+                warn!("removing corrupt download cache entry: {err}");
+                let _ = file::remove_file(&entry);
+                file::remove_file(dest)?;
+                return Ok(false);
+            }
+        }
+        // the mtime is used as the last access time for eviction
+        let _ = set_file_mtime(&entry, FileTime::now());
+        debug!("using cached download {}", display_path(&entry));
+        Ok(true)
+    }
+
+    /// adds a completely downloaded artifact to the cache then evicts the least recently used
+    /// entries if the cache is over its size limit
+    pub fn store(&self, key: &str, path: &Path) -> Result<()> {
+        let entry = self.dir.join(key);
+        if entry.exists() {
+            return Ok(());
+        }
+        file::create_dir_all(&self.dir)?;
+        let tmp = self.dir.join(format!(".{key}.{}.tmp", std::process::id()));
+        if fs::hard_link(path, &tmp).is_err() {
+            file::copy(path, &tmp)?;
+        }
+        file::rename(&tmp, &entry)?;
+        self.evict()
+    }
+
+    fn evict(&self) -> Result<()> {
+        let entries = fs::read_dir(&self.dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(|e| {
+                let metadata = e.metadata().ok().filter(|m| m.is_file())?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((e.path(), metadata.len(), modified))
+            })
+            .sorted_by_key(|(_, _, modified)| *modified)
+            .collect_vec();
+        let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        for (path, len, _) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            debug!(
+                "download cache is {} over its limit, removing {}",
+                format_bytes((size - self.max_size) as f64),
+                display_path(&path)
+            );
+            file::remove_file(&path)?;
+            size -= len;
+        }
+        Ok(())
+    }
+}
+
+fn checksum_for_key(key: &str) -> Option<String> {
+    key.split_once('-')
+        .filter(|(algo, _)| *algo != "url")
+        .map(|(algo, hex)| format!("{algo}:{hex}"))
+}
+
+/// parses sizes like `500MB`, `2GiB` or `1024`. Units are always powers of 1024.
+pub fn parse_size(size: &str) -> Result<u64> {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(size.len());
+    let (num, unit) = size.split_at(split);
+    let num: f64 = num.parse()?;
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        "t" | "tb" | "tib" => 1024 * 1024 * 1024 * 1024,
+        unit => bail!("unknown size unit: {unit}"),
+    };
+    Ok((num * multiplier as f64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("0").unwrap(), 0);
+        assert_eq!(parse_size("1024").unwrap(), 1024);
+        assert_eq!(parse_size("500MB").unwrap(), 500 * 1024 * 1024);
+        assert_eq!(parse_size("1.5 GiB").unwrap(), 3 * 512 * 1024 * 1024);
+        assert!(parse_size("10 parsecs").is_err());
+    }
+
+    #[test]
+    fn test_key() {
+        let url = Url::parse("https://example.com/node.tar.gz").unwrap();
+        let checksum = Checksum::parse("sha256:ABC123").unwrap();
+        assert_eq!(
+            DownloadCache::key(&url, Some(&checksum)).unwrap(),
+            "sha256-abc123"
+        );
+        assert!(DownloadCache::key(&url, None).unwrap().starts_with("url-"));
+        let url = Url::parse("file:///tmp/node.tar.gz").unwrap();
+        assert_eq!(DownloadCache::key(&url, None), None);
+        assert_eq!(checksum_for_key("sha256-abc123").unwrap(), "sha256:abc123");
+        assert_eq!(checksum_for_key("url-abc123"), None);
+    }
+
+    #[test]
+    fn test_store_restore_evict() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(tmp.path().join("cache"), 10).unwrap();
+        let src = tmp.path().join("src");
+        let dest = tmp.path().join("out/dest");
+        file::write(&src, "hello").unwrap();
+        let key = "sha256-2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+        assert!(!cache.restore(key, &dest).unwrap());
+        cache.store(key, &src).unwrap();
+        assert!(cache.restore(key, &dest).unwrap());
+        assert_eq!(file::read_to_string(&dest).unwrap(), "hello");
+
+        // a corrupt entry is removed instead of restored
+        file::write(tmp.path().join("cache/sha256-00"), "hello").unwrap();
+        assert!(!cache.restore("sha256-00", &dest).unwrap());
+        assert!(!tmp.path().join("cache/sha256-00").exists());
+
+        // storing a second 5 byte entry puts the cache over 10 bytes so the oldest is evicted
+        let old = FileTime::from_unix_time(0, 0);
+        set_file_mtime(tmp.path().join("cache").join(key), old).unwrap();
+        for name in ["url-1", "url-2"] {
+            let src = tmp.path().join(name);
+            file::write(&src, "world").unwrap();
+            cache.store(name, &src).unwrap();
+        }
+        assert!(!tmp.path().join("cache").join(key).exists());
+        assert!(tmp.path().join("cache/url-2").exists());
+        assert!(DownloadCache::new(tmp.path().join("cache"), 0).is_none());
+    }
+}
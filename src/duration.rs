@@ -2,4 +2,4 @@ pub use std::time::Duration;
 
 pub const HOURLY: Duration = Duration::from_secs(60 * 60);
 pub const DAILY: Duration = Duration::from_secs(60 * 60 * 24);
-//pub const WEEKLY: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+pub const WEEKLY: Duration = Duration::from_secs(60 * 60 * 24 * 7);
@@ -1,11 +1,15 @@
 use std::path::PathBuf;
 use std::process::ExitStatus;
+use std::time::Duration;
 
 use eyre::Report;
 use thiserror::Error;
+use versions::Versioning;
 
 use crate::file::display_path;
+use crate::http;
 use crate::toolset::{ToolRequest, ToolSource};
+use crate::ui::style;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -17,17 +21,49 @@ pub enum Error {
     },
     #[error("[{0}] plugin not installed")]
     PluginNotInstalled(String),
+    #[error("No repository found for plugin {0}")]
+    PluginNotFound(String),
     #[error("{0}@{1} not installed")]
     VersionNotInstalled(String, String),
     #[error("{} exited with non-zero status: {}", .0, render_exit_status(.1))]
     ScriptFailed(String, Option<ExitStatus>),
+    #[error(
+        "{} timed out after {:?} and was killed\nIf it needs more time, increase MISE_PLUGIN_TIMEOUT",
+        .0,
+        .1
+    )]
+    ScriptTimedOut(String, Duration),
+    #[error(
+        "task {} timed out after {}\nIf it needs more time, increase its `timeout` or use `mise run --timeout`",
+        .0,
+        humantime::format_duration(*.1)
+    )]
+    TaskTimedOut(String, Duration),
+    /// the transfer stopped before all of the file arrived, what was received so far is kept
+    /// and resumed by the next download of it
+    #[error("download of {url} {reason}")]
+    DownloadInterrupted { url: String, reason: String },
     #[error(
         "Config file {} is not trusted.\nTrust it with `mise trust`.",
         display_path(.0)
     )]
     UntrustedConfig(PathBuf),
+    #[error(
+        "mise version {} is required, but you are using {}",
+        style::eyellow(.0),
+        style::eyellow(.1)
+    )]
+    MiseVersionRequired(Versioning, Versioning),
 }
 
+/// exit codes for failures scripts may want to tell apart, anything else exits with 1.
+/// These are documented in docs/errors.md and should not change.
+pub const EXIT_TOOL_NOT_FOUND: i32 = 3;
+pub const EXIT_VERSION_NOT_FOUND: i32 = 4;
+pub const EXIT_NETWORK: i32 = 5;
+pub const EXIT_UNTRUSTED_CONFIG: i32 = 6;
+pub const EXIT_VERSION_CONFLICT: i32 = 7;
+
 fn render_exit_status(exit_status: &Option<ExitStatus>) -> String {
     match exit_status.and_then(|s| s.code()) {
         Some(exit_status) => format!("exit code {exit_status}"),
@@ -49,4 +85,113 @@ impl Error {
             })
             .unwrap_or(false)
     }
+
+    /// errors that retrying won't fix, such as a tool or version that doesn't exist, a
+    /// checksum mismatch or a client error response like 404. Network errors, timeouts and
+    /// failed scripts may be transient.
+    pub fn is_permanent(err: &Report) -> bool {
+        let code = Self::exit_code(err);
+        if code != 1 && code != EXIT_NETWORK {
+            return true;
+        }
+        if let Some(status) = http::error_code(err) {
+            return (400..500).contains(&status) && status != 408 && status != 429;
+        }
+        err.chain()
+            .any(|e| e.to_string().to_lowercase().contains("checksum mismatch"))
+    }
+
+    /// the exit code for `err`, from the first of these that it or one of its causes is
+    pub fn exit_code(err: &Report) -> i32 {
+        let errors = err
+            .chain()
+            .filter_map(|e| e.downcast_ref::<Error>())
+            .collect::<Vec<_>>();
+        let any = |f: fn(&Error) -> bool| errors.iter().any(|e| f(e));
+        if any(|e| matches!(e, Error::UntrustedConfig(_))) {
+            EXIT_UNTRUSTED_CONFIG
+        } else if any(|e| matches!(e, Error::PluginNotFound(_) | Error::PluginNotInstalled(_))) {
+            EXIT_TOOL_NOT_FOUND
+        } else if http::is_http_error(err) {
+            EXIT_NETWORK
+        } else if any(|e| {
+            matches!(
+                e,
+                Error::FailedToResolveVersion { .. } | Error::VersionNotInstalled(..)
+            )
+        }) {
+            EXIT_VERSION_NOT_FOUND
+        } else if any(|e| matches!(e, Error::MiseVersionRequired(..))) {
+            EXIT_VERSION_CONFLICT
+        } else {
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eyre::eyre;
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::args::BackendArg;
+    use crate::toolset::{ToolRequest, ToolSource};
+
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        let code = |err: Report| Error::exit_code(&err);
+        assert_eq!(code(eyre!("something else")), 1);
+        assert_eq!(
+            code(Report::from(Error::UntrustedConfig("mise.toml".into())).wrap_err("loading")),
+            EXIT_UNTRUSTED_CONFIG
+        );
+        assert_eq!(
+            code(Error::PluginNotFound("foo".into()).into()),
+            EXIT_TOOL_NOT_FOUND
+        );
+        let tr = ToolRequest::new(BackendArg::from("tiny"), "99").unwrap();
+        let failed_to_resolve = |source| Error::FailedToResolveVersion {
+            tr: tr.clone(),
+            ts: ToolSource::Argument,
+            source,
+        };
+        assert_eq!(
+            code(failed_to_resolve(eyre!("no version found")).into()),
+            EXIT_VERSION_NOT_FOUND
+        );
+        let http_err =
+            eyre!("HTTP status client error (404 Not Found) for url (https://example.com)");
+        assert_eq!(code(failed_to_resolve(http_err).into()), EXIT_NETWORK);
+        assert_eq!(
+            code(
+                Error::MiseVersionRequired(
+                    Versioning::new("3000.1.1").unwrap(),
+                    Versioning::new("2024.1.1").unwrap()
+                )
+                .into()
+            ),
+            EXIT_VERSION_CONFLICT
+        );
+    }
+
+    #[test]
+    fn test_is_permanent() {
+        let permanent = |err: Report| Error::is_permanent(&err);
+        assert!(permanent(Error::PluginNotFound("foo".into()).into()));
+        assert!(permanent(eyre!(
+            "HTTP status client error (404 Not Found) for url (https://example.com)"
+        )));
+        assert!(permanent(
+            eyre!("Checksum mismatch for file node.tar.gz").wrap_err("failed to install node")
+        ));
+        assert!(!permanent(eyre!(
+            "HTTP status server error (503 Service Unavailable) for url (https://example.com)"
+        )));
+        assert!(!permanent(eyre!(
+            "HTTP status client error (429 Too Many Requests) for url (https://example.com)"
+        )));
+        assert!(!permanent(Error::ScriptFailed("make".into(), None).into()));
+    }
 }
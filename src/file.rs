@@ -2,6 +2,8 @@ use std::collections::HashMap;
 use std::fmt::Display;
 use std::fs;
 use std::fs::File;
+use std::io;
+use std::io::Read;
 #[cfg(unix)]
 use std::os::unix::fs::symlink;
 #[cfg(unix)]
@@ -10,14 +12,17 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::Duration;
 
-use color_eyre::eyre::{Context, Result};
+use bzip2::read::BzDecoder;
+use color_eyre::eyre::{eyre, Context, Result};
 use filetime::{set_file_times, FileTime};
 use flate2::read::GzDecoder;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
+use regex::Regex;
 use tar::Archive;
 use walkdir::WalkDir;
+use xz2::read::XzDecoder;
 use zip::ZipArchive;
 
 use crate::{dirs, env};
@@ -44,19 +49,6 @@ pub fn remove_all<P: AsRef<Path>>(path: P) -> Result<()> {
     Ok(())
 }
 
-pub fn remove_file_or_dir<P: AsRef<Path>>(path: P) -> Result<()> {
-    let path = path.as_ref();
-    match path.metadata().map(|m| m.file_type()) {
-        Ok(x) if x.is_dir() => {
-            remove_dir(path)?;
-        }
-        _ => {
-            remove_file(path)?;
-        }
-    };
-    Ok(())
-}
-
 pub fn remove_file<P: AsRef<Path>>(path: P) -> Result<()> {
     let path = path.as_ref();
     trace!("rm {}", display_path(path));
@@ -144,6 +136,24 @@ pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()>
     fs::write(path, contents).wrap_err_with(|| format!("failed write: {}", display_path(path)))
 }
 
+/// like `write` but only the current user can read the file, for values such as tokens
+pub fn write_private<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()> {
+    let path = path.as_ref();
+    trace!("write {}", display_path(path));
+    (|| -> io::Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut f = options.open(path)?;
+        // the mode is only used when the file is created
+        #[cfg(unix)]
+        f.set_permissions(fs::Permissions::from_mode(0o600))?;
+        io::Write::write_all(&mut f, contents.as_ref())
+    })()
+    .wrap_err_with(|| format!("failed write: {}", display_path(path)))
+}
+
 pub fn read_to_string<P: AsRef<Path>>(path: P) -> Result<String> {
     let path = path.as_ref();
     trace!("cat {}", display_path(path));
@@ -182,6 +192,32 @@ pub fn display_path<P: AsRef<Path>>(path: P) -> String {
     }
 }
 
+/// a size in bytes in binary units, e.g.: `1.5 MiB`
+pub fn format_bytes(bytes: f64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut bytes = bytes;
+    let mut unit = 0;
+    while bytes >= 1024.0 && unit < units.len() - 1 {
+        bytes /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{bytes:.0} {}", units[unit]),
+        _ => format!("{bytes:.1} {}", units[unit]),
+    }
+}
+
+/// total size of the files under `dir`, symlinks are not followed
+pub fn dir_size(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
 /// replaces $HOME in a string with "~" and $PATH with "$PATH", generally used to clean up output
 /// after it is rendered
 pub fn replace_paths_in_string<S: Display>(input: S) -> String {
@@ -189,13 +225,62 @@ pub fn replace_paths_in_string<S: Display>(input: S) -> String {
     input.to_string().replace(&home, "~")
 }
 
-/// replaces "~" with $HOME
+/// expands a leading "~" or "~user" to the home dir and "$VAR" or "${VAR}" to the value of the
+/// env var. Users that don't exist and env vars that aren't set are left as they are
 pub fn replace_path<P: AsRef<Path>>(path: P) -> PathBuf {
     let path = path.as_ref();
-    match path.starts_with("~/") {
-        true => dirs::HOME.join(path.strip_prefix("~/").unwrap()),
-        false => path.to_path_buf(),
+    let Some(s) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    let s = expand_env_vars(s);
+    let (user, rest) = match s.strip_prefix('~') {
+        Some(rest) => match rest.split_once('/') {
+            Some((user, rest)) => (user, rest),
+            None => (rest, ""),
+        },
+        None => return s.into(),
+    };
+    match user_home(user) {
+        Some(home) if rest.is_empty() => home,
+        Some(home) => home.join(rest),
+        None => s.into(),
+    }
+}
+
+fn expand_env_vars(s: &str) -> String {
+    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$(?:\{(\w+)\}|(\w+))").unwrap());
+    RE.replace_all(s, |caps: &regex::Captures| {
+        let name = caps.get(1).or(caps.get(2)).unwrap().as_str();
+        env::var(name).unwrap_or_else(|_| caps[0].to_string())
+    })
+    .into_owned()
+}
+
+/// the home dir of `user`, or of the current user if it's empty
+fn user_home(user: &str) -> Option<PathBuf> {
+    if user.is_empty() {
+        return Some(dirs::HOME.to_path_buf());
+    }
+    #[cfg(unix)]
+    {
+        nix::unistd::User::from_name(user)
+            .ok()
+            .flatten()
+            .map(|u| u.dir)
     }
+    #[cfg(windows)]
+    None
+}
+
+/// true if only root can change the file: it is owned by root and not writable by its group or others
+#[cfg(unix)]
+pub fn is_root_owned(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.uid() == 0 && m.mode() & 0o022 == 0)
+}
+
+#[cfg(windows)]
+pub fn is_root_owned(_path: &Path) -> bool {
+    false
 }
 
 pub fn touch_dir(dir: &Path) -> Result<()> {
@@ -320,28 +405,6 @@ pub fn make_symlink_or_file(target: &Path, link: &Path) -> Result<()> {
     Ok(())
 }
 
-pub fn remove_symlinks_with_target_prefix(
-    symlink_dir: &Path,
-    target_prefix: &Path,
-) -> Result<Vec<PathBuf>> {
-    if !symlink_dir.exists() {
-        return Ok(vec![]);
-    }
-    let mut removed = vec![];
-    for entry in symlink_dir.read_dir()? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_symlink() {
-            let target = path.read_link()?;
-            if target.starts_with(target_prefix) {
-                fs::remove_file(&path)?;
-                removed.push(path);
-            }
-        }
-    }
-    Ok(removed)
-}
-
 #[cfg(unix)]
 pub fn is_executable(path: &Path) -> bool {
     if let Ok(metadata) = path.metadata() {
@@ -374,7 +437,8 @@ pub fn make_executable<P: AsRef<Path>>(_path: P) -> Result<()> {
 
 pub fn all_dirs() -> Result<Vec<PathBuf>> {
     let mut output = vec![];
-    let mut cwd = dirs::CWD.as_ref().map(|p| p.as_path());
+    let cwd = dirs::cwd();
+    let mut cwd = cwd.as_deref();
     while let Some(dir) = cwd {
         output.push(dir.to_path_buf());
         cwd = dir.parent();
@@ -477,18 +541,186 @@ fn _which<P: AsRef<Path>>(name: P, paths: &[PathBuf]) -> Option<PathBuf> {
     })
 }
 
+/// the compression of an archive, see [`ArchiveFormat::detect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    TarBz2,
+    TarXz,
+    TarZst,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// the format of an archive from its first bytes, `None` if they don't match a known format
+    pub fn sniff(header: &[u8]) -> Option<Self> {
+        match header {
+            [0x1f, 0x8b, ..] => Some(Self::TarGz),
+            [b'B', b'Z', b'h', ..] => Some(Self::TarBz2),
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Some(Self::TarXz),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Self::TarZst),
+            [b'P', b'K', 0x03, 0x04, ..] | [b'P', b'K', 0x05, 0x06, ..] => Some(Self::Zip),
+            h if h.get(257..262) == Some(b"ustar") => Some(Self::Tar),
+            _ => None,
+        }
+    }
+
+    /// the format an archive named `name` should have from its extension
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        let ends_with = |exts: &[&str]| exts.iter().any(|e| name.ends_with(e));
+        if ends_with(&[".tar.gz", ".tgz", ".gz"]) {
+            Some(Self::TarGz)
+        } else if ends_with(&[".tar.bz2", ".tbz2", ".tbz", ".bz2"]) {
+            Some(Self::TarBz2)
+        } else if ends_with(&[".tar.xz", ".txz", ".xz"]) {
+            Some(Self::TarXz)
+        } else if ends_with(&[".tar.zst", ".tzst", ".zst"]) {
+            Some(Self::TarZst)
+        } else if ends_with(&[".zip"]) {
+            Some(Self::Zip)
+        } else if ends_with(&[".tar"]) {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// the format of an archive from its first bytes since download urls don't always have the
+    /// right extension. Falls back to the extension of `name` if the bytes don't match a format.
+    pub fn detect(header: &[u8], name: &str) -> Result<Self> {
+        let from_name = Self::from_name(name);
+        match (Self::sniff(header), from_name) {
+            (Some(sniffed), Some(from_name)) if sniffed != from_name => {
+                debug!("{name} is a {sniffed} archive despite its extension");
+                Ok(sniffed)
+            }
+            (Some(sniffed), _) => Ok(sniffed),
+            (None, Some(from_name)) => {
+                warn!("could not tell the format of {name}, extracting it as {from_name}");
+                Ok(from_name)
+            }
+            (None, None) => Err(eyre!("unknown archive format: {name}")),
+        }
+    }
+}
+
+impl Display for ArchiveFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tar => write!(f, "tar"),
+            Self::TarGz => write!(f, "tar.gz"),
+            Self::TarBz2 => write!(f, "tar.bz2"),
+            Self::TarXz => write!(f, "tar.xz"),
+            Self::TarZst => write!(f, "tar.zst"),
+            Self::Zip => write!(f, "zip"),
+        }
+    }
+}
+
+/// extracts a tarball or zip archive, the format is detected with [`ArchiveFormat::detect`]
 pub fn untar(archive: &Path, dest: &Path) -> Result<()> {
-    debug!("tar -xzf {} -C {}", archive.display(), dest.display());
-    let f = File::open(archive)?;
-    let tar = GzDecoder::new(f);
-    Archive::new(tar).unpack(dest).wrap_err_with(|| {
-        let archive = display_path(archive);
+    untar_named(archive, &display_path(archive), dest)
+}
+
+/// like [`untar`] but `name`, e.g.: the url it was downloaded from, is used to guess the format
+/// and to name the file of a compressed binary that isn't a tarball
+pub fn untar_named(archive: &Path, name: &str, dest: &Path) -> Result<()> {
+    let (header, reader) = read_header(open(archive)?)?;
+    match ArchiveFormat::detect(&header, name)? {
+        ArchiveFormat::Zip => extract_zip(archive, dest),
+        format => extract_tar(reader, format, name, dest),
+    }
+}
+
+/// extracts a tarball as it is read, `name` is only used in messages and to guess the format
+/// if it can't be detected. Zip archives can't be read this way.
+pub fn untar_reader<R: Read>(reader: R, name: &str, dest: &Path) -> Result<()> {
+    let (header, reader) = read_header(reader)?;
+    match ArchiveFormat::detect(&header, name)? {
+        ArchiveFormat::Zip => Err(eyre!("{name} is a zip archive, it can't be streamed")),
+        format => extract_tar(reader, format, name, dest),
+    }
+}
+
+/// extracts a zip archive, or a tarball if that is what it actually is
+pub fn unzip(archive: &Path, dest: &Path) -> Result<()> {
+    untar(archive, dest)
+}
+
+/// the first bytes of `reader` to sniff its format and a reader that still starts with them.
+/// This is enough to see the `ustar` magic of an uncompressed tarball.
+fn read_header<R: Read>(mut reader: R) -> Result<(Vec<u8>, impl Read)> {
+    let mut header = vec![];
+    reader.by_ref().take(262).read_to_end(&mut header)?;
+    Ok((header.clone(), io::Cursor::new(header).chain(reader)))
+}
+
+fn extract_tar<R: Read>(reader: R, format: ArchiveFormat, name: &str, dest: &Path) -> Result<()> {
+    debug!("tar -xf {name} -C {} ({format})", dest.display());
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::Tar => Box::new(reader),
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(reader)),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(reader)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(reader)),
+        ArchiveFormat::TarZst => Box::new(zstd::Decoder::new(reader)?),
+        ArchiveFormat::Zip => unreachable!("zip archives are not tarballs"),
+    };
+    let (header, reader) = read_header(reader)?;
+    if ArchiveFormat::sniff(&header) != Some(ArchiveFormat::Tar) && !is_tarball_name(name) {
+        return decompress_binary(reader, name, dest);
+    }
+    Archive::new(reader).unpack(dest).wrap_err_with(|| {
         let dest = display_path(dest);
-        format!("failed to extract tar: {archive} to {dest}")
+        format!("failed to extract tar: {name} to {dest}")
     })
 }
 
-pub fn unzip(archive: &Path, dest: &Path) -> Result<()> {
+/// tools that ship a single gzipped binary, e.g.: `tool-linux-x64.gz`, are decompressed into
+/// `dest` with the name of the download minus its compression extension
+fn decompress_binary<R: Read>(mut reader: R, name: &str, dest: &Path) -> Result<()> {
+    let file_name = decompressed_file_name(name)
+        .ok_or_else(|| eyre!("{name} is not a tarball and has no file name to decompress to"))?;
+    create_dir_all(dest)?;
+    let path = dest.join(file_name);
+    debug!("decompressing {name} to {}", display_path(&path));
+    let mut file = File::create(&path)?;
+    io::copy(&mut reader, &mut file)
+        .wrap_err_with(|| format!("failed to decompress {name} to {}", display_path(&path)))?;
+    make_executable(&path)?;
+    // on windows whether a file is executable depends on its extension
+    if cfg!(unix) && !is_executable(&path) {
+        return Err(eyre!("{} is not executable", display_path(&path)));
+    }
+    Ok(())
+}
+
+/// `name` without its compression extension, query or directories:
+/// `https://example.com/tool-linux.gz?raw=1` is `tool-linux`
+fn decompressed_file_name(name: &str) -> Option<String> {
+    let name = name.split(['?', '#']).next()?;
+    let name = name.rsplit(['/', '\\']).next()?;
+    let lower = name.to_lowercase();
+    let ext = [".gz", ".bz2", ".xz", ".zst"]
+        .into_iter()
+        .find(|ext| lower.ends_with(ext))?;
+    let name = &name[..name.len() - ext.len()];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// whether `name` says it is a tarball so it is extracted even if its header isn't recognized
+fn is_tarball_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains(".tar.")
+        || name.ends_with(".tar")
+        || [".tgz", ".tbz", ".tbz2", ".txz", ".tzst"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+}
+
+fn extract_zip(archive: &Path, dest: &Path) -> Result<()> {
+    debug!("unzip {} -d {}", display_path(archive), dest.display());
     ZipArchive::new(File::open(archive)?)
         .wrap_err_with(|| format!("failed to open zip archive: {}", display_path(archive)))?
         .extract(dest)
@@ -570,6 +802,164 @@ mod tests {
     fn test_replace_path() {
         reset();
         assert_eq!(replace_path(Path::new("~/cwd")), dirs::HOME.join("cwd"));
+        assert_eq!(replace_path(Path::new("~")), *dirs::HOME);
         assert_eq!(replace_path(Path::new("/cwd")), Path::new("/cwd"));
+        assert_eq!(replace_path(Path::new("a~/b")), Path::new("a~/b"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_replace_path_user() {
+        let root = nix::unistd::User::from_uid(0.into()).unwrap().unwrap();
+        let name = &root.name;
+        assert_eq!(replace_path(format!("~{name}")), root.dir);
+        assert_eq!(
+            replace_path(format!("~{name}/tools")),
+            root.dir.join("tools")
+        );
+        assert_eq!(
+            replace_path("~no-such-user-mise/tools"),
+            Path::new("~no-such-user-mise/tools")
+        );
+    }
+
+    #[test]
+    fn test_replace_path_env_vars() {
+        env::set_var("MISE_TEST_TOOLS_DIR", "/opt/tools");
+        assert_eq!(
+            replace_path("$MISE_TEST_TOOLS_DIR/bin"),
+            Path::new("/opt/tools/bin")
+        );
+        assert_eq!(
+            replace_path("${MISE_TEST_TOOLS_DIR}_old/bin"),
+            Path::new("/opt/tools_old/bin")
+        );
+        assert_eq!(replace_path("$HOME/tools"), dirs::HOME.join("tools"));
+        // unset env vars are left as they are
+        assert_eq!(
+            replace_path("$MISE_TEST_UNSET_DIR/bin"),
+            Path::new("$MISE_TEST_UNSET_DIR/bin")
+        );
+        assert_eq!(replace_path("/a/$/b"), Path::new("/a/$/b"));
+        env::remove_var("MISE_TEST_TOOLS_DIR");
+    }
+
+    fn tarball() -> Vec<u8> {
+        let mut tar = tar::Builder::new(vec![]);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, "dir/file.txt", &b"hello"[..])
+            .unwrap();
+        tar.into_inner().unwrap()
+    }
+
+    fn zip() -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        zip.start_file("dir/file.txt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut zip, b"hello").unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn compress(format: ArchiveFormat) -> Vec<u8> {
+        use std::io::Write;
+        let tar = tarball();
+        match format {
+            ArchiveFormat::Tar => tar,
+            ArchiveFormat::TarGz => {
+                let mut enc = flate2::write::GzEncoder::new(vec![], Default::default());
+                enc.write_all(&tar).unwrap();
+                enc.finish().unwrap()
+            }
+            ArchiveFormat::TarBz2 => {
+                let mut enc = bzip2::write::BzEncoder::new(vec![], Default::default());
+                enc.write_all(&tar).unwrap();
+                enc.finish().unwrap()
+            }
+            ArchiveFormat::TarXz => {
+                let mut enc = xz2::write::XzEncoder::new(vec![], 6);
+                enc.write_all(&tar).unwrap();
+                enc.finish().unwrap()
+            }
+            ArchiveFormat::TarZst => zstd::encode_all(&tar[..], 0).unwrap(),
+            ArchiveFormat::Zip => zip(),
+        }
+    }
+
+    #[test]
+    fn test_untar_formats() {
+        let tmp = tempfile::tempdir().unwrap();
+        for (format, name) in [
+            (ArchiveFormat::Tar, "a.tar"),
+            (ArchiveFormat::TarGz, "a.tar.gz"),
+            (ArchiveFormat::TarBz2, "a.tar.bz2"),
+            (ArchiveFormat::TarXz, "a.tar.xz"),
+            (ArchiveFormat::TarZst, "a.tar.zst"),
+            (ArchiveFormat::Zip, "a.zip"),
+            // mislabeled archives are detected by their content
+            (ArchiveFormat::TarXz, "b.tar.gz"),
+            (ArchiveFormat::TarGz, "b.zip"),
+            (ArchiveFormat::Zip, "b.tgz"),
+        ] {
+            let bytes = compress(format);
+            assert_eq!(ArchiveFormat::sniff(&bytes), Some(format), "{name}");
+            let archive = tmp.path().join(name);
+            write(&archive, &bytes).unwrap();
+            let dest = tmp.path().join(format!("{name}-out"));
+            untar(&archive, &dest).unwrap();
+            assert_eq!(read_to_string(dest.join("dir/file.txt")).unwrap(), "hello");
+        }
+    }
+
+    #[test]
+    fn test_untar_reader() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bytes = compress(ArchiveFormat::TarZst);
+        untar_reader(&bytes[..], "a.tar.gz", tmp.path()).unwrap();
+        assert_eq!(
+            read_to_string(tmp.path().join("dir/file.txt")).unwrap(),
+            "hello"
+        );
+        assert!(untar_reader(&compress(ArchiveFormat::Zip)[..], "a.zip", tmp.path()).is_err());
+    }
+
+    #[test]
+    fn test_archive_format_detect() {
+        // unknown content falls back to the extension
+        assert_eq!(
+            ArchiveFormat::detect(b"garbage", "a.tar.bz2").unwrap(),
+            ArchiveFormat::TarBz2
+        );
+        assert_eq!(
+            ArchiveFormat::detect(&compress(ArchiveFormat::TarGz), "a.tar.xz").unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert!(ArchiveFormat::detect(b"garbage", "a.bin").is_err());
+    }
+
+    #[test]
+    fn test_untar_compressed_binary() {
+        use std::io::Write;
+        let tmp = tempfile::tempdir().unwrap();
+        let mut enc = flate2::write::GzEncoder::new(vec![], Default::default());
+        enc.write_all(b"#!/bin/sh\necho hi\n").unwrap();
+        let archive = tmp.path().join("download");
+        write(&archive, enc.finish().unwrap()).unwrap();
+        let dest = tmp.path().join("out");
+        untar_named(&archive, "https://example.com/tool-linux.gz?raw=1", &dest).unwrap();
+        let bin = dest.join("tool-linux");
+        assert_eq!(read_to_string(&bin).unwrap(), "#!/bin/sh\necho hi\n");
+        assert!(is_executable(&bin));
+
+        let xz = tmp.path().join("tool.xz");
+        let mut enc = xz2::write::XzEncoder::new(vec![], 6);
+        enc.write_all(b"bin").unwrap();
+        write(&xz, enc.finish().unwrap()).unwrap();
+        untar(&xz, &dest).unwrap();
+        assert_eq!(read_to_string(dest.join("tool")).unwrap(), "bin");
+        assert_eq!(decompressed_file_name("a/b.tar.gz"), Some("b.tar".into()));
+        assert_eq!(decompressed_file_name(".gz"), None);
     }
 }
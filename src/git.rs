@@ -77,14 +77,18 @@ impl Git {
             }
             Err(err) => Err(eyre!("git failed: {cmd:?} {err:#}")),
         };
-        exec(git_cmd!(
-            &self.dir,
-            "fetch",
-            "--prune",
-            "--update-head-ok",
-            "origin",
-            &format!("{}:{}", gitref, gitref),
-        ))?;
+        match is_sha(&gitref) {
+            // a commit can't be fetched into a branch of the same name
+            true => exec(git_cmd!(&self.dir, "fetch", "--prune", "origin"))?,
+            false => exec(git_cmd!(
+                &self.dir,
+                "fetch",
+                "--prune",
+                "--update-head-ok",
+                "origin",
+                &format!("{}:{}", gitref, gitref),
+            ))?,
+        }
         let prev_rev = self.current_sha()?;
         exec(git_cmd!(
             &self.dir,
@@ -204,10 +208,14 @@ impl Git {
         }
     }
 
+    /// splits `url#ref`, or `url@sha` when what follows the `@` is a commit sha
     pub fn split_url_and_ref(url: &str) -> (String, Option<String>) {
         match url.split_once('#') {
             Some((url, _ref)) => (url.to_string(), Some(_ref.to_string())),
-            None => (url.to_string(), None),
+            None => match url.rsplit_once('@') {
+                Some((url, sha)) if is_sha(sha) => (url.to_string(), Some(sha.to_string())),
+                _ => (url.to_string(), None),
+            },
         }
     }
 
@@ -223,6 +231,11 @@ impl Git {
     }
 }
 
+/// true if `gitref` looks like a full or abbreviated commit sha rather than a branch or tag
+pub fn is_sha(gitref: &str) -> bool {
+    regex!(r"^[0-9a-f]{7,40}$").is_match(gitref)
+}
+
 fn get_git_version() -> Result<String> {
     let version = cmd!("git", "--version").read()?;
     Ok(version.trim().into())
@@ -1,6 +1,38 @@
-use serde_derive::Deserialize;
+use duct::Expression;
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+use crate::cache::{CacheManager, CacheManagerBuilder};
+use crate::config::Settings;
+use crate::hash::hash_to_str;
+use crate::{dirs, env, file};
+
+/// the token sent to api.github.com. Looked up once in order from MISE_GITHUB_TOKEN/GITHUB_API_TOKEN,
+/// the system keyring (service "mise", account "github.com"), then `gh auth token`.
+pub static API_TOKEN: Lazy<Option<String>> = Lazy::new(|| {
+    if let Some(token) = &*env::MISE_GITHUB_TOKEN {
+        debug!("github token: using MISE_GITHUB_TOKEN/GITHUB_API_TOKEN");
+        return Some(token.clone());
+    }
+    if let Some(token) = keyring_token() {
+        debug!("github token: using system keyring");
+        return Some(token);
+    }
+    if !Settings::get().github_use_gh_cli {
+        debug!("github token: github_use_gh_cli is disabled, skipping gh");
+        return None;
+    }
+    if file::which("gh").is_some() {
+        debug!("github token: trying gh auth token");
+        if let Some(token) = read_token(cmd!("gh", "auth", "token", "--hostname", "github.com")) {
+            return Some(token);
+        }
+    }
+    debug!("github token: none found");
+    None
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GithubRelease {
     pub tag_name: String,
     // pub name: Option<String>,
@@ -10,7 +42,103 @@ pub struct GithubRelease {
     // pub published_at: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GithubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubReleaseAssets {
+    assets: Vec<GithubAsset>,
+}
+
+fn keyring_token() -> Option<String> {
+    let cmd = if cfg!(target_os = "macos") && file::which("security").is_some() {
+        debug!("github token: trying macOS keychain");
+        cmd!(
+            "security",
+            "find-generic-password",
+            "-s",
+            "mise",
+            "-a",
+            "github.com",
+            "-w"
+        )
+    } else if cfg!(target_os = "linux") && file::which("secret-tool").is_some() {
+        debug!("github token: trying secret-tool");
+        cmd!(
+            "secret-tool",
+            "lookup",
+            "service",
+            "mise",
+            "account",
+            "github.com"
+        )
+    } else {
+        return None;
+    };
+    read_token(cmd)
+}
+
+fn read_token(cmd: Expression) -> Option<String> {
+    match cmd.stderr_null().unchecked().stdout_capture().run() {
+        Ok(output) if output.status.success() => {
+            let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!token.is_empty()).then_some(token)
+        }
+        _ => None,
+    }
+}
+
+/// every release of `repo` across all pages. The assembled list is cached for
+/// MISE_FETCH_REMOTE_VERSIONS_CACHE so repeat listings don't page through the api again.
 pub fn list_releases(repo: &str) -> eyre::Result<Vec<GithubRelease>> {
-    let url = format!("https://api.github.com/repos/{}/releases", repo);
-    crate::http::HTTP_FETCH.json(url)
+    let url = format!("https://api.github.com/repos/{repo}/releases?per_page=100");
+    let cache: CacheManager<Vec<GithubRelease>> = CacheManagerBuilder::new(
+        dirs::CACHE
+            .join("github")
+            .join(format!("releases-{}.msgpack.z", hash_to_str(&url))),
+    )
+    .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
+    .build();
+    Ok(cache
+        .get_or_try_init(|| crate::http::HTTP_FETCH.json_paginated(&url))?
+        .clone())
+}
+
+/// the release github shows as the latest of `repo`, which is never a draft or a prerelease.
+/// One request instead of paging through every release, `None` if the repo has no releases.
+pub fn latest_release(repo: &str) -> eyre::Result<Option<GithubRelease>> {
+    let url = format!("https://api.github.com/repos/{repo}/releases/latest");
+    match crate::http::HTTP_FETCH.json(&url) {
+        Ok(release) => Ok(Some(release)),
+        Err(err) if crate::http::error_code(&err) == Some(404) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// the files attached to the release of `repo` tagged `tag`, or the latest release if `tag` is
+/// "latest"
+pub fn list_release_assets(repo: &str, tag: &str) -> eyre::Result<Vec<GithubAsset>> {
+    let url = match tag {
+        "latest" => format!("https://api.github.com/repos/{repo}/releases/latest"),
+        tag => format!("https://api.github.com/repos/{repo}/releases/tags/{tag}"),
+    };
+    let release: GithubReleaseAssets = crate::http::HTTP_FETCH.json(&url)?;
+    Ok(release.assets)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_read_token() {
+        assert_eq!(read_token(cmd!("echo", " abc ")), Some("abc".to_string()));
+        assert_eq!(read_token(cmd!("echo", "")), None);
+        assert_eq!(read_token(cmd!("false")), None);
+    }
 }
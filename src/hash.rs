@@ -6,9 +6,9 @@ use std::path::Path;
 use crate::file;
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
-use eyre::{ensure, Result};
+use eyre::{bail, ensure, Result};
 use rayon::prelude::*;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 use siphasher::sip::SipHasher;
 
 pub fn hash_to_str<T: Hash>(t: &T) -> String {
@@ -63,17 +63,99 @@ pub fn ensure_checksum_sha256(
     Ok(())
 }
 
+/// a hasher for a checksum in `algo:hex` form, e.g. `sha256:abc123...`
+pub struct Checksum {
+    pub algo: String,
+    pub expected: String,
+    hasher: Box<dyn sha2::digest::DynDigest + Send>,
+}
+
+impl Checksum {
+    pub fn parse(checksum: &str) -> Result<Self> {
+        let Some((algo, expected)) = checksum.split_once(':') else {
+            bail!("Invalid checksum: {checksum} (expected format is algo:hex)");
+        };
+        let hasher: Box<dyn sha2::digest::DynDigest + Send> = match algo {
+            "sha256" => Box::new(Sha256::new()),
+            "sha512" => Box::new(Sha512::new()),
+            _ => bail!("Unsupported checksum algorithm: {algo} (expected sha256 or sha512)"),
+        };
+        Ok(Self {
+            algo: algo.to_string(),
+            expected: expected.to_lowercase(),
+            hasher,
+        })
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    pub fn update_file(&mut self, path: &Path) -> Result<()> {
+        let mut file = file::open(path)?;
+        let mut buf = [0; 32 * 1024];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            self.update(&buf[..n]);
+        }
+        Ok(())
+    }
+
+    pub fn ensure(self, path: &Path) -> Result<()> {
+        let actual = self
+            .hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        let algo = self.algo;
+        let expected = self.expected;
+        ensure!(
+            actual == expected,
+            "Checksum mismatch for file {}:\nExpected: {algo}:{expected}\nActual:   {algo}:{actual}",
+            display_path(path),
+        );
+        Ok(())
+    }
+}
+
+/// parses a `sha256sum` style listing of `<hash>  <filename>` lines into filename => hash
+///
+/// Accepts tabs as the separator, a leading BOM, blank lines and the `*` or `./` some tools put
+/// in front of file names.
 pub fn parse_shasums(text: &str) -> HashMap<String, String> {
-    text.par_lines()
-        .map(|l| {
-            let mut parts = l.split_whitespace();
-            let hash = parts.next().unwrap();
-            let name = parts.next().unwrap();
-            (name.into(), hash.into())
+    text.trim_start_matches('\u{feff}')
+        .par_lines()
+        .filter_map(|l| {
+            let (hash, name) = l.trim().split_once(char::is_whitespace)?;
+            let name = name.trim().trim_start_matches('*').trim_start_matches("./");
+            (!name.is_empty()).then(|| (name.to_string(), hash.to_lowercase()))
         })
         .collect()
 }
 
+/// the hash of `filename` in a shasums listing
+pub fn find_shasum(text: &str, filename: &str) -> Option<String> {
+    parse_shasums(text).remove(filename)
+}
+
+/// reads the checksum of `filename` in `algo:hex` form from either a file containing only the
+/// hash or a shasums listing, a hash without an algorithm is sha256
+pub fn parse_checksum_file(body: &str, filename: &str) -> Option<String> {
+    let body = body.trim_start_matches('\u{feff}').trim();
+    let hash = match body.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [hash] => hash.to_string(),
+        _ => find_shasum(body, filename)?,
+    };
+    match hash.contains(':') {
+        true => Some(hash),
+        false => Some(format!("sha256:{hash}")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_snapshot;
@@ -89,6 +171,72 @@ mod tests {
         assert_eq!(hash_to_str(&"foo"), "e1b19adfb2e348a2");
     }
 
+    #[test]
+    fn test_find_shasum() {
+        let shasums = "\u{feff}ABC  ./mise-v1.0.0-linux-arm64.tar.gz\n\
+                       def\tmise-v1.0.0-linux-x64.tar.gz\n\
+                       \n\
+                       012 *mise-v1.0.0-macos-x64.tar.gz\n";
+        assert_eq!(
+            find_shasum(shasums, "mise-v1.0.0-linux-arm64.tar.gz"),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            find_shasum(shasums, "mise-v1.0.0-linux-x64.tar.gz"),
+            Some("def".to_string())
+        );
+        assert_eq!(
+            find_shasum(shasums, "mise-v1.0.0-macos-x64.tar.gz"),
+            Some("012".to_string())
+        );
+        assert_eq!(find_shasum(shasums, "mise-v1.0.0-macos-arm64.tar.gz"), None);
+    }
+
+    #[test]
+    fn test_parse_checksum_file() {
+        let sha = "a".repeat(64);
+        assert_eq!(
+            parse_checksum_file(&format!("{sha}\n"), "go.tar.gz"),
+            Some(format!("sha256:{sha}"))
+        );
+        assert_eq!(
+            parse_checksum_file(&format!("\u{feff}{sha}  go.tar.gz\n"), "go.tar.gz"),
+            Some(format!("sha256:{sha}"))
+        );
+        assert_eq!(
+            parse_checksum_file(&format!("{sha}  other.tar.gz\n"), "go.tar.gz"),
+            None
+        );
+        let listing = format!("{}  other.tar.gz\n{sha}\tgo.tar.gz\n", "b".repeat(64));
+        assert_eq!(
+            parse_checksum_file(&listing, "go.tar.gz"),
+            Some(format!("sha256:{sha}"))
+        );
+        assert_eq!(parse_checksum_file(&listing, "missing.tar.gz"), None);
+        assert_eq!(
+            parse_checksum_file("sha512:abc", "go.tar.gz"),
+            Some("sha512:abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_checksum() {
+        let path = Path::new("foo");
+        let mut checksum = Checksum::parse(
+            "sha256:2C26B46B68FFC68FF99B453C1D30413413422D706483BFA0F98A5E886266E7AE",
+        )
+        .unwrap();
+        checksum.update(b"foo");
+        checksum.ensure(path).unwrap();
+
+        let mut checksum = Checksum::parse("sha512:abc").unwrap();
+        checksum.update(b"foo");
+        assert!(checksum.ensure(path).is_err());
+
+        assert!(Checksum::parse("md5:abc").is_err());
+        assert!(Checksum::parse("abc").is_err());
+    }
+
     #[test]
     fn test_hash_sha256() {
         reset();
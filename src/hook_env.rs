@@ -33,6 +33,9 @@ pub fn should_exit_early(watch_files: impl IntoIterator<Item = impl AsRef<Path>>
             if have_mise_env_vars_been_modified(watches) {
                 return false;
             }
+            if watches.dir.is_some() && watches.dir != dirs::cwd() {
+                return false;
+            }
         }
         None => {
             return false;
@@ -84,6 +87,10 @@ fn have_mise_env_vars_been_modified(watches: &HookEnvWatches) -> bool {
 pub struct HookEnvWatches {
     files: BTreeMap<PathBuf, SystemTime>,
     env_var_hash: String,
+    /// set if tools are limited with `only_in`, changing directories means they need to be
+    /// checked again
+    #[serde(default)]
+    dir: Option<PathBuf>,
 }
 
 pub fn serialize_watches(watches: &HookEnvWatches) -> Result<String> {
@@ -103,6 +110,7 @@ pub fn deserialize_watches(raw: String) -> Result<HookEnvWatches> {
 
 pub fn build_watches(
     watch_files: impl IntoIterator<Item = impl AsRef<Path>>,
+    dir_scoped: bool,
 ) -> Result<HookEnvWatches> {
     let mut watches = BTreeMap::new();
     for cf in get_watch_files(watch_files) {
@@ -112,6 +120,7 @@ pub fn build_watches(
     Ok(HookEnvWatches {
         files: watches,
         env_var_hash: get_mise_env_vars_hashed(),
+        dir: dir_scoped.then(dirs::cwd).flatten(),
     })
 }
 
@@ -130,7 +139,7 @@ pub fn get_watch_files(
 }
 
 /// gets a hash of all MISE_ environment variables
-fn get_mise_env_vars_hashed() -> String {
+pub fn get_mise_env_vars_hashed() -> String {
     let env_vars: Vec<(&String, &String)> = env::PRISTINE_ENV
         .deref()
         .iter()
@@ -186,6 +195,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::new(),
             env_var_hash: "".into(),
+            dir: None,
         };
         assert!(!have_config_files_been_modified(&watches, files));
 
@@ -193,6 +203,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::from([(fp.clone(), UNIX_EPOCH)]),
             env_var_hash: "".into(),
+            dir: None,
         };
         let files = BTreeSet::from([fp.clone()]);
         assert!(have_config_files_been_modified(&watches, files));
@@ -201,6 +212,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::from([(fp.clone(), modtime)]),
             env_var_hash: "".into(),
+            dir: None,
         };
         let files = BTreeSet::from([fp]);
         assert!(!have_config_files_been_modified(&watches, files));
@@ -212,6 +224,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::new(),
             env_var_hash: "".into(),
+            dir: None,
         };
         let serialized = serialize_watches(&watches).unwrap();
         let deserialized = deserialize_watches(serialized).unwrap();
@@ -224,10 +237,12 @@ mod tests {
         let serialized = serialize_watches(&HookEnvWatches {
             files: BTreeMap::from([("foo".into(), UNIX_EPOCH)]),
             env_var_hash: "testing-123".into(),
+            dir: None,
         })
         .unwrap();
         let deserialized = deserialize_watches(serialized).unwrap();
         assert_eq!(deserialized.files.len(), 1);
+        assert_eq!(deserialized.dir, None);
         assert_str_eq!(deserialized.env_var_hash, "testing-123");
         assert_eq!(
             deserialized
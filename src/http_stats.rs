@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use itertools::Itertools;
+
+use crate::env;
+use crate::file::format_bytes;
+
+static STATS: Mutex<Stats> = Mutex::new(Stats {
+    requests: BTreeMap::new(),
+    bytes: 0,
+    connections: 0,
+    time: Duration::ZERO,
+    cache_hits: 0,
+    cache_misses: 0,
+});
+
+/// counters for `MISE_HTTP_STATS=1`, printed to stderr when mise exits.
+/// Nothing is recorded unless it is enabled.
+#[derive(Debug, Default, PartialEq)]
+struct Stats {
+    requests: BTreeMap<String, u64>,
+    bytes: u64,
+    /// new connections, requests beyond these reused an idle connection. These are counted from
+    /// dns lookups so connections to ip addresses aren't included
+    connections: u64,
+    /// sum of the time spent in each request, this can be larger than the wall time since
+    /// requests run in parallel
+    time: Duration,
+    cache_hits: u64,
+    cache_misses: u64,
+}
+
+fn record<F: FnOnce(&mut Stats)>(f: F) {
+    if *env::MISE_HTTP_STATS {
+        f(&mut STATS.lock().unwrap());
+    }
+}
+
+pub fn request(host: &str, time: Duration) {
+    record(|s| {
+        *s.requests.entry(host.to_string()).or_default() += 1;
+        s.time += time;
+    });
+}
+
+pub fn connection() {
+    record(|s| s.connections += 1);
+}
+
+pub fn bytes(len: u64) {
+    record(|s| s.bytes += len);
+}
+
+/// time spent reading a response body after the request itself was recorded
+pub fn time(time: Duration) {
+    record(|s| s.time += time);
+}
+
+/// used by both the http response cache and the download cache
+pub fn cache(hit: bool) {
+    record(|s| match hit {
+        true => s.cache_hits += 1,
+        false => s.cache_misses += 1,
+    });
+}
+
+pub fn print() {
+    if *env::MISE_HTTP_STATS {
+        eprintln!("{}", STATS.lock().unwrap().render());
+    }
+}
+
+impl Stats {
+    fn render(&self) -> String {
+        let total: u64 = self.requests.values().sum();
+        let width = self
+            .requests
+            .keys()
+            .map(|h| h.len())
+            .max()
+            .unwrap_or_default();
+        let hosts = self
+            .requests
+            .iter()
+            .map(|(host, count)| format!("  {host:width$}  {count} requests"))
+            .join("\n");
+        let cache_total = self.cache_hits + self.cache_misses;
+        let ratio = match cache_total {
+            0 => "n/a".to_string(),
+            _ => format!(
+                "{:.0}%",
+                self.cache_hits as f64 / cache_total as f64 * 100.0
+            ),
+        };
+        let mut lines = vec!["mise http stats:".to_string()];
+        if !hosts.is_empty() {
+            lines.push(hosts);
+        }
+        lines.push(format!(
+            "  total: {total} requests, {} downloaded, {:.2}s in http",
+            format_bytes(self.bytes as f64),
+            self.time.as_secs_f64()
+        ));
+        lines.push(format!(
+            "  connections: {} opened, {} reused",
+            self.connections,
+            total.saturating_sub(self.connections)
+        ));
+        lines.push(format!(
+            "  cache: {} hits, {} misses ({ratio} hit rate)",
+            self.cache_hits, self.cache_misses
+        ));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let stats = Stats {
+            requests: BTreeMap::from([("github.com".into(), 3), ("nodejs.org".into(), 1)]),
+            bytes: 3 * 1024 * 1024,
+            connections: 2,
+            time: Duration::from_millis(1500),
+            cache_hits: 3,
+            cache_misses: 1,
+        };
+        assert_snapshot!(stats.render(), @r###"
+        mise http stats:
+          github.com  3 requests
+          nodejs.org  1 requests
+          total: 4 requests, 3.0 MiB downloaded, 1.50s in http
+          connections: 2 opened, 2 reused
+          cache: 3 hits, 1 misses (75% hit rate)
+        "###);
+        assert_snapshot!(Stats::default().render(), @r###"
+        mise http stats:
+          total: 0 requests, 0 B downloaded, 0.00s in http
+          connections: 0 opened, 0 reused
+          cache: 0 hits, 0 misses (n/a hit rate)
+        "###);
+    }
+}
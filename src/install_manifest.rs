@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use walkdir::WalkDir;
+
+use crate::config::settings::SETTINGS;
+use crate::file;
+use crate::file::display_path;
+use crate::hash::{file_hash_sha256, hash_sha256_to_str};
+use crate::toolset::ToolVersion;
+
+/// files are hashed on their own pool so `checksum_jobs` limits them no matter how many
+/// installs are running
+static POOL: Lazy<ThreadPool> = Lazy::new(|| {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(SETTINGS.checksum_jobs.unwrap_or_else(num_cpus::get).max(1))
+        .thread_name(|i| format!("checksum-{i}"))
+        .build()
+        .expect("failed to build checksum thread pool")
+});
+
+/// a sha256 of every file in an install dir, written when the install finishes so
+/// `mise install --verify` can later detect files that were modified, added or removed.
+/// It's stored next to the install dir, e.g.: `installs/node/.20.1.0.sha256sums`, in the same
+/// format as `sha256sum` output.
+pub fn path(tv: &ToolVersion) -> PathBuf {
+    let install_path = tv.install_path();
+    let name = install_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    tv.backend.installs_path.join(format!(".{name}.sha256sums"))
+}
+
+pub fn write(tv: &ToolVersion) -> Result<()> {
+    let checksums = hash_dir(&tv.install_path())?;
+    let body = checksums
+        .iter()
+        .map(|(path, hash)| format!("{hash}  {path}\n"))
+        .join("");
+    file::write(path(tv), body)
+}
+
+pub fn remove(tv: &ToolVersion) {
+    let path = path(tv);
+    if path.exists() {
+        let _ = file::remove_file(path);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verification {
+    Ok,
+    /// no checksums were recorded when this version was installed
+    Unverifiable,
+    Mismatch(Vec<String>),
+}
+
+pub fn verify(tv: &ToolVersion) -> Result<Verification> {
+    let path = path(tv);
+    if !path.exists() {
+        return Ok(Verification::Unverifiable);
+    }
+    let expected = parse(&file::read_to_string(&path)?);
+    let actual = hash_dir(&tv.install_path())?;
+    Ok(compare(&expected, &actual))
+}
+
+/// verifies each version concurrently, the results are in the same order as `tvs`
+pub fn verify_all(tvs: &[ToolVersion]) -> Vec<Result<Verification>> {
+    POOL.install(|| {
+        tvs.par_iter()
+            .map(|tv| verify(tv).wrap_err_with(|| format!("failed to verify {tv}")))
+            .collect()
+    })
+}
+
+fn compare(expected: &BTreeMap<String, String>, actual: &BTreeMap<String, String>) -> Verification {
+    let mut mismatches = vec![];
+    for (path, hash) in expected {
+        match actual.get(path) {
+            Some(h) if h == hash => {}
+            Some(_) => mismatches.push(format!("modified: {path}")),
+            None => mismatches.push(format!("missing: {path}")),
+        }
+    }
+    for path in actual.keys().filter(|p| !expected.contains_key(*p)) {
+        mismatches.push(format!("added: {path}"));
+    }
+    if mismatches.is_empty() {
+        Verification::Ok
+    } else {
+        Verification::Mismatch(mismatches)
+    }
+}
+
+fn parse(body: &str) -> BTreeMap<String, String> {
+    body.lines()
+        .filter_map(|l| l.split_once("  "))
+        .map(|(hash, path)| (path.to_string(), hash.to_string()))
+        .collect()
+}
+
+/// symlinks are recorded by the hash of their target instead of being followed
+fn hash_dir(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let entries = WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.file_type().is_dir())
+        .map(|e| e.into_path())
+        .collect_vec();
+    POOL.install(|| {
+        entries
+            .into_par_iter()
+            .map(|path| {
+                let rel = path.strip_prefix(dir)?.to_string_lossy().replace('\\', "/");
+                let hash = if path.is_symlink() {
+                    let target = fs::read_link(&path)?;
+                    hash_sha256_to_str(&target.to_string_lossy())
+                } else {
+                    file_hash_sha256(&path)
+                        .wrap_err_with(|| format!("failed to hash {}", display_path(&path)))?
+                };
+                Ok((rel, hash))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let tmp = tempfile::tempdir().unwrap();
+        file::create_dir_all(tmp.path().join("bin")).unwrap();
+        file::write(tmp.path().join("bin/tool"), "#!/bin/sh").unwrap();
+        file::write(tmp.path().join("README"), "hi").unwrap();
+        let expected = hash_dir(tmp.path()).unwrap();
+        let body = expected
+            .iter()
+            .map(|(path, hash)| format!("{hash}  {path}\n"))
+            .join("");
+        assert_eq!(parse(&body), expected);
+        assert_eq!(compare(&expected, &expected), Verification::Ok);
+
+        file::write(tmp.path().join("bin/tool"), "#!/bin/bash").unwrap();
+        file::remove_file(tmp.path().join("README")).unwrap();
+        file::write(tmp.path().join("extra file"), "").unwrap();
+        let actual = hash_dir(tmp.path()).unwrap();
+        assert_eq!(
+            compare(&expected, &actual),
+            Verification::Mismatch(vec![
+                "missing: README".into(),
+                "modified: bin/tool".into(),
+                "added: extra file".into(),
+            ])
+        );
+    }
+}
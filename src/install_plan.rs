@@ -0,0 +1,203 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use eyre::Result;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::backend;
+use crate::cli::args::BackendArg;
+use crate::hash::hash_to_str;
+use crate::install_manifest::{self, Verification};
+use crate::toolset::{ToolRequest, ToolVersion, ToolVersionOptions};
+use crate::{dirs, file};
+
+/// what `mise install` is going to install, written to the cache dir before anything is
+/// installed and updated as each tool finishes so `mise install --resume` can pick up where an
+/// interrupted install left off. Versions are stored as they were resolved the first time so
+/// resuming installs exactly the same versions.
+/// A tool is only marked done once it is fully installed, if mise is killed in the middle of
+/// an install the partial install is removed and the tool stays pending.
+#[derive(Debug)]
+pub struct InstallPlan {
+    path: PathBuf,
+    tools: Mutex<Vec<PlannedTool>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PlannedTool {
+    backend: String,
+    request: String,
+    #[serde(default, skip_serializing_if = "ToolVersionOptions::is_empty")]
+    options: ToolVersionOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(default)]
+    done: bool,
+}
+
+/// one plan per directory since the tools to install depend on the config files that apply
+pub fn path() -> PathBuf {
+    let cwd = dirs::cwd().unwrap_or_default();
+    dirs::CACHE
+        .join("install-plans")
+        .join(format!("{}.json", hash_to_str(&cwd)))
+}
+
+impl InstallPlan {
+    pub fn new(requests: &[ToolRequest]) -> Result<Self> {
+        let tools = requests
+            .iter()
+            .map(|tr| PlannedTool {
+                backend: tr.backend().to_string(),
+                request: tr.version(),
+                options: tr.options(),
+                version: None,
+                done: false,
+            })
+            .collect();
+        let plan = Self {
+            path: path(),
+            tools: Mutex::new(tools),
+        };
+        plan.save()?;
+        Ok(plan)
+    }
+
+    /// reads the plan left behind by an install that didn't finish
+    pub fn load() -> Result<Option<Self>> {
+        let path = path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let tools = serde_json::from_str(&file::read_to_string(&path)?)?;
+        Ok(Some(Self {
+            path,
+            tools: Mutex::new(tools),
+        }))
+    }
+
+    /// the requests that still need to be installed. Versions that were resolved before are
+    /// requested exactly. Tools marked done are skipped if their files still match the
+    /// checksums recorded when they were installed, otherwise they are installed again.
+    pub fn remaining(&self) -> Result<Vec<ToolRequest>> {
+        let mut tools = self.tools.lock().unwrap();
+        let mut requests = vec![];
+        for tool in tools.iter_mut() {
+            let backend = BackendArg::from(&tool.backend);
+            let mut tr =
+                ToolRequest::new_opts(backend.clone(), &tool.request, tool.options.clone())?;
+            if let (Some(version), ToolRequest::Version { .. } | ToolRequest::Prefix { .. }) =
+                (&tool.version, &tr)
+            {
+                tr = ToolRequest::new_opts(backend.clone(), version, tool.options.clone())?;
+            }
+            if tool.done {
+                if let Some(version) = &tool.version {
+                    let b = backend::get(&backend);
+                    let tv = ToolVersion::new(b.as_ref(), tr.clone(), version.clone());
+                    match install_manifest::verify(&tv)? {
+                        Verification::Ok | Verification::Unverifiable
+                            if b.is_version_installed(&tv, true) =>
+                        {
+                            info!("{tv} was already installed");
+                            continue;
+                        }
+                        Verification::Mismatch(_) => {
+                            warn!("{tv} was modified since it was installed, reinstalling");
+                            file::remove_all(tv.install_path())?;
+                        }
+                        _ => {}
+                    }
+                }
+                tool.done = false;
+            }
+            requests.push(tr);
+        }
+        drop(tools);
+        self.save()?;
+        Ok(requests)
+    }
+
+    /// records the version `tr` resolved to before it is installed
+    pub fn resolved(&self, tr: &ToolRequest, tv: &ToolVersion) {
+        self.update(tr, |tool| tool.version = Some(tv.version.clone()));
+    }
+
+    pub fn done(&self, tr: &ToolRequest) {
+        self.update(tr, |tool| tool.done = true);
+    }
+
+    /// removes the plan once everything in it has been installed
+    pub fn finish(&self) -> Result<()> {
+        if self.path.exists() {
+            file::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn update<F: Fn(&mut PlannedTool)>(&self, tr: &ToolRequest, f: F) {
+        let backend = tr.backend().to_string();
+        let request = tr.version();
+        let mut tools = self.tools.lock().unwrap();
+        // a resumed request may be for the version the original request resolved to
+        let tool = tools.iter_mut().find(|t| {
+            t.backend == backend && (t.request == request || t.version.as_ref() == Some(&request))
+        });
+        if let Some(tool) = tool {
+            f(tool);
+        }
+        drop(tools);
+        if let Err(err) = self.save() {
+            warn!("failed to update install plan: {err:#}");
+        }
+    }
+
+    /// written to a temp file first so it is never left half written if mise is killed
+    fn save(&self) -> Result<()> {
+        let tools = self.tools.lock().unwrap();
+        file::create_dir_all(self.path.parent().unwrap())?;
+        let tmp = self.path.with_extension("json.tmp");
+        file::write(&tmp, serde_json::to_string_pretty(&*tools)?)?;
+        file::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test::reset;
+
+    use super::*;
+
+    #[test]
+    fn test_install_plan() {
+        reset();
+        let tiny = BackendArg::from("tiny");
+        let requests = vec![
+            ToolRequest::new(tiny.clone(), "2").unwrap(),
+            ToolRequest::new(BackendArg::from("dummy"), "ref:master").unwrap(),
+        ];
+        let plan = InstallPlan::new(&requests).unwrap();
+        let tv = ToolVersion::new(
+            backend::get(&tiny).as_ref(),
+            requests[0].clone(),
+            "2.1.0".into(),
+        );
+        plan.resolved(&requests[0], &tv);
+
+        let plan = InstallPlan::load().unwrap().unwrap();
+        let remaining = plan.remaining().unwrap();
+        assert_eq!(
+            remaining
+                .iter()
+                .map(|tr| tr.to_string())
+                .collect::<Vec<_>>(),
+            vec!["tiny@2.1.0", "dummy@ref:master"]
+        );
+        plan.done(&remaining[1]);
+        assert!(plan.tools.lock().unwrap()[1].done);
+        plan.finish().unwrap();
+        assert!(InstallPlan::load().unwrap().is_none());
+    }
+}
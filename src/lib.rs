@@ -0,0 +1,116 @@
+//! mise as a library
+//!
+//! The [`api`] module loads the config for a directory and returns the tools and environment it
+//! resolves to without any of the side effects of the cli. The rest of the crate isn't public and
+//! can change between any releases.
+
+use std::process::exit;
+
+use color_eyre::{Section, SectionExt};
+use eyre::Report;
+use itertools::Itertools;
+
+use crate::cli::version::VERSION;
+use crate::cli::Cli;
+
+#[cfg(test)]
+#[macro_use]
+mod test;
+
+#[macro_use]
+mod output;
+
+#[macro_use]
+mod regex;
+
+#[macro_use]
+mod cmd;
+
+pub mod api;
+mod backend;
+pub(crate) mod build_time;
+mod cache;
+mod cli;
+mod config;
+mod default_shorthands;
+mod direnv;
+mod dirs;
+mod download_cache;
+pub(crate) mod duration;
+mod env;
+mod env_diff;
+mod errors;
+#[cfg_attr(windows, path = "fake_asdf_windows.rs")]
+mod fake_asdf;
+mod file;
+mod git;
+pub(crate) mod github;
+mod hash;
+mod hook_env;
+mod http;
+mod http_stats;
+mod install_context;
+mod install_manifest;
+mod install_plan;
+mod lock_file;
+mod logger;
+mod migrate;
+mod mise_lock;
+mod netrc;
+mod path_env;
+mod plugins;
+mod rand;
+mod redactions;
+mod registry;
+pub(crate) mod result;
+mod runtime_symlinks;
+mod shell;
+mod shims;
+mod shorthands;
+pub(crate) mod task;
+pub(crate) mod tera;
+pub(crate) mod timeout;
+mod toml;
+mod toolset;
+mod ui;
+
+/// runs the `mise` cli, this is what the `mise` binary calls
+#[doc(hidden)]
+pub fn cli_main() -> eyre::Result<()> {
+    let args = env::args().collect_vec();
+    color_eyre::install()?;
+
+    let res = Cli::run(&args).with_section(|| VERSION.to_string().header("Version:"));
+    http_stats::print();
+    match res {
+        Ok(()) => Ok(()),
+        Err(err) => handle_err(err),
+    }
+}
+
+fn handle_err(err: Report) -> eyre::Result<()> {
+    if let Some(err) = err.downcast_ref::<std::io::Error>() {
+        if err.kind() == std::io::ErrorKind::BrokenPipe {
+            return Ok(());
+        }
+    }
+    let code = errors::Error::exit_code(&err);
+    if cfg!(not(debug_assertions)) && log::max_level() < log::LevelFilter::Debug {
+        display_friendly_err(err);
+        exit(code);
+    }
+    if code == 1 {
+        return Err(err);
+    }
+    // same output as returning the error from main but with its exit code
+    eprintln!("Error: {err:?}");
+    exit(code);
+}
+
+fn display_friendly_err(err: Report) {
+    for err in err.chain() {
+        error!("{err}");
+    }
+    let msg = ui::style::edim("Run with --verbose or MISE_VERBOSE=1 for more information");
+    error!("{msg}");
+}
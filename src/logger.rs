@@ -1,9 +1,12 @@
 extern crate simplelog;
 
 use std::fs::{create_dir_all, File, OpenOptions};
+use std::io::Write;
 use std::path::Path;
 
 use eyre::Result;
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Log, Metadata, Record};
 use simplelog::*;
 
 use crate::config::Settings;
@@ -21,7 +24,11 @@ pub fn _init() {
     let settings = Settings::try_get().unwrap_or_else(|_| Default::default());
     let mut loggers: Vec<Box<dyn SharedLogger>> = vec![];
     let level = settings.log_level.parse().unwrap_or(LevelFilter::Info);
-    loggers.push(init_term_logger(level));
+    if *env::MISE_LOG_FORMAT_JSON {
+        loggers.push(Box::new(JsonLogger { level }));
+    } else {
+        loggers.push(init_term_logger(level));
+    }
 
     if let Some(log_file) = &*env::MISE_LOG_FILE {
         let file_level = env::MISE_LOG_FILE_LEVEL.unwrap_or(level);
@@ -64,6 +71,76 @@ fn init_term_logger(level: LevelFilter) -> Box<dyn SharedLogger> {
     )
 }
 
+/// writes each record to stderr as a json line with level, target, message and timestamp fields
+/// as well as any key-values attached to the record
+struct JsonLogger {
+    level: LevelFilter,
+}
+
+impl Log for JsonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level && !metadata.target().starts_with("globset")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = json_line(record, chrono::Utc::now());
+        let _ = writeln!(std::io::stderr().lock(), "{line}");
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stderr().flush();
+    }
+}
+
+impl SharedLogger for JsonLogger {
+    fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    fn config(&self) -> Option<&Config> {
+        None
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn Log> {
+        Box::new(*self)
+    }
+}
+
+fn json_line(record: &Record, now: chrono::DateTime<chrono::Utc>) -> String {
+    let mut fields = serde_json::Map::new();
+    fields.insert("timestamp".into(), now.to_rfc3339().into());
+    fields.insert(
+        "level".into(),
+        record.level().as_str().to_lowercase().into(),
+    );
+    fields.insert("target".into(), record.target().into());
+    let message = console::strip_ansi_codes(&record.args().to_string()).to_string();
+    fields.insert("message".into(), message.into());
+    let _ = record.key_values().visit(&mut JsonFields(&mut fields));
+    serde_json::Value::Object(fields).to_string()
+}
+
+struct JsonFields<'a>(&'a mut serde_json::Map<String, serde_json::Value>);
+
+impl<'kvs> VisitSource<'kvs> for JsonFields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        let value = if let Some(v) = value.to_u64() {
+            v.into()
+        } else if let Some(v) = value.to_i64() {
+            v.into()
+        } else if let Some(v) = value.to_bool() {
+            v.into()
+        } else {
+            value.to_string().into()
+        };
+        self.0.insert(key.to_string(), value);
+        Ok(())
+    }
+}
+
 fn init_write_logger(level: LevelFilter, log_path: &Path) -> Option<Box<dyn SharedLogger>> {
     match init_log_file(log_path) {
         Ok(log_file) => Some(WriteLogger::new(
@@ -89,4 +166,23 @@ mod tests {
     fn test_init() {
         init();
     }
+
+    #[test]
+    fn test_json_line() {
+        let now = chrono::DateTime::from_timestamp(0, 0).unwrap();
+        let url = "https://mise.jdx.dev";
+        let line = json_line(
+            &Record::builder()
+                .level(Level::Debug)
+                .target("mise::http")
+                .args(format_args!("GET {url} 200 OK"))
+                .key_values(&[("url", Value::from(url)), ("status", Value::from(200u16))])
+                .build(),
+            now,
+        );
+        assert_eq!(
+            line,
+            r#"{"level":"debug","message":"GET https://mise.jdx.dev 200 OK","status":200,"target":"mise::http","timestamp":"1970-01-01T00:00:00+00:00","url":"https://mise.jdx.dev"}"#
+        );
+    }
 }
@@ -0,0 +1,307 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use eyre::{bail, Result};
+use serde_derive::{Deserialize, Serialize};
+use url::Url;
+
+use crate::backend::Backend;
+use crate::cli::version::{ARCH, OS};
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::toolset::{ToolRequest, ToolSource, ToolVersion};
+use crate::{file, hash};
+
+pub const LOCKFILE_NAME: &str = "mise.lock";
+
+/// prevents parallel installs from clobbering each other's writes to the same lockfile
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    /// downloads made by the install running on this thread, see [`record`]
+    static DOWNLOADS: RefCell<Option<Vec<Download>>> = const { RefCell::new(None) };
+    /// the toolchain the install running on this thread built with, see [`record_compiler`]
+    static COMPILER: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// how the install running on this thread installed the tool, see [`record_strategy`]
+    static STRATEGY: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// `mise.lock` pins the exact version each tool resolved to along with the artifact that was
+/// downloaded for it on each platform. Tools and platforms are each their own table and keys are
+/// sorted so that changes to one tool don't touch the lines of another.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default)]
+    pub tools: BTreeMap<String, LockedTool>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedTool {
+    /// the version as written in the config file, e.g. "20"
+    pub requested: String,
+    /// the version it resolved to, e.g. "20.1.0"
+    pub version: String,
+    /// the version of the toolchain it was built with if the `compiler` option pinned one,
+    /// e.g. "1.78.0" for `cargo:ripgrep = { version = "14", compiler = "1.78" }`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler: Option<String>,
+    /// on each platform, whether a backend that can do either installed a "prebuilt" binary
+    /// or built it from "source"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub strategies: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub platforms: BTreeMap<String, Download>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Download {
+    pub url: String,
+    pub checksum: String,
+}
+
+/// what an install recorded to pin in the lockfile, see [`start_recording`]
+#[derive(Debug, Default)]
+pub struct Recording {
+    pub downloads: Vec<Download>,
+    pub compiler: Option<String>,
+    pub strategy: Option<String>,
+}
+
+impl Lockfile {
+    pub fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let body = file::read_to_string(path)?;
+        let lockfile = toml::from_str(&body)
+            .map_err(|err| eyre::eyre!("failed to parse {}: {err}", display_path(path)))?;
+        Ok(lockfile)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let body = toml::to_string_pretty(self)?;
+        file::write(path, body)
+    }
+}
+
+/// the lockfile that pins tools requested by the config file at `source`
+pub fn path_for(source: &ToolSource) -> Option<PathBuf> {
+    source.path().and_then(path_for_config)
+}
+
+pub fn path_for_config(config: &Path) -> Option<PathBuf> {
+    config.parent().map(|dir| dir.join(LOCKFILE_NAME))
+}
+
+pub fn platform() -> String {
+    format!("{}-{}", *OS, *ARCH)
+}
+
+/// resolves `tr` to the version pinned in the lockfile if it is still requested the same way,
+/// otherwise resolves it normally
+pub fn resolve(
+    tr: &ToolRequest,
+    backend: &dyn Backend,
+    source: &ToolSource,
+    latest_versions: bool,
+) -> Result<ToolVersion> {
+    if Settings::get().lockfile && !latest_versions {
+        if let Some(locked) = locked(source, tr)? {
+            return Ok(ToolVersion::new(backend, tr.clone(), locked.version));
+        }
+    }
+    tr.resolve(backend, latest_versions)
+}
+
+fn locked(source: &ToolSource, tr: &ToolRequest) -> Result<Option<LockedTool>> {
+    let Some(path) = path_for(source) else {
+        return Ok(None);
+    };
+    let lockfile = Lockfile::read(&path)?;
+    Ok(lockfile
+        .tools
+        .get(&tr.backend().short)
+        .filter(|t| t.requested == tr.version())
+        .cloned())
+}
+
+/// starts collecting the downloads made on this thread so they can be pinned once the install
+/// finishes
+pub fn start_recording() {
+    DOWNLOADS.with(|d| *d.borrow_mut() = Some(vec![]));
+    COMPILER.with(|c| c.borrow_mut().take());
+    STRATEGY.with(|s| s.borrow_mut().take());
+}
+
+pub fn finish_recording() -> Recording {
+    Recording {
+        downloads: DOWNLOADS
+            .with(|d| d.borrow_mut().take())
+            .unwrap_or_default(),
+        compiler: COMPILER.with(|c| c.borrow_mut().take()),
+        strategy: STRATEGY.with(|s| s.borrow_mut().take()),
+    }
+}
+
+/// called by backends that build with the toolchain pinned by the `compiler` option
+pub fn record_compiler(version: &str) {
+    COMPILER.with(|c| *c.borrow_mut() = Some(version.to_string()));
+}
+
+/// called by backends that install a prebuilt binary if there is one and build from source
+/// otherwise
+pub fn record_strategy(strategy: &str) {
+    STRATEGY.with(|s| *s.borrow_mut() = Some(strategy.to_string()));
+}
+
+/// called by the http client after a download completes
+pub fn record(url: &Url, path: &Path) {
+    let recording = DOWNLOADS.with(|d| d.borrow().is_some());
+    if !recording {
+        return;
+    }
+    let checksum = match hash::file_hash_sha256(path) {
+        Ok(hash) => format!("sha256:{hash}"),
+        Err(err) => {
+            debug!("failed to hash {}: {err:#}", display_path(path));
+            return;
+        }
+    };
+    let download = Download {
+        url: url.to_string(),
+        checksum,
+    };
+    DOWNLOADS.with(|d| d.borrow_mut().as_mut().map(|d| d.push(download)));
+}
+
+/// checks the artifact that was just installed against the lockfile and pins it. Fails if the
+/// lockfile already has a different checksum for this version on this platform.
+pub fn update(source: &ToolSource, tv: &ToolVersion, recording: Recording) -> Result<()> {
+    let Some(path) = path_for(source) else {
+        return Ok(());
+    };
+    let _guard = WRITE_LOCK.lock().unwrap();
+    let mut lockfile = Lockfile::read(&path)?;
+    let download = recording.downloads.into_iter().last();
+    let tool = lockfile.tools.entry(tv.backend.short.clone()).or_default();
+    let platform = platform();
+    if tool.version == tv.version {
+        if let (Some(locked), Some(download)) = (tool.platforms.get(&platform), &download) {
+            if locked.checksum != download.checksum {
+                bail!(
+                    "checksum mismatch for {tv} on {platform}\n{}: {}\ndownloaded: {} from {}",
+                    display_path(&path),
+                    locked.checksum,
+                    download.checksum,
+                    download.url,
+                );
+            }
+        }
+    } else {
+        tool.platforms.clear();
+        tool.strategies.clear();
+    }
+    tool.requested = tv.request.version();
+    tool.version = tv.version.clone();
+    tool.compiler = recording.compiler;
+    if let Some(strategy) = recording.strategy {
+        tool.strategies.insert(platform.clone(), strategy);
+    }
+    if let Some(download) = download {
+        tool.platforms.insert(platform, download);
+    }
+    lockfile.write(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+
+    use crate::backend;
+    use crate::cli::args::BackendArg;
+    use crate::test::reset;
+
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut lockfile = Lockfile::default();
+        lockfile.tools.insert(
+            "node".into(),
+            LockedTool {
+                requested: "20".into(),
+                version: "20.1.0".into(),
+                compiler: None,
+                strategies: Default::default(),
+                platforms: BTreeMap::from([(
+                    "linux-x64".into(),
+                    Download {
+                        url: "https://nodejs.org/dist/v20.1.0/node-v20.1.0-linux-x64.tar.gz".into(),
+                        checksum: "sha256:abc".into(),
+                    },
+                )]),
+            },
+        );
+        lockfile.tools.insert(
+            "cargo:eza".into(),
+            LockedTool {
+                requested: "latest".into(),
+                version: "0.18.0".into(),
+                compiler: Some("1.78.0".into()),
+                strategies: BTreeMap::from([("linux-x64".into(), "prebuilt".into())]),
+                ..Default::default()
+            },
+        );
+        let body = toml::to_string_pretty(&lockfile).unwrap();
+        assert_snapshot!(body, @r###"
+        [tools."cargo:eza"]
+        requested = "latest"
+        version = "0.18.0"
+        compiler = "1.78.0"
+
+        [tools."cargo:eza".strategies]
+        linux-x64 = "prebuilt"
+
+        [tools.node]
+        requested = "20"
+        version = "20.1.0"
+
+        [tools.node.platforms.linux-x64]
+        url = "https://nodejs.org/dist/v20.1.0/node-v20.1.0-linux-x64.tar.gz"
+        checksum = "sha256:abc"
+        "###);
+        assert_eq!(toml::from_str::<Lockfile>(&body).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn test_update_checksum_mismatch() {
+        reset();
+        let dir = tempfile::tempdir().unwrap();
+        let source = ToolSource::MiseToml(dir.path().join("mise.toml"));
+        let tiny = BackendArg::from("tiny");
+        let tr = ToolRequest::new(tiny.clone(), "2").unwrap();
+        let tv = ToolVersion::new(backend::get(&tiny).as_ref(), tr, "2.1.0".into());
+        let recording = |checksum: &str| Recording {
+            downloads: vec![Download {
+                url: "https://example.com/tiny-2.1.0.tar.gz".into(),
+                checksum: checksum.into(),
+            }],
+            ..Default::default()
+        };
+        update(&source, &tv, recording("sha256:abc")).unwrap();
+        update(&source, &tv, recording("sha256:abc")).unwrap();
+        let err = update(&source, &tv, recording("sha256:def")).unwrap_err();
+        assert!(err.to_string().starts_with(&format!(
+            "checksum mismatch for tiny@2.1.0 on {}",
+            platform()
+        )));
+        let lockfile = Lockfile::read(&dir.path().join(LOCKFILE_NAME)).unwrap();
+        assert_eq!(
+            lockfile.tools["tiny"].platforms[&platform()].checksum,
+            "sha256:abc"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use once_cell::sync::Lazy;
+
+use crate::{env, file};
+
+pub static NETRC: Lazy<Netrc> = Lazy::new(|| match file::read_to_string(&*env::NETRC) {
+    Ok(contents) => Netrc::parse(&contents),
+    Err(_) => Netrc::default(),
+});
+
+#[derive(Debug, Default)]
+pub struct Netrc {
+    machines: Vec<Machine>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Machine {
+    pub name: String,
+    pub login: Option<String>,
+    pub password: Option<String>,
+}
+
+impl Netrc {
+    /// parses the tokens of a netrc file. `default` entries are ignored since sending
+    /// credentials to every host would leak them to tool download hosts.
+    pub fn parse(contents: &str) -> Self {
+        let mut machines = vec![];
+        let mut current: Option<Machine> = None;
+        let mut tokens = contents
+            .lines()
+            .filter(|l| !l.trim_start().starts_with('#'))
+            .flat_map(|l| l.split_whitespace());
+        while let Some(token) = tokens.next() {
+            match token {
+                "machine" => {
+                    machines.extend(current.take());
+                    current = tokens.next().map(|name| Machine {
+                        name: name.to_string(),
+                        ..Default::default()
+                    });
+                }
+                "default" => {
+                    machines.extend(current.take());
+                }
+                "login" => {
+                    let login = tokens.next().map(|s| s.to_string());
+                    if let Some(m) = current.as_mut() {
+                        m.login = login;
+                    }
+                }
+                "password" => {
+                    let password = tokens.next().map(|s| s.to_string());
+                    if let Some(m) = current.as_mut() {
+                        m.password = password;
+                    }
+                }
+                "account" => {
+                    tokens.next();
+                }
+                // macros run until the end of the file as far as we're concerned
+                "macdef" => break,
+                _ => {}
+            }
+        }
+        machines.extend(current);
+        Self { machines }
+    }
+
+    pub fn get(&self, host: &str) -> Option<&Machine> {
+        self.machines
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(host) && m.login.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let netrc = Netrc::parse(
+            r#"
+# private artifacts
+machine artifacts.example.com
+  login alice
+  password s3cret
+machine other.example.com login bob password hunter2 account ignored
+default login anonymous password guest
+"#,
+        );
+        assert_eq!(
+            netrc.get("artifacts.example.com"),
+            Some(&Machine {
+                name: "artifacts.example.com".into(),
+                login: Some("alice".into()),
+                password: Some("s3cret".into()),
+            })
+        );
+        let other = netrc.get("OTHER.example.com").unwrap();
+        assert_eq!(other.login.as_deref(), Some("bob"));
+        assert_eq!(other.password.as_deref(), Some("hunter2"));
+        assert_eq!(netrc.get("github.com"), None);
+    }
+}
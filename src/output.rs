@@ -159,7 +159,7 @@ macro_rules! info {
 #[macro_export]
 macro_rules! info_unprefix {
     ($($arg:tt)*) => {{
-        if log::log_enabled!(log::Level::Debug) {
+        if log::log_enabled!(log::Level::Debug) || *$crate::env::MISE_LOG_FORMAT_JSON {
            log::info!($($arg)*);
         } else if log::log_enabled!(log::Level::Info) {
             $crate::ui::multi_progress_report::MultiProgressReport::suspend_if_active(|| {
@@ -183,7 +183,7 @@ macro_rules! info_unprefix_trunc {
 #[macro_export]
 macro_rules! warn {
     ($($arg:tt)*) => {{
-        if log::log_enabled!(log::Level::Debug) {
+        if log::log_enabled!(log::Level::Debug) || *$crate::env::MISE_LOG_FORMAT_JSON {
            log::warn!($($arg)*);
         } else if log::log_enabled!(log::Level::Warn) {
             $crate::ui::multi_progress_report::MultiProgressReport::suspend_if_active(|| {
@@ -198,7 +198,7 @@ macro_rules! warn {
 #[macro_export]
 macro_rules! error {
     ($($arg:tt)*) => {{
-        if log::log_enabled!(log::Level::Debug) {
+        if log::log_enabled!(log::Level::Debug) || *$crate::env::MISE_LOG_FORMAT_JSON {
            log::error!($($arg)*);
         } else if log::log_enabled!(log::Level::Error) {
             $crate::ui::multi_progress_report::MultiProgressReport::suspend_if_active(|| {
@@ -1,9 +1,9 @@
 use crate::config::{Config, Settings};
 use crate::default_shorthands::{DEFAULT_SHORTHANDS, TRUSTED_SHORTHANDS};
 use crate::env::MISE_FETCH_REMOTE_VERSIONS_TIMEOUT;
-use crate::errors::Error::PluginNotInstalled;
+use crate::errors::Error::{PluginNotFound, PluginNotInstalled};
 use crate::file::{display_path, remove_all};
-use crate::git::Git;
+use crate::git::{is_sha, Git};
 use crate::plugins::{Plugin, PluginList, PluginType, Script, ScriptManager};
 use crate::result::Result;
 use crate::timeout::run_with_timeout;
@@ -71,12 +71,68 @@ impl AsdfPlugin {
         self.repo.lock().unwrap()
     }
 
+    fn pin_file(&self) -> PathBuf {
+        self.plugin_path.join(".git").join("mise-pin")
+    }
+
+    /// the commit the plugin was pinned to by installing or updating it with `#<sha>`
+    pub fn pinned_sha(&self) -> Option<String> {
+        let pin = crate::file::read_to_string(self.pin_file()).ok()?;
+        Some(pin.trim().to_string())
+    }
+
+    /// errors if the plugin is pinned to a commit its checkout is no longer at
+    pub fn verify_pin(&self) -> eyre::Result<()> {
+        let Some(pin) = self.pinned_sha() else {
+            return Ok(());
+        };
+        let sha = self.repo().current_sha()?;
+        if sha != pin {
+            bail!(
+                "plugin:{name} is pinned to {pin} but {dir} is at {sha}\n\
+                 Run `mise plugins update {name}#{pin}` to restore it or \
+                 `mise plugins update {name}#<sha>` to move the pin",
+                name = self.name,
+                dir = display_path(&self.plugin_path),
+            );
+        }
+        Ok(())
+    }
+
+    /// pins the plugin to `gitref` if it is a commit sha, branches and tags can change under
+    /// the plugin whenever it is updated
+    fn record_pin(&self, git: &Git, gitref: Option<&str>) -> eyre::Result<()> {
+        let pin_file = self.pin_file();
+        match gitref.filter(|r| is_sha(r)) {
+            Some(gitref) => {
+                let sha = git.current_sha()?;
+                if !sha.starts_with(gitref) {
+                    bail!("plugin:{} checked out {sha} instead of {gitref}", self.name);
+                }
+                crate::file::write(&pin_file, &sha)?;
+            }
+            None => {
+                if pin_file.exists() {
+                    crate::file::remove_file(&pin_file)?;
+                }
+                if gitref.is_some() || self.repo_url.is_some() {
+                    warn!(
+                        "plugin:{} is installed from {}, which can change. Use <url>#<sha> to pin it to a commit",
+                        style(&self.name).blue().for_stderr(),
+                        gitref.unwrap_or("the default branch"),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn get_repo_url(&self, config: &Config) -> eyre::Result<String> {
         self.repo_url
             .clone()
             .or_else(|| self.repo().get_remote_url())
             .or_else(|| config.get_repo_url(&self.name))
-            .ok_or_else(|| eyre!("No repository found for plugin {}", self.name))
+            .ok_or_else(|| PluginNotFound(self.name.clone()).into())
     }
 
     fn exec_hook_post_plugin_update(
@@ -119,11 +175,12 @@ impl AsdfPlugin {
         Ok(())
     }
     pub fn fetch_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        self.verify_pin()?;
         let cmd = self.script_man.cmd(&Script::ListAll);
         let result = run_with_timeout(
             move || {
-                let result = cmd.stdout_capture().stderr_capture().unchecked().run()?;
-                Ok(result)
+                let cmd = cmd.stdout_capture().stderr_capture();
+                self.script_man.output(&Script::ListAll, cmd)
             },
             *MISE_FETCH_REMOTE_VERSIONS_TIMEOUT,
         )
@@ -293,8 +350,18 @@ impl Plugin for AsdfPlugin {
             );
             return Ok(());
         }
+        if let (None, Some(pin)) = (&gitref, self.pinned_sha()) {
+            warn!(
+                "plugin:{name} is pinned to {pin}, not updating. Run `mise plugins update {name}#<sha>` to move the pin",
+                name = style(&self.name).blue().for_stderr(),
+            );
+            return Ok(());
+        }
         pr.set_message("updating git repo".into());
-        let (pre, post) = git.update(gitref)?;
+        let (pre, post) = git.update(gitref.clone())?;
+        if gitref.is_some() {
+            self.record_pin(&git, gitref.as_deref())?;
+        }
         let sha = git.current_sha_short()?;
         let repo_url = self.get_remote_url()?.unwrap_or_default();
         self.exec_hook_post_plugin_update(pr, pre, post)?;
@@ -354,6 +421,7 @@ Plugins could support local directories in the future but for now a symlink is r
             pr.set_message(format!("checking out {ref_}"));
             git.update(Some(ref_.to_string()))?;
         }
+        self.record_pin(&git, repo_ref.as_deref())?;
         self.exec_hook(pr, "post-plugin-add")?;
 
         let sha = git.current_sha_short()?;
@@ -7,13 +7,12 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
 use crate::cmd::CmdLineRunner;
 use crate::file;
-use crate::github::GithubRelease;
-use crate::http::{HTTP, HTTP_FETCH};
+use crate::github;
+use crate::http::HTTP;
 use crate::install_context::InstallContext;
-use crate::plugins::core::CorePlugin;
+use crate::plugins::core::{CorePlugin, Platform};
 use crate::toolset::{ToolRequest, ToolVersion};
 use crate::ui::progress_report::SingleReport;
 
@@ -34,8 +33,7 @@ impl BunPlugin {
             Ok(None) => {}
             Err(e) => warn!("failed to fetch remote versions: {}", e),
         }
-        let releases: Vec<GithubRelease> =
-            HTTP_FETCH.json("https://api.github.com/repos/oven-sh/bun/releases?per_page=100")?;
+        let releases = github::list_releases("oven-sh/bun")?;
         let versions = releases
             .into_iter()
             .map(|r| r.tag_name)
@@ -59,11 +57,15 @@ impl BunPlugin {
     }
 
     fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> Result<PathBuf> {
+        if let Some(tarball_path) = CorePlugin::download_from_url_option(tv, pr)? {
+            return Ok(tarball_path);
+        }
+        let platform = Platform::for_tv(tv)?;
         let url = format!(
             "https://github.com/oven-sh/bun/releases/download/bun-v{}/bun-{}-{}.zip",
             tv.version,
-            os(),
-            arch()
+            os(&platform),
+            arch(&platform)
         );
         let filename = url.split('/').last().unwrap();
         let tarball_path = tv.download_path().join(filename);
@@ -77,13 +79,14 @@ impl BunPlugin {
     fn install(&self, ctx: &InstallContext, tarball_path: &Path) -> Result<()> {
         let filename = tarball_path.file_name().unwrap().to_string_lossy();
         ctx.pr.set_message(format!("installing {filename}"));
+        let platform = Platform::for_tv(&ctx.tv)?;
         file::remove_all(ctx.tv.install_path())?;
         file::create_dir_all(ctx.tv.install_path().join("bin"))?;
         file::unzip(tarball_path, &ctx.tv.download_path())?;
         file::rename(
             ctx.tv
                 .download_path()
-                .join(format!("bun-{}-{}", os(), arch()))
+                .join(format!("bun-{}-{}", os(&platform), arch(&platform)))
                 .join("bun"),
             self.bun_bin(&ctx.tv),
         )?;
@@ -123,26 +126,19 @@ impl Backend for BunPlugin {
     }
 }
 
-fn os() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "darwin"
-    } else if cfg!(target_os = "linux") {
-        "linux"
-    } else {
-        &OS
+fn os(platform: &Platform) -> &str {
+    match platform.os.as_str() {
+        "macos" => "darwin",
+        os => os,
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
-        if cfg!(target_feature = "avx2") {
-            "x64"
-        } else {
-            "x64-baseline"
-        }
-    } else if cfg!(target_arch = "aarch64") {
-        "aarch64"
-    } else {
-        &ARCH
+fn arch(platform: &Platform) -> &str {
+    match platform.arch.as_str() {
+        // emulated x64, e.g. under Rosetta, can't be assumed to have avx2
+        "x64" if cfg!(target_arch = "x86_64") && cfg!(target_feature = "avx2") => "x64",
+        "x64" => "x64-baseline",
+        "arm64" => "aarch64",
+        arch => arch,
     }
 }
@@ -8,14 +8,13 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
 use crate::cmd::CmdLineRunner;
 use crate::config::Config;
 use crate::file;
-use crate::github::GithubRelease;
-use crate::http::{HTTP, HTTP_FETCH};
+use crate::github;
+use crate::http::HTTP;
 use crate::install_context::InstallContext;
-use crate::plugins::core::CorePlugin;
+use crate::plugins::core::{CorePlugin, Platform};
 use crate::toolset::{ToolRequest, ToolVersion, Toolset};
 use crate::ui::progress_report::SingleReport;
 
@@ -36,8 +35,7 @@ impl DenoPlugin {
             Ok(None) => {}
             Err(e) => warn!("failed to fetch remote versions: {}", e),
         }
-        let releases: Vec<GithubRelease> =
-            HTTP_FETCH.json("https://api.github.com/repos/denoland/deno/releases?per_page=100")?;
+        let releases = github::list_releases("denoland/deno")?;
         let versions = releases
             .into_iter()
             .map(|r| r.tag_name)
@@ -62,11 +60,15 @@ impl DenoPlugin {
     }
 
     fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> Result<PathBuf> {
+        if let Some(tarball_path) = CorePlugin::download_from_url_option(tv, pr)? {
+            return Ok(tarball_path);
+        }
+        let platform = Platform::for_tv(tv)?;
         let url = format!(
             "https://github.com/denoland/deno/releases/download/v{}/deno-{}-{}.zip",
             tv.version,
-            arch(),
-            os()
+            arch(&platform),
+            os(&platform)
         );
         let filename = url.split('/').last().unwrap();
         let tarball_path = tv.download_path().join(filename);
@@ -145,22 +147,19 @@ impl Backend for DenoPlugin {
     }
 }
 
-fn os() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "apple-darwin"
-    } else if cfg!(target_os = "linux") {
-        "unknown-linux-gnu"
-    } else {
-        &OS
+fn os(platform: &Platform) -> &str {
+    match platform.os.as_str() {
+        "macos" => "apple-darwin",
+        "linux" => "unknown-linux-gnu",
+        "windows" => "pc-windows-msvc",
+        os => os,
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
-        "x86_64"
-    } else if cfg!(target_arch = "aarch64") {
-        "aarch64"
-    } else {
-        &ARCH
+fn arch(platform: &Platform) -> &str {
+    match platform.arch.as_str() {
+        "x64" => "x86_64",
+        "arm64" => "aarch64",
+        arch => arch,
     }
 }
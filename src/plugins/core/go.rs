@@ -1,6 +1,5 @@
 use std::collections::BTreeMap;
-use std::path::{Path, PathBuf};
-use std::thread;
+use std::path::PathBuf;
 
 use itertools::Itertools;
 use tempfile::tempdir_in;
@@ -8,15 +7,14 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::http::HTTP;
 use crate::install_context::InstallContext;
-use crate::plugins::core::CorePlugin;
+use crate::plugins::core::{CorePlugin, Platform};
 use crate::toolset::{ToolRequest, ToolVersion, Toolset};
 use crate::ui::progress_report::SingleReport;
-use crate::{cmd, env, file, hash};
+use crate::{cmd, env, file};
 
 #[derive(Debug)]
 pub struct GoPlugin {
@@ -111,42 +109,37 @@ impl GoPlugin {
             .execute()
     }
 
-    fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> eyre::Result<PathBuf> {
-        let settings = Settings::get();
-        let filename = format!("go{}.{}-{}.tar.gz", tv.version, platform(), arch());
-        let tarball_url = format!("{}/{}", &settings.go_download_mirror, &filename);
-        let tarball_path = tv.download_path().join(&filename);
-
-        thread::scope(|s| {
-            let checksum_handle = s.spawn(|| {
+    fn install(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> eyre::Result<()> {
+        let tmp_extract_path = tempdir_in(tv.install_path().parent().unwrap())?;
+        if let Some(tarball_path) = CorePlugin::download_from_url_option(tv, pr)? {
+            let tarball = tarball_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            pr.set_message(format!("installing {}", tarball));
+            file::untar(&tarball_path, tmp_extract_path.path())?;
+        } else {
+            let settings = Settings::get();
+            let p = Platform::for_tv(tv)?;
+            let filename = format!("go{}.{}-{}.tar.gz", tv.version, platform(&p), arch(&p));
+            let tarball_url = format!("{}/{}", &settings.go_download_mirror, &filename);
+            let tarball_path = tv.download_path().join(&filename);
+            let checksum = if settings.go_skip_checksum {
+                None
+            } else {
                 let checksum_url = format!("{}.sha256", &tarball_url);
-                HTTP.get_text(checksum_url)
-            });
-            pr.set_message(format!("downloading {filename}"));
-            HTTP.download_file(&tarball_url, &tarball_path, Some(pr))?;
-
-            if !settings.go_skip_checksum {
-                pr.set_message(format!("verifying {filename}"));
-                let checksum = checksum_handle.join().unwrap()?;
-                hash::ensure_checksum_sha256(&tarball_path, &checksum, Some(pr))?;
-            }
-            Ok(tarball_path)
-        })
-    }
+                Some(format!("sha256:{}", HTTP.get_text(checksum_url)?.trim()))
+            };
 
-    fn install(
-        &self,
-        tv: &ToolVersion,
-        pr: &dyn SingleReport,
-        tarball_path: &Path,
-    ) -> eyre::Result<()> {
-        let tarball = tarball_path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy();
-        pr.set_message(format!("installing {}", tarball));
-        let tmp_extract_path = tempdir_in(tv.install_path().parent().unwrap())?;
-        file::untar(tarball_path, tmp_extract_path.path())?;
+            pr.set_message(format!("downloading {filename}"));
+            HTTP.download_and_untar(
+                &tarball_url,
+                &tarball_path,
+                tmp_extract_path.path(),
+                checksum.as_deref(),
+                Some(pr),
+            )?;
+        }
         file::remove_all(tv.install_path())?;
         file::rename(tmp_extract_path.path().join("go"), tv.install_path())?;
         Ok(())
@@ -200,8 +193,7 @@ impl Backend for GoPlugin {
     }
 
     fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
-        let tarball_path = self.download(&ctx.tv, ctx.pr.as_ref())?;
-        self.install(&ctx.tv, ctx.pr.as_ref(), &tarball_path)?;
+        self.install(&ctx.tv, ctx.pr.as_ref())?;
         self.verify(&ctx.tv, ctx.pr.as_ref())?;
 
         Ok(())
@@ -243,22 +235,18 @@ impl Backend for GoPlugin {
     }
 }
 
-fn platform() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "darwin"
-    } else {
-        &OS
+fn platform(platform: &Platform) -> &str {
+    match platform.os.as_str() {
+        "macos" => "darwin",
+        os => os,
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
-        "amd64"
-    } else if cfg!(target_arch = "arm") {
-        "armv6l"
-    } else if cfg!(target_arch = "aarch64") {
-        "arm64"
-    } else {
-        &ARCH
+fn arch(platform: &Platform) -> &str {
+    match platform.arch.as_str() {
+        "x64" => "amd64",
+        "x86" => "386",
+        "arm" => "armv6l",
+        arch => arch,
     }
 }
@@ -12,7 +12,7 @@ use regex::Regex;
 use serde_derive::{Deserialize, Serialize};
 use versions::Versioning;
 
-use crate::backend::Backend;
+use crate::backend::{self, Backend};
 use crate::cache::{CacheManager, CacheManagerBuilder};
 use crate::cli::args::BackendArg;
 use crate::cli::version::{ARCH, OS};
@@ -21,10 +21,9 @@ use crate::config::Config;
 use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
 use crate::plugins::core::CorePlugin;
-use crate::plugins::VERSION_REGEX;
 use crate::toolset::{ToolRequest, ToolVersion, Toolset};
 use crate::ui::progress_report::SingleReport;
-use crate::{env, file, hash};
+use crate::{env, file};
 
 #[derive(Debug)]
 pub struct JavaPlugin {
@@ -140,9 +139,8 @@ impl JavaPlugin {
         let tarball_path = tv.download_path().join(filename);
 
         pr.set_message(format!("downloading {filename}"));
-        HTTP.download_file(&m.url, &tarball_path, Some(pr))?;
-
-        hash::ensure_checksum_sha256(&tarball_path, &m.sha256, Some(pr))?;
+        let checksum = format!("sha256:{}", m.sha256);
+        HTTP.download_file_with_checksum(&m.url, &tarball_path, &checksum, Some(pr))?;
 
         Ok(tarball_path)
     }
@@ -291,7 +289,7 @@ impl JavaPlugin {
         );
 
         let metadata = HTTP_FETCH
-            .json::<Vec<JavaMetadata>, _>(url)?
+            .json_stream::<Vec<JavaMetadata>, _>(url)?
             .into_iter()
             .filter(|m| {
                 m.file_type
@@ -395,7 +393,8 @@ impl Backend for JavaPlugin {
     }
 
     fn fuzzy_match_filter(&self, versions: Vec<String>, query: &str) -> eyre::Result<Vec<String>> {
-        let escaped_query = regex::escape(query.trim_end_matches('-'));
+        let orig_query = query;
+        let escaped_query = backend::version_query_regex(query.trim_end_matches('-'));
         let query = if query == "latest" {
             "[0-9].*"
         } else {
@@ -408,7 +407,7 @@ impl Backend for JavaPlugin {
                 if query == v {
                     return true;
                 }
-                if VERSION_REGEX.is_match(v) {
+                if backend::is_unrequested_prerelease(v, orig_query) {
                     return false;
                 }
                 query_regex.is_match(v)
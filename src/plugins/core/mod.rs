@@ -1,9 +1,12 @@
-use eyre::Result;
+use eyre::{bail, eyre, Result, WrapErr};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use std::ffi::OsString;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+pub use platform::Platform;
 pub use python::PythonPlugin;
 
 use crate::backend::{Backend, BackendMap};
@@ -13,7 +16,7 @@ use crate::config::settings::SETTINGS;
 use crate::config::Settings;
 use crate::env;
 use crate::env::PATH_KEY;
-use crate::http::HTTP_FETCH;
+use crate::http::{self, HTTP, HTTP_FETCH};
 #[cfg(unix)]
 use crate::plugins::core::bun::BunPlugin;
 #[cfg(unix)]
@@ -32,8 +35,10 @@ use crate::plugins::core::ruby_windows::RubyPlugin;
 #[cfg(unix)]
 use crate::plugins::core::zig::ZigPlugin;
 use crate::plugins::{Plugin, PluginList, PluginType};
+use crate::tera::BASE_CONTEXT;
 use crate::timeout::run_with_timeout;
 use crate::toolset::ToolVersion;
+use crate::ui::progress_report::SingleReport;
 
 #[cfg(unix)]
 mod bun;
@@ -46,6 +51,7 @@ mod go;
 #[cfg(unix)]
 mod java;
 mod node;
+mod platform;
 mod python;
 #[cfg(unix)]
 mod ruby;
@@ -109,7 +115,8 @@ impl CorePlugin {
     pub fn new(fa: BackendArg) -> Self {
         Self {
             remote_version_cache: CacheManagerBuilder::new(
-                fa.cache_path.join("remote_versions.msgpack.z"),
+                fa.remote_versions_cache_path
+                    .join("remote_versions.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .with_cache_key(SETTINGS.node.mirror_url.clone().unwrap_or_default())
@@ -125,6 +132,66 @@ impl CorePlugin {
         Ok(env::join_paths(path)?)
     }
 
+    /// downloads `tv` from the `url` tool option instead of the tool's default host, returns
+    /// `None` if the option is not set
+    ///
+    /// the download is verified against `checksum_url` if that option is set, it may point to a
+    /// file with just the hash or a shasums listing that includes the downloaded file. Both are
+    /// requested with the `user_agent` option as the `User-Agent` if it is set.
+    pub fn download_from_url_option(
+        tv: &ToolVersion,
+        pr: &dyn SingleReport,
+    ) -> Result<Option<PathBuf>> {
+        let opts = tv.request.options();
+        let Some(url) = opts.get("url") else {
+            return Ok(None);
+        };
+        let url = render_url_option(tv, "url", url)?;
+        let filename = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|u| u.rsplit('/').next())
+            .filter(|f| !f.is_empty())
+            .ok_or_else(|| eyre!("url for {tv} does not end in a file name: {url}"))?
+            .to_string();
+        let tarball_path = tv.download_path().join(&filename);
+        // a template that renders a url that doesn't exist, e.g.: for a version that was never
+        // released, fails before the checksum file or anything else is downloaded
+        if !tarball_path.exists() {
+            match HTTP.head(&url) {
+                Err(err) if http::error_code(&err) == Some(404) => {
+                    bail!(
+                        "url for {tv} does not exist, check the version and the url option: {url}"
+                    )
+                }
+                Err(err) => debug!("HEAD {url} failed, downloading it anyway: {err:#}"),
+                Ok(_) => {}
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        if let Some(user_agent) = opts.get("user_agent") {
+            let user_agent = HeaderValue::from_str(user_agent)
+                .wrap_err_with(|| format!("invalid user_agent for {tv}: {user_agent}"))?;
+            headers.insert(USER_AGENT, user_agent);
+        }
+        pr.set_message(format!("downloading {filename}"));
+        match opts.get("checksum_url") {
+            Some(checksum_url) => {
+                let checksum_url = render_url_option(tv, "checksum_url", checksum_url)?;
+                HTTP.download_file_with_checksum_file(
+                    &url,
+                    &tarball_path,
+                    &checksum_url,
+                    &headers,
+                    Some(pr),
+                )?;
+            }
+            None => HTTP.download_file_with_headers(&url, &tarball_path, &headers, Some(pr))?,
+        }
+        Ok(Some(tarball_path))
+    }
+
     pub fn run_fetch_task_with_timeout<F, T>(f: F) -> Result<T>
     where
         F: FnOnce() -> Result<T> + Send,
@@ -139,10 +206,15 @@ impl CorePlugin {
             return Ok(None);
         }
         // using http is not a security concern and enabling tls makes mise significantly slower
-        let raw = match settings.paranoid {
-            true => HTTP_FETCH.get_text(format!("https://mise-versions.jdx.dev/{}", &self.fa.name)),
-            false => HTTP_FETCH.get_text(format!("http://mise-versions.jdx.dev/{}", &self.fa.name)),
-        }?;
+        let url = match settings.paranoid {
+            true => format!("https://mise-versions.jdx.dev/{}", &self.fa.name),
+            false => format!("http://mise-versions.jdx.dev/{}", &self.fa.name),
+        };
+        let cache_path = self
+            .fa
+            .remote_versions_cache_path
+            .join("remote_versions.list.json");
+        let raw = HTTP_FETCH.get_text_incremental(url, &cache_path)?;
         let versions = raw
             .lines()
             .map(|v| v.trim().to_string())
@@ -152,6 +224,24 @@ impl CorePlugin {
     }
 }
 
+/// renders a url template from the tool options with `version`, `os` and `arch` added to the
+/// usual template context
+fn render_url_option(tv: &ToolVersion, key: &str, template: &str) -> Result<String> {
+    let platform = Platform::for_tv(tv)?;
+    let mut ctx = BASE_CONTEXT.clone();
+    ctx.insert("version", &tv.version);
+    ctx.insert("os", &platform.os);
+    ctx.insert("arch", &platform.arch);
+    tera::Tera::one_off(template, &ctx, false).map_err(|err| {
+        // tera puts the useful part of the message, e.g. the missing variable, in the source
+        let cause = std::iter::successors(Some(&err as &dyn std::error::Error), |e| e.source())
+            .last()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        eyre!("invalid {key} template for {tv}: {template}\n{cause}\navailable variables are version, os, arch and env")
+    })
+}
+
 impl Plugin for CorePlugin {
     fn name(&self) -> &str {
         &self.fa.name
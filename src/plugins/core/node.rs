@@ -7,14 +7,13 @@ use tempfile::tempdir_in;
 use url::Url;
 
 use crate::backend::Backend;
-use crate::build_time::built_info;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::settings::{DEFAULT_NODE_MIRROR_URL, SETTINGS};
 use crate::config::{Config, Settings};
 use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
-use crate::plugins::core::CorePlugin;
+use crate::plugins::core::{CorePlugin, Platform};
 use crate::toolset::ToolVersion;
 use crate::ui::progress_report::SingleReport;
 use crate::{env, file, hash, http};
@@ -46,13 +45,14 @@ impl NodePlugin {
     fn fetch_remote_versions_from_node(&self, base: &Url) -> Result<Vec<String>> {
         let settings = Settings::get();
         let versions = HTTP_FETCH
-            .json::<Vec<NodeVersion>, _>(base.join("index.json")?)?
+            .json_stream::<Vec<NodeVersion>, _>(base.join("index.json")?)?
             .into_iter()
             .filter(|v| {
                 if let Some(flavor) = &settings.node.flavor {
+                    let p = Platform::host();
                     v.files
                         .iter()
-                        .any(|f| f == &format!("{}-{}-{}", os(), arch(), flavor))
+                        .any(|f| f == &format!("{}-{}-{}", os(&p), arch(&p), flavor))
                 } else {
                     true
                 }
@@ -91,10 +91,7 @@ impl NodePlugin {
         let tmp_extract_path = tempdir_in(opts.install_path.parent().unwrap())?;
         file::untar(&opts.binary_tarball_path, tmp_extract_path.path())?;
         file::remove_all(&opts.install_path)?;
-        file::rename(
-            tmp_extract_path.path().join(slug(&opts.version)),
-            &opts.install_path,
-        )?;
+        file::rename(tmp_extract_path.path().join(&opts.slug), &opts.install_path)?;
         Ok(())
     }
 
@@ -115,10 +112,7 @@ impl NodePlugin {
         let tmp_extract_path = tempdir_in(opts.install_path.parent().unwrap())?;
         file::unzip(&opts.binary_tarball_path, tmp_extract_path.path())?;
         file::remove_all(&opts.install_path)?;
-        file::rename(
-            tmp_extract_path.path().join(slug(&opts.version)),
-            &opts.install_path,
-        )?;
+        file::rename(tmp_extract_path.path().join(&opts.slug), &opts.install_path)?;
         Ok(())
     }
 
@@ -294,6 +288,10 @@ impl Backend for NodePlugin {
         &self.core.fa
     }
 
+    fn is_build_heavy(&self) -> bool {
+        Settings::get().node.compile == Some(true)
+    }
+
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -384,6 +382,8 @@ impl Backend for NodePlugin {
 #[derive(Debug)]
 struct BuildOpts {
     version: String,
+    /// the name of the precompiled tarball without the extension
+    slug: String,
     path: Vec<PathBuf>,
     install_path: PathBuf,
     build_dir: PathBuf,
@@ -404,14 +404,16 @@ impl BuildOpts {
         let install_path = ctx.tv.install_path();
         let source_tarball_name = format!("node-v{v}.tar.gz");
 
-        let slug = slug(v);
-        #[cfg(windows)]
-        let binary_tarball_name = format!("{slug}.zip");
-        #[cfg(not(windows))]
-        let binary_tarball_name = format!("{slug}.tar.gz");
+        let platform = Platform::for_tv(&ctx.tv)?;
+        let slug = slug(v, &platform);
+        let binary_tarball_name = match platform.os.as_str() {
+            "windows" => format!("{slug}.zip"),
+            _ => format!("{slug}.tar.gz"),
+        };
 
         Ok(Self {
             version: v.clone(),
+            slug,
             path: ctx.ts.list_paths(),
             build_dir: env::MISE_TMP_DIR.join(format!("node-v{v}")),
             configure_cmd: configure_cmd(&install_path),
@@ -464,45 +466,29 @@ fn make_install_cmd() -> String {
     make_install_cmd
 }
 
-fn os() -> &'static str {
-    if cfg!(target_os = "linux") {
-        "linux"
-    } else if cfg!(target_os = "macos") {
-        "darwin"
-    } else if cfg!(target_os = "windows") {
-        "win"
-    } else {
-        built_info::CFG_OS
+fn os(platform: &Platform) -> &str {
+    match platform.os.as_str() {
+        "macos" => "darwin",
+        "windows" => "win",
+        os => os,
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86") {
-        "x86"
-    } else if cfg!(target_arch = "x86_64") {
-        "x64"
-    } else if cfg!(target_arch = "arm") {
-        if cfg!(target_feature = "v6") {
-            "armv6l"
-        } else {
-            "armv7l"
-        }
-    } else if cfg!(target_arch = "loongarch64") {
-        "loong64"
-    } else if cfg!(target_arch = "riscv64") {
-        "riscv64"
-    } else if cfg!(target_arch = "aarch64") {
-        "arm64"
-    } else {
-        built_info::CFG_TARGET_ARCH
+fn arch(platform: &Platform) -> &str {
+    match platform.arch.as_str() {
+        "arm" if cfg!(target_feature = "v6") => "armv6l",
+        "arm" => "armv7l",
+        "loongarch64" => "loong64",
+        arch => arch,
     }
 }
 
-fn slug(v: &str) -> String {
+fn slug(v: &str, platform: &Platform) -> String {
+    let (os, arch) = (os(platform), arch(platform));
     if let Some(flavor) = &SETTINGS.node.flavor {
-        format!("node-v{v}-{}-{}-{flavor}", os(), arch())
+        format!("node-v{v}-{os}-{arch}-{flavor}")
     } else {
-        format!("node-v{v}-{}-{}", os(), arch())
+        format!("node-v{v}-{os}-{arch}")
     }
 }
 
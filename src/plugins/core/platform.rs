@@ -0,0 +1,106 @@
+use eyre::{bail, Result};
+
+use crate::cli::version::{ARCH, OS};
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+
+/// the os and arch to download a tool for. This is the host unless the tool sets the `os` or
+/// `arch` options, e.g.: `node = { version = "20", arch = "x64" }` to use x64 builds under Rosetta
+///
+/// values use the same names as `OS` and `ARCH`: linux, macos or windows and x64, arm64, x86 or arm
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    pub os: String,
+    pub arch: String,
+}
+
+/// the arches each os can be overridden to
+const SUPPORTED: &[(&str, &[&str])] = &[
+    ("linux", &["x64", "arm64", "x86", "arm"]),
+    ("macos", &["x64", "arm64"]),
+    ("windows", &["x64", "arm64", "x86"]),
+];
+
+impl Platform {
+    pub fn host() -> Self {
+        Self {
+            os: OS.to_string(),
+            arch: ARCH.to_string(),
+        }
+    }
+
+    pub fn for_tv(tv: &ToolVersion) -> Result<Self> {
+        Self::from_options(&tv.request.options())
+            .map_err(|err| err.wrap_err(format!("invalid platform for {tv}")))
+    }
+
+    pub fn from_options(opts: &ToolVersionOptions) -> Result<Self> {
+        let host = Self::host();
+        if !opts.contains_key("os") && !opts.contains_key("arch") {
+            return Ok(host);
+        }
+        let os = match opts.get("os").map(|s| s.as_str()) {
+            None => host.os,
+            Some("linux") => "linux".to_string(),
+            Some("macos" | "darwin") => "macos".to_string(),
+            Some("windows" | "win") => "windows".to_string(),
+            Some(os) => bail!("unknown os: {os}, expected linux, macos or windows"),
+        };
+        let arch = match opts.get("arch").map(|s| s.as_str()) {
+            None => host.arch,
+            Some("x64" | "x86_64" | "amd64") => "x64".to_string(),
+            Some("arm64" | "aarch64") => "arm64".to_string(),
+            Some("x86" | "i686" | "386") => "x86".to_string(),
+            Some("arm" | "armv7") => "arm".to_string(),
+            Some(arch) => bail!("unknown arch: {arch}, expected x64, arm64, x86 or arm"),
+        };
+        let supported = SUPPORTED
+            .iter()
+            .find(|(o, _)| *o == os)
+            .is_some_and(|(_, arches)| arches.contains(&arch.as_str()));
+        if !supported {
+            bail!("{arch} is not a supported arch for {os}");
+        }
+        Ok(Self { os, arch })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn platform(opts: &[(&str, &str)]) -> Result<Platform> {
+        let opts = ToolVersionOptions {
+            opts: opts
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        Platform::from_options(&opts)
+    }
+
+    #[test]
+    fn test_platform_from_options() {
+        assert_eq!(platform(&[]).unwrap(), Platform::host());
+        let p = platform(&[("os", "darwin"), ("arch", "x86_64")]).unwrap();
+        assert_eq!((p.os.as_str(), p.arch.as_str()), ("macos", "x64"));
+        let p = platform(&[("os", "linux"), ("arch", "aarch64")]).unwrap();
+        assert_eq!((p.os.as_str(), p.arch.as_str()), ("linux", "arm64"));
+        assert_eq!(
+            platform(&[("os", "macos"), ("arch", "x86")])
+                .unwrap_err()
+                .to_string(),
+            "x86 is not a supported arch for macos"
+        );
+        assert_eq!(
+            platform(&[("arch", "sparc")]).unwrap_err().to_string(),
+            "unknown arch: sparc, expected x64, arm64, x86 or arm"
+        );
+        assert_eq!(
+            platform(&[("os", "beos")]).unwrap_err().to_string(),
+            "unknown os: beos, expected linux, macos or windows"
+        );
+    }
+}
@@ -339,6 +339,10 @@ impl Backend for PythonPlugin {
         &self.core.fa
     }
 
+    fn is_build_heavy(&self) -> bool {
+        cfg!(windows) || Settings::get().python_compile == Some(true)
+    }
+
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         if Settings::get().python_compile == Some(false) {
             Ok(self
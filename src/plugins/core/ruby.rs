@@ -345,6 +345,9 @@ impl Backend for RubyPlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn is_build_heavy(&self) -> bool {
+        true
+    }
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
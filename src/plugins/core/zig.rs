@@ -7,15 +7,14 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
 use crate::cmd::CmdLineRunner;
-use crate::file;
-use crate::github::GithubRelease;
+use crate::github;
 use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
-use crate::plugins::core::CorePlugin;
+use crate::plugins::core::{CorePlugin, Platform};
 use crate::toolset::{ToolRequest, ToolVersion};
 use crate::ui::progress_report::SingleReport;
+use crate::{env, file};
 
 #[derive(Debug)]
 pub struct ZigPlugin {
@@ -49,8 +48,7 @@ impl ZigPlugin {
             Err(e) => warn!("failed to fetch remote versions: {}", e),
         }
 
-        let releases: Vec<GithubRelease> =
-            HTTP_FETCH.json("https://api.github.com/repos/ziglang/zig/releases?per_page=100")?;
+        let releases = github::list_releases("ziglang/zig")?;
         let versions = releases
             .into_iter()
             .map(|r| r.tag_name)
@@ -61,27 +59,27 @@ impl ZigPlugin {
     }
 
     fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> Result<PathBuf> {
+        if let Some(tarball_path) = CorePlugin::download_from_url_option(tv, pr)? {
+            return Ok(tarball_path);
+        }
+        let platform = Platform::for_tv(tv)?;
+        let (os, arch) = (os(&platform), arch(&platform));
         let url = if tv.version == "ref:master" {
             format!(
                 "https://ziglang.org/builds/zig-{}-{}-{}.tar.xz",
-                os(),
-                arch(),
+                os,
+                arch,
                 self.get_master_version()?
             )
         } else if regex!(r"^[0-9]+\.[0-9]+\.[0-9]+-dev.[0-9]+\+[0-9a-f]+$").is_match(&tv.version) {
             format!(
                 "https://ziglang.org/builds/zig-{}-{}-{}.tar.xz",
-                os(),
-                arch(),
-                tv.version
+                os, arch, tv.version
             )
         } else {
             format!(
                 "https://ziglang.org/download/{}/zig-{}-{}-{}.tar.xz",
-                tv.version,
-                os(),
-                arch(),
-                tv.version
+                tv.version, os, arch, tv.version
             )
         };
 
@@ -89,7 +87,10 @@ impl ZigPlugin {
         let tarball_path = tv.download_path().join(filename);
 
         pr.set_message(format!("downloading {filename}"));
-        HTTP.download_file(&url, &tarball_path, Some(pr))?;
+        match self.tarball_size(&tv.version, &platform) {
+            Some(size) => HTTP.download_file_with_size(&url, &tarball_path, size, Some(pr))?,
+            None => HTTP.download_file(&url, &tarball_path, Some(pr))?,
+        }
 
         Ok(tarball_path)
     }
@@ -97,13 +98,14 @@ impl ZigPlugin {
     fn install(&self, ctx: &InstallContext, tarball_path: &Path) -> Result<()> {
         let filename = tarball_path.file_name().unwrap().to_string_lossy();
         ctx.pr.set_message(format!("installing {filename}"));
+        let platform = Platform::for_tv(&ctx.tv)?;
         file::remove_all(ctx.tv.install_path())?;
         untar_xy(tarball_path, &ctx.tv.download_path())?;
         file::rename(
             ctx.tv.download_path().join(format!(
                 "zig-{}-{}-{}",
-                os(),
-                arch(),
+                os(&platform),
+                arch(&platform),
                 if ctx.tv.version == "ref:master" {
                     self.get_master_version()?
                 } else {
@@ -125,9 +127,28 @@ impl ZigPlugin {
         self.test_zig(ctx)
     }
 
+    fn download_index(&self) -> Result<serde_json::Value> {
+        HTTP_FETCH.fetch_cached(
+            "https://ziglang.org/download/index.json",
+            *env::MISE_FETCH_REMOTE_VERSIONS_CACHE,
+        )
+    }
+
+    /// the size of the tarball listed in the download index, dev builds other than the current
+    /// master aren't listed
+    fn tarball_size(&self, version: &str, platform: &Platform) -> Option<u64> {
+        let index = match self.download_index() {
+            Ok(index) => index,
+            Err(err) => {
+                debug!("failed to fetch zig download index: {err:#}");
+                return None;
+            }
+        };
+        tarball_size(&index, version, platform)
+    }
+
     fn get_master_version(&self) -> Result<String> {
-        let version_json: serde_json::Value =
-            HTTP_FETCH.json("https://ziglang.org/download/index.json")?;
+        let version_json = self.download_index()?;
         let master_version = version_json
             .pointer("/master/version")
             .and_then(|v| v.as_str())
@@ -161,29 +182,33 @@ impl Backend for ZigPlugin {
     }
 }
 
-fn os() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "macos"
-    } else if cfg!(target_os = "linux") {
-        "linux"
-    } else if cfg!(target_os = "freebsd") {
-        "freebsd"
-    } else {
-        &OS
+fn tarball_size(index: &serde_json::Value, version: &str, platform: &Platform) -> Option<u64> {
+    let master = index.pointer("/master/version").and_then(|v| v.as_str());
+    let key = match version {
+        "ref:master" => "master",
+        v if master == Some(v) => "master",
+        v => v,
+    };
+    let entry = index
+        .get(key)?
+        .get(format!("{}-{}", arch(platform), os(platform)))?;
+    // the index has sizes as strings
+    match entry.get("size")? {
+        serde_json::Value::String(s) => s.parse().ok(),
+        v => v.as_u64(),
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
-        "x86_64"
-    } else if cfg!(target_arch = "aarch64") {
-        "aarch64"
-    } else if cfg!(target_arch = "arm") {
-        "armv7a"
-    } else if cfg!(target_arch = "riscv64") {
-        "riscv64"
-    } else {
-        &ARCH
+fn os(platform: &Platform) -> &str {
+    platform.os.as_str()
+}
+
+fn arch(platform: &Platform) -> &str {
+    match platform.arch.as_str() {
+        "x64" => "x86_64",
+        "arm64" => "aarch64",
+        "arm" => "armv7a",
+        arch => arch,
     }
 }
 
@@ -209,3 +234,27 @@ pub fn untar_xy(archive: &Path, dest: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tarball_size() {
+        let p = Platform::host();
+        let platform = format!("{}-{}", arch(&p), os(&p));
+        let index = serde_json::json!({
+            "master": {"version": "0.14.0-dev.1+abc", platform.clone(): {"size": "456"}},
+            "0.13.0": {platform: {"size": "123"}, "x86_64-macos": {"size": "789"}},
+        });
+        assert_eq!(tarball_size(&index, "0.13.0", &p), Some(123));
+        assert_eq!(tarball_size(&index, "ref:master", &p), Some(456));
+        assert_eq!(tarball_size(&index, "0.14.0-dev.1+abc", &p), Some(456));
+        assert_eq!(tarball_size(&index, "0.12.0", &p), None);
+        let rosetta = Platform {
+            os: "macos".into(),
+            arch: "x64".into(),
+        };
+        assert_eq!(tarball_size(&index, "0.13.0", &rosetta), Some(789));
+    }
+}
@@ -2,9 +2,12 @@ use std::path::Path;
 
 use color_eyre::eyre::eyre;
 use color_eyre::Result;
-use eyre::WrapErr;
+use eyre::{ensure, WrapErr};
 use toml_edit::{DocumentMut, Item, Value};
+use versions::Versioning;
 
+use crate::cli::version;
+use crate::ui::style;
 use crate::{file, parse_error};
 
 #[derive(Debug, Default, Clone)]
@@ -19,6 +22,8 @@ pub struct MisePluginToml {
     pub list_aliases: MisePluginTomlScriptConfig,
     pub list_bin_paths: MisePluginTomlScriptConfig,
     pub list_legacy_filenames: MisePluginTomlScriptConfig,
+    /// the oldest mise that can run this plugin, no constraint if unset
+    pub min_mise_version: Option<Versioning>,
 }
 
 impl MisePluginToml {
@@ -49,6 +54,9 @@ impl MisePluginToml {
                 "list-legacy-filenames" => {
                     self.list_legacy_filenames = self.parse_script_config(k, v)?
                 }
+                "min_mise_version" | "min-mise-version" => {
+                    self.min_mise_version = Some(self.parse_version(k, v)?)
+                }
                 // this is an old key used in rtx-python
                 // this file is invalid, so just stop parsing entirely if we see it
                 "legacy-filenames" => return Ok(()),
@@ -92,6 +100,18 @@ impl MisePluginToml {
         }
     }
 
+    fn parse_version(&mut self, k: &str, v: &Item) -> Result<Versioning> {
+        match v.as_str().and_then(Versioning::new) {
+            Some(v) => Ok(v),
+            _ => parse_error!(k, v, "version string"),
+        }
+    }
+
+    /// fails if the running mise is older than `min_mise_version`
+    pub fn ensure_mise_version(&self, plugin: &str) -> Result<()> {
+        check_mise_version(plugin, self.min_mise_version.as_ref(), &version::V)
+    }
+
     fn parse_string(&mut self, k: &str, v: &Value) -> Result<String> {
         match v.as_str() {
             Some(v) => Ok(v.to_string()),
@@ -100,6 +120,20 @@ impl MisePluginToml {
     }
 }
 
+fn check_mise_version(plugin: &str, min: Option<&Versioning>, cur: &Versioning) -> Result<()> {
+    if let Some(min) = min {
+        ensure!(
+            cur >= min,
+            "plugin {} requires mise {} or newer, but you are using {}\nupgrade mise, e.g. with {}",
+            style::eblue(plugin),
+            style::eyellow(min),
+            style::eyellow(cur),
+            style::eyellow("mise self-update"),
+        );
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use indoc::formatdoc;
@@ -142,6 +176,31 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_min_mise_version() {
+        let cf = parse(r#"min_mise_version = "2024.1.0-rc.1""#);
+        let min = cf.min_mise_version.as_ref();
+        let check = |cur: &str| check_mise_version("tiny", min, &Versioning::new(cur).unwrap());
+        assert!(check("2024.1.0-rc.1").is_ok());
+        assert!(check("2024.1.0-rc.2").is_ok());
+        assert!(check("2024.1.0").is_ok());
+        assert!(check("2024.10.1").is_ok());
+        assert!(check("2024.1.0-beta.3").is_err());
+        assert!(check("2023.12.40").is_err());
+
+        let cf = parse(r#"min-mise-version = "2024.1.0""#);
+        let min = cf.min_mise_version.as_ref();
+        let check = |cur: &str| check_mise_version("tiny", min, &Versioning::new(cur).unwrap());
+        assert!(check("2024.1.0-rc.1").is_err());
+        assert!(check("2024.1.1").is_ok());
+
+        assert!(parse("").min_mise_version.is_none());
+        assert!(check_mise_version("tiny", None, &version::V).is_ok());
+        assert!(MisePluginToml::init()
+            .parse(r#"min_mise_version = 2024"#)
+            .is_err());
+    }
+
     fn parse(s: &str) -> MisePluginToml {
         let mut cf = MisePluginToml::init();
         cf.parse(s).unwrap();
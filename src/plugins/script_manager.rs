@@ -3,17 +3,20 @@ use std::ffi::OsString;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
+use std::process::Output;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use color_eyre::eyre::{Context, Result};
 use duct::Expression;
 use indexmap::indexmap;
 use once_cell::sync::Lazy;
 
-use crate::cmd::{cmd, CmdLineRunner};
+use crate::cmd::{cmd, kill_process_group, CmdLineRunner};
 use crate::config::Settings;
 use crate::env::PATH_KEY;
 use crate::errors::Error;
-use crate::errors::Error::ScriptFailed;
+use crate::errors::Error::{ScriptFailed, ScriptTimedOut};
 use crate::fake_asdf::get_path_with_fake_asdf;
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
@@ -155,13 +158,54 @@ impl ScriptManager {
     }
 
     pub fn read(&self, script: &Script) -> Result<String> {
-        let mut cmd = self.cmd(script);
+        let mut cmd = self.cmd(script).stdout_capture();
         let settings = &Settings::try_get()?;
         if !settings.verbose {
             cmd = cmd.stderr_null();
         }
-        cmd.read()
-            .wrap_err_with(|| ScriptFailed(display_path(self.get_script_path(script)), None))
+        let output = self.output(script, cmd)?;
+        if !output.status.success() {
+            let path = display_path(self.get_script_path(script));
+            return Err(ScriptFailed(path, Some(output.status)).into());
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.trim_end_matches(['\n', '\r']).to_string())
+    }
+
+    /// runs `cmd`, as returned by `self.cmd(script)`, without checking its exit status. If it
+    /// runs longer than MISE_PLUGIN_TIMEOUT the script and anything it started are killed.
+    pub fn output(&self, script: &Script, cmd: Expression) -> Result<Output> {
+        let path = display_path(self.get_script_path(script));
+        let cmd = cmd.unchecked();
+        let Some(timeout) = *env::MISE_PLUGIN_TIMEOUT else {
+            return cmd.run().wrap_err_with(|| ScriptFailed(path, None));
+        };
+        let handle = cmd
+            .before_spawn(|cmd| {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::process::CommandExt;
+                    cmd.process_group(0);
+                }
+                #[cfg(windows)]
+                let _ = cmd;
+                Ok(())
+            })
+            .start()
+            .wrap_err_with(|| ScriptFailed(path.clone(), None))?;
+        let deadline = Instant::now() + timeout;
+        while handle.try_wait()?.is_none() {
+            if Instant::now() >= deadline {
+                if let Some(pid) = handle.pids().first() {
+                    kill_process_group(*pid);
+                }
+                // reap it so it doesn't linger as a zombie
+                let _ = handle.wait();
+                return Err(ScriptTimedOut(path, timeout).into());
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        Ok(handle.into_output()?)
     }
 
     pub fn run_by_line(&self, script: &Script, pr: &dyn SingleReport) -> Result<()> {
@@ -170,10 +214,14 @@ impl ScriptManager {
         let cmd = CmdLineRunner::new(path.clone())
             .with_pr(pr)
             .env_clear()
-            .envs(&self.env);
+            .envs(&self.env)
+            .timeout(*env::MISE_PLUGIN_TIMEOUT);
         if let Err(e) = cmd.execute() {
             let status = match e.downcast_ref::<Error>() {
                 Some(ScriptFailed(_, status)) => *status,
+                Some(ScriptTimedOut(_, timeout)) => {
+                    return Err(ScriptTimedOut(display_path(&path), *timeout).into())
+                }
                 _ => None,
             };
             return Err(ScriptFailed(display_path(&path), status).into());
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use regex::Regex;
+
+/// values shorter than this are never masked since they are likely to also be common words
+pub const MIN_LENGTH: usize = 4;
+
+/// masks the values of env vars listed in `redactions` so they don't end up in logs.
+/// This is best-effort, it only sees output that mise captures itself.
+#[derive(Debug, Default, Clone)]
+pub struct Redactor {
+    values: Vec<String>,
+}
+
+impl Redactor {
+    /// `patterns` are env var names or regexes matching the whole name, e.g.: `.*_TOKEN`
+    pub fn new(patterns: &[String], env: &BTreeMap<String, String>) -> Self {
+        let patterns = patterns
+            .iter()
+            .filter_map(|p| match Regex::new(&format!("^(?:{p})$")) {
+                Ok(re) => Some(re),
+                Err(err) => {
+                    warn!("invalid redaction {p}: {err}");
+                    None
+                }
+            })
+            .collect_vec();
+        let values = env
+            .iter()
+            .filter(|(k, _)| patterns.iter().any(|re| re.is_match(k)))
+            .map(|(_, v)| v.clone())
+            .filter(|v| v.len() >= MIN_LENGTH)
+            .unique()
+            // replace longer values first in case one secret contains another
+            .sorted_by_key(|v| std::cmp::Reverse(v.len()))
+            .collect();
+        Self { values }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn redact(&self, input: &str) -> String {
+        let mut output = input.to_string();
+        for value in &self.values {
+            if output.contains(value.as_str()) {
+                output = output.replace(value.as_str(), "***");
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let env = BTreeMap::from([
+            ("GITHUB_TOKEN".to_string(), "ghp_abc123".to_string()),
+            ("NPM_TOKEN".to_string(), "npm_xyz".to_string()),
+            ("SHORT_TOKEN".to_string(), "on".to_string()),
+            ("HOME".to_string(), "/home/me".to_string()),
+        ]);
+        let redactor = Redactor::new(&["GITHUB_TOKEN".into(), ".*_TOKEN".into()], &env);
+        assert_eq!(
+            redactor.redact("token=ghp_abc123,npm=npm_xyz on /home/me"),
+            "token=***,npm=*** on /home/me"
+        );
+        assert!(Redactor::new(&["(".into()], &env).is_empty());
+        assert!(Redactor::new(&["TOKEN".into()], &env).is_empty());
+    }
+}
@@ -1,8 +1,23 @@
-use crate::config::Settings;
-use crate::plugins::core::CORE_PLUGINS;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use eyre::{bail, Result};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use std::collections::BTreeMap;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::Settings;
+use crate::hash::hash_sha256_to_str;
+use crate::http::HTTP_FETCH;
+use crate::plugins::core::CORE_PLUGINS;
+use crate::rand::random_string;
+use crate::{dirs, file};
 
 const _REGISTRY: &[(&str, &str)] = &[
     ("ubi", "cargo:ubi"),
@@ -42,7 +57,7 @@ const _REGISTRY_VFOX: &[(&str, &str)] = &[
     ("zig", "vfox:version-fox/vfox-zig"),
 ];
 
-pub static REGISTRY: Lazy<BTreeMap<&str, String>> = Lazy::new(|| {
+pub static REGISTRY: Lazy<BTreeMap<String, String>> = Lazy::new(|| {
     let settings = Settings::get();
 
     let registry = if cfg!(windows) || settings.vfox {
@@ -53,10 +68,141 @@ pub static REGISTRY: Lazy<BTreeMap<&str, String>> = Lazy::new(|| {
 
     registry
         .into_iter()
-        .filter(|(id, _)| !CORE_PLUGINS.contains_key(*id))
-        .map(|(k, v)| (*k, v.to_string()))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .chain(remote_registry(&settings))
+        .filter(|(id, _)| !CORE_PLUGINS.contains_key(id.as_str()))
         .collect()
 });
 
 pub static REGISTRY_VFOX: Lazy<BTreeMap<&str, &str>> =
     Lazy::new(|| _REGISTRY_VFOX.iter().map(|(k, v)| (*k, *v)).collect());
+
+/// where the registry downloaded from `registry_url` is kept
+pub static CACHE_PATH: Lazy<PathBuf> = Lazy::new(|| dirs::CACHE.join("registry.msgpack.z"));
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RegistryCache {
+    url: String,
+    /// sha256 of `body`, used to tell if the cache file was corrupted
+    checksum: String,
+    body: String,
+}
+
+/// the tools in the registry at `registry_url`, read from the cache unless it is missing, corrupt
+/// or older than `registry_cache_ttl`
+fn remote_registry(settings: &Settings) -> BTreeMap<String, String> {
+    let Some(url) = &settings.registry_url else {
+        return BTreeMap::new();
+    };
+    let cached = match read_cache(url).map(|c| c.filter(|_| settings.use_cache())) {
+        Ok(cached) => cached,
+        Err(err) => {
+            warn!("registry cache is corrupt, downloading it again: {err:#}");
+            None
+        }
+    };
+    let ttl = settings
+        .registry_cache_ttl_duration()
+        .unwrap_or_else(|err| {
+            warn!("{err:#}");
+            None
+        });
+    match cached {
+        Some(tools) if settings.offline || !is_stale(ttl) => tools,
+        cached => match update(url) {
+            Ok(tools) => tools,
+            Err(err) => {
+                warn!("failed to update the registry from {url}: {err:#}");
+                cached.unwrap_or_default()
+            }
+        },
+    }
+}
+
+/// downloads the registry at `url` and replaces the cached copy
+pub fn update(url: &str) -> Result<BTreeMap<String, String>> {
+    let body = HTTP_FETCH.get_text(url)?;
+    let tools = parse(&body)?;
+    if !Settings::get().write_cache() {
+        return Ok(tools);
+    }
+    let cache = RegistryCache {
+        url: url.to_string(),
+        checksum: hash_sha256_to_str(&body),
+        body,
+    };
+    file::create_dir_all(*dirs::CACHE)?;
+    let partial_path = CACHE_PATH.with_extension(format!("part-{}", random_string(8)));
+    let mut zlib = ZlibEncoder::new(File::create(&partial_path)?, Compression::fast());
+    zlib.write_all(&rmp_serde::to_vec_named(&cache)?)?;
+    zlib.finish()?;
+    file::rename(&partial_path, &*CACHE_PATH)?;
+    Ok(tools)
+}
+
+/// how long ago the registry was downloaded, `None` if it hasn't been
+pub fn cache_age() -> Option<Duration> {
+    file::modified_duration(&CACHE_PATH).ok()
+}
+
+fn is_stale(ttl: Option<Duration>) -> bool {
+    match (ttl, cache_age()) {
+        (Some(ttl), Some(age)) => age >= ttl,
+        _ => false,
+    }
+}
+
+/// `None` if there is no cache for `url`, an error if the cache can't be read or fails its checksum
+fn read_cache(url: &str) -> Result<Option<BTreeMap<String, String>>> {
+    if !CACHE_PATH.exists() {
+        return Ok(None);
+    }
+    let mut bytes = vec![];
+    ZlibDecoder::new(File::open(&*CACHE_PATH)?).read_to_end(&mut bytes)?;
+    let cache: RegistryCache = rmp_serde::from_slice(&bytes)?;
+    if cache.url != url {
+        return Ok(None);
+    }
+    if hash_sha256_to_str(&cache.body) != cache.checksum {
+        bail!("checksum mismatch in {}", file::display_path(&*CACHE_PATH));
+    }
+    Ok(Some(parse(&cache.body)?))
+}
+
+fn parse(body: &str) -> Result<BTreeMap<String, String>> {
+    let table: toml::Table = body.parse()?;
+    Ok(table
+        .into_iter()
+        .filter_map(|(k, v)| v.as_str().map(|v| (k, v.to_string())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test::reset;
+
+    use super::*;
+
+    #[test]
+    fn test_registry_cache() {
+        reset();
+        let path = dirs::CACHE.join("test-registry.toml");
+        file::create_dir_all(*dirs::CACHE).unwrap();
+        file::write(&path, "poetry = \"asdf:mise-plugins/mise-poetry\"\n").unwrap();
+        let url = format!("file://{}", path.display());
+        let tools = update(&url).unwrap();
+        assert_eq!(tools["poetry"], "asdf:mise-plugins/mise-poetry");
+        assert_eq!(read_cache(&url).unwrap(), Some(tools));
+        assert_eq!(
+            read_cache("https://example.com/registry.toml").unwrap(),
+            None
+        );
+
+        file::write(&*CACHE_PATH, "not zlib").unwrap();
+        assert!(read_cache(&url).is_err());
+        file::remove_file(&*CACHE_PATH).unwrap();
+        file::remove_file(&path).unwrap();
+    }
+}
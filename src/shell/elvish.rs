@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use indoc::formatdoc;
+
+use crate::shell::Shell;
+
+#[derive(Default)]
+pub struct Elvish {}
+
+/// elvish single-quoted strings have no escape sequences, a `'` is written as `''` and
+/// newlines are kept literally
+fn elvish_quote(input: &str) -> String {
+    format!("'{}'", input.replace('\'', "''"))
+}
+
+impl Shell for Elvish {
+    fn activate(&self, exe: &Path, flags: String) -> String {
+        let exe = elvish_quote(&exe.to_string_lossy());
+
+        formatdoc! {r#"
+            set-env MISE_SHELL elvish
+            var mise-exe = (external {exe})
+            fn mise-hook {{||
+              if (has-env MISE_SHELL) {{
+                eval ($mise-exe hook-env{flags} -s elvish | slurp)
+              }}
+            }}
+            set edit:before-readline = [$@edit:before-readline $mise-hook~]
+            fn mise {{|@args|
+              if (and (> (count $args) 0) (has-value [deactivate shell sh] $args[0])) {{
+                eval ($mise-exe $@args | slurp)
+              }} else {{
+                $mise-exe $@args
+              }}
+            }}
+        "#}
+    }
+
+    fn deactivate(&self) -> String {
+        self.unset_env("MISE_SHELL")
+    }
+
+    fn set_env(&self, k: &str, v: &str) -> String {
+        format!("set-env {} {}\n", elvish_quote(k), elvish_quote(v))
+    }
+
+    fn prepend_env(&self, k: &str, v: &str) -> String {
+        let sep = if cfg!(windows) { ';' } else { ':' };
+        let v = elvish_quote(&format!("{v}{sep}"));
+        format!("set-env {} {v}$E:{k}\n", elvish_quote(k))
+    }
+
+    fn unset_env(&self, k: &str) -> String {
+        format!("unset-env {}\n", elvish_quote(k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    use crate::test::replace_path;
+
+    use super::*;
+
+    #[test]
+    fn test_activate() {
+        let elvish = Elvish::default();
+        let exe = Path::new("/some/dir/mise");
+        assert_snapshot!(elvish.activate(exe, " --status".into()));
+    }
+
+    #[test]
+    fn test_set_env() {
+        assert_snapshot!(Elvish::default().set_env("FOO", "1"));
+    }
+
+    #[test]
+    fn test_prepend_env() {
+        let sh = Elvish::default();
+        assert_snapshot!(replace_path(&sh.prepend_env("PATH", "/some/dir:/2/dir")));
+    }
+
+    #[test]
+    fn test_unset_env() {
+        assert_snapshot!(Elvish::default().unset_env("FOO"));
+    }
+
+    #[test]
+    fn test_deactivate() {
+        assert_snapshot!(Elvish::default().deactivate());
+    }
+
+    #[test]
+    fn test_elvish_quote() {
+        assert_eq!(elvish_quote("foo bar"), "'foo bar'");
+        assert_eq!(elvish_quote("it's"), "'it''s'");
+        assert_eq!(elvish_quote("a\nb"), "'a\nb'");
+        assert_eq!(elvish_quote("$x \"y\""), "'$x \"y\"'");
+    }
+}
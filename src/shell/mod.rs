@@ -4,16 +4,20 @@ use std::path::Path;
 use crate::env;
 
 mod bash;
+mod elvish;
 mod fish;
 mod nushell;
+mod pwsh;
 mod xonsh;
 mod zsh;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum ShellType {
     Bash,
+    Elvish,
     Fish,
     Nu,
+    Pwsh,
     Xonsh,
     Zsh,
 }
@@ -23,10 +27,14 @@ impl ShellType {
         let shell = env::var("MISE_SHELL").or(env::var("SHELL")).ok()?;
         if shell.ends_with("bash") {
             Some(ShellType::Bash)
+        } else if shell.ends_with("elvish") {
+            Some(ShellType::Elvish)
         } else if shell.ends_with("fish") {
             Some(ShellType::Fish)
         } else if shell.ends_with("nu") {
             Some(ShellType::Nu)
+        } else if shell.ends_with("pwsh") || shell.ends_with("powershell") {
+            Some(ShellType::Pwsh)
         } else if shell.ends_with("xonsh") {
             Some(ShellType::Xonsh)
         } else if shell.ends_with("zsh") {
@@ -41,8 +49,10 @@ impl Display for ShellType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Bash => write!(f, "bash"),
+            Self::Elvish => write!(f, "elvish"),
             Self::Fish => write!(f, "fish"),
             Self::Nu => write!(f, "nu"),
+            Self::Pwsh => write!(f, "pwsh"),
             Self::Xonsh => write!(f, "xonsh"),
             Self::Zsh => write!(f, "zsh"),
         }
@@ -60,8 +70,10 @@ pub trait Shell {
 pub fn get_shell(shell: Option<ShellType>) -> Option<Box<dyn Shell>> {
     match shell.or_else(ShellType::load) {
         Some(ShellType::Bash) => Some(Box::<bash::Bash>::default()),
+        Some(ShellType::Elvish) => Some(Box::<elvish::Elvish>::default()),
         Some(ShellType::Fish) => Some(Box::<fish::Fish>::default()),
         Some(ShellType::Nu) => Some(Box::<nushell::Nushell>::default()),
+        Some(ShellType::Pwsh) => Some(Box::<pwsh::Pwsh>::default()),
         Some(ShellType::Xonsh) => Some(Box::<xonsh::Xonsh>::default()),
         Some(ShellType::Zsh) => Some(Box::<zsh::Zsh>::default()),
         _ => None,
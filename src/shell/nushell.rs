@@ -67,7 +67,7 @@ impl Shell for Nushell {
           def --env "update-env" [] {{
             for $var in $in {{
               if $var.op == "set" {{
-                load-env {{($var.name): $var.value}}
+                load-env {{($var.name): ($var.value | from json)}}
               }} else if $var.op == "hide" {{
                 hide-env $var.name
               }}
@@ -89,14 +89,15 @@ impl Shell for Nushell {
 
     fn set_env(&self, k: &str, v: &str) -> String {
         let k = shell_escape::unix::escape(k.into());
-        let v = shell_escape::unix::escape(v.into());
-        let v = v.replace('\'', "");
+        // the value is a json string so that newlines don't break the line-based format
+        let v = serde_json::to_string(v).unwrap();
 
         EnvOp::Set { key: &k, val: &v }.to_string()
     }
 
     fn prepend_env(&self, k: &str, v: &str) -> String {
-        format!("$env.{k} = ($env.{k} | prepend '{v}')\n")
+        let v = serde_json::to_string(v).unwrap();
+        format!("$env.{k} = ($env.{k} | prepend {v})\n")
     }
 
     fn unset_env(&self, k: &str) -> String {
@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use indoc::formatdoc;
+
+use crate::shell::Shell;
+
+#[derive(Default)]
+pub struct Pwsh {}
+
+/// PowerShell single-quoted strings are verbatim except for quotes which are doubled, this
+/// includes the curly quotes that PowerShell also accepts as single quotes
+fn pwsh_quote(input: &str) -> String {
+    let mut quoted = String::with_capacity(input.len() + 2);
+    quoted.push('\'');
+    for ch in input.chars() {
+        if matches!(ch, '\'' | '‘' | '’' | '‚' | '‛') {
+            quoted.push(ch);
+        }
+        quoted.push(ch);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// `$Env:NAME` only works for simple names, anything else needs the `${Env:NAME}` form
+fn pwsh_env_var(k: &str) -> String {
+    if k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!("$Env:{k}")
+    } else {
+        let k = k.replace('`', "``").replace('{', "`{").replace('}', "`}");
+        format!("${{Env:{k}}}")
+    }
+}
+
+impl Shell for Pwsh {
+    fn activate(&self, exe: &Path, flags: String) -> String {
+        let exe = pwsh_quote(&exe.to_string_lossy());
+
+        formatdoc! {r#"
+            $Env:MISE_SHELL = 'pwsh'
+            function global:_mise_hook {{
+              if ($Env:MISE_SHELL -eq 'pwsh') {{
+                & {exe} hook-env{flags} -s pwsh | Out-String | Invoke-Expression
+              }}
+            }}
+            if (-not (Test-Path variable:global:_mise_prompt)) {{
+              $global:_mise_prompt = $function:prompt
+              function global:prompt {{
+                _mise_hook
+                & $global:_mise_prompt
+              }}
+            }}
+            function global:mise {{
+              if ($args.Count -gt 0 -and $args[0] -in 'deactivate', 'shell', 'sh') {{
+                & {exe} @args | Out-String | Invoke-Expression
+              }} else {{
+                & {exe} @args
+              }}
+            }}
+        "#}
+    }
+
+    fn deactivate(&self) -> String {
+        self.unset_env("MISE_SHELL")
+    }
+
+    fn set_env(&self, k: &str, v: &str) -> String {
+        format!("{} = {}\n", pwsh_env_var(k), pwsh_quote(v))
+    }
+
+    fn prepend_env(&self, k: &str, v: &str) -> String {
+        let k = pwsh_env_var(k);
+        let v = pwsh_quote(v);
+        format!("{k} = {v} + [IO.Path]::PathSeparator + {k}\n")
+    }
+
+    fn unset_env(&self, k: &str) -> String {
+        let k = pwsh_quote(&format!("Env:{k}"));
+        format!("Remove-Item -LiteralPath {k} -ErrorAction SilentlyContinue\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    use crate::test::replace_path;
+
+    use super::*;
+
+    #[test]
+    fn test_activate() {
+        let pwsh = Pwsh::default();
+        let exe = Path::new("/some/dir/mise");
+        assert_snapshot!(pwsh.activate(exe, " --status".into()));
+    }
+
+    #[test]
+    fn test_set_env() {
+        assert_snapshot!(Pwsh::default().set_env("FOO", "1"));
+    }
+
+    #[test]
+    fn test_prepend_env() {
+        let sh = Pwsh::default();
+        assert_snapshot!(replace_path(&sh.prepend_env("PATH", "/some/dir:/2/dir")));
+    }
+
+    #[test]
+    fn test_unset_env() {
+        assert_snapshot!(Pwsh::default().unset_env("FOO"));
+    }
+
+    #[test]
+    fn test_deactivate() {
+        assert_snapshot!(Pwsh::default().deactivate());
+    }
+
+    #[test]
+    fn test_pwsh_quote() {
+        assert_eq!(pwsh_quote("foo bar"), "'foo bar'");
+        assert_eq!(pwsh_quote("it's"), "'it''s'");
+        assert_eq!(pwsh_quote("it’s"), "'it’’s'");
+        assert_eq!(pwsh_quote("a\nb"), "'a\nb'");
+        assert_eq!(pwsh_quote("$x `y` \"z\""), "'$x `y` \"z\"'");
+        assert_eq!(pwsh_env_var("FOO_1"), "$Env:FOO_1");
+        assert_eq!(pwsh_env_var("FOO(x86)"), "${Env:FOO(x86)}");
+    }
+}
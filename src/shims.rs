@@ -85,7 +85,14 @@ fn which_shim(bin_name: &str) -> Result<PathBuf> {
     err_no_version_set(ts, bin_name, tvs)
 }
 
-pub fn reshim(ts: &Toolset) -> Result<()> {
+/// how many shims a reshim created and deleted
+#[derive(Debug, Default)]
+pub struct ReshimStats {
+    pub added: usize,
+    pub removed: usize,
+}
+
+pub fn reshim(ts: &Toolset) -> Result<ReshimStats> {
     let _lock = LockFile::new(&dirs::SHIMS)
         .with_callback(|l| {
             trace!("reshim callback {}", l.display());
@@ -97,6 +104,10 @@ pub fn reshim(ts: &Toolset) -> Result<()> {
     create_dir_all(*dirs::SHIMS)?;
 
     let (shims_to_add, shims_to_remove) = get_shim_diffs(&mise_bin, ts)?;
+    let stats = ReshimStats {
+        added: shims_to_add.len(),
+        removed: shims_to_remove.len(),
+    };
 
     for shim in shims_to_add {
         let symlink_path = dirs::SHIMS.join(&shim);
@@ -122,7 +133,7 @@ pub fn reshim(ts: &Toolset) -> Result<()> {
         }
     }
 
-    Ok(())
+    Ok(stats)
 }
 
 #[cfg(windows)]
@@ -5,27 +5,35 @@ use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
+use std::time::Duration;
 use std::{ffi, fmt, path};
 
 use console::truncate_str;
 use either::Either;
-use eyre::{eyre, Result};
+use eyre::{bail, ensure, eyre, Result};
 use globset::Glob;
 use itertools::Itertools;
 use petgraph::prelude::*;
+use petgraph::visit::Reversed;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::config::config_file::toml::{deserialize_arr, TomlParser};
 use crate::config::Config;
 use crate::file;
+use crate::file::display_path;
 use crate::task::task_script_parser::{
-    has_any_args_defined, replace_template_placeholders_with_args, TaskScriptParser,
+    has_any_args_defined, replace_template_placeholders_with_args, task_command, TaskScriptParser,
 };
 use crate::tera::{get_tera, BASE_CONTEXT};
 use crate::ui::tree::TreeItem;
 
 mod task_script_parser;
 
+#[cfg(unix)]
+const SIGTERM: i32 = nix::sys::signal::SIGTERM as i32;
+#[cfg(windows)]
+const SIGTERM: i32 = 15;
+
 #[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize)]
 pub struct Task {
     #[serde(skip)]
@@ -40,8 +48,20 @@ pub struct Task {
     pub depends: Vec<String>,
     #[serde(default)]
     pub env: BTreeMap<String, EitherStringOrBool>,
+    /// tools to install and add to PATH for just this task, e.g.: `{ node = "20" }`
+    #[serde(default)]
+    pub tools: BTreeMap<String, String>,
     #[serde(default)]
     pub dir: Option<PathBuf>,
+    /// octal file mode creation mask, e.g.: "027"
+    #[serde(default)]
+    pub umask: Option<String>,
+    /// how long the task may run before it is stopped, e.g.: "5m"
+    #[serde(default)]
+    pub timeout: Option<String>,
+    /// signal sent to the task when it times out, e.g.: "SIGINT"
+    #[serde(default)]
+    pub timeout_signal: Option<String>,
     #[serde(default)]
     pub hide: bool,
     #[serde(default)]
@@ -127,7 +147,11 @@ impl Task {
             outputs: p.parse_array("outputs")?.unwrap_or_default(),
             depends: p.parse_array("depends")?.unwrap_or_default(),
             dir: p.parse_str("dir")?,
+            umask: p.parse_str("umask")?,
+            timeout: p.parse_str("timeout")?,
+            timeout_signal: p.parse_str("timeout_signal")?,
             env: p.parse_env("env")?.unwrap_or_default(),
+            tools: p.parse_table("tools")?.unwrap_or_default(),
             file: Some(path.to_path_buf()),
             ..Task::new(name_from_path(config_root, path)?, path.to_path_buf())
         };
@@ -164,6 +188,84 @@ impl Task {
         self
     }
 
+    /// the directory to run the task in, relative to the config file (or the config root for
+    /// file tasks). Templates such as `{{cwd}}` are rendered.
+    pub fn dir(&self) -> Result<Option<PathBuf>> {
+        let Some(dir) = &self.dir else {
+            return Ok(None);
+        };
+        let config_root = config_root(&self.config_source);
+        let mut tera_ctx = BASE_CONTEXT.clone();
+        if let Some(config_root) = config_root {
+            tera_ctx.insert("config_root", config_root);
+        }
+        let dir = get_tera(config_root).render_str(&dir.to_string_lossy(), &tera_ctx)?;
+        let dir = file::replace_path(Path::new(&dir));
+        let dir = match config_root {
+            Some(config_root) if dir.is_relative() => config_root.join(dir),
+            _ => dir,
+        };
+        ensure!(
+            dir.is_dir(),
+            "dir for task {} does not exist: {}",
+            self.name,
+            display_path(&dir)
+        );
+        Ok(Some(dir))
+    }
+
+    pub fn umask(&self) -> Result<Option<u32>> {
+        let Some(umask) = &self.umask else {
+            return Ok(None);
+        };
+        match u32::from_str_radix(umask.trim_start_matches("0o"), 8) {
+            Ok(umask) if umask <= 0o777 => Ok(Some(umask)),
+            _ => bail!(
+                "invalid umask for task {}: {umask} (expected an octal mode like 022)",
+                self.name
+            ),
+        }
+    }
+
+    pub fn timeout(&self) -> Result<Option<Duration>> {
+        let Some(timeout) = &self.timeout else {
+            return Ok(None);
+        };
+        match humantime::parse_duration(timeout) {
+            Ok(timeout) => Ok(Some(timeout)),
+            Err(err) => bail!(
+                "invalid timeout for task {}: {timeout} (expected a duration like 5m): {err}",
+                self.name
+            ),
+        }
+    }
+
+    /// the signal number of `timeout_signal`, which may be a name with or without the `SIG`
+    /// prefix or a number. Defaults to SIGTERM.
+    pub fn timeout_signal(&self) -> Result<i32> {
+        let Some(signal) = &self.timeout_signal else {
+            return Ok(SIGTERM);
+        };
+        if let Ok(signal) = signal.parse::<i32>() {
+            return Ok(signal);
+        }
+        #[cfg(unix)]
+        {
+            let name = signal.to_uppercase();
+            let name = match name.starts_with("SIG") {
+                true => name,
+                false => format!("SIG{name}"),
+            };
+            if let Ok(signal) = name.parse::<nix::sys::signal::Signal>() {
+                return Ok(signal as i32);
+            }
+        }
+        bail!(
+            "invalid timeout_signal for task {}: {signal} (expected a signal like SIGTERM)",
+            self.name
+        )
+    }
+
     pub fn prefix(&self) -> String {
         format!("[{}]", self.name)
     }
@@ -200,7 +302,7 @@ impl Task {
         let (spec, scripts) = self.parse_usage_spec(cwd)?;
         if has_any_args_defined(&spec) {
             Ok(
-                replace_template_placeholders_with_args(&spec, &scripts, args)
+                replace_template_placeholders_with_args(&spec, &scripts, args)?
                     .into_iter()
                     .map(|s| (s, vec![]))
                     .collect(),
@@ -221,6 +323,16 @@ impl Task {
         }
     }
 
+    /// the `--help` of a task that defines args, options or flags
+    pub fn render_help(&self, cwd: Option<PathBuf>) -> Result<Option<String>> {
+        let (spec, _) = self.parse_usage_spec(cwd)?;
+        if !has_any_args_defined(&spec) {
+            return Ok(None);
+        }
+        let mut cmd = task_command(&spec).about(self.description.clone());
+        Ok(Some(cmd.render_help().to_string()))
+    }
+
     pub fn render_markdown(&self) -> Result<String> {
         let (spec, _) = self.parse_usage_spec(None)?;
         Ok(spec.render_markdown()?)
@@ -292,6 +404,11 @@ pub struct Deps {
 
 impl Deps {
     pub fn new(config: &Config, tasks: Vec<Task>) -> Result<Self> {
+        Self::from_graph(Self::graph(config, tasks)?)
+    }
+
+    /// builds the dependency graph of `tasks` without checking it for cycles
+    pub fn graph(config: &Config, tasks: Vec<Task>) -> Result<DiGraph<Task, ()>> {
         let mut graph = DiGraph::new();
         let mut indexes = HashMap::new();
         let mut stack = vec![];
@@ -306,15 +423,44 @@ impl Deps {
                 .entry(a.name.clone())
                 .or_insert_with(|| graph.add_node(a.clone()));
             for b in a.resolve_depends(config)? {
-                let b_idx = *indexes
-                    .entry(b.name.clone())
-                    .or_insert_with(|| graph.add_node(b.clone()));
+                let b_idx = match indexes.get(&b.name) {
+                    Some(idx) => *idx,
+                    None => {
+                        // only walk the dependencies of a task once, a cycle would loop forever
+                        let idx = graph.add_node(b.clone());
+                        indexes.insert(b.name.clone(), idx);
+                        stack.push(b.clone());
+                        idx
+                    }
+                };
                 if !graph.contains_edge(a_idx, b_idx) {
                     graph.add_edge(a_idx, b_idx, ());
                 }
-                stack.push(b.clone());
             }
         }
+        Ok(graph)
+    }
+
+    /// the names of the tasks in each dependency cycle of `graph`, sorted
+    pub fn cycles(graph: &DiGraph<Task, ()>) -> Vec<Vec<String>> {
+        petgraph::algo::tarjan_scc(graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1)
+            .map(|scc| {
+                scc.iter()
+                    .map(|idx| graph[*idx].name.clone())
+                    .sorted()
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn from_graph(graph: DiGraph<Task, ()>) -> Result<Self> {
+        // a cycle would never produce a leaf and hang `mise run` forever
+        if let Some(cycle) = Self::cycles(&graph).first() {
+            let names = cycle.join(", ");
+            return Err(eyre!("circular dependency detected between tasks: {names}"));
+        }
         let (tx, _) = mpsc::channel();
         let sent = HashSet::new();
         Ok(Self { graph, tx, sent })
@@ -362,6 +508,36 @@ impl Deps {
         }
     }
 
+    /// removes a failed task along with every task that depends on it, directly or not, so
+    /// only independent tasks keep running. Returns the dependents that will not run.
+    pub fn remove_failed(&mut self, task: &Task) -> Vec<Task> {
+        let Some(idx) = self
+            .graph
+            .node_indices()
+            .find(|&idx| &self.graph[idx] == task)
+        else {
+            return vec![];
+        };
+        let dependents = Reversed(&self.graph);
+        let mut dfs = Dfs::new(dependents, idx);
+        let mut remove = vec![];
+        while let Some(idx) = dfs.next(dependents) {
+            remove.push(idx);
+        }
+        let skipped = remove
+            .iter()
+            .filter(|i| **i != idx)
+            .map(|i| self.graph[*i].clone())
+            .collect();
+        // removing a node moves the last node into its index, so remove from the end first
+        remove.sort_by(|a, b| b.cmp(a));
+        for idx in remove {
+            self.graph.remove_node(idx);
+        }
+        self.emit_leaves();
+        skipped
+    }
+
     pub fn all(&self) -> impl Iterator<Item = &Task> {
         self.graph.node_indices().map(|idx| &self.graph[idx])
     }
@@ -454,10 +630,13 @@ where
 #[cfg(test)]
 mod tests {
     use std::path::Path;
+    use std::time::Duration;
 
+    use itertools::Itertools;
+    use petgraph::graph::DiGraph;
     use pretty_assertions::assert_eq;
 
-    use crate::task::Task;
+    use crate::task::{Deps, Task};
     use crate::test::reset;
 
     use super::{config_root, name_from_path};
@@ -474,6 +653,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_timeout() {
+        let task = |timeout: &str, signal: &str| Task {
+            name: "t".into(),
+            timeout: Some(timeout.into()),
+            timeout_signal: Some(signal.into()),
+            ..Default::default()
+        };
+        let t = task("1m30s", "9");
+        assert_eq!(t.timeout().unwrap(), Some(Duration::from_secs(90)));
+        assert_eq!(t.timeout_signal().unwrap(), 9);
+        assert!(task("soon", "9").timeout().is_err());
+        assert_eq!(Task::default().timeout().unwrap(), None);
+        #[cfg(unix)]
+        {
+            assert_eq!(task("1s", "int").timeout_signal().unwrap(), 2);
+            assert_eq!(task("1s", "SIGINT").timeout_signal().unwrap(), 2);
+            assert_eq!(Task::default().timeout_signal().unwrap(), 15);
+            assert!(task("1s", "SIGNOPE").timeout_signal().is_err());
+        }
+    }
+
     #[test]
     fn test_name_from_path() {
         reset();
@@ -500,6 +701,57 @@ mod tests {
         }
     }
 
+    fn task(name: &str) -> Task {
+        Task {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_deps_cycle() {
+        let mut graph = DiGraph::new();
+        let build = graph.add_node(task("build"));
+        let test = graph.add_node(task("test"));
+        let lint = graph.add_node(task("lint"));
+        graph.add_edge(build, test, ());
+        graph.add_edge(test, build, ());
+        graph.add_edge(lint, build, ());
+        let err = Deps::from_graph(graph).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "circular dependency detected between tasks: build, test"
+        );
+    }
+
+    #[test]
+    fn test_deps_remove_failed() {
+        // deploy -> build -> deps, lint is independent
+        let mut graph = DiGraph::new();
+        let deploy = graph.add_node(task("deploy"));
+        let build = graph.add_node(task("build"));
+        let deps = graph.add_node(task("deps"));
+        graph.add_node(task("lint"));
+        graph.add_edge(deploy, build, ());
+        graph.add_edge(build, deps, ());
+        let mut deps = Deps::from_graph(graph).unwrap();
+        let rx = deps.subscribe();
+        let mut started = vec![];
+        while let Ok(Some(t)) = rx.try_recv() {
+            started.push(t.name);
+        }
+        assert_eq!(started.iter().sorted().collect_vec(), vec!["deps", "lint"]);
+
+        let skipped = deps.remove_failed(&task("deps"));
+        assert_eq!(
+            skipped.into_iter().map(|t| t.name).sorted().collect_vec(),
+            vec!["build", "deploy"]
+        );
+        assert_eq!(deps.all().map(|t| &t.name).collect_vec(), vec!["lint"]);
+        deps.remove(&task("lint"));
+        assert_eq!(rx.try_recv().unwrap(), None);
+    }
+
     #[test]
     fn test_config_root() {
         reset();
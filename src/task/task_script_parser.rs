@@ -1,5 +1,5 @@
 use crate::tera::{get_tera, BASE_CONTEXT};
-use eyre::Result;
+use eyre::{eyre, Result};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
@@ -47,8 +47,11 @@ impl TaskScriptParser {
                         .map(|n| n.as_str().unwrap().to_string())
                         .unwrap_or(i.to_string());
                     let usage = args.get("usage").map(|r| r.to_string()).unwrap_or_default();
-                    let help = args.get("help").map(|r| r.to_string());
-                    let long_help = args.get("long_help").map(|r| r.to_string());
+                    let help = args.get("help").and_then(|r| r.as_str()).map(String::from);
+                    let long_help = args
+                        .get("long_help")
+                        .and_then(|r| r.as_str())
+                        .map(String::from);
                     let var_min = args.get("var_min").map(|r| r.as_i64().unwrap() as usize);
                     let var_max = args.get("var_max").map(|r| r.as_i64().unwrap() as usize);
                     let hide = args
@@ -56,6 +59,8 @@ impl TaskScriptParser {
                         .map(|r| r.as_bool().unwrap())
                         .unwrap_or(false);
                     let default = args.get("default").map(|d| d.as_str().unwrap().to_string());
+                    // an arg with a default can always be left out
+                    let required = required && default.is_none();
                     let choices = args.get("choices").map(|c| {
                         let choices = c
                             .as_array()
@@ -112,8 +117,11 @@ impl TaskScriptParser {
                         .map(|r| r.as_bool().unwrap())
                         .unwrap_or(false);
                     let deprecated = args.get("deprecated").map(|r| r.to_string());
-                    let help = args.get("help").map(|r| r.to_string());
-                    let long_help = args.get("long_help").map(|r| r.to_string());
+                    let help = args.get("help").and_then(|r| r.as_str()).map(String::from);
+                    let long_help = args
+                        .get("long_help")
+                        .and_then(|r| r.as_str())
+                        .map(String::from);
                     let hide = args
                         .get("hide")
                         .map(|r| r.as_bool().unwrap())
@@ -196,8 +204,11 @@ impl TaskScriptParser {
                         .map(|r| r.as_bool().unwrap())
                         .unwrap_or(false);
                     let deprecated = args.get("deprecated").map(|r| r.to_string());
-                    let help = args.get("help").map(|r| r.to_string());
-                    let long_help = args.get("long_help").map(|r| r.to_string());
+                    let help = args.get("help").and_then(|r| r.as_str()).map(String::from);
+                    let long_help = args
+                        .get("long_help")
+                        .and_then(|r| r.as_str())
+                        .map(String::from);
                     let hide = args
                         .get("hide")
                         .map(|r| r.as_bool().unwrap())
@@ -263,59 +274,122 @@ impl TaskScriptParser {
     }
 }
 
+/// the command line a task's args, options and flags are parsed with. Args with a name can also
+/// be passed by it, e.g.: `arg(name="env")` as `--env=prod`.
+pub fn task_command(spec: &usage::Spec) -> clap::Command {
+    let mut cmd = clap::Command::new(spec.cmd.name.clone()).no_binary_name(true);
+    let flag_names: HashSet<&str> = spec.cmd.flags.iter().map(|f| f.name.as_str()).collect();
+    for arg in &spec.cmd.args {
+        let action = match arg.var {
+            true => clap::ArgAction::Append,
+            false => clap::ArgAction::Set,
+        };
+        let mut positional = clap::Arg::new(arg.name.clone()).action(action.clone());
+        if let Some(help) = &arg.help {
+            positional = positional.help(help.clone());
+        }
+        if let Some(default) = &arg.default {
+            positional = positional.default_value(default.clone());
+        }
+        if let Some(choices) = &arg.choices {
+            positional = positional.value_parser(choices_parser(choices));
+        }
+        let named = named_arg_id(&arg.name);
+        if arg.name.parse::<usize>().is_err() && !flag_names.contains(arg.name.as_str()) {
+            if arg.required {
+                positional = positional.required_unless_present(&named);
+            }
+            let mut named = clap::Arg::new(named)
+                .long(arg.name.clone())
+                .value_name(arg.name.to_uppercase())
+                .help(format!("Same as <{}>", arg.name))
+                .conflicts_with(&arg.name)
+                .action(action);
+            if let Some(choices) = &arg.choices {
+                named = named.value_parser(choices_parser(choices));
+            }
+            cmd = cmd.arg(named);
+        } else {
+            positional = positional.required(arg.required);
+        }
+        cmd = cmd.arg(positional);
+    }
+    for flag in &spec.cmd.flags {
+        let mut a = clap::Arg::new(flag.name.clone()).long(flag.name.clone());
+        a = match (&flag.arg, flag.var) {
+            (Some(_), true) => a.action(clap::ArgAction::Append),
+            (Some(_), false) => a.action(clap::ArgAction::Set),
+            (None, _) => a.action(clap::ArgAction::SetTrue),
+        };
+        if let Some(help) = &flag.help {
+            a = a.help(help.clone());
+        }
+        if let Some(default) = flag.default.as_ref().filter(|_| flag.arg.is_some()) {
+            a = a.default_value(default.clone());
+        }
+        if let Some(choices) = flag.arg.as_ref().and_then(|a| a.choices.as_ref()) {
+            a = a.value_parser(choices_parser(choices));
+        }
+        cmd = cmd.arg(a.required(flag.required));
+    }
+    cmd
+}
+
+fn choices_parser(choices: &usage::SpecChoices) -> clap::builder::PossibleValuesParser {
+    clap::builder::PossibleValuesParser::new(choices.choices.clone())
+}
+
+fn named_arg_id(name: &str) -> String {
+    format!("{name}:named")
+}
+
+/// fails with the usage of the task if `args` don't match its spec, e.g.: a required arg is
+/// missing
 pub fn replace_template_placeholders_with_args(
     spec: &usage::Spec,
     scripts: &[String],
     args: &[String],
-) -> Vec<String> {
-    let mut cmd = clap::Command::new("mise-task");
+) -> Result<Vec<String>> {
+    let matches = task_command(spec)
+        .try_get_matches_from(args)
+        .map_err(|err| {
+            // `mise run <task> --help` is handled by `mise run` itself so point to the form that works
+            let help = format!("'mise run {} -- --help'", spec.cmd.name);
+            let msg = err.render().to_string().replace("'--help'", &help);
+            eyre!("{}", msg.trim_start_matches("error: ").trim_end())
+        })?;
+    let values = |id: &str| {
+        matches
+            .try_get_many::<String>(id)
+            .ok()
+            .flatten()
+            .map(|mut v| v.join(" "))
+    };
+    let mut replacements = vec![];
     for arg in &spec.cmd.args {
-        cmd = cmd.arg(
-            clap::Arg::new(arg.name.clone())
-                .required(arg.required)
-                .action(if arg.var {
-                    clap::ArgAction::Append
-                } else {
-                    clap::ArgAction::Set
-                }),
-        );
+        // a named value wins over the default of the positional arg
+        let value = values(&named_arg_id(&arg.name))
+            .or_else(|| values(&arg.name))
+            .unwrap_or_default();
+        replacements.push((arg.name.as_str(), value));
     }
-    let mut flags = HashSet::new();
     for flag in &spec.cmd.flags {
-        if flag.arg.is_some() {
-            cmd = cmd.arg(
-                clap::Arg::new(flag.name.clone())
-                    .long(flag.name.clone())
-                    .action(if flag.var {
-                        clap::ArgAction::Append
-                    } else {
-                        clap::ArgAction::Set
-                    }),
-            );
-        } else {
-            flags.insert(flag.name.as_str());
-            cmd = cmd.arg(
-                clap::Arg::new(flag.name.clone())
-                    .long(flag.name.clone())
-                    .action(clap::ArgAction::SetTrue),
-            );
-        }
-    }
-    let matches = cmd.get_matches_from(["mise-task".to_string()].iter().chain(args.iter()));
-    let mut out = vec![];
-    for script in scripts {
-        let mut script = script.clone();
-        for id in matches.ids() {
-            let value = if flags.contains(id.as_str()) {
-                matches.get_one::<bool>(id.as_str()).unwrap().to_string()
-            } else {
-                matches.get_many::<String>(id.as_str()).unwrap().join(" ")
-            };
-            script = script.replace(&format!("MISE_TASK_ARG:{id}:MISE_TASK_ARG"), &value);
-        }
-        out.push(script);
+        let value = match flag.arg {
+            Some(_) => values(&flag.name).unwrap_or_default(),
+            None => matches.get_flag(&flag.name).to_string(),
+        };
+        replacements.push((flag.name.as_str(), value));
     }
-    out
+    Ok(scripts
+        .iter()
+        .map(|script| {
+            replacements
+                .iter()
+                .fold(script.clone(), |script, (name, value)| {
+                    script.replace(&format!("MISE_TASK_ARG:{name}:MISE_TASK_ARG"), value)
+                })
+        })
+        .collect())
 }
 
 pub fn has_any_args_defined(spec: &usage::Spec) -> bool {
@@ -338,7 +412,7 @@ mod tests {
         assert_eq!(arg0.name, "foo");
 
         let scripts =
-            replace_template_placeholders_with_args(&spec, &scripts, &["abc".to_string()]);
+            replace_template_placeholders_with_args(&spec, &scripts, &["abc".to_string()]).unwrap();
         assert_eq!(scripts, vec!["echo abc"]);
     }
 
@@ -356,7 +430,8 @@ mod tests {
             &spec,
             &scripts,
             &["abc".to_string(), "def".to_string()],
-        );
+        )
+        .unwrap();
         assert_eq!(scripts, vec!["echo abc def"]);
     }
 
@@ -371,7 +446,8 @@ mod tests {
         assert_eq!(&flag.name, "foo");
 
         let scripts =
-            replace_template_placeholders_with_args(&spec, &scripts, &["--foo".to_string()]);
+            replace_template_placeholders_with_args(&spec, &scripts, &["--foo".to_string()])
+                .unwrap();
         assert_eq!(scripts, vec!["echo true"]);
     }
 
@@ -389,7 +465,35 @@ mod tests {
             &spec,
             &scripts,
             &["--foo".to_string(), "abc".to_string()],
-        );
+        )
+        .unwrap();
         assert_eq!(scripts, vec!["echo abc"]);
     }
+
+    #[test]
+    fn test_task_parse_arg_default_and_named() {
+        reset();
+        let parser = TaskScriptParser::new(None);
+        let scripts = vec!["echo {{ arg(name='env', default='dev') }}".to_string()];
+        let (scripts, spec) = parser.parse_run_scripts(&scripts).unwrap();
+
+        let rendered = replace_template_placeholders_with_args(&spec, &scripts, &[]).unwrap();
+        assert_eq!(rendered, vec!["echo dev"]);
+        let rendered =
+            replace_template_placeholders_with_args(&spec, &scripts, &["--env=prod".to_string()])
+                .unwrap();
+        assert_eq!(rendered, vec!["echo prod"]);
+    }
+
+    #[test]
+    fn test_task_parse_arg_missing() {
+        reset();
+        let parser = TaskScriptParser::new(None);
+        let scripts = vec!["echo {{ arg(name='who') }}".to_string()];
+        let (scripts, spec) = parser.parse_run_scripts(&scripts).unwrap();
+
+        let err = replace_template_placeholders_with_args(&spec, &scripts, &[]).unwrap_err();
+        assert!(err.to_string().contains("<who>"));
+        assert!(err.to_string().contains("Usage:"));
+    }
 }
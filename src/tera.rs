@@ -296,7 +296,6 @@ pub fn get_tera(dir: Option<&Path>) -> Tera {
         move |input: Option<&Value>, args: &[Value]| match input {
             Some(Value::String(version)) => match args.first() {
                 Some(Value::String(requirement)) => {
-                    println!("{}", requirement);
                     let result = Requirement::new(requirement)
                         .unwrap()
                         .matches(&Versioning::new(version).unwrap());
@@ -1,18 +1,21 @@
 use std::collections::BTreeMap;
+use std::path::Path;
 
-use eyre::Result;
+use eyre::{Result, WrapErr};
+use globset::Glob;
 use itertools::Itertools;
 
 use crate::cli::args::{BackendArg, ToolArg};
-use crate::config::Config;
-use crate::env;
+use crate::config::{Config, Settings};
 use crate::errors::Error;
-use crate::toolset::{ToolRequest, ToolSource, Toolset};
+use crate::toolset::{env_tools, resolve_cache, ToolRequest, ToolSource, Toolset};
+use crate::{dirs, env};
 
 #[derive(Debug, Default)]
 pub struct ToolsetBuilder {
     args: Vec<ToolArg>,
     default_to_latest: bool,
+    resolve_cache: bool,
 }
 
 impl ToolsetBuilder {
@@ -30,6 +33,13 @@ impl ToolsetBuilder {
         self
     }
 
+    /// resolves the versions from a cache that is kept until something they depend on changes,
+    /// this is for the shell hook which resolves the same toolset over and over
+    pub fn with_resolve_cache(mut self, resolve_cache: bool) -> Self {
+        self.resolve_cache = resolve_cache;
+        self
+    }
+
     pub fn build(self, config: &Config) -> Result<Toolset> {
         let mut toolset = Toolset {
             ..Default::default()
@@ -38,7 +48,11 @@ impl ToolsetBuilder {
         self.load_runtime_env(&mut toolset, env::vars().collect())?;
         self.load_runtime_args(&mut toolset)?;
         let start_ms = std::time::Instant::now();
-        if let Err(err) = toolset.resolve() {
+        let resolved = match self.resolve_cache && Settings::get().resolve_cache {
+            true => resolve_cache::resolve(config, &mut toolset),
+            false => toolset.resolve(),
+        };
+        if let Err(err) = resolved {
             if Error::is_argument_err(&err) {
                 return Err(err);
             }
@@ -51,7 +65,27 @@ impl ToolsetBuilder {
 
     fn load_config_files(&self, config: &Config, ts: &mut Toolset) -> eyre::Result<()> {
         for cf in config.config_files.values().rev() {
-            ts.merge(cf.to_toolset()?);
+            let mut cf_ts = cf.to_toolset()?;
+            let root = project_root(cf.get_path());
+            for tvl in cf_ts.versions.values_mut() {
+                let mut requests = vec![];
+                for tr in tvl.requests.drain(..) {
+                    let only_in = tr.only_in();
+                    if !only_in.is_empty() {
+                        ts.dir_scoped = true;
+                        let active = is_active_in(root, &only_in)
+                            .wrap_err_with(|| format!("invalid only_in for {}", tr.backend()))?;
+                        if !active {
+                            trace!("{tr} is not active in this directory");
+                            continue;
+                        }
+                    }
+                    requests.push(tr);
+                }
+                tvl.requests = requests;
+            }
+            cf_ts.versions.retain(|_, tvl| !tvl.requests.is_empty());
+            ts.merge(cf_ts);
         }
         Ok(())
     }
@@ -61,25 +95,16 @@ impl ToolsetBuilder {
         ts: &mut Toolset,
         env: BTreeMap<String, String>,
     ) -> eyre::Result<()> {
-        for (k, v) in env {
-            if k.starts_with("MISE_") && k.ends_with("_VERSION") && k != "MISE_VERSION" {
-                let plugin_name = k
-                    .trim_start_matches("MISE_")
-                    .trim_end_matches("_VERSION")
-                    .to_lowercase();
-                if plugin_name == "install" {
-                    // ignore MISE_INSTALL_VERSION
-                    continue;
-                }
-                let fa: BackendArg = plugin_name.as_str().into();
-                let source = ToolSource::Environment(k, v.clone());
-                let mut env_ts = Toolset::new(source);
-                for v in v.split_whitespace() {
-                    let tvr = ToolRequest::new(fa.clone(), v)?;
-                    env_ts.add_version(tvr);
-                }
-                ts.merge(env_ts);
+        let known = ts.versions.keys().map(|fa| fa.short.clone()).collect_vec();
+        for (tool, k, v) in env_tools::env_tool_versions(env, known) {
+            let fa: BackendArg = tool.as_str().into();
+            let source = ToolSource::Environment(k, v.clone());
+            let mut env_ts = Toolset::new(source);
+            for v in v.split_whitespace() {
+                let tvr = ToolRequest::new(fa.clone(), v)?;
+                env_ts.add_version(tvr);
             }
+            ts.merge(env_ts);
         }
         Ok(())
     }
@@ -116,3 +141,34 @@ impl ToolsetBuilder {
         Ok(())
     }
 }
+
+/// the directory `only_in` globs are relative to, `.config/mise.toml` and `.mise/config.toml`
+/// apply to the directory containing `.config` or `.mise`
+fn project_root(config_path: &Path) -> &Path {
+    let dir = config_path.parent().unwrap_or(config_path);
+    if dir.ends_with(".config/mise") {
+        dir.parent().and_then(|d| d.parent()).unwrap_or(dir)
+    } else if dir.ends_with(".config") || dir.ends_with(".mise") {
+        dir.parent().unwrap_or(dir)
+    } else {
+        dir
+    }
+}
+
+/// true if the cwd, or one of its parents below `root`, matches one of the globs
+fn is_active_in(root: &Path, only_in: &[String]) -> Result<bool> {
+    let cwd = dirs::cwd();
+    let Some(rel) = cwd.as_ref().and_then(|cwd| cwd.strip_prefix(root).ok()) else {
+        return Ok(false);
+    };
+    for pattern in only_in {
+        let glob = Glob::new(pattern.trim_end_matches('/'))?.compile_matcher();
+        if rel
+            .ancestors()
+            .any(|dir| !dir.as_os_str().is_empty() && glob.is_match(dir))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::backend;
+use crate::registry::REGISTRY;
+
+/// the env var that sets the version of `tool`, e.g.: `MISE_NODE_VERSION`. Anything other than
+/// letters and digits becomes `_` so `npm:prettier` is set with `MISE_NPM_PRETTIER_VERSION`.
+pub fn version_env_key(tool: &str) -> String {
+    let name: String = tool
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .collect();
+    format!("MISE_{name}_VERSION")
+}
+
+/// the tools set in `env` with [`version_env_key`] vars and their versions as
+/// `(tool, key, value)`. Since the key doesn't say which characters were replaced it is matched
+/// against `known` tools, the registry and installed plugins. Keys that don't match one are
+/// lowercased, so `MISE_FOO_BAR_VERSION` is `foo_bar`.
+pub(crate) fn env_tool_versions(
+    env: impl IntoIterator<Item = (String, String)>,
+    known: impl IntoIterator<Item = String>,
+) -> Vec<(String, String, String)> {
+    let env: BTreeMap<String, String> = env
+        .into_iter()
+        .filter(|(k, _)| {
+            k.starts_with("MISE_")
+                && k.ends_with("_VERSION")
+                // the version of mise itself
+                && k != "MISE_VERSION"
+                // set while a plugin is installing a tool
+                && k != "MISE_INSTALL_VERSION"
+                && k.len() > "MISE__VERSION".len()
+        })
+        .collect();
+    if env.is_empty() {
+        return vec![];
+    }
+    let mut tools: HashMap<String, String> = HashMap::new();
+    let candidates = REGISTRY
+        .keys()
+        .cloned()
+        .chain(backend::list().into_iter().map(|b| b.id().to_string()))
+        .chain(known);
+    for tool in candidates {
+        // tools passed in `known` come last so they take precedence
+        tools.insert(version_env_key(&tool), tool);
+    }
+    env.into_iter()
+        .map(|(k, v)| {
+            let tool = tools.get(&k).cloned().unwrap_or_else(|| {
+                k.trim_start_matches("MISE_")
+                    .trim_end_matches("_VERSION")
+                    .to_lowercase()
+            });
+            (tool, k, v)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test::reset;
+
+    #[test]
+    fn test_version_env_key() {
+        assert_eq!(version_env_key("node"), "MISE_NODE_VERSION");
+        assert_eq!(version_env_key("npm:prettier"), "MISE_NPM_PRETTIER_VERSION");
+        assert_eq!(
+            version_env_key("ubi:BurntSushi/ripgrep"),
+            "MISE_UBI_BURNTSUSHI_RIPGREP_VERSION"
+        );
+        assert_eq!(
+            version_env_key("golangci-lint"),
+            "MISE_GOLANGCI_LINT_VERSION"
+        );
+    }
+
+    #[test]
+    fn test_env_tool_versions() {
+        reset();
+        let env = [
+            ("MISE_NODE_VERSION", "20"),
+            ("MISE_NPM_PRETTIER_VERSION", "3"),
+            ("MISE_OTHER_TOOL_VERSION", "1"),
+            ("MISE_VERSION", "2024.1.1"),
+            ("MISE_INSTALL_VERSION", "1"),
+            ("MISE_NODE_COMPILE", "1"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let tools = env_tool_versions(env, ["npm:prettier".to_string()]);
+        assert_eq!(
+            tools
+                .iter()
+                .map(|(tool, _, v)| format!("{tool}@{v}"))
+                .collect::<Vec<_>>(),
+            vec!["node@20", "npm:prettier@3", "other_tool@1"]
+        );
+    }
+}
@@ -1,18 +1,19 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread::sleep;
 use std::time::Duration;
 use std::{panic, thread};
 
 pub use builder::ToolsetBuilder;
 use console::truncate_str;
-use eyre::{eyre, Result};
+pub use env_tools::version_env_key;
+use eyre::{bail, eyre, Result};
 use indexmap::IndexMap;
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use rayon::prelude::*;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 use tabled::Tabled;
 pub use tool_request::ToolRequest;
 pub use tool_request_set::{ToolRequestSet, ToolRequestSetBuilder};
@@ -21,25 +22,52 @@ pub use tool_version::ToolVersion;
 pub use tool_version_list::ToolVersionList;
 use versions::{Version, Versioning};
 
-use crate::backend::Backend;
+use crate::backend::{ABackend, Backend};
 use crate::cli::args::BackendArg;
 use crate::config::settings::{SettingsStatusMissingTools, SETTINGS};
 use crate::config::Config;
 use crate::env::{PATH_KEY, TERM_WIDTH};
 use crate::errors::Error;
 use crate::install_context::InstallContext;
+use crate::install_plan::InstallPlan;
 use crate::path_env::PathEnv;
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::{backend, env, runtime_symlinks, shims};
+use crate::ui::progress_report::SingleReport;
+use crate::{backend, env, http, mise_lock, runtime_symlinks, shims, ui};
 
 mod builder;
+mod env_tools;
+mod resolve_cache;
 mod tool_request;
 mod tool_request_set;
 mod tool_source;
 mod tool_version;
 mod tool_version_list;
 
-pub type ToolVersionOptions = BTreeMap<String, String>;
+/// the options set on a tool in a config file, e.g.: `node = { version = "20", arch = "x64" }`.
+/// Backends read `opts`, `env` is the tool's env table which mise sets itself while the tool is
+/// active and isn't passed on to the backend.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolVersionOptions {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub opts: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl ToolVersionOptions {
+    pub fn is_empty(&self) -> bool {
+        self.opts.is_empty() && self.env.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.opts.get(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.opts.contains_key(key)
+    }
+}
 
 #[derive(Debug, Default)]
 pub struct InstallOptions {
@@ -47,6 +75,11 @@ pub struct InstallOptions {
     pub jobs: Option<usize>,
     pub raw: bool,
     pub latest_versions: bool,
+    /// how many times to retry a tool's install if it fails with an error that might not
+    /// happen again, like a network error
+    pub retries: usize,
+    /// updated as each tool is resolved and installed so an interrupted install can be resumed
+    pub plan: Option<Arc<InstallPlan>>,
 }
 
 impl InstallOptions {
@@ -68,6 +101,8 @@ impl InstallOptions {
 pub struct Toolset {
     pub versions: IndexMap<BackendArg, ToolVersionList>,
     pub source: Option<ToolSource>,
+    /// true if a config has tools with `only_in` so which tools are active depends on the cwd
+    pub dir_scoped: bool,
 }
 
 impl Toolset {
@@ -154,6 +189,8 @@ impl Toolset {
         if versions.is_empty() {
             return Ok(vec![]);
         }
+        // held for the whole run so every install is cleaned up if mise is interrupted
+        let _ctrlc = ui::ctrlc::handle_ctrlc()?;
         show_python_install_hint(&versions);
         let leaf_deps = get_leaf_dependencies(&versions)?;
         if leaf_deps.len() < versions.len() {
@@ -161,14 +198,25 @@ impl Toolset {
             self.install_versions(config, leaf_deps.into_iter().cloned().collect(), mpr, opts)?;
         }
         debug!("install_versions: {}", versions.iter().join(" "));
-        let queue: Vec<_> = versions
+        // one job per version so that several versions of the same tool install in parallel,
+        // each one is only serialized against itself by the lock on its install path
+        let mut queue: Vec<_> = versions
             .into_iter()
+            .enumerate()
             .rev()
-            .chunk_by(|v| v.backend().clone())
-            .into_iter()
-            .map(|(fa, v)| (backend::get(&fa), v.collect_vec()))
+            .map(|(i, tr)| {
+                let t = backend::get(tr.backend());
+                let heavy = is_build_heavy(t.as_ref());
+                (i, t, tr, heavy)
+            })
             .collect();
-        for (backend, _) in &queue {
+        // jobs are popped from the end so this starts the slow builds first
+        queue.sort_by_key(|(_, _, _, heavy)| *heavy);
+        for backend in queue
+            .iter()
+            .map(|(_, b, _, _)| b)
+            .unique_by(|b| b.id().to_string())
+        {
             if let Some(plugin) = backend.plugin() {
                 if !plugin.is_installed() {
                     plugin.ensure_installed(mpr, false).or_else(|err| {
@@ -187,54 +235,118 @@ impl Toolset {
             true => 1,
             false => opts.jobs.unwrap_or(SETTINGS.jobs),
         };
-        let installing: HashSet<String> = HashSet::new();
+        let build_slots = BuildSlots::new(SETTINGS.build_jobs.unwrap_or_else(num_cpus::get));
+        // number of versions of each tool that are currently installing
+        let installing: HashMap<String, usize> = HashMap::new();
         let installing = Arc::new(Mutex::new(installing));
-        let installed = thread::scope(|s| {
-            #[allow(clippy::map_collect_result_unit)]
+        let install = |t: &ABackend, tr: &ToolRequest, heavy: bool| -> Result<ToolVersion> {
+            // only the leaf tools were installed first, a tool can still depend on another one
+            // in this batch, e.g.: the middle of a chain of three, so it waits for that to finish
+            for dep in t.get_all_dependencies(tr)? {
+                while installing
+                    .lock()
+                    .unwrap()
+                    .get(&dep.to_string())
+                    .is_some_and(|n| *n > 0)
+                {
+                    trace!("{tr} waiting for dependency {dep} to finish installing");
+                    sleep(Duration::from_millis(100));
+                }
+            }
+            let tv = match self.versions.get(tr.backend()) {
+                Some(tvl) => mise_lock::resolve(tr, t.as_ref(), &tvl.source, opts.latest_versions)?,
+                None => tr.resolve(t.as_ref(), opts.latest_versions)?,
+            };
+            if let Some(plan) = &opts.plan {
+                plan.resolved(tr, &tv);
+            }
+            let mut attempt = 0;
+            loop {
+                let res = {
+                    let pr = mpr.add(&tv.style());
+                    let _build_slot = heavy.then(|| build_slots.acquire(pr.as_ref()));
+                    let ctx = InstallContext {
+                        ts: self,
+                        pr,
+                        tv: tv.clone(),
+                        force: opts.force,
+                    };
+                    t.install_version(ctx)
+                };
+                // a failed install cleans up after itself so the next attempt starts over
+                match res {
+                    Ok(()) => {
+                        if let Some(plan) = &opts.plan {
+                            plan.done(tr);
+                        }
+                        return Ok(tv);
+                    }
+                    Err(err) if attempt < opts.retries && !Error::is_permanent(&err) => {
+                        attempt += 1;
+                        let delay = http::backoff_delay(attempt as u64);
+                        warn!(
+                            "failed to install {tv}, retrying in {:.1}s ({attempt}/{}): {err}",
+                            delay.as_secs_f64(),
+                            opts.retries
+                        );
+                        sleep(delay);
+                    }
+                    Err(err) if attempt > 0 => {
+                        let attempts = attempt + 1;
+                        return Err(err.wrap_err(format!(
+                            "failed to install {tv} after {attempts} attempts"
+                        )));
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        };
+        let results = thread::scope(|s| {
             (0..jobs)
                 .map(|_| {
                     let queue = queue.clone();
                     let installing = installing.clone();
-                    let ts = &*self;
+                    let install = &install;
+                    let build_slots = &build_slots;
                     s.spawn(move || {
-                        let next_job = || queue.lock().unwrap().pop();
-                        let mut installed = vec![];
-                        while let Some((t, versions)) = next_job() {
-                            installing.lock().unwrap().insert(t.id().into());
-                            for tr in versions {
-                                // TODO: this logic should be able to be removed now I think
-                                for dep in t.get_all_dependencies(&tr)? {
-                                    while installing.lock().unwrap().contains(&dep.to_string()) {
-                                        trace!(
-                                        "{tr} waiting for dependency {dep} to finish installing"
-                                    );
-                                        sleep(Duration::from_millis(100));
-                                    }
-                                }
-                                let tv = tr.resolve(t.as_ref(), opts.latest_versions)?;
-                                let ctx = InstallContext {
-                                    ts,
-                                    pr: mpr.add(&tv.style()),
-                                    tv: tv.clone(),
-                                    force: opts.force,
-                                };
-                                t.install_version(ctx)?;
-                                installed.push(tv);
+                        let next_job = || {
+                            let mut queue = queue.lock().unwrap();
+                            // rather than waiting for a build slot, install something that
+                            // only needs to be downloaded if there is anything left
+                            let download_only = match build_slots.is_full() {
+                                true => queue.iter().rposition(|(_, _, _, heavy)| !heavy),
+                                false => None,
+                            };
+                            match download_only {
+                                Some(idx) => Some(queue.remove(idx)),
+                                None => queue.pop(),
                             }
-                            installing.lock().unwrap().remove(t.id());
+                        };
+                        let mut results = vec![];
+                        while let Some((i, t, tr, heavy)) = next_job() {
+                            *installing.lock().unwrap().entry(t.id().into()).or_default() += 1;
+                            let res = install(&t, &tr, heavy);
+                            *installing.lock().unwrap().get_mut(t.id()).unwrap() -= 1;
+                            results.push((i, tr, res));
                         }
-                        Ok(installed)
+                        results
                     })
                 })
                 .collect::<Vec<_>>()
                 .into_iter()
-                .map(|t| match t.join() {
+                .flat_map(|t| match t.join() {
                     Ok(x) => x,
                     Err(e) => panic::resume_unwind(e),
                 })
-                .collect::<Result<Vec<Vec<ToolVersion>>>>()
-                .map(|x| x.into_iter().flatten().rev().collect())
-        })?;
+                .collect::<Vec<_>>()
+        });
+        let (installed, mut failed): (Vec<_>, Vec<_>) = results
+            .into_iter()
+            .sorted_by_key(|(i, _, _)| *i)
+            .partition_map(|(_, tr, res)| match res {
+                Ok(tv) => Either::Left(tv),
+                Err(err) => Either::Right((tr, err)),
+            });
         trace!("install: resolving");
         if let Err(err) = self.resolve() {
             debug!("error resolving versions after install: {err:#}");
@@ -243,7 +355,24 @@ impl Toolset {
         shims::reshim(self)?;
         runtime_symlinks::rebuild(config)?;
         trace!("install: done");
-        Ok(installed)
+        match failed.len() {
+            0 => Ok(installed),
+            1 if installed.is_empty() => Err(failed.pop().unwrap().1),
+            _ => {
+                if !installed.is_empty() {
+                    info!("installed {}", installed.iter().join(", "));
+                }
+                let failures = failed
+                    .iter()
+                    .map(|(tr, err)| format!("  {tr}: {err:#}"))
+                    .join("\n");
+                bail!(
+                    "failed to install {} of {} tools:\n{failures}",
+                    failed.len(),
+                    failed.len() + installed.len()
+                )
+            }
+        }
     }
 
     pub fn list_missing_versions(&self) -> Vec<ToolVersion> {
@@ -316,78 +445,87 @@ impl Toolset {
             .collect()
     }
     pub fn list_outdated_versions(&self, bump: bool) -> Vec<OutdatedInfo> {
-        self.list_current_versions()
+        let versions = self
+            .list_current_versions()
             .into_iter()
-            .filter_map(|(t, tv)| {
-                if t.symlink_path(&tv).is_some() {
+            .filter(|(t, tv)| {
+                if t.symlink_path(tv).is_some() {
                     trace!("skipping symlinked version {tv}");
                     // do not consider symlinked versions to be outdated
-                    return None;
+                    return false;
                 }
+                true
+            })
+            .map(|(t, tv)| {
                 // prefix is something like "temurin-" or "corretto-"
                 let prefix = regex!(r"^[a-zA-Z]+-")
                     .find(&tv.request.version())
                     .map(|m| m.as_str().to_string());
-                let latest_result = if bump {
-                    t.latest_version(prefix.clone())
-                } else {
-                    tv.latest_version(t.as_ref()).map(Option::from)
-                };
-                let mut out =
-                    OutdatedInfo::new(tv.clone(), self.find_source(&tv.request).unwrap().clone());
-                out.current = if t.is_version_installed(&tv, true) {
-                    Some(tv.version.clone())
-                } else {
-                    None
-                };
-                out.latest = match latest_result {
-                    Ok(Some(latest)) => latest,
-                    Ok(None) => {
-                        warn!("Error getting latest version for {t}: no latest version found");
-                        return None;
-                    }
-                    Err(e) => {
-                        warn!("Error getting latest version for {t}: {e:#}");
-                        return None;
-                    }
-                };
-                if t.is_version_installed(&tv, true)
-                    && !is_outdated_version(tv.version.as_str(), out.latest.as_str())
-                {
-                    trace!("skipping up-to-date version {tv}");
+                (t, tv, prefix)
+            })
+            .collect();
+        // the latest versions are fetched for every tool at once
+        backend::fetch_each(versions, |(t, tv, prefix)| match bump {
+            true => t.latest_version(prefix.clone()),
+            false => tv.latest_version(t.as_ref()).map(Option::from),
+        })
+        .into_iter()
+        .filter_map(|((t, tv, prefix), latest_result)| {
+            let mut out =
+                OutdatedInfo::new(tv.clone(), self.find_source(&tv.request).unwrap().clone());
+            out.current = if t.is_version_installed(&tv, true) {
+                Some(tv.version.clone())
+            } else {
+                None
+            };
+            out.latest = match latest_result {
+                Ok(Some(latest)) => latest,
+                Ok(None) => {
+                    warn!("Error getting latest version for {t}: no latest version found");
+                    return None;
+                }
+                Err(e) => {
+                    warn!("Error getting latest version for {t}: {e:#}");
                     return None;
                 }
-                if bump {
-                    let prefix = prefix.unwrap_or_default();
-                    let old = tv.request.version();
-                    let old = old.strip_prefix(&prefix).unwrap_or_default();
-                    let new = out.latest.strip_prefix(&prefix).unwrap_or_default();
-                    if let Some(bumped_version) = check_semver_bump(old, new) {
-                        if bumped_version != tv.request.version() {
-                            out.bump = Some(format!("{prefix}{bumped_version}"));
-                            match out.tool_request.clone() {
-                                ToolRequest::Version {
+            };
+            if t.is_version_installed(&tv, true)
+                && !is_outdated_version(tv.version.as_str(), out.latest.as_str())
+            {
+                trace!("skipping up-to-date version {tv}");
+                return None;
+            }
+            if bump {
+                let prefix = prefix.unwrap_or_default();
+                let old = tv.request.version();
+                let old = old.strip_prefix(&prefix).unwrap_or_default();
+                let new = out.latest.strip_prefix(&prefix).unwrap_or_default();
+                if let Some(bumped_version) = check_semver_bump(old, new) {
+                    if bumped_version != tv.request.version() {
+                        out.bump = Some(format!("{prefix}{bumped_version}"));
+                        match out.tool_request.clone() {
+                            ToolRequest::Version {
+                                backend,
+                                version: _version,
+                                options,
+                            } => {
+                                out.tool_request = ToolRequest::Version {
                                     backend,
-                                    version: _version,
                                     options,
-                                } => {
-                                    out.tool_request = ToolRequest::Version {
-                                        backend,
-                                        options,
-                                        version: out.bump.clone().unwrap(),
-                                    };
-                                }
-                                _ => {
-                                    warn!("upgrading non-version tool requests");
-                                    out.bump = None;
-                                }
+                                    version: out.bump.clone().unwrap(),
+                                };
+                            }
+                            _ => {
+                                warn!("upgrading non-version tool requests");
+                                out.bump = None;
                             }
                         }
                     }
                 }
-                Some(out)
-            })
-            .collect()
+            }
+            Some(out)
+        })
+        .collect()
     }
     pub fn full_env(&self) -> Result<BTreeMap<String, String>> {
         let mut env = env::PRISTINE_ENV
@@ -442,9 +580,46 @@ impl Toolset {
         if !add_paths.is_empty() {
             entries.insert(PATH_KEY.to_string(), add_paths);
         }
-        entries.extend(config.env()?.clone());
+        // a tool's own env table takes precedence over [env]. if several tools set the same
+        // var they're applied by tool name so the result doesn't depend on config load order
+        let tool_tables = self
+            .list_current_installed_versions()
+            .into_iter()
+            .sorted_by(|(_, a), (_, b)| a.backend.cmp(&b.backend))
+            .flat_map(|(_, tv)| tv.request.env())
+            .collect_vec();
+        // [env] templates are rendered with what the tools provide so e.g. `{{env.JAVA_HOME}}`
+        // works and `env.PATH` includes the tool bin paths
+        let mut tool_env = entries.clone();
+        tool_env.extend(tool_tables.clone());
+        let tool_path = self
+            .list_paths()
+            .into_iter()
+            .chain(entries.get(&*PATH_KEY).map(PathBuf::from))
+            .chain(env::PATH.clone());
+        tool_env.insert(
+            PATH_KEY.to_string(),
+            env::join_paths(tool_path)?.to_string_lossy().to_string(),
+        );
+        entries.extend(config.env_with_tool_env(&tool_env)?);
+        entries.extend(tool_tables);
         Ok(entries)
     }
+    /// the directories activation puts at the front of PATH, in order: `env._.path` entries, paths
+    /// tools add with exec-env and then the tools' bin paths. `env_path` is the PATH from
+    /// [`Toolset::env`].
+    pub fn list_final_paths(
+        &self,
+        config: &Config,
+        env_path: Option<&str>,
+    ) -> Result<Vec<PathBuf>> {
+        let mut paths = config.path_dirs()?.clone();
+        if let Some(p) = env_path {
+            paths.extend(env::split_paths(p));
+        }
+        paths.extend(self.list_paths());
+        Ok(paths)
+    }
     pub fn list_paths(&self) -> Vec<PathBuf> {
         self.list_current_installed_versions()
             .into_par_iter()
@@ -567,6 +742,55 @@ fn show_python_install_hint(versions: &[ToolRequest]) {
     );
 }
 
+fn is_build_heavy(t: &dyn Backend) -> bool {
+    let overrides = &SETTINGS.build_heavy_tools;
+    t.is_build_heavy()
+        || overrides.contains(t.id())
+        || overrides.contains(&t.fa().backend_type.to_string())
+}
+
+/// limits how many build-heavy installs run at once, see the `build_jobs` setting
+struct BuildSlots {
+    limit: usize,
+    used: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl BuildSlots {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            used: Mutex::new(0),
+            freed: Condvar::new(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        *self.used.lock().unwrap() >= self.limit
+    }
+
+    fn acquire(&self, pr: &dyn SingleReport) -> BuildSlot<'_> {
+        let mut used = self.used.lock().unwrap();
+        if *used >= self.limit {
+            pr.set_message(format!("waiting for a build slot ({} in use)", self.limit));
+        }
+        while *used >= self.limit {
+            used = self.freed.wait(used).unwrap();
+        }
+        *used += 1;
+        BuildSlot(self)
+    }
+}
+
+struct BuildSlot<'a>(&'a BuildSlots);
+
+impl Drop for BuildSlot<'_> {
+    fn drop(&mut self) {
+        *self.0.used.lock().unwrap() -= 1;
+        self.0.freed.notify_one();
+    }
+}
+
 impl Display for Toolset {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let plugins = &self
@@ -734,7 +958,9 @@ mod tests {
     use pretty_assertions::assert_eq;
     use test_log::test;
 
-    use super::{check_semver_bump, is_outdated_version};
+    use crate::ui::progress_report::QuietReport;
+
+    use super::{check_semver_bump, is_outdated_version, BuildSlots};
 
     #[test]
     fn test_is_outdated_version() {
@@ -778,4 +1004,18 @@ mod tests {
             Some("20.0.1".to_string())
         );
     }
+
+    #[test]
+    fn test_build_slots() {
+        let slots = BuildSlots::new(2);
+        let pr = QuietReport::new();
+        let a = slots.acquire(&pr);
+        assert!(!slots.is_full());
+        let b = slots.acquire(&pr);
+        assert!(slots.is_full());
+        drop(a);
+        assert!(!slots.is_full());
+        drop(b);
+        assert!(!BuildSlots::new(0).is_full());
+    }
 }
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use eyre::Result;
+
+use crate::backend;
+use crate::cache::CacheManagerBuilder;
+use crate::config::{Config, Settings};
+use crate::hash::hash_to_str;
+use crate::toolset::{ToolVersion, Toolset};
+use crate::{dirs, mise_lock};
+
+/// the index of the request each version was resolved from, by backend
+type Resolved = BTreeMap<String, Vec<(usize, String)>>;
+
+/// resolves the toolset from the cache if nothing that contributed to the last resolution has
+/// changed since, otherwise resolves it and caches the result
+pub fn resolve(config: &Config, ts: &mut Toolset) -> Result<()> {
+    let key = cache_key(config, ts);
+    let cache =
+        CacheManagerBuilder::new(dirs::CACHE.join("resolve").join(format!("{key}.msgpack.z")))
+            .build::<Resolved>();
+    let resolved = cache.get_or_try_init(|| {
+        ts.resolve()?;
+        Ok(resolved(ts))
+    })?;
+    if !apply(ts, resolved) {
+        // the key should rule this out but resolving is always correct
+        return ts.resolve();
+    }
+    Ok(())
+}
+
+/// the config files and lockfiles with their modification times, the requests they make and the
+/// installed versions of the tools. Changes to any of these can change what versions resolve to.
+fn cache_key(config: &Config, ts: &Toolset) -> String {
+    let mut key = vec![Settings::get().lockfile.to_string(), stat(&dirs::PLUGINS)];
+    for path in config.config_files.keys() {
+        key.push(stat(path));
+        if let Some(lockfile) = mise_lock::path_for_config(path) {
+            key.push(stat(&lockfile));
+        }
+    }
+    for (ba, tvl) in &ts.versions {
+        key.push(format!("{}={:?}", ba.full, tvl.source));
+        key.extend(tvl.requests.iter().map(|tr| format!("{tr:?}")));
+        key.push(stat(&ba.installs_path));
+    }
+    hash_to_str(&key)
+}
+
+/// the path with its size and modification time, or just the path if it doesn't exist
+fn stat(path: &Path) -> String {
+    let modified = path
+        .metadata()
+        .ok()
+        .and_then(|m| Some((m.len(), m.modified().ok()?.duration_since(UNIX_EPOCH).ok()?)));
+    match modified {
+        Some((len, modified)) => format!("{}:{len}:{}", path.display(), modified.as_nanos()),
+        None => path.display().to_string(),
+    }
+}
+
+fn resolved(ts: &Toolset) -> Resolved {
+    ts.versions
+        .iter()
+        .map(|(ba, tvl)| {
+            let versions = tvl
+                .versions
+                .iter()
+                .filter_map(|tv| {
+                    let i = tvl.requests.iter().position(|tr| tr == &tv.request)?;
+                    Some((i, tv.version.clone()))
+                })
+                .collect();
+            (ba.full.clone(), versions)
+        })
+        .collect()
+}
+
+/// sets the versions of the toolset from the cache, false if the cache doesn't match the toolset
+fn apply(ts: &mut Toolset, resolved: &Resolved) -> bool {
+    if resolved.len() != ts.versions.len() {
+        return false;
+    }
+    for (ba, tvl) in ts.versions.iter_mut() {
+        let Some(versions) = resolved.get(&ba.full) else {
+            return false;
+        };
+        let b = backend::get(ba);
+        let mut tvs = vec![];
+        for (i, version) in versions {
+            let Some(tr) = tvl.requests.get(*i) else {
+                return false;
+            };
+            tvs.push(ToolVersion::new(b.as_ref(), tr.clone(), version.clone()));
+        }
+        tvl.versions = tvs;
+    }
+    trace!("resolved toolset from cache");
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Config;
+    use crate::test::reset;
+    use crate::toolset::ToolsetBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_cache() {
+        reset();
+        let config = Config::get();
+        let versions = |ts: &Toolset| {
+            ts.list_current_versions()
+                .into_iter()
+                .map(|(_, tv)| tv.to_string())
+                .collect::<Vec<_>>()
+        };
+        let expected = versions(&ToolsetBuilder::new().build(&config).unwrap());
+        assert!(!expected.is_empty());
+        let mut ts = ToolsetBuilder::new().build(&config).unwrap();
+        // once to fill the cache and once to read from it
+        for _ in 0..2 {
+            for tvl in ts.versions.values_mut() {
+                tvl.versions.clear();
+            }
+            resolve(&config, &mut ts).unwrap();
+            assert_eq!(versions(&ts), expected);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
@@ -128,6 +129,20 @@ impl ToolRequest {
         }
     }
 
+    /// env vars from the tool's `env` table which are only set while this tool is active
+    pub fn env(&self) -> BTreeMap<String, String> {
+        self.options().env
+    }
+
+    /// globs relative to the config file's directory, the tool is only active below a directory
+    /// matching one of these. Empty if the tool is active everywhere.
+    pub fn only_in(&self) -> Vec<String> {
+        self.options()
+            .get("only_in")
+            .map(|p| p.lines().map(|p| p.to_string()).collect())
+            .unwrap_or_default()
+    }
+
     pub fn is_installed(&self) -> bool {
         let backend = backend::get(self.backend());
         let tv = ToolVersion::new(backend.as_ref(), self.clone(), self.version());
@@ -7,7 +7,7 @@ use itertools::Itertools;
 use crate::cli::args::{BackendArg, ToolArg};
 use crate::config::{Config, Settings};
 use crate::env;
-use crate::toolset::{ToolRequest, ToolSource};
+use crate::toolset::{env_tools, ToolRequest, ToolSource};
 
 #[derive(Debug, Default, Clone)]
 pub struct ToolRequestSet {
@@ -166,25 +166,16 @@ impl ToolRequestSetBuilder {
     }
 
     fn load_runtime_env(&self, trs: &mut ToolRequestSet) -> eyre::Result<()> {
-        for (k, v) in env::vars() {
-            if k.starts_with("MISE_") && k.ends_with("_VERSION") && k != "MISE_VERSION" {
-                let plugin_name = k
-                    .trim_start_matches("MISE_")
-                    .trim_end_matches("_VERSION")
-                    .to_lowercase();
-                if plugin_name == "install" {
-                    // ignore MISE_INSTALL_VERSION
-                    continue;
-                }
-                let fa: BackendArg = plugin_name.as_str().into();
-                let source = ToolSource::Environment(k, v.clone());
-                let mut env_ts = ToolRequestSet::new();
-                for v in v.split_whitespace() {
-                    let tvr = ToolRequest::new(fa.clone(), v)?;
-                    env_ts.add_version(tvr, &source);
-                }
-                merge(trs, env_ts);
+        let known = trs.tools.keys().map(|fa| fa.short.clone()).collect_vec();
+        for (tool, k, v) in env_tools::env_tool_versions(env::vars(), known) {
+            let fa: BackendArg = tool.as_str().into();
+            let source = ToolSource::Environment(k, v.clone());
+            let mut env_ts = ToolRequestSet::new();
+            for v in v.split_whitespace() {
+                let tvr = ToolRequest::new(fa.clone(), v)?;
+                env_ts.add_version(tvr, &source);
             }
+            merge(trs, env_ts);
         }
         Ok(())
     }
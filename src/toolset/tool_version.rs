@@ -202,7 +202,14 @@ impl ToolVersion {
                 return build(v.clone());
             }
         }
-        let matches = backend.list_versions_matching(&v)?;
+        let matches = match backend.list_versions_matching(&v) {
+            // tools downloaded from a custom url may not have a reachable version list
+            Err(err) if request.options().contains_key("url") => {
+                debug!("using {v} as written, failed to list versions: {err:#}");
+                return build(v);
+            }
+            matches => matches?,
+        };
         if matches.contains(&v) {
             return build(v);
         }
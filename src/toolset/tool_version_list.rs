@@ -1,8 +1,8 @@
-use crate::backend;
 use crate::cli::args::BackendArg;
 use crate::errors::Error;
 use crate::toolset::tool_request::ToolRequest;
 use crate::toolset::{ToolSource, ToolVersion};
+use crate::{backend, mise_lock};
 
 /// represents several versions of a tool for a particular plugin
 #[derive(Debug, Clone)]
@@ -25,9 +25,15 @@ impl ToolVersionList {
     pub fn resolve(&mut self, latest_versions: bool) -> eyre::Result<()> {
         self.versions.clear();
         let plugin = backend::get(&self.backend);
+        // like asdf, extra versions on a .tool-versions line are fallbacks so one that can't be
+        // resolved is skipped as long as another one can
+        let fallbacks =
+            matches!(self.source, ToolSource::ToolVersions(_)) && self.requests.len() > 1;
+        let mut errors = vec![];
         for tvr in &mut self.requests {
-            match tvr.resolve(plugin.as_ref(), latest_versions) {
+            match mise_lock::resolve(tvr, plugin.as_ref(), &self.source, latest_versions) {
                 Ok(v) => self.versions.push(v),
+                Err(err) if fallbacks => errors.push((tvr.clone(), err)),
                 Err(err) => {
                     return Err(Error::FailedToResolveVersion {
                         tr: tvr.clone(),
@@ -38,6 +44,18 @@ impl ToolVersionList {
                 }
             }
         }
+        if self.versions.is_empty() && !errors.is_empty() {
+            let (tr, err) = errors.remove(0);
+            return Err(Error::FailedToResolveVersion {
+                tr,
+                ts: self.source.clone(),
+                source: err,
+            }
+            .into());
+        }
+        for (tr, err) in errors {
+            warn!("skipping {tr} from {}: {err:#}", self.source);
+        }
         Ok(())
     }
 }
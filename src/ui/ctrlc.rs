@@ -1,16 +1,21 @@
+use std::path::PathBuf;
 use std::process::exit;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::thread;
+use std::sync::Mutex;
+use std::{fs, thread};
 
 use console::Term;
-use signal_hook::consts::SIGINT;
+use signal_hook::consts::{SIGINT, SIGTERM};
 use signal_hook::iterator::{Handle, Signals};
 
 #[must_use]
 #[derive(Debug)]
 pub struct HandleGuard(Handle);
 
-/// ensures cursor is displayed on ctrl-c
+/// directories that are partially written and must be removed if mise is interrupted
+static CLEANUP_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(vec![]);
+
+/// ensures cursor is displayed and partial installs are removed on ctrl-c
 pub fn handle_ctrlc() -> eyre::Result<Option<HandleGuard>> {
     static HANDLED: AtomicBool = AtomicBool::new(false);
     let handled = HANDLED.swap(true, Ordering::Relaxed);
@@ -18,21 +23,57 @@ pub fn handle_ctrlc() -> eyre::Result<Option<HandleGuard>> {
         return Ok(None);
     }
 
-    let mut signals = Signals::new([SIGINT])?;
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
     let handle = HandleGuard(signals.handle());
     thread::spawn(move || {
-        if signals.into_iter().next().is_some() {
+        if let Some(signal) = signals.into_iter().next() {
+            // nothing in here may touch the progress bars or the output macros, another
+            // thread could be holding their locks while it is rendering
             let _ = Term::stderr().show_cursor();
-            debug!("Ctrl-C pressed, exiting...");
-            exit(1);
+            cleanup();
+            exit(128 + signal);
         }
         HANDLED.store(false, Ordering::Relaxed);
     });
     Ok(Some(handle))
 }
 
+fn cleanup() {
+    let paths = match CLEANUP_PATHS.lock() {
+        Ok(paths) => paths,
+        Err(err) => err.into_inner(),
+    };
+    for path in paths.iter() {
+        let _ = fs::remove_dir_all(path);
+    }
+}
+
+#[must_use]
+#[derive(Debug)]
+pub struct CleanupGuard(Vec<PathBuf>);
+
+/// removes `paths` if mise is interrupted before the returned guard is dropped
+pub fn cleanup_on_interrupt(paths: Vec<PathBuf>) -> CleanupGuard {
+    CLEANUP_PATHS.lock().unwrap().extend(paths.iter().cloned());
+    CleanupGuard(paths)
+}
+
 impl Drop for HandleGuard {
     fn drop(&mut self) {
         self.0.close();
     }
 }
+
+impl Drop for CleanupGuard {
+    fn drop(&mut self) {
+        let mut paths = match CLEANUP_PATHS.lock() {
+            Ok(paths) => paths,
+            Err(err) => err.into_inner(),
+        };
+        for path in &self.0 {
+            if let Some(i) = paths.iter().position(|p| p == path) {
+                paths.remove(i);
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 #[must_use]
 #[derive(Debug)]
 pub struct HandleGuard();
@@ -6,3 +8,11 @@ pub struct HandleGuard();
 pub fn handle_ctrlc() -> eyre::Result<Option<HandleGuard>> {
     Ok(Some(HandleGuard()))
 }
+
+#[must_use]
+#[derive(Debug)]
+pub struct CleanupGuard();
+
+pub fn cleanup_on_interrupt(_paths: Vec<PathBuf>) -> CleanupGuard {
+    CleanupGuard()
+}
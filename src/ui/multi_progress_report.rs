@@ -3,6 +3,7 @@ use std::sync::{Arc, Mutex, Weak};
 use indicatif::MultiProgress;
 
 use crate::config::Settings;
+use crate::env;
 use crate::ui::progress_report::{ProgressReport, QuietReport, SingleReport, VerboseReport};
 
 #[derive(Debug)]
@@ -35,8 +36,10 @@ impl MultiProgressReport {
     fn new() -> Self {
         let settings = Settings::get();
         let mp = match settings.raw
+            || settings.raw_log
             || settings.quiet
             || settings.verbose
+            || *env::MISE_LOG_FORMAT_JSON
             || !console::user_attended_stderr()
         {
             true => None,
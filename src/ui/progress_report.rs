@@ -1,8 +1,10 @@
+use std::sync::Mutex;
 use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
 use once_cell::sync::Lazy;
 
+use crate::config::Settings;
 use crate::ui::style;
 use crate::{backend, env, ui};
 
@@ -11,6 +13,8 @@ pub trait SingleReport: Send + Sync {
     fn set_message(&self, _message: String) {}
     fn inc(&self, _delta: u64) {}
     fn set_length(&self, _length: u64) {}
+    /// extra status shown after the message, such as the download rate
+    fn set_suffix(&self, _suffix: String) {}
     fn finish(&self) {}
     fn finish_with_message(&self, _message: String) {}
 }
@@ -25,9 +29,7 @@ static PROG_TEMPLATE: Lazy<ProgressStyle> = Lazy::new(|| {
         0..=89 => "{prefix} {wide_msg} {bar:10.cyan/blue} {percent:>2}%",
         90..=99 => "{prefix} {wide_msg} {bar:15.cyan/blue} {percent:>2}%",
         100..=114 => "{prefix} {wide_msg} {bytes}/{total_bytes:10} {bar:10.cyan/blue}",
-        _ => {
-            "{prefix} {wide_msg} {bytes}/{total_bytes} ({eta}) {bar:20.cyan/blue} {elapsed:>3.dim.italic}"
-        }
+        _ => "{prefix} {wide_msg} {bytes}/{total_bytes} {bar:20.cyan/blue} {elapsed:>3.dim.italic}",
     };
     ProgressStyle::with_template(tmpl).unwrap()
 });
@@ -42,6 +44,8 @@ pub struct ProgressReport {
     pub pb: ProgressBar,
     prefix: String,
     pad: usize,
+    message: Mutex<String>,
+    suffix: Mutex<String>,
     _ctrlc: Option<ui::ctrlc::HandleGuard>,
 }
 
@@ -80,9 +84,22 @@ impl ProgressReport {
             prefix,
             pb,
             pad,
+            message: Default::default(),
+            suffix: Default::default(),
             _ctrlc,
         }
     }
+
+    fn render_message(&self) {
+        let message = self.message.lock().unwrap();
+        let suffix = self.suffix.lock().unwrap();
+        if suffix.is_empty() {
+            self.pb.set_message(message.clone());
+        } else {
+            self.pb
+                .set_message(format!("{message} {}", style::edim(&*suffix)));
+        }
+    }
 }
 
 impl SingleReport for ProgressReport {
@@ -92,7 +109,8 @@ impl SingleReport for ProgressReport {
         });
     }
     fn set_message(&self, message: String) {
-        self.pb.set_message(message.replace('\r', ""));
+        *self.message.lock().unwrap() = message.replace('\r', "");
+        self.render_message();
     }
     fn inc(&self, delta: u64) {
         self.pb.inc(delta);
@@ -107,6 +125,10 @@ impl SingleReport for ProgressReport {
         self.pb.disable_steady_tick();
         self.pb.set_length(length);
     }
+    fn set_suffix(&self, suffix: String) {
+        *self.suffix.lock().unwrap() = suffix;
+        self.render_message();
+    }
     fn finish(&self) {
         self.pb.set_style(SUCCESS_TEMPLATE.clone());
         self.pb
@@ -134,6 +156,8 @@ impl SingleReport for QuietReport {}
 pub struct VerboseReport {
     prefix: String,
     pad: usize,
+    /// prefix each line with the tool so the output of parallel installs can be told apart
+    raw_log: bool,
 }
 
 impl VerboseReport {
@@ -141,23 +165,45 @@ impl VerboseReport {
         VerboseReport {
             prefix,
             pad: *LONGEST_PLUGIN_NAME,
+            raw_log: Settings::get().raw_log,
+        }
+    }
+
+    fn print(&self, message: &str) {
+        match self.raw_log {
+            true => eprintln!("{} {message}", normal_prefix(self.pad, &self.prefix)),
+            false => eprintln!("{message}"),
+        }
+    }
+
+    /// with MISE_LOG_FORMAT=json progress is logged so it doesn't break up the json lines
+    fn log(&self, message: &str) -> bool {
+        if *env::MISE_LOG_FORMAT_JSON {
+            let prefix = console::strip_ansi_codes(&self.prefix);
+            log::info!(prefix = prefix.as_ref(); "{message}");
         }
+        *env::MISE_LOG_FORMAT_JSON
     }
 }
 
 impl SingleReport for VerboseReport {
     fn println(&self, message: String) {
-        eprintln!("{message}");
+        if !self.log(&message) {
+            self.print(&message);
+        }
     }
     fn set_message(&self, message: String) {
-        // let prefix = normal_prefix(self.pad, &self.prefix);
-        // eprintln!("{prefix} {message}");
-        eprintln!("{message}");
+        if !self.log(&message) {
+            self.print(&message);
+        }
     }
     fn finish(&self) {
         self.finish_with_message(style::egreen("done").to_string());
     }
     fn finish_with_message(&self, message: String) {
+        if self.log(&message) {
+            return;
+        }
         let prefix = success_prefix(self.pad - 2, &self.prefix);
         let ico = style::egreen("✓").bright();
         eprintln!("{prefix} {ico} {message}");
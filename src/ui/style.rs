@@ -64,6 +64,10 @@ pub fn nunderline<D>(val: D) -> StyledObject<D> {
     nstyle(val).underlined()
 }
 
+pub fn ngreen<D>(val: D) -> StyledObject<D> {
+    nstyle(val).green()
+}
+
 pub fn nyellow<D>(val: D) -> StyledObject<D> {
     nstyle(val).yellow()
 }